@@ -0,0 +1,690 @@
+use std::{
+    env, fs,
+    net::{IpAddr, Ipv4Addr},
+    str::FromStr,
+    time::Duration,
+};
+
+use libargondb::{
+    ArgonFsConfig, CommitLogConfig, CommitLogSyncPolicy, SSTableCompactorConfig,
+    kv::config::KVConfig,
+};
+
+use crate::errors::{CriticalError, CriticalResult};
+
+#[derive(Debug, Clone)]
+pub struct GrpcConfig {
+    pub bind_address: IpAddr,
+    pub port: u16,
+}
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        Self {
+            bind_address: IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
+            port: 50051,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ArgonDbConfig {
+    pub grpc: GrpcConfig,
+    pub argon_fs: ArgonFsConfig,
+    pub kv: KVConfig,
+    pub compactor: SSTableCompactorConfig,
+    /**
+     * Block cache size in bytes, split into pages once all sources are read. Unset until either
+     * block cache key is set.
+     */
+    block_cache_size: Option<usize>,
+    /**
+     * Group window or periodic interval of the commit log sync policy, applied once all sources
+     * are read. Unset until the interval key is set.
+     */
+    commit_log_sync_interval: Option<Duration>,
+}
+
+const CONFIG_FILE_FLAG: &str = "--config";
+const CONFIG_FILE_ENV: &str = "ARGONDB_CONFIG";
+const ENV_PREFIX: &str = "ARGONDB_";
+
+impl ArgonDbConfig {
+    /**
+     * Builds config from defaults overridden by the TOML config file, then by environment variables
+     * and then by command line flags. Config file is read from the path given by `--config` flag or
+     * `ARGONDB_CONFIG` variable. Every setting is named by its dotted key, like `grpc.port` - it is
+     * set by `port` in `[grpc]` table of the file, by `ARGONDB_GRPC_PORT` variable and by
+     * `--grpc.port` flag.
+     */
+    pub fn load() -> CriticalResult<Self> {
+        let args: Vec<String> = env::args().skip(1).collect();
+        let cli_values = parse_args(&args)?;
+
+        let config_file = cli_values
+            .iter()
+            .find(|(key, _)| key == CONFIG_FILE_FLAG)
+            .map(|(_, path)| path.clone())
+            .or_else(|| env::var(CONFIG_FILE_ENV).ok());
+
+        let config_file_content = config_file
+            .map(|config_file| {
+                fs::read_to_string(&config_file).map_err(|e| {
+                    CriticalError::from_msg_and_source(
+                        format!("cannot read config file {}", config_file),
+                        e,
+                    )
+                })
+            })
+            .transpose()?;
+
+        Self::from_sources(
+            config_file_content.as_deref(),
+            |name| env::var(name).ok(),
+            cli_values,
+        )
+    }
+
+    /** Applies config file content, environment variables and command line flags in this order. */
+    pub(super) fn from_sources(
+        config_file_content: Option<&str>,
+        env_var: impl Fn(&str) -> Option<String>,
+        cli_values: Vec<(String, String)>,
+    ) -> CriticalResult<Self> {
+        let mut config = Self::default();
+
+        if let Some(content) = config_file_content {
+            for (key, value) in parse_toml(content)? {
+                config.set(&key, &value)?;
+            }
+        }
+
+        for config_key in CONFIG_KEYS {
+            if let Some(value) = env_var(&env_var_name(config_key.name)) {
+                config.set(config_key.name, &value)?;
+            }
+        }
+
+        for (key, value) in cli_values {
+            if key != CONFIG_FILE_FLAG {
+                config.set(&key["--".len()..], &value)?;
+            }
+        }
+
+        config.resolve_block_cache_pages();
+        config.resolve_commit_log_sync_interval();
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    pub fn print(&self) {
+        println!("effective config:");
+        for config_key in CONFIG_KEYS {
+            println!("  {} = {}", config_key.name, (config_key.get)(self));
+        }
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> CriticalResult<()> {
+        let config_key = CONFIG_KEYS
+            .iter()
+            .find(|config_key| config_key.name == key)
+            .ok_or_else(|| CriticalError::from_msg(format!("unknown config key {}", key)))?;
+
+        (config_key.set)(self, value).map_err(|msg| {
+            CriticalError::from_msg(format!(
+                "invalid value {} of config key {} - {}",
+                value, key, msg
+            ))
+        })
+    }
+
+    /** Block cache size is kept when only its page size changes. */
+    fn resolve_block_cache_pages(&mut self) {
+        if let Some(size) = self.block_cache_size {
+            self.argon_fs.block_cache_pages_count =
+                size / self.argon_fs.block_cache_page_size.max(1);
+        }
+    }
+
+    /** Block cache size in bytes, as resolved once all sources are read. */
+    fn block_cache_size(&self) -> Result<usize, String> {
+        match self.block_cache_size {
+            Some(size) => Ok(size),
+            None => self
+                .argon_fs
+                .block_cache_page_size
+                .checked_mul(self.argon_fs.block_cache_pages_count)
+                .ok_or_else(|| "block cache size overflows".to_string()),
+        }
+    }
+
+    /** Interval is kept whichever of the sync policy and interval keys comes first. */
+    fn resolve_commit_log_sync_interval(&mut self) {
+        let Some(interval) = self.commit_log_sync_interval else {
+            return;
+        };
+
+        match &mut self.argon_fs.commit_log_config.sync_policy {
+            CommitLogSyncPolicy::PerWrite => {}
+            CommitLogSyncPolicy::Group { window } => *window = interval,
+            CommitLogSyncPolicy::Periodic { interval: period } => *period = interval,
+        }
+    }
+
+    /**
+     * Commit log sync interval, as resolved once all sources are read. Per write policy has none,
+     * switching away from it starts from the default interval.
+     */
+    fn commit_log_sync_interval(&self) -> Duration {
+        self.commit_log_sync_interval
+            .or_else(|| sync_policy_interval(self.argon_fs.commit_log_config.sync_policy))
+            .or_else(|| sync_policy_interval(CommitLogConfig::default().sync_policy))
+            .unwrap_or_default()
+    }
+
+    pub(super) fn validate(&self) -> CriticalResult<()> {
+        let check = |is_valid: bool, msg: &str| {
+            if is_valid {
+                Ok(())
+            } else {
+                Err(CriticalError::from_msg(format!("invalid config - {}", msg)))
+            }
+        };
+
+        check(self.grpc.port != 0, "grpc.port must not be 0")?;
+        check(
+            !self
+                .argon_fs
+                .fs_filesystem_config
+                .storage_root
+                .as_os_str()
+                .is_empty(),
+            "storage.root must not be empty",
+        )?;
+        check(
+            self.argon_fs.fs_filesystem_config.fs_read_pool_thread_count > 0,
+            "storage.read_pool_threads must be positive",
+        )?;
+        check(
+            self.argon_fs.worker_pool_thread_count > 0,
+            "storage.worker_pool_threads must be positive",
+        )?;
+        check(
+            self.argon_fs.block_cache_page_size.is_power_of_two(),
+            "block_cache.page_size must be a power of two",
+        )?;
+        check(
+            self.argon_fs.block_cache_pages_count > 0,
+            "block_cache.size must hold at least one page",
+        )?;
+        check(
+            self.kv.mutation_max_size > 0 && self.kv.mutation_max_size <= self.kv.memtable_size,
+            "kv.mutation_max_size must be positive and fit in kv.memtable_size",
+        )?;
+        check(
+            self.kv.object_id_lease_size > 0,
+            "kv.object_id_lease_size must be positive",
+        )?;
+        check(
+            self.kv.write_buffer_size >= self.kv.memtable_size,
+            "kv.write_buffer_size must fit at least one memtable",
+        )?;
+        check(
+            self.kv.memtable_slowdown_count > 0
+                && self.kv.memtable_slowdown_count <= self.kv.memtable_stop_count,
+            "kv.memtable_slowdown_count must be positive and not above kv.memtable_stop_count",
+        )?;
+        check(
+            self.kv.level0_slowdown_count > 0
+                && self.kv.level0_slowdown_count <= self.kv.level0_stop_count,
+            "kv.level0_slowdown_count must be positive and not above kv.level0_stop_count",
+        )?;
+        check(
+            !matches!(
+                self.argon_fs.commit_log_config.sync_policy,
+                CommitLogSyncPolicy::Periodic { interval } if interval.is_zero()
+            ),
+            "commit_log.sync_interval_ms must be positive for periodic sync policy",
+        )?;
+        check(
+            self.argon_fs.commit_log_config.segment_size > 0,
+            "commit_log.segment_size must be positive",
+        )?;
+        check(
+            self.compactor.worker_count > 0,
+            "compaction.workers must be positive",
+        )?;
+        check(
+            !self.compactor.scan_interval.is_zero(),
+            "compaction.scan_interval_seconds must be positive",
+        )?;
+        check(
+            self.compactor
+                .strategy_config
+                .size_tiered
+                .level_file_threshold
+                > 0
+                && self.compactor.strategy_config.leveled.level0_file_threshold > 0
+                && self.compactor.strategy_config.time_window_file_threshold > 0,
+            "compaction file thresholds must be positive",
+        )?;
+        check(
+            self.compactor.strategy_config.leveled.max_file_size > 0
+                && self.compactor.strategy_config.leveled.level_base_size > 0,
+            "compaction.leveled sizes must be positive",
+        )?;
+
+        Ok(())
+    }
+}
+
+/** Setting addressable by its dotted key in all config sources. */
+struct ConfigKey {
+    name: &'static str,
+    set: fn(&mut ArgonDbConfig, &str) -> Result<(), String>,
+    get: fn(&ArgonDbConfig) -> String,
+}
+
+const CONFIG_KEYS: &[ConfigKey] = &[
+    ConfigKey {
+        name: "grpc.bind_address",
+        set: |config, value| {
+            config.grpc.bind_address = parse(value)?;
+            Ok(())
+        },
+        get: |config| config.grpc.bind_address.to_string(),
+    },
+    ConfigKey {
+        name: "grpc.port",
+        set: |config, value| {
+            config.grpc.port = parse(value)?;
+            Ok(())
+        },
+        get: |config| config.grpc.port.to_string(),
+    },
+    ConfigKey {
+        name: "storage.root",
+        set: |config, value| {
+            config.argon_fs.fs_filesystem_config.storage_root = parse(value)?;
+            Ok(())
+        },
+        get: |config| {
+            config
+                .argon_fs
+                .fs_filesystem_config
+                .storage_root
+                .display()
+                .to_string()
+        },
+    },
+    ConfigKey {
+        name: "storage.read_pool_threads",
+        set: |config, value| {
+            config
+                .argon_fs
+                .fs_filesystem_config
+                .fs_read_pool_thread_count = parse(value)?;
+            Ok(())
+        },
+        get: |config| {
+            config
+                .argon_fs
+                .fs_filesystem_config
+                .fs_read_pool_thread_count
+                .to_string()
+        },
+    },
+    ConfigKey {
+        name: "storage.worker_pool_threads",
+        set: |config, value| {
+            config.argon_fs.worker_pool_thread_count = parse(value)?;
+            Ok(())
+        },
+        get: |config| config.argon_fs.worker_pool_thread_count.to_string(),
+    },
+    ConfigKey {
+        name: "block_cache.page_size",
+        set: |config, value| {
+            config.block_cache_size = Some(config.block_cache_size()?);
+            config.argon_fs.block_cache_page_size = parse(value)?;
+            Ok(())
+        },
+        get: |config| config.argon_fs.block_cache_page_size.to_string(),
+    },
+    ConfigKey {
+        name: "block_cache.size",
+        set: |config, value| {
+            config.block_cache_size = Some(parse(value)?);
+            Ok(())
+        },
+        get: |config| {
+            config
+                .block_cache_size()
+                .map_or_else(|msg| msg, |size| size.to_string())
+        },
+    },
+    ConfigKey {
+        name: "commit_log.sync_policy",
+        set: |config, value| {
+            let interval = config.commit_log_sync_interval();
+            config.argon_fs.commit_log_config.sync_policy = match value.trim() {
+                "per_write" => CommitLogSyncPolicy::PerWrite,
+                "group" => CommitLogSyncPolicy::Group { window: interval },
+                "periodic" => CommitLogSyncPolicy::Periodic { interval },
+                _ => return Err("expected per_write, group or periodic".to_string()),
+            };
+            Ok(())
+        },
+        get: |config| match config.argon_fs.commit_log_config.sync_policy {
+            CommitLogSyncPolicy::PerWrite => "per_write".to_string(),
+            CommitLogSyncPolicy::Group { .. } => "group".to_string(),
+            CommitLogSyncPolicy::Periodic { .. } => "periodic".to_string(),
+        },
+    },
+    ConfigKey {
+        name: "commit_log.sync_interval_ms",
+        set: |config, value| {
+            config.commit_log_sync_interval = Some(Duration::from_millis(parse(value)?));
+            Ok(())
+        },
+        get: |config| config.commit_log_sync_interval().as_millis().to_string(),
+    },
+    ConfigKey {
+        name: "commit_log.segment_size",
+        set: |config, value| {
+            config.argon_fs.commit_log_config.segment_size = parse(value)?;
+            Ok(())
+        },
+        get: |config| config.argon_fs.commit_log_config.segment_size.to_string(),
+    },
+    ConfigKey {
+        name: "kv.memtable_size",
+        set: |config, value| {
+            config.kv.memtable_size = parse(value)?;
+            Ok(())
+        },
+        get: |config| config.kv.memtable_size.to_string(),
+    },
+    ConfigKey {
+        name: "kv.mutation_max_size",
+        set: |config, value| {
+            config.kv.mutation_max_size = parse(value)?;
+            Ok(())
+        },
+        get: |config| config.kv.mutation_max_size.to_string(),
+    },
+    ConfigKey {
+        name: "kv.object_id_lease_size",
+        set: |config, value| {
+            config.kv.object_id_lease_size = parse(value)?;
+            Ok(())
+        },
+        get: |config| config.kv.object_id_lease_size.to_string(),
+    },
+    ConfigKey {
+        name: "kv.write_buffer_size",
+        set: |config, value| {
+            config.kv.write_buffer_size = parse(value)?;
+            Ok(())
+        },
+        get: |config| config.kv.write_buffer_size.to_string(),
+    },
+    ConfigKey {
+        name: "kv.memtable_slowdown_count",
+        set: |config, value| {
+            config.kv.memtable_slowdown_count = parse(value)?;
+            Ok(())
+        },
+        get: |config| config.kv.memtable_slowdown_count.to_string(),
+    },
+    ConfigKey {
+        name: "kv.memtable_stop_count",
+        set: |config, value| {
+            config.kv.memtable_stop_count = parse(value)?;
+            Ok(())
+        },
+        get: |config| config.kv.memtable_stop_count.to_string(),
+    },
+    ConfigKey {
+        name: "kv.level0_slowdown_count",
+        set: |config, value| {
+            config.kv.level0_slowdown_count = parse(value)?;
+            Ok(())
+        },
+        get: |config| config.kv.level0_slowdown_count.to_string(),
+    },
+    ConfigKey {
+        name: "kv.level0_stop_count",
+        set: |config, value| {
+            config.kv.level0_stop_count = parse(value)?;
+            Ok(())
+        },
+        get: |config| config.kv.level0_stop_count.to_string(),
+    },
+    ConfigKey {
+        name: "kv.write_slowdown_delay_ms",
+        set: |config, value| {
+            config.kv.write_slowdown_delay = Duration::from_millis(parse(value)?);
+            Ok(())
+        },
+        get: |config| config.kv.write_slowdown_delay.as_millis().to_string(),
+    },
+    ConfigKey {
+        name: "kv.write_stall_timeout_ms",
+        set: |config, value| {
+            config.kv.write_stall_timeout = Duration::from_millis(parse(value)?);
+            Ok(())
+        },
+        get: |config| config.kv.write_stall_timeout.as_millis().to_string(),
+    },
+    ConfigKey {
+        name: "compaction.workers",
+        set: |config, value| {
+            config.compactor.worker_count = parse(value)?;
+            Ok(())
+        },
+        get: |config| config.compactor.worker_count.to_string(),
+    },
+    ConfigKey {
+        name: "compaction.scan_interval_seconds",
+        set: |config, value| {
+            config.compactor.scan_interval = Duration::from_secs(parse(value)?);
+            Ok(())
+        },
+        get: |config| config.compactor.scan_interval.as_secs().to_string(),
+    },
+    ConfigKey {
+        name: "compaction.write_rate_limit",
+        set: |config, value| {
+            let write_rate_limit: u64 = parse(value)?;
+            config.compactor.write_rate_limit = (write_rate_limit > 0).then_some(write_rate_limit);
+            Ok(())
+        },
+        get: |config| config.compactor.write_rate_limit.unwrap_or(0).to_string(),
+    },
+    ConfigKey {
+        name: "compaction.tombstone_gc_grace_period_seconds",
+        set: |config, value| {
+            config.compactor.tombstone_gc_grace_period = Duration::from_secs(parse(value)?);
+            Ok(())
+        },
+        get: |config| {
+            config
+                .compactor
+                .tombstone_gc_grace_period
+                .as_secs()
+                .to_string()
+        },
+    },
+    ConfigKey {
+        name: "compaction.size_tiered.level_file_threshold",
+        set: |config, value| {
+            config
+                .compactor
+                .strategy_config
+                .size_tiered
+                .level_file_threshold = parse(value)?;
+            Ok(())
+        },
+        get: |config| {
+            config
+                .compactor
+                .strategy_config
+                .size_tiered
+                .level_file_threshold
+                .to_string()
+        },
+    },
+    ConfigKey {
+        name: "compaction.leveled.level0_file_threshold",
+        set: |config, value| {
+            config
+                .compactor
+                .strategy_config
+                .leveled
+                .level0_file_threshold = parse(value)?;
+            Ok(())
+        },
+        get: |config| {
+            config
+                .compactor
+                .strategy_config
+                .leveled
+                .level0_file_threshold
+                .to_string()
+        },
+    },
+    ConfigKey {
+        name: "compaction.leveled.level_base_size",
+        set: |config, value| {
+            config.compactor.strategy_config.leveled.level_base_size = parse(value)?;
+            Ok(())
+        },
+        get: |config| {
+            config
+                .compactor
+                .strategy_config
+                .leveled
+                .level_base_size
+                .to_string()
+        },
+    },
+    ConfigKey {
+        name: "compaction.leveled.max_file_size",
+        set: |config, value| {
+            config.compactor.strategy_config.leveled.max_file_size = parse(value)?;
+            Ok(())
+        },
+        get: |config| {
+            config
+                .compactor
+                .strategy_config
+                .leveled
+                .max_file_size
+                .to_string()
+        },
+    },
+    ConfigKey {
+        name: "compaction.time_window.window_file_threshold",
+        set: |config, value| {
+            config.compactor.strategy_config.time_window_file_threshold = parse(value)?;
+            Ok(())
+        },
+        get: |config| {
+            config
+                .compactor
+                .strategy_config
+                .time_window_file_threshold
+                .to_string()
+        },
+    },
+];
+
+fn parse<T: FromStr>(value: &str) -> Result<T, String>
+where
+    T::Err: ToString,
+{
+    value.trim().parse().map_err(|e: T::Err| e.to_string())
+}
+
+fn sync_policy_interval(sync_policy: CommitLogSyncPolicy) -> Option<Duration> {
+    match sync_policy {
+        CommitLogSyncPolicy::PerWrite => None,
+        CommitLogSyncPolicy::Group { window } => Some(window),
+        CommitLogSyncPolicy::Periodic { interval } => Some(interval),
+    }
+}
+
+fn env_var_name(key: &str) -> String {
+    format!("{}{}", ENV_PREFIX, key.replace('.', "_").to_uppercase())
+}
+
+/**
+ * Returns flag names, with leading dashes, and their values. Both `--key value` and `--key=value`
+ * are accepted.
+ */
+pub(super) fn parse_args(args: &[String]) -> CriticalResult<Vec<(String, String)>> {
+    let mut values = vec![];
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            return Err(CriticalError::from_msg(format!(
+                "unexpected argument {}",
+                arg
+            )));
+        }
+
+        let (key, value) = match arg.split_once('=') {
+            Some((key, value)) => (key.to_string(), value.to_string()),
+            None => {
+                let value = args.next().ok_or_else(|| {
+                    CriticalError::from_msg(format!("missing value of flag {}", arg))
+                })?;
+                (arg.clone(), value.clone())
+            }
+        };
+
+        values.push((key, value));
+    }
+
+    Ok(values)
+}
+
+/** Flattens tables of the file into dotted keys. */
+pub(super) fn parse_toml(content: &str) -> CriticalResult<Vec<(String, String)>> {
+    let table = content
+        .parse::<toml::Table>()
+        .map_err(|e| CriticalError::from_msg_and_source("malformed config file", e))?;
+
+    let mut values = vec![];
+    flatten_toml_table("", &table, &mut values)?;
+
+    Ok(values)
+}
+
+fn flatten_toml_table(
+    prefix: &str,
+    table: &toml::Table,
+    values: &mut Vec<(String, String)>,
+) -> CriticalResult<()> {
+    for (name, value) in table {
+        let key = format!("{}{}", prefix, name);
+
+        match value {
+            toml::Value::Table(table) => flatten_toml_table(&format!("{}.", key), table, values)?,
+            toml::Value::String(value) => values.push((key, value.clone())),
+            toml::Value::Integer(value) => values.push((key, value.to_string())),
+            toml::Value::Boolean(value) => values.push((key, value.to_string())),
+            _ => {
+                return Err(CriticalError::from_msg(format!(
+                    "config key {} has unsupported value type",
+                    key
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
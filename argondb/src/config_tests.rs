@@ -0,0 +1,202 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use libargondb::CommitLogSyncPolicy;
+
+use crate::config::{ArgonDbConfig, parse_args, parse_toml};
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+fn cli_values(values: &[(&str, &str)]) -> Vec<(String, String)> {
+    values
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: BTreeMap<String, String> = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+
+    move |name| vars.get(name).cloned()
+}
+
+#[test]
+fn test_parse_args() {
+    assert_eq!(
+        parse_args(&args(&["--grpc.port", "1234", "--kv.memtable_size=4096"])).unwrap(),
+        cli_values(&[("--grpc.port", "1234"), ("--kv.memtable_size", "4096")])
+    );
+
+    assert!(parse_args(&args(&["--grpc.port"])).is_err());
+    assert!(parse_args(&args(&["grpc.port", "1234"])).is_err());
+}
+
+#[test]
+fn test_parse_toml_flattens_tables() {
+    let mut values = parse_toml(
+        r#"
+        [grpc]
+        bind_address = "127.0.0.1"
+        port = 1234
+
+        [compaction.leveled]
+        max_file_size = 1024
+        "#,
+    )
+    .unwrap();
+    values.sort();
+
+    assert_eq!(
+        values,
+        cli_values(&[
+            ("compaction.leveled.max_file_size", "1024"),
+            ("grpc.bind_address", "127.0.0.1"),
+            ("grpc.port", "1234"),
+        ])
+    );
+
+    assert!(parse_toml("[grpc]\nport = [1, 2]").is_err());
+    assert!(parse_toml("[grpc").is_err());
+}
+
+#[test]
+fn test_sources_override_each_other() {
+    let file = "[grpc]\nport = 1000\n[kv]\nmemtable_size = 1048576";
+
+    let config = ArgonDbConfig::from_sources(Some(file), env(&[]), vec![]).unwrap();
+    assert_eq!(config.grpc.port, 1000);
+    assert_eq!(config.kv.memtable_size, 1_048_576);
+
+    // Environment overrides the file, command line flags override both
+    let env_vars = env(&[("ARGONDB_GRPC_PORT", "2000")]);
+    let config = ArgonDbConfig::from_sources(Some(file), env_vars, vec![]).unwrap();
+    assert_eq!(config.grpc.port, 2000);
+
+    let env_vars = env(&[("ARGONDB_GRPC_PORT", "2000")]);
+    let config =
+        ArgonDbConfig::from_sources(Some(file), env_vars, cli_values(&[("--grpc.port", "3000")]))
+            .unwrap();
+    assert_eq!(config.grpc.port, 3000);
+    assert_eq!(config.kv.memtable_size, 1_048_576);
+
+    assert!(
+        ArgonDbConfig::from_sources(None, env(&[]), cli_values(&[("--grpc.unknown", "1")]))
+            .is_err()
+    );
+    assert!(
+        ArgonDbConfig::from_sources(None, env(&[]), cli_values(&[("--grpc.port", "x")])).is_err()
+    );
+}
+
+#[test]
+fn test_block_cache_size_independent_of_key_order() {
+    let size_then_page_size = ArgonDbConfig::from_sources(
+        Some("[block_cache]\nsize = 1048576"),
+        env(&[]),
+        cli_values(&[("--block_cache.page_size", "4096")]),
+    )
+    .unwrap();
+
+    let page_size_then_size = ArgonDbConfig::from_sources(
+        Some("[block_cache]\npage_size = 4096"),
+        env(&[("ARGONDB_BLOCK_CACHE_SIZE", "1048576")]),
+        vec![],
+    )
+    .unwrap();
+
+    for config in [size_then_page_size, page_size_then_size] {
+        assert_eq!(config.argon_fs.block_cache_page_size, 4096);
+        assert_eq!(config.argon_fs.block_cache_pages_count, 256);
+    }
+
+    // Cache size is kept when only the page size changes
+    let default_config = ArgonDbConfig::default();
+    let config = ArgonDbConfig::from_sources(
+        None,
+        env(&[]),
+        cli_values(&[("--block_cache.page_size", "4096")]),
+    )
+    .unwrap();
+    assert_eq!(
+        config.argon_fs.block_cache_pages_count * 4096,
+        default_config.argon_fs.block_cache_pages_count
+            * default_config.argon_fs.block_cache_page_size
+    );
+}
+
+#[test]
+fn test_commit_log_sync_interval_independent_of_key_order() {
+    let policy_then_interval = ArgonDbConfig::from_sources(
+        Some("[commit_log]\nsync_policy = \"periodic\""),
+        env(&[]),
+        cli_values(&[("--commit_log.sync_interval_ms", "200")]),
+    )
+    .unwrap();
+
+    let interval_then_policy = ArgonDbConfig::from_sources(
+        Some("[commit_log]\nsync_interval_ms = 200"),
+        env(&[("ARGONDB_COMMIT_LOG_SYNC_POLICY", "periodic")]),
+        vec![],
+    )
+    .unwrap();
+
+    for config in [policy_then_interval, interval_then_policy] {
+        assert!(matches!(
+            config.argon_fs.commit_log_config.sync_policy,
+            CommitLogSyncPolicy::Periodic { interval } if interval == Duration::from_millis(200)
+        ));
+    }
+
+    // Policy switched away from per write starts from the default interval
+    let config = ArgonDbConfig::from_sources(
+        None,
+        env(&[("ARGONDB_COMMIT_LOG_SYNC_POLICY", "per_write")]),
+        cli_values(&[("--commit_log.sync_policy", "group")]),
+    )
+    .unwrap();
+    assert!(matches!(
+        config.argon_fs.commit_log_config.sync_policy,
+        CommitLogSyncPolicy::Group { window } if window == Duration::from_millis(1)
+    ));
+}
+
+#[test]
+fn test_validate() {
+    assert!(ArgonDbConfig::default().validate().is_ok());
+
+    let invalid_values = [
+        ("--grpc.port", "0"),
+        ("--block_cache.page_size", "1000"),
+        ("--block_cache.size", "1"),
+        ("--kv.write_buffer_size", "1"),
+        ("--kv.level0_slowdown_count", "1000"),
+        ("--commit_log.sync_policy", "never"),
+        ("--commit_log.segment_size", "0"),
+        ("--compaction.workers", "0"),
+    ];
+
+    for (key, value) in invalid_values {
+        assert!(
+            ArgonDbConfig::from_sources(None, env(&[]), cli_values(&[(key, value)])).is_err(),
+            "{} = {} accepted",
+            key,
+            value
+        );
+    }
+
+    assert!(
+        ArgonDbConfig::from_sources(
+            None,
+            env(&[]),
+            cli_values(&[
+                ("--commit_log.sync_policy", "periodic"),
+                ("--commit_log.sync_interval_ms", "0"),
+            ]),
+        )
+        .is_err()
+    );
+}
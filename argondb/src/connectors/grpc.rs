@@ -1,6 +1,6 @@
 use std::{
-    collections::HashMap,
-    net::{IpAddr, Ipv4Addr, SocketAddr},
+    collections::{BTreeSet, HashMap},
+    net::SocketAddr,
     str::FromStr,
     sync::Arc,
     time::Duration,
@@ -9,33 +9,57 @@ use std::{
 use async_trait::async_trait;
 use base64::{Engine, prelude::BASE64_STANDARD};
 use libargonconnector_grpc::argondb_service_definition::{
-    self, ColumnDefinition, CreateTableRequest, InsertMutationsRequest, InsertMutationsResponse,
-    ListTablesResponse, MutateRowRequest, PrimaryKeyMarker, ReadRowRequest, ReadRowResponse,
-    ScanTableRequest, ScanTableResponse, ScanTableResponseRow, Table,
-    argon_db_server::ArgonDbServer,
+    self, AlterTableRequest, CellVersion, CellVersions, ColumnDefinition, ColumnRetention,
+    CompactTableRequest, CompactTableResponse, CompactionOptions, CompactionStrategy,
+    ComparisonOperator, CreateTableRequest, CreateTableRequestColumn, CreateTableRequestColumnType,
+    DeleteRangeRequest, DropTableRequest, FlushTableRequest, GetTableStatsRequest,
+    GetTableStatsResponse, GetWriteBufferStatsResponse, InsertMutationsRequest,
+    InsertMutationsResponse, ListTablesResponse, MutateRowRequest, PrimaryKeyMarker,
+    ReadRowRequest, ReadRowResponse, RowFilter, ScanTableRequest, ScanTableResponse,
+    ScanTableResponseRow, SsTableStats, Table, TruncateTableRequest,
+    alter_table_change::Change as AlterTableChangeKind, argon_db_admin_server::ArgonDbAdminServer,
+    argon_db_server::ArgonDbServer, mutation::Mutation as MutationKind,
+    row_filter::Filter as RowFilterKind,
 };
 use libargondb::{
-    ConnectorError, ConnectorHandle, DbCtx,
+    ConnectorError, ConnectorHandle, DbCtx, SSTableCompactorClient,
     kv::{
-        KVColumnFilter, KVColumnValue, KVColumnValueBuilder, KVPrimaryKeyMarker, KVRangeScan,
-        KVRow, KVRowScan, KVTable, KVTableName, KVTableSchema,
+        KVColumnFilter, KVColumnValue, KVColumnValueBuilder, KVCompactionOptions, KVCompareOp,
+        KVPrimaryKeyMarker, KVRangeScan, KVRow, KVRowFilter, KVRowIter, KVRowScan, KVRuntimeError,
+        KVScanDirection, KVTable, KVTableName, KVTableOptions, KVTableSchema, KVVersionFilter,
         column_type::{
-            ColumnTypeBytes, ColumnTypeCode, ColumnTypeText, ColumnTypeU16, ColumnTypeU16Array,
+            ColumnTypeBool, ColumnTypeBytes, ColumnTypeCode, ColumnTypeDeserialize, ColumnTypeF64,
+            ColumnTypeI32, ColumnTypeI64, ColumnTypeText, ColumnTypeTimestamp, ColumnTypeU16,
+            ColumnTypeU16Array, ColumnTypeU32, ColumnTypeU64, ColumnTypeUuid, KVColumnTypeUtils,
         },
-        primary_key::{KVPrimaryKeySchema, PrimaryKeyBuilder},
-        schema::KVColumnSchema,
+        primary_key::{KVPrimaryKeySchema, KVPrimaryKeyUtils, PrimaryKeyBuilder},
+        schema::{KVColumnRetention, KVColumnSchema},
     },
 };
 use prost_types::{ListValue, Value, value::Kind};
-use tokio::{runtime::Runtime, sync::oneshot, task::JoinHandle};
-use tonic::{Request, Response, Status, transport::Server};
-
-use crate::ops::{CreateTableOp, CreateTableOpColumn, CreateTableOpError, InsertIntoOp};
+use tokio::{
+    runtime::Runtime,
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Code, Request, Response, Status, transport::Server};
+use tonic_types::{ErrorDetails, StatusExt};
+
+use crate::config::GrpcConfig;
+use crate::ops::{
+    AlterTableOp, AlterTableOpColumnChange, AlterTableOpError, CreateTableOp, CreateTableOpColumn,
+    CreateTableOpError, DeleteRangeOp, DeleteRangeOpBound, DeleteRangeOpError, DropTableOp,
+    DropTableOpError, InsertIntoOp, InsertOpError, MutateRowOp, MutateRowOpError,
+    MutateRowOpMutation, TruncateTableOp, TruncateTableOpError,
+};
 
-pub fn init_connector_grpc(db_ctx: Arc<DbCtx>) -> Result<Box<dyn ConnectorHandle>, ConnectorError> {
-    const DEFAULT_PORT: u16 = 50051;
-    const IP_ADDR: IpAddr = IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0));
-    const SOCKET_ADDR: SocketAddr = SocketAddr::new(IP_ADDR, DEFAULT_PORT);
+pub fn init_connector_grpc(
+    db_ctx: Arc<DbCtx>,
+    compactor: SSTableCompactorClient,
+    config: &GrpcConfig,
+) -> Result<Box<dyn ConnectorHandle>, ConnectorError> {
+    let socket_addr = SocketAddr::new(config.bind_address, config.port);
 
     let (tx, rx) = oneshot::channel::<()>();
 
@@ -69,7 +93,7 @@ pub fn init_connector_grpc(db_ctx: Arc<DbCtx>) -> Result<Box<dyn ConnectorHandle
 
     println!(
         "gprc connector - starting server on address {}",
-        SOCKET_ADDR
+        socket_addr
     );
 
     let reflection_service = tonic_reflection::server::Builder::configure()
@@ -82,8 +106,11 @@ pub fn init_connector_grpc(db_ctx: Arc<DbCtx>) -> Result<Box<dyn ConnectorHandle
         Server::builder()
             // .layer(layer)
             .add_service(reflection_service)
-            .add_service(ArgonDbServer::new(ArgonDbHandlers::new(db_ctx)))
-            .serve_with_shutdown(SOCKET_ADDR, shutdown_fn())
+            .add_service(ArgonDbServer::new(ArgonDbHandlers::new(db_ctx.clone())))
+            .add_service(ArgonDbAdminServer::new(ArgonDbAdminHandlers::new(
+                db_ctx, compactor,
+            )))
+            .serve_with_shutdown(socket_addr, shutdown_fn())
             .await
             .map_err(|err| ConnectorError::UnexpectedError(Arc::new(Box::from(err))))
     });
@@ -130,37 +157,40 @@ impl argondb_service_definition::argon_db_server::ArgonDb for ArgonDbHandlers {
     ) -> Result<Response<Table>, Status> {
         let req = request.get_ref();
 
+        let default_retention = req
+            .default_retention
+            .as_ref()
+            .map(GrpcHandlerUtils::column_retention)
+            .transpose()?
+            .unwrap_or_default();
+
+        let mut columns = vec![];
+        for col in &req.columns {
+            columns.push(GrpcHandlerUtils::create_table_op_column(
+                col,
+                default_retention,
+            )?);
+        }
+
         let op = CreateTableOp {
             table_name: req.table_name.clone(),
-            columns: req
-                .columns
-                .iter()
-                .map(|col| CreateTableOpColumn {
-                    column_name: col.column_name.clone(),
-                    column_type: match col.column_type {
-                        0 => ColumnTypeCode::Bytes,
-                        1 => ColumnTypeCode::Text,
-                        2 => ColumnTypeCode::U16,
-                        3 => ColumnTypeCode::U16Array,
-                        _ => unreachable!(),
-                    },
-                })
-                .collect(),
+            columns,
             primary_key: req.primary_key.clone(),
+            table_options: KVTableOptions {
+                compaction: req
+                    .compaction
+                    .as_ref()
+                    .map(GrpcHandlerUtils::compaction_options)
+                    .transpose()?
+                    .unwrap_or_default(),
+                default_retention,
+            },
         };
 
         match op.execute(&self.db_ctx).await {
-            Ok(table) => Ok(tonic::Response::new(Table {
-                table_name: table.table_name.to_string(),
-                columns: table
-                    .table_schema
-                    .columns
-                    .iter()
-                    .map(|col| ColumnDefinition {
-                        column_name: col.column_name.clone(),
-                    })
-                    .collect(),
-            })),
+            Ok(table) => Ok(tonic::Response::new(GrpcHandlerUtils::table_definition(
+                &table,
+            ))),
             Err(e) => match e {
                 CreateTableOpError::InvalidTableName => {
                     Err(Status::invalid_argument("invalid table name"))
@@ -178,116 +208,239 @@ impl argondb_service_definition::argon_db_server::ArgonDb for ArgonDbHandlers {
                     Err(Status::invalid_argument("table max column count exceeded"))
                 }
                 CreateTableOpError::SchemaError => Err(Status::invalid_argument("schema error")),
+                CreateTableOpError::InvalidRetention => Err(Status::invalid_argument(
+                    "column min versions exceeds max versions",
+                )),
+                CreateTableOpError::InvalidTableOptions => Err(Status::invalid_argument(
+                    "time window compaction requires non-zero window",
+                )),
+                CreateTableOpError::InvalidDefaultValue => {
+                    Err(Status::invalid_argument("invalid default value"))
+                }
+                CreateTableOpError::PrimaryKeyDefaultValue => Err(Status::invalid_argument(
+                    "primary key columns cannot have a default value",
+                )),
+                CreateTableOpError::PersistFailed => Err(Status::internal("create table failed")),
             },
         }
     }
 
+    async fn alter_table(
+        &self,
+        request: Request<AlterTableRequest>,
+    ) -> Result<Response<Table>, Status> {
+        let req = request.get_ref();
+
+        // Added columns without their own retention get the one the table was created with
+        let default_retention = GrpcHandlerUtils::lookup_table(&self.db_ctx, &req.table_name)?
+            .table_options
+            .default_retention;
+
+        let mut changes = vec![];
+        for change in &req.changes {
+            let Some(change) = &change.change else {
+                return Err(Status::invalid_argument("empty table change"));
+            };
+
+            changes.push(match change {
+                AlterTableChangeKind::AddColumn(col) => AlterTableOpColumnChange::Add(
+                    GrpcHandlerUtils::create_table_op_column(col, default_retention)?,
+                ),
+                AlterTableChangeKind::DropColumn(drop_column) => AlterTableOpColumnChange::Drop {
+                    column_name: drop_column.column_name.clone(),
+                },
+                AlterTableChangeKind::RenameColumn(rename_column) => {
+                    AlterTableOpColumnChange::Rename {
+                        column_name: rename_column.column_name.clone(),
+                        new_column_name: rename_column.new_column_name.clone(),
+                    }
+                }
+            });
+        }
+
+        let op = AlterTableOp {
+            table_name: req.table_name.clone(),
+            changes,
+        };
+
+        match op.execute(&self.db_ctx).await {
+            Ok(table) => Ok(tonic::Response::new(GrpcHandlerUtils::table_definition(
+                &table,
+            ))),
+            Err(e) => Err(match e {
+                AlterTableOpError::InvalidTableName => {
+                    Status::invalid_argument("invalid table name")
+                }
+                AlterTableOpError::TableNotFound => {
+                    Status::not_found(format!("table {} does not exist", req.table_name))
+                }
+                AlterTableOpError::ColumnNotFound => Status::invalid_argument("column not found"),
+                AlterTableOpError::ColumnAlreadyExists => {
+                    Status::invalid_argument("column already exists")
+                }
+                AlterTableOpError::PrimaryKeyColumnDrop => {
+                    Status::invalid_argument("primary key columns cannot be dropped")
+                }
+                AlterTableOpError::PrimaryKeyChanged => {
+                    Status::invalid_argument("primary key columns cannot be changed")
+                }
+                AlterTableOpError::InvalidRetention => {
+                    Status::invalid_argument("column min versions exceeds max versions")
+                }
+                AlterTableOpError::InvalidDefaultValue => {
+                    Status::invalid_argument("invalid default value")
+                }
+                AlterTableOpError::NotNullWithoutDefault => {
+                    Status::invalid_argument("added not null column requires a default value")
+                }
+                AlterTableOpError::TooManyColumns => {
+                    Status::invalid_argument("table max column count exceeded")
+                }
+                AlterTableOpError::SchemaError => Status::invalid_argument("schema error"),
+                AlterTableOpError::PersistFailed => {
+                    Status::internal("failed to persist table schema")
+                }
+            }),
+        }
+    }
+
+    async fn drop_table(&self, request: Request<DropTableRequest>) -> Result<Response<()>, Status> {
+        let req = request.get_ref();
+
+        DropTableOp {
+            table_name: req.table_name.clone(),
+        }
+        .execute(&self.db_ctx)
+        .await
+        .map_err(|e| match e {
+            DropTableOpError::InvalidTableName => Status::invalid_argument("invalid table name"),
+            DropTableOpError::TableNotFound => {
+                Status::not_found(format!("table {} does not exist", req.table_name))
+            }
+            DropTableOpError::SystemTable => {
+                Status::invalid_argument("system tables cannot be dropped")
+            }
+            DropTableOpError::PersistFailed => Status::internal("failed to drop table"),
+        })?;
+
+        Ok(tonic::Response::new(()))
+    }
+
+    async fn truncate_table(
+        &self,
+        request: Request<TruncateTableRequest>,
+    ) -> Result<Response<()>, Status> {
+        let req = request.get_ref();
+
+        TruncateTableOp {
+            table_name: req.table_name.clone(),
+        }
+        .execute(&self.db_ctx)
+        .await
+        .map_err(|e| match e {
+            TruncateTableOpError::InvalidTableName => {
+                Status::invalid_argument("invalid table name")
+            }
+            TruncateTableOpError::TableNotFound => {
+                Status::not_found(format!("table {} does not exist", req.table_name))
+            }
+            TruncateTableOpError::SystemTable => {
+                Status::invalid_argument("system tables cannot be truncated")
+            }
+            TruncateTableOpError::SchemaError => Status::internal("schema error"),
+            TruncateTableOpError::PersistFailed => Status::internal("failed to truncate table"),
+        })?;
+
+        Ok(tonic::Response::new(()))
+    }
+
     async fn list_tables(&self, _: Request<()>) -> Result<Response<ListTablesResponse>, Status> {
         let tables = self.db_ctx.catalog.list_tables();
 
         Ok(tonic::Response::new(ListTablesResponse {
             tables: tables
-                .into_iter()
-                .map(|table| Table {
-                    table_name: table.table_name.to_string(),
-                    columns: table
-                        .table_schema
-                        .columns
-                        .iter()
-                        .map(|col| ColumnDefinition {
-                            column_name: col.column_name.clone(),
-                        })
-                        .collect(),
-                })
+                .iter()
+                .map(|table| GrpcHandlerUtils::table_definition(table))
                 .collect(),
         }))
     }
 
+    type ScanTableStream = ReceiverStream<Result<ScanTableResponse, Status>>;
+
     async fn scan_table(
         &self,
         request: Request<ScanTableRequest>,
-    ) -> Result<Response<ScanTableResponse>, Status> {
+    ) -> Result<Response<Self::ScanTableStream>, Status> {
         let req = request.get_ref();
 
-        let table_name = KVTableName::from_str(&req.table_name)
-            .map_err(|_| Status::invalid_argument("invalid table name"))?;
+        let table = GrpcHandlerUtils::lookup_table(&self.db_ctx, &req.table_name)?;
 
-        let table = self
-            .db_ctx
-            .catalog
-            .lookup_table_by_name(&table_name)
-            .ok_or(Status::not_found(format!(
-                "table {} does not exist",
-                table_name
-            )))?;
-
-        let pk_schema = KVPrimaryKeySchema::from_table_schema(&table.table_schema);
-
-        let from: KVPrimaryKeyMarker = req
-            .from
-            .clone()
-            .map(|PrimaryKeyMarker { values }| {
-                let mut pk_builder = PrimaryKeyBuilder::new(&pk_schema);
-
-                for column_id in &table.table_schema.primary_key {
-                    let column_schema = table.table_schema.lookup_by_column_id(*column_id).unwrap();
+        if req.limit == Some(0) {
+            return Err(Status::invalid_argument("limit must be positive"));
+        }
 
-                    let value = values.get(&column_schema.column_name).unwrap();
+        let schema = &table.table_schema();
 
-                    let pk_col_val = GrpcHandlerUtils::value_to_column_value(value).unwrap();
-                    pk_builder.add_value(&pk_col_val.serialize().unwrap());
-                }
-                let primary_key = pk_builder.build();
+        let direction = if req.reverse {
+            KVScanDirection::Reverse
+        } else {
+            KVScanDirection::Forward
+        };
 
-                KVPrimaryKeyMarker::Key(primary_key)
-            })
+        let mut from = req
+            .from
+            .as_ref()
+            .map(|marker| GrpcHandlerUtils::marker_to_bound(schema, marker))
+            .transpose()?
             .unwrap_or(KVPrimaryKeyMarker::Start);
-
-        let to: KVPrimaryKeyMarker = req
+        let mut to = req
             .to
-            .clone()
-            .map(|PrimaryKeyMarker { values }| {
-                let mut pk_builder = PrimaryKeyBuilder::new(&pk_schema);
+            .as_ref()
+            .map(|marker| GrpcHandlerUtils::marker_to_bound(schema, marker))
+            .transpose()?
+            .unwrap_or(KVPrimaryKeyMarker::End);
 
-                for column_id in &table.table_schema.primary_key {
-                    let column_schema = table.table_schema.lookup_by_column_id(*column_id).unwrap();
+        // Resume tokens are exclusive bounds on the last returned row
+        if let Some(resume_token) = &req.resume_token {
+            let resume_bound = GrpcHandlerUtils::marker_to_bound(schema, resume_token)?;
 
-                    let value = values.get(&column_schema.column_name).unwrap();
+            match direction {
+                KVScanDirection::Forward => from = resume_bound,
+                KVScanDirection::Reverse => to = resume_bound,
+            }
+        }
 
-                    let pk_col_val = GrpcHandlerUtils::value_to_column_value(value).unwrap();
-                    pk_builder.add_value(&pk_col_val.serialize().unwrap());
-                }
-                let primary_key = pk_builder.build();
+        let version_filter =
+            GrpcHandlerUtils::version_filter(req.read_timestamp, req.max_versions)?;
 
-                KVPrimaryKeyMarker::Key(primary_key)
-            })
-            .unwrap_or(KVPrimaryKeyMarker::End);
+        let column_filter = GrpcHandlerUtils::column_filter(schema, &req.columns)?;
 
-        let map_scan_err = |e: libargondb::kv::KVRuntimeError| {
-            println!("scan failed - {}", e);
+        let mut scan_op = KVRangeScan::new(KVTableSchema::clone(schema), from, to, column_filter)
+            .with_version_filter(version_filter)
+            .with_direction(direction);
 
-            Status::internal("scan failed")
-        };
+        if let Some(filter) = &req.filter {
+            scan_op = scan_op.with_row_filter(GrpcHandlerUtils::row_filter(schema, filter)?);
+        }
 
-        let mut scan = table
-            .scan(KVRangeScan::new(
-                table.table_schema.clone(),
-                from,
-                to,
-                KVColumnFilter::All,
-            ))
+        let scan = table
+            .scan(scan_op)
             .await
-            .map_err(map_scan_err)?;
+            .map_err(GrpcHandlerUtils::scan_err)?;
 
-        let mut rows = Vec::<ScanTableResponseRow>::new();
+        let (sender, receiver) = mpsc::channel(SCAN_TABLE_STREAM_BUFFER);
 
-        while let Some(row) = scan.next_row().await.map_err(map_scan_err)? {
-            rows.push(ScanTableResponseRow {
-                values: GrpcHandlerUtils::row_to_values_map(&table.table_schema, row),
-            });
-        }
+        tokio::spawn(GrpcHandlerUtils::stream_scan(
+            table.clone(),
+            scan,
+            ScanTableStreamOptions {
+                limit: req.limit.map_or(usize::MAX, |limit| limit as usize),
+                with_versions: req.max_versions.is_some(),
+            },
+            sender,
+        ));
 
-        Ok(tonic::Response::new(ScanTableResponse { rows }))
+        Ok(tonic::Response::new(ReceiverStream::new(receiver)))
     }
 
     async fn insert_mutations(
@@ -296,38 +449,36 @@ impl argondb_service_definition::argon_db_server::ArgonDb for ArgonDbHandlers {
     ) -> Result<Response<InsertMutationsResponse>, Status> {
         let req = request.get_ref();
 
-        let table_name = KVTableName::from_str(&req.table_name)
-            .map_err(|_| Status::invalid_argument("invalid table name"))?;
-
-        let table = self
-            .db_ctx
-            .catalog
-            .lookup_table_by_name(&table_name)
-            .ok_or(Status::not_found(format!(
-                "table {} does not exist",
-                table_name
-            )))?;
+        let table = GrpcHandlerUtils::lookup_table(&self.db_ctx, &req.table_name)?;
 
+        let table_schema = table.table_schema();
         let mut values = vec![];
 
         for (key, val) in req.values.iter() {
+            let column_schema = GrpcHandlerUtils::column_schema(&table_schema, key)?;
+
             values.push((
                 key.to_string(),
-                GrpcHandlerUtils::value_to_column_value(val)
-                    .map_err(|_| Status::invalid_argument("cannot map value to column"))?,
+                GrpcHandlerUtils::value_to_column_value(column_schema, val)?,
             ))
         }
 
         InsertIntoOp {
-            table_name: table_name.to_string(),
+            table_name: req.table_name.clone(),
             values,
         }
         .execute(&self.db_ctx)
         .await
-        .map_err(|e| {
-            println!("insert failed - {:?}", e);
+        .map_err(|e| match e {
+            InsertOpError::WriteStalled => GrpcHandlerUtils::write_stalled_status(),
+            InsertOpError::MissingNotNullColumn(column_name) => {
+                GrpcHandlerUtils::invalid_column_value(&column_name, "column is not null")
+            }
+            _ => {
+                println!("insert failed - {:?}", e);
 
-            Status::internal("insert failed")
+                Status::internal("insert failed")
+            }
         })?;
 
         Ok(tonic::Response::new(InsertMutationsResponse {}))
@@ -339,72 +490,443 @@ impl argondb_service_definition::argon_db_server::ArgonDb for ArgonDbHandlers {
     ) -> Result<Response<ReadRowResponse>, Status> {
         let req = request.get_ref();
 
-        let table_name = KVTableName::from_str(&req.table_name)
-            .map_err(|_| Status::invalid_argument("invalid table name"))?;
-
-        let table = self
-            .db_ctx
-            .catalog
-            .lookup_table_by_name(&table_name)
-            .ok_or(Status::not_found(format!(
-                "table {} does not exist",
-                table_name
-            )))?;
+        let table = GrpcHandlerUtils::lookup_table(&self.db_ctx, &req.table_name)?;
 
-        let pk_schema = KVPrimaryKeySchema::from_table_schema(&table.table_schema);
+        let table_schema = table.table_schema();
+        let pk_schema = KVPrimaryKeySchema::from_table_schema(&table_schema);
 
         let values = req.primary_key_values.clone();
         let mut pk_builder = PrimaryKeyBuilder::new(&pk_schema);
 
-        for column_id in &table.table_schema.primary_key {
-            let column_schema = table.table_schema.lookup_by_column_id(*column_id).unwrap();
+        for column_id in &table_schema.primary_key {
+            let column_schema = table_schema.lookup_by_column_id(*column_id).unwrap();
 
-            let value = values.get(&column_schema.column_name).unwrap();
+            let value = values.get(&column_schema.column_name).ok_or_else(|| {
+                GrpcHandlerUtils::invalid_column_value(
+                    &column_schema.column_name,
+                    "missing primary key value",
+                )
+            })?;
 
-            let pk_col_val = GrpcHandlerUtils::value_to_column_value(value).unwrap();
-            pk_builder.add_value(&pk_col_val.serialize().unwrap());
+            pk_builder.add_value(&GrpcHandlerUtils::value_to_bytes(column_schema, value)?);
         }
-        let primary_key = pk_builder.build();
+        let primary_key = pk_builder
+            .build()
+            .map_err(|_| Status::invalid_argument("missing primary key value"))?;
 
-        let map_scan_err = |e: libargondb::kv::KVRuntimeError| {
-            println!("scan failed - {}", e);
+        let version_filter =
+            GrpcHandlerUtils::version_filter(req.read_timestamp, req.max_versions)?;
 
-            Status::internal("scan failed")
-        };
+        let column_filter = GrpcHandlerUtils::column_filter(&table_schema, &req.columns)?;
 
         let mut scan = table
-            .scan(KVRowScan::new(
-                table.table_schema.clone(),
-                primary_key,
-                KVColumnFilter::All,
-            ))
+            .scan(
+                KVRowScan::new(
+                    KVTableSchema::clone(&table_schema),
+                    primary_key,
+                    column_filter,
+                )
+                .with_version_filter(version_filter),
+            )
             .await
-            .map_err(map_scan_err)?;
-
-        let maybe_row = scan.next_row().await.map_err(map_scan_err)?;
-
-        Ok(tonic::Response::new(ReadRowResponse {
-            values: if let Some(row) = maybe_row {
-                GrpcHandlerUtils::row_to_values_map(&table.table_schema, row)
-            } else {
-                HashMap::new()
+            .map_err(GrpcHandlerUtils::scan_err)?;
+
+        let maybe_row = scan.next_row().await.map_err(GrpcHandlerUtils::scan_err)?;
+
+        Ok(tonic::Response::new(match maybe_row {
+            Some(row) => ReadRowResponse {
+                versions: if req.max_versions.is_some() {
+                    GrpcHandlerUtils::row_to_versions_map(&table_schema, &row)
+                } else {
+                    HashMap::new()
+                },
+                values: GrpcHandlerUtils::row_to_values_map(&table_schema, row),
+            },
+            None => ReadRowResponse {
+                values: HashMap::new(),
+                versions: HashMap::new(),
             },
         }))
     }
 
     async fn mutate_row(&self, request: Request<MutateRowRequest>) -> Result<Response<()>, Status> {
-        unimplemented!()
+        let req = request.get_ref();
+        let table = GrpcHandlerUtils::lookup_table(&self.db_ctx, &req.table_name)?;
+        let table_schema = table.table_schema();
+
+        let mut primary_key_values = vec![];
+        for (key, val) in req.primary_key_values.iter() {
+            let column_schema = GrpcHandlerUtils::column_schema(&table_schema, key)?;
+
+            primary_key_values.push((
+                key.to_string(),
+                GrpcHandlerUtils::value_to_column_value(column_schema, val)?,
+            ));
+        }
+
+        let mut mutations = vec![];
+        for mutation in &req.mutations {
+            let Some(mutation) = &mutation.mutation else {
+                return Err(Status::invalid_argument("empty mutation"));
+            };
+
+            mutations.push(GrpcHandlerUtils::mutation_to_op_mutation(
+                &table_schema,
+                mutation,
+            )?);
+        }
+
+        MutateRowOp {
+            table_name: req.table_name.clone(),
+            primary_key_values,
+            mutations,
+        }
+        .execute(&self.db_ctx)
+        .await
+        .map_err(|e| match e {
+            MutateRowOpError::InvalidTableName => Status::invalid_argument("invalid table name"),
+            MutateRowOpError::TableNotFound => {
+                Status::not_found(format!("table {} does not exist", req.table_name))
+            }
+            MutateRowOpError::InvalidColumnName => Status::invalid_argument("invalid column name"),
+            MutateRowOpError::MissingPrimaryKey => {
+                Status::invalid_argument("missing primary key value")
+            }
+            MutateRowOpError::PrimaryKeyColumnMutation => {
+                Status::invalid_argument("primary key columns cannot be mutated")
+            }
+            MutateRowOpError::InvalidValue => Status::invalid_argument("invalid value"),
+            MutateRowOpError::MissingNotNullColumn(column_name) => {
+                GrpcHandlerUtils::invalid_column_value(&column_name, "column is not null")
+            }
+            MutateRowOpError::WriteStalled => GrpcHandlerUtils::write_stalled_status(),
+            MutateRowOpError::MutationFailed => Status::internal("mutate row failed"),
+        })?;
+
+        Ok(tonic::Response::new(()))
+    }
+
+    async fn delete_range(
+        &self,
+        request: Request<DeleteRangeRequest>,
+    ) -> Result<Response<()>, Status> {
+        let req = request.get_ref();
+        let table = GrpcHandlerUtils::lookup_table(&self.db_ctx, &req.table_name)?;
+        let table_schema = table.table_schema();
+
+        let from = req
+            .from
+            .as_ref()
+            .map(|marker| GrpcHandlerUtils::marker_to_op_bound(&table_schema, marker))
+            .transpose()?;
+        let to = req
+            .to
+            .as_ref()
+            .map(|marker| GrpcHandlerUtils::marker_to_op_bound(&table_schema, marker))
+            .transpose()?;
+
+        DeleteRangeOp {
+            table_name: req.table_name.clone(),
+            from,
+            to,
+            timestamp: GrpcHandlerUtils::timestamp(req.timestamp)?,
+        }
+        .execute(&self.db_ctx)
+        .await
+        .map_err(|e| match e {
+            DeleteRangeOpError::InvalidTableName => Status::invalid_argument("invalid table name"),
+            DeleteRangeOpError::TableNotFound => {
+                Status::not_found(format!("table {} does not exist", req.table_name))
+            }
+            DeleteRangeOpError::InvalidColumnName
+            | DeleteRangeOpError::MissingPrimaryKey
+            | DeleteRangeOpError::InvalidValue => GrpcHandlerUtils::range_bound_err(e),
+            DeleteRangeOpError::WriteStalled => GrpcHandlerUtils::write_stalled_status(),
+            DeleteRangeOpError::DeleteFailed => Status::internal("delete range failed"),
+        })?;
+
+        Ok(tonic::Response::new(()))
+    }
+}
+
+pub struct ArgonDbAdminHandlers {
+    db_ctx: Arc<DbCtx>,
+    compactor: SSTableCompactorClient,
+}
+
+impl ArgonDbAdminHandlers {
+    pub fn new(db_ctx: Arc<DbCtx>, compactor: SSTableCompactorClient) -> Self {
+        Self { db_ctx, compactor }
+    }
+}
+
+#[async_trait]
+impl argondb_service_definition::argon_db_admin_server::ArgonDbAdmin for ArgonDbAdminHandlers {
+    async fn flush_table(
+        &self,
+        request: Request<FlushTableRequest>,
+    ) -> Result<Response<()>, Status> {
+        let table = GrpcHandlerUtils::lookup_table(&self.db_ctx, &request.get_ref().table_name)?;
+
+        table.request_current_memtable_flush().await.map_err(|e| {
+            println!("flush failed - {}", e);
+
+            Status::internal("flush failed")
+        })?;
+
+        Ok(tonic::Response::new(()))
+    }
+
+    async fn compact_table(
+        &self,
+        request: Request<CompactTableRequest>,
+    ) -> Result<Response<CompactTableResponse>, Status> {
+        let req = request.get_ref();
+        let table = GrpcHandlerUtils::lookup_table(&self.db_ctx, &req.table_name)?;
+        let table_schema = table.table_schema();
+
+        let from = match &req.from {
+            Some(marker) => GrpcHandlerUtils::marker_to_bound(&table_schema, marker)?,
+            None => KVPrimaryKeyMarker::Start,
+        };
+        let to = match &req.to {
+            Some(marker) => GrpcHandlerUtils::marker_to_bound(&table_schema, marker)?,
+            None => KVPrimaryKeyMarker::End,
+        };
+
+        let compacted = self.compactor.compact_table(table, from, to).await;
+
+        Ok(tonic::Response::new(CompactTableResponse { compacted }))
+    }
+
+    async fn get_table_stats(
+        &self,
+        request: Request<GetTableStatsRequest>,
+    ) -> Result<Response<GetTableStatsResponse>, Status> {
+        let table = GrpcHandlerUtils::lookup_table(&self.db_ctx, &request.get_ref().table_name)?;
+        let table_schema = table.table_schema();
+
+        let key_to_string = |key: &[u8]| {
+            if key.is_empty() {
+                return Ok(String::new());
+            }
+
+            KVPrimaryKeyUtils::debug_fmt(&table_schema, key)
+                .map_err(|_| Status::internal("malformed sstable key"))
+        };
+
+        let mut sstables = vec![];
+        let mut levels = BTreeSet::new();
+        for sstable in table.list_sstables() {
+            levels.insert(sstable.level());
+
+            sstables.push(SsTableStats {
+                sstable_id: sstable.sstable_id().0,
+                level: sstable.level(),
+                mutation_count: sstable.mutation_count(),
+                min_key: key_to_string(sstable.min_row_key())?,
+                max_key: key_to_string(sstable.max_row_key())?,
+                file_size: sstable.size(),
+            });
+        }
+
+        Ok(tonic::Response::new(GetTableStatsResponse {
+            table_name: table.table_name.to_string(),
+            level_count: levels.len() as u32,
+            total_file_size: sstables.iter().map(|sstable| sstable.file_size).sum(),
+            total_mutation_count: sstables.iter().map(|sstable| sstable.mutation_count).sum(),
+            sstables,
+        }))
+    }
+
+    async fn get_write_buffer_stats(
+        &self,
+        _request: Request<()>,
+    ) -> Result<Response<GetWriteBufferStatsResponse>, Status> {
+        let stats = self.db_ctx.kv_instance.write_buffer_manager().stats();
+
+        Ok(tonic::Response::new(GetWriteBufferStatsResponse {
+            budget: stats.budget as u64,
+            memory_usage: stats.memory_usage as u64,
+            flushing_memory_usage: stats.flushing_memory_usage as u64,
+            memtable_count: stats.memtable_count as u64,
+            triggered_flush_count: stats.triggered_flush_count,
+        }))
     }
 }
 
-struct GrpcHandlerUtils;
+/** Integers above this magnitude cannot be represented exactly by a double. */
+const MAX_EXACT_INTEGER: f64 = (1u64 << 53) as f64;
+/** Rows sent in a single ScanTable response message. */
+const SCAN_TABLE_BATCH_ROWS: usize = 64;
+/** Response messages buffered before the scan waits for the client. */
+const SCAN_TABLE_STREAM_BUFFER: usize = 4;
+
+struct ScanTableStreamOptions {
+    limit: usize,
+    with_versions: bool,
+}
+
+pub(super) struct GrpcHandlerUtils;
 
 impl GrpcHandlerUtils {
+    fn lookup_table(db_ctx: &DbCtx, table_name: &str) -> Result<Arc<KVTable>, Status> {
+        let table_name = KVTableName::from_str(table_name)
+            .map_err(|_| Status::invalid_argument("invalid table name"))?;
+
+        db_ctx
+            .catalog
+            .lookup_table_by_name(&table_name)
+            .ok_or(Status::not_found(format!(
+                "table {} does not exist",
+                table_name
+            )))
+    }
+
+    /** Streams rows of the scan in batches. Scan stops as soon as the client goes away. */
+    async fn stream_scan(
+        table: Arc<KVTable>,
+        mut scan: KVRowIter,
+        options: ScanTableStreamOptions,
+        sender: mpsc::Sender<Result<ScanTableResponse, Status>>,
+    ) {
+        let schema = &table.table_schema();
+        let mut rows = Vec::with_capacity(SCAN_TABLE_BATCH_ROWS);
+        let mut rows_count = 0usize;
+        let mut resume_token = None;
+
+        while rows_count < options.limit {
+            // Filtered scans may read many rows before the next match, don't wait for it
+            let row = tokio::select! {
+                _ = sender.closed() => return,
+                row = scan.next_row() => row,
+            };
+
+            let row = match row {
+                Ok(Some(row)) => row,
+                Ok(None) => break,
+                Err(e) => {
+                    let _ = sender.send(Err(GrpcHandlerUtils::scan_err(e))).await;
+                    return;
+                }
+            };
+
+            let versions = if options.with_versions {
+                GrpcHandlerUtils::row_to_versions_map(schema, &row)
+            } else {
+                HashMap::new()
+            };
+            let values = GrpcHandlerUtils::row_to_values_map(schema, row);
+
+            rows_count += 1;
+            if rows_count == options.limit {
+                resume_token = Some(GrpcHandlerUtils::resume_token(schema, &values));
+            }
+
+            rows.push(ScanTableResponseRow { values, versions });
+
+            if rows.len() == SCAN_TABLE_BATCH_ROWS && resume_token.is_none() {
+                let response = ScanTableResponse {
+                    rows: std::mem::take(&mut rows),
+                    resume_token: None,
+                };
+
+                if sender.send(Ok(response)).await.is_err() {
+                    return;
+                }
+            }
+        }
+
+        if !rows.is_empty() || resume_token.is_some() {
+            let _ = sender
+                .send(Ok(ScanTableResponse { rows, resume_token }))
+                .await;
+        }
+    }
+
+    /** Exclusive marker on the primary key of the row, projections always keep key columns. */
+    fn resume_token(schema: &KVTableSchema, values: &HashMap<String, Value>) -> PrimaryKeyMarker {
+        PrimaryKeyMarker {
+            values: schema
+                .primary_key
+                .iter()
+                .filter_map(|column_id| schema.lookup_by_column_id(*column_id))
+                .filter_map(|column| {
+                    let value = values.get(&column.column_name)?;
+                    Some((column.column_name.clone(), value.clone()))
+                })
+                .collect(),
+            exclusive: true,
+        }
+    }
+
+    /** Client is expected to back off and retry once the table catches up on flushes. */
+    fn write_stalled_status() -> Status {
+        Status::resource_exhausted("table writes stalled - flushes or compactions fall behind")
+    }
+
+    fn scan_err(e: KVRuntimeError) -> Status {
+        println!("scan failed - {}", e);
+
+        Status::internal("scan failed")
+    }
+
+    fn marker_to_op_bound(
+        table_schema: &KVTableSchema,
+        marker: &PrimaryKeyMarker,
+    ) -> Result<DeleteRangeOpBound, Status> {
+        let mut primary_key_values = vec![];
+        for (key, val) in marker.values.iter() {
+            let column_schema = GrpcHandlerUtils::column_schema(table_schema, key)?;
+
+            primary_key_values.push((
+                key.to_string(),
+                GrpcHandlerUtils::value_to_column_value(column_schema, val)?,
+            ));
+        }
+
+        Ok(DeleteRangeOpBound {
+            primary_key_values,
+            exclusive: marker.exclusive,
+        })
+    }
+
+    /**
+     * Maps a marker to a range bound. Markers setting only leading primary key columns become
+     * prefix bounds.
+     */
+    fn marker_to_bound(
+        table_schema: &KVTableSchema,
+        marker: &PrimaryKeyMarker,
+    ) -> Result<KVPrimaryKeyMarker, Status> {
+        GrpcHandlerUtils::marker_to_op_bound(table_schema, marker)?
+            .to_marker(table_schema)
+            .map_err(GrpcHandlerUtils::range_bound_err)
+    }
+
+    /** Maps errors of turning a marker into a range bound, the rest are not expected. */
+    fn range_bound_err(e: DeleteRangeOpError) -> Status {
+        match e {
+            DeleteRangeOpError::InvalidColumnName => Status::invalid_argument(
+                "range bound sets a column that is not a leading primary key column",
+            ),
+            DeleteRangeOpError::MissingPrimaryKey => {
+                Status::invalid_argument("range bound sets no primary key column")
+            }
+            DeleteRangeOpError::InvalidValue => Status::invalid_argument("invalid value"),
+            e => {
+                println!("range bound failed - {:?}", e);
+
+                Status::internal("range bound failed")
+            }
+        }
+    }
+
     fn row_to_values_map(schema: &KVTableSchema, row: KVRow) -> HashMap<String, Value> {
         let mut values = HashMap::new();
 
         for column in &schema.columns {
-            if row.has_cell(column.column_id) {
+            if row.has_value(column.column_id) {
                 let value = GrpcHandlerUtils::cell_to_value(column, &row);
 
                 values.insert(column.column_name.clone(), value);
@@ -414,64 +936,542 @@ impl GrpcHandlerUtils {
         values
     }
 
-    fn cell_to_value(schema: &KVColumnSchema, row: &KVRow) -> Value {
-        match schema.column_type {
-            ColumnTypeCode::Bytes => {
-                let val = row
-                    .column_deserialized::<ColumnTypeBytes>(&schema.column_name)
-                    .unwrap();
+    fn row_to_versions_map(schema: &KVTableSchema, row: &KVRow) -> HashMap<String, CellVersions> {
+        let mut versions = HashMap::new();
 
-                let encoded = BASE64_STANDARD.encode(&val);
+        for column in &schema.columns {
+            if row.has_value(column.column_id) {
+                let cell_versions = GrpcHandlerUtils::cell_versions_to_values(column, row)
+                    .into_iter()
+                    .map(|(timestamp, value)| CellVersion {
+                        timestamp: timestamp as i64,
+                        value: Some(value),
+                    })
+                    .collect();
 
-                Value {
-                    kind: Some(Kind::StringValue(encoded)),
-                }
+                versions.insert(
+                    column.column_name.clone(),
+                    CellVersions {
+                        versions: cell_versions,
+                    },
+                );
             }
+        }
+
+        versions
+    }
+
+    fn cell_to_value(schema: &KVColumnSchema, row: &KVRow) -> Value {
+        let (_, value) = GrpcHandlerUtils::cell_versions_to_values(schema, row)
+            .into_iter()
+            .next()
+            .unwrap();
+
+        value
+    }
+
+    fn cell_versions_to_values(schema: &KVColumnSchema, row: &KVRow) -> Vec<(u64, Value)> {
+        match schema.column_type {
+            ColumnTypeCode::Bytes => Self::cell_versions::<ColumnTypeBytes>(schema, row, |val| {
+                Kind::StringValue(BASE64_STANDARD.encode(&val))
+            }),
             ColumnTypeCode::Text => {
-                let val = row
-                    .column_deserialized::<ColumnTypeText>(&schema.column_name)
-                    .unwrap();
-                Value {
-                    kind: Some(Kind::StringValue(val)),
-                }
-            }
-            ColumnTypeCode::U16 => {
-                let val = row
-                    .column_deserialized::<ColumnTypeU16>(&schema.column_name)
-                    .unwrap();
-                Value {
-                    kind: Some(Kind::NumberValue(val.into())),
-                }
+                Self::cell_versions::<ColumnTypeText>(schema, row, Kind::StringValue)
             }
+            ColumnTypeCode::U16 => Self::cell_versions::<ColumnTypeU16>(schema, row, |val| {
+                Kind::NumberValue(val.into())
+            }),
             ColumnTypeCode::U16Array => {
-                let val = row
-                    .column_deserialized::<ColumnTypeU16Array>(&schema.column_name)
-                    .unwrap();
-                Value {
-                    kind: Some(Kind::ListValue(ListValue {
+                Self::cell_versions::<ColumnTypeU16Array>(schema, row, |val| {
+                    Kind::ListValue(ListValue {
                         values: val
                             .into_iter()
                             .map(|val| Value {
                                 kind: Some(Kind::NumberValue(val.into())),
                             })
                             .collect(),
-                    })),
-                }
+                    })
+                })
+            }
+            ColumnTypeCode::I32 => Self::cell_versions::<ColumnTypeI32>(schema, row, |val| {
+                Kind::NumberValue(val.into())
+            }),
+            // 64-bit integers don't fit a double, they are passed as decimal strings
+            ColumnTypeCode::I64 => Self::cell_versions::<ColumnTypeI64>(schema, row, |val| {
+                Kind::StringValue(val.to_string())
+            }),
+            ColumnTypeCode::U32 => Self::cell_versions::<ColumnTypeU32>(schema, row, |val| {
+                Kind::NumberValue(val.into())
+            }),
+            ColumnTypeCode::U64 => Self::cell_versions::<ColumnTypeU64>(schema, row, |val| {
+                Kind::StringValue(val.to_string())
+            }),
+            ColumnTypeCode::F64 => {
+                Self::cell_versions::<ColumnTypeF64>(schema, row, Kind::NumberValue)
+            }
+            ColumnTypeCode::Bool => {
+                Self::cell_versions::<ColumnTypeBool>(schema, row, Kind::BoolValue)
+            }
+            ColumnTypeCode::Timestamp => {
+                Self::cell_versions::<ColumnTypeTimestamp>(schema, row, |val| {
+                    Kind::NumberValue(val as f64)
+                })
+            }
+            ColumnTypeCode::Uuid => Self::cell_versions::<ColumnTypeUuid>(schema, row, |val| {
+                Kind::StringValue(KVColumnTypeUtils::uuid_fmt(&val))
+            }),
+        }
+    }
+
+    fn cell_versions<T: ColumnTypeDeserialize>(
+        schema: &KVColumnSchema,
+        row: &KVRow,
+        to_kind: impl Fn(T::Output) -> Kind,
+    ) -> Vec<(u64, Value)> {
+        row.column_versions_deserialized::<T>(&schema.column_name)
+            .unwrap()
+            .into_iter()
+            .map(|(timestamp, val)| {
+                let value = Value {
+                    kind: Some(to_kind(val)),
+                };
+
+                (timestamp, value)
+            })
+            .collect()
+    }
+
+    fn column_type(column_type: i32) -> Result<ColumnTypeCode, Status> {
+        match CreateTableRequestColumnType::try_from(column_type) {
+            Ok(CreateTableRequestColumnType::Bytes) => Ok(ColumnTypeCode::Bytes),
+            Ok(CreateTableRequestColumnType::Text) => Ok(ColumnTypeCode::Text),
+            Ok(CreateTableRequestColumnType::U16) => Ok(ColumnTypeCode::U16),
+            Ok(CreateTableRequestColumnType::U16Array) => Ok(ColumnTypeCode::U16Array),
+            Ok(CreateTableRequestColumnType::I32) => Ok(ColumnTypeCode::I32),
+            Ok(CreateTableRequestColumnType::I64) => Ok(ColumnTypeCode::I64),
+            Ok(CreateTableRequestColumnType::U32) => Ok(ColumnTypeCode::U32),
+            Ok(CreateTableRequestColumnType::U64) => Ok(ColumnTypeCode::U64),
+            Ok(CreateTableRequestColumnType::F64) => Ok(ColumnTypeCode::F64),
+            Ok(CreateTableRequestColumnType::Bool) => Ok(ColumnTypeCode::Bool),
+            Ok(CreateTableRequestColumnType::Timestamp) => Ok(ColumnTypeCode::Timestamp),
+            Ok(CreateTableRequestColumnType::Uuid) => Ok(ColumnTypeCode::Uuid),
+            Err(_) => Err(Status::invalid_argument("unknown column type")),
+        }
+    }
+
+    fn table_definition(table: &KVTable) -> Table {
+        Table {
+            table_name: table.table_name.to_string(),
+            columns: table
+                .table_schema()
+                .columns
+                .iter()
+                .map(|col| ColumnDefinition {
+                    column_name: col.column_name.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    fn create_table_op_column(
+        col: &CreateTableRequestColumn,
+        default_retention: KVColumnRetention,
+    ) -> Result<CreateTableOpColumn, Status> {
+        let column_type = Self::column_type(col.column_type)?;
+
+        Ok(CreateTableOpColumn {
+            column_name: col.column_name.clone(),
+            column_type,
+            retention: col
+                .retention
+                .as_ref()
+                .map(Self::column_retention)
+                .transpose()?
+                .unwrap_or(default_retention),
+            nullable: !col.not_null,
+            default_value: col
+                .default_value
+                .as_ref()
+                .map(|value| {
+                    Self::value_to_typed_column_value(&col.column_name, column_type, value)
+                })
+                .transpose()?,
+        })
+    }
+
+    fn column_retention(retention: &ColumnRetention) -> Result<KVColumnRetention, Status> {
+        let versions_limit = |versions: u32| {
+            u16::try_from(versions)
+                .map_err(|_| Status::invalid_argument("column retention versions limit exceeded"))
+        };
+
+        Ok(KVColumnRetention {
+            max_versions: versions_limit(retention.max_versions)?,
+            min_versions: versions_limit(retention.min_versions)?,
+            ttl: (retention.ttl_seconds > 0).then(|| Duration::from_secs(retention.ttl_seconds)),
+        })
+    }
+
+    fn compaction_options(compaction: &CompactionOptions) -> Result<KVCompactionOptions, Status> {
+        match CompactionStrategy::try_from(compaction.strategy) {
+            Ok(CompactionStrategy::Leveled) => Ok(KVCompactionOptions::Leveled),
+            Ok(CompactionStrategy::SizeTiered) => Ok(KVCompactionOptions::SizeTiered),
+            Ok(CompactionStrategy::TimeWindow) => Ok(KVCompactionOptions::TimeWindow {
+                window: Duration::from_secs(compaction.window_seconds),
+            }),
+            Err(_) => Err(Status::invalid_argument("unknown compaction strategy")),
+        }
+    }
+
+    /** Primary key columns are always included, so projected rows remain identifiable. */
+    fn column_filter(
+        table_schema: &KVTableSchema,
+        columns: &[String],
+    ) -> Result<KVColumnFilter, Status> {
+        if columns.is_empty() {
+            return Ok(KVColumnFilter::All);
+        }
+
+        let mut column_ids = table_schema.primary_key.clone();
+        for column_name in columns {
+            let column_schema = table_schema.lookup_by_name(column_name).ok_or_else(|| {
+                Status::invalid_argument(format!("column {} does not exist", column_name))
+            })?;
+
+            if !column_ids.contains(&column_schema.column_id) {
+                column_ids.push(column_schema.column_id);
             }
         }
+
+        Ok(KVColumnFilter::Columns(column_ids))
+    }
+
+    fn row_filter(table_schema: &KVTableSchema, filter: &RowFilter) -> Result<KVRowFilter, Status> {
+        let row_filter = GrpcHandlerUtils::row_filter_node(table_schema, filter)?;
+
+        row_filter
+            .validate(table_schema)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        Ok(row_filter)
+    }
+
+    /** Converts filter with its nested filters, validated as a whole by the caller. */
+    fn row_filter_node(
+        table_schema: &KVTableSchema,
+        filter: &RowFilter,
+    ) -> Result<KVRowFilter, Status> {
+        let column_schema = |column_name: &String| {
+            table_schema.lookup_by_name(column_name).ok_or_else(|| {
+                Status::invalid_argument(format!("column {} does not exist", column_name))
+            })
+        };
+
+        let Some(filter) = &filter.filter else {
+            return Err(Status::invalid_argument("empty row filter"));
+        };
+
+        let row_filter = match filter {
+            RowFilterKind::Compare(compare) => {
+                let column_schema = column_schema(&compare.column_name)?;
+                let value = compare
+                    .value
+                    .as_ref()
+                    .ok_or(Status::invalid_argument("missing comparison value"))?;
+
+                KVRowFilter::Compare {
+                    column_id: column_schema.column_id,
+                    op: match ComparisonOperator::try_from(compare.operator) {
+                        Ok(ComparisonOperator::Eq) => KVCompareOp::Eq,
+                        Ok(ComparisonOperator::NotEq) => KVCompareOp::NotEq,
+                        Ok(ComparisonOperator::Lt) => KVCompareOp::Lt,
+                        Ok(ComparisonOperator::LtEq) => KVCompareOp::LtEq,
+                        Ok(ComparisonOperator::Gt) => KVCompareOp::Gt,
+                        Ok(ComparisonOperator::GtEq) => KVCompareOp::GtEq,
+                        Err(_) => {
+                            return Err(Status::invalid_argument("unknown comparison operator"));
+                        }
+                    },
+                    value: GrpcHandlerUtils::value_to_bytes(column_schema, value)?,
+                }
+            }
+            RowFilterKind::TextPrefix(text_prefix) => KVRowFilter::TextPrefix {
+                column_id: column_schema(&text_prefix.column_name)?.column_id,
+                prefix: text_prefix.prefix.as_bytes().into(),
+            },
+            RowFilterKind::ArrayContains(array_contains) => KVRowFilter::ArrayContains {
+                column_id: column_schema(&array_contains.column_name)?.column_id,
+                item: u16::try_from(array_contains.item)
+                    .map_err(|_| Status::invalid_argument("array item out of u16 range"))?,
+            },
+            RowFilterKind::IsNull(column_name) => {
+                KVRowFilter::IsNull(column_schema(column_name)?.column_id)
+            }
+            RowFilterKind::IsNotNull(column_name) => {
+                KVRowFilter::IsNotNull(column_schema(column_name)?.column_id)
+            }
+            RowFilterKind::And(list) => KVRowFilter::And(
+                list.filters
+                    .iter()
+                    .map(|filter| GrpcHandlerUtils::row_filter_node(table_schema, filter))
+                    .collect::<Result<_, _>>()?,
+            ),
+            RowFilterKind::Or(list) => KVRowFilter::Or(
+                list.filters
+                    .iter()
+                    .map(|filter| GrpcHandlerUtils::row_filter_node(table_schema, filter))
+                    .collect::<Result<_, _>>()?,
+            ),
+            RowFilterKind::Not(filter) => KVRowFilter::Not(Box::new(
+                GrpcHandlerUtils::row_filter_node(table_schema, filter)?,
+            )),
+        };
+
+        Ok(row_filter)
     }
 
+    fn version_filter(
+        read_timestamp: Option<i64>,
+        max_versions: Option<u32>,
+    ) -> Result<KVVersionFilter, Status> {
+        let version_filter = match read_timestamp {
+            Some(read_timestamp) => KVVersionFilter::at(
+                u64::try_from(read_timestamp)
+                    .map_err(|_| Status::invalid_argument("negative read timestamp"))?,
+            ),
+            None => KVVersionFilter::latest(),
+        };
+
+        Ok(match max_versions {
+            Some(0) => return Err(Status::invalid_argument("max versions must be positive")),
+            Some(max_versions) => version_filter.with_max_versions(max_versions as usize),
+            None => version_filter,
+        })
+    }
+
+    fn mutation_to_op_mutation(
+        table_schema: &KVTableSchema,
+        mutation: &MutationKind,
+    ) -> Result<MutateRowOpMutation, Status> {
+        let ensure_default_family = |family_name: &String| {
+            if family_name.is_empty() {
+                Ok(())
+            } else {
+                Err(Status::invalid_argument(format!(
+                    "column family {} does not exist",
+                    family_name
+                )))
+            }
+        };
+
+        let op_mutation = match mutation {
+            MutationKind::PutCell(put_cell) => {
+                ensure_default_family(&put_cell.family_name)?;
+
+                let value = put_cell
+                    .value
+                    .as_ref()
+                    .ok_or(Status::invalid_argument("missing cell value"))?;
+
+                let column_schema =
+                    GrpcHandlerUtils::column_schema(table_schema, &put_cell.column_name)?;
+
+                MutateRowOpMutation::PutCell {
+                    column_name: put_cell.column_name.clone(),
+                    timestamp: Self::timestamp(put_cell.timestamp)?,
+                    value: GrpcHandlerUtils::value_to_column_value(column_schema, value)?,
+                }
+            }
+            MutationKind::DeleteCell(delete_cell) => {
+                ensure_default_family(&delete_cell.family_name)?;
+
+                MutateRowOpMutation::DeleteCell {
+                    column_name: delete_cell.column_name.clone(),
+                    timestamp: Self::timestamp(delete_cell.timestamp)?,
+                }
+            }
+            MutationKind::DeleteColumn(delete_column) => {
+                ensure_default_family(&delete_column.family_name)?;
+
+                MutateRowOpMutation::DeleteColumn {
+                    column_name: delete_column.column_name.clone(),
+                    timestamp: Self::timestamp(delete_column.timestamp)?,
+                }
+            }
+            MutationKind::DeleteFamily(delete_family) => {
+                ensure_default_family(&delete_family.family_name)?;
+
+                MutateRowOpMutation::DeleteRow {
+                    timestamp: Self::timestamp(delete_family.timestamp)?,
+                }
+            }
+        };
+
+        Ok(op_mutation)
+    }
+
+    fn timestamp(timestamp: i64) -> Result<u64, Status> {
+        u64::try_from(timestamp).map_err(|_| Status::invalid_argument("negative timestamp"))
+    }
+
+    fn column_schema<'a>(
+        table_schema: &'a KVTableSchema,
+        column_name: &str,
+    ) -> Result<&'a KVColumnSchema, Status> {
+        table_schema
+            .lookup_by_name(column_name)
+            .ok_or_else(|| Self::invalid_column_value(column_name, "column does not exist"))
+    }
+
+    /** Rejected value carries a bad request violation with the column name as its field. */
+    fn invalid_column_value(column_name: &str, description: impl Into<String>) -> Status {
+        let description = description.into();
+
+        Status::with_error_details(
+            Code::InvalidArgument,
+            format!("invalid value for column {} - {}", column_name, description),
+            ErrorDetails::with_bad_request_violation(column_name, description),
+        )
+    }
+
+    fn value_to_bytes(column_schema: &KVColumnSchema, value: &Value) -> Result<Box<[u8]>, Status> {
+        Self::value_to_column_value(column_schema, value)?
+            .serialize()
+            .map_err(|e| Self::invalid_column_value(&column_schema.column_name, e.to_string()))
+    }
+
+    /**
+     * Converts value with the type of the column it is written to, compared to or used as a key
+     * of. Values read from a column convert back to the same column value.
+     */
     fn value_to_column_value(
+        column_schema: &KVColumnSchema,
+        value: &Value,
+    ) -> Result<Box<dyn KVColumnValue + Send + Sync + 'static>, Status> {
+        Self::value_to_typed_column_value(
+            &column_schema.column_name,
+            column_schema.column_type,
+            value,
+        )
+    }
+
+    /** Same as value_to_column_value, used before the column schema exists. */
+    pub(super) fn value_to_typed_column_value(
+        column_name: &str,
+        column_type: ColumnTypeCode,
         value: &Value,
-    ) -> Result<Box<(dyn KVColumnValue + Send + Sync + 'static)>, ()> {
+    ) -> Result<Box<dyn KVColumnValue + Send + Sync + 'static>, Status> {
+        let expected = |type_description: &str| {
+            Self::invalid_column_value(column_name, format!("expected {}", type_description))
+        };
+
         let Some(kind) = &value.kind else {
-            return Err(());
+            return Err(expected("a value"));
         };
 
-        match kind {
-            Kind::StringValue(value) => Ok(KVColumnValueBuilder::text(value.clone())),
-            Kind::NumberValue(value) => Ok(KVColumnValueBuilder::u16(*value as u16)),
-            _ => Err(()),
-        }
+        let column_value = match (column_type, kind) {
+            (ColumnTypeCode::Bytes, Kind::StringValue(encoded)) => {
+                let decoded = BASE64_STANDARD
+                    .decode(encoded)
+                    .map_err(|_| expected("base64 encoded string"))?;
+
+                KVColumnValueBuilder::bytes(decoded)
+            }
+            (ColumnTypeCode::Bytes, _) => return Err(expected("base64 encoded string")),
+            (ColumnTypeCode::Text, Kind::StringValue(text)) => {
+                KVColumnValueBuilder::text(text.clone())
+            }
+            (ColumnTypeCode::Text, _) => return Err(expected("string")),
+            (ColumnTypeCode::U16, kind) => {
+                KVColumnValueBuilder::u16(Self::integer_value(column_name, kind)?)
+            }
+            (ColumnTypeCode::U16Array, Kind::ListValue(list)) => {
+                let mut items = Vec::with_capacity(list.values.len());
+                for item in &list.values {
+                    let Some(kind) = &item.kind else {
+                        return Err(expected("list of integers"));
+                    };
+
+                    items.push(Self::integer_value(column_name, kind)?);
+                }
+
+                KVColumnValueBuilder::u16_array(items)
+            }
+            (ColumnTypeCode::U16Array, _) => return Err(expected("list of integers")),
+            (ColumnTypeCode::I32, kind) => {
+                KVColumnValueBuilder::i32(Self::integer_value(column_name, kind)?)
+            }
+            (ColumnTypeCode::I64, kind) => {
+                KVColumnValueBuilder::i64(Self::integer_value(column_name, kind)?)
+            }
+            (ColumnTypeCode::U32, kind) => {
+                KVColumnValueBuilder::u32(Self::integer_value(column_name, kind)?)
+            }
+            (ColumnTypeCode::U64, kind) => {
+                KVColumnValueBuilder::u64(Self::integer_value(column_name, kind)?)
+            }
+            (ColumnTypeCode::F64, Kind::NumberValue(number)) => KVColumnValueBuilder::f64(*number),
+            (ColumnTypeCode::F64, _) => return Err(expected("number")),
+            (ColumnTypeCode::Bool, Kind::BoolValue(value)) => KVColumnValueBuilder::bool(*value),
+            (ColumnTypeCode::Bool, _) => return Err(expected("bool")),
+            (ColumnTypeCode::Timestamp, kind) => {
+                KVColumnValueBuilder::timestamp(Self::integer_value(column_name, kind)?)
+            }
+            (ColumnTypeCode::Uuid, Kind::StringValue(text)) => KVColumnValueBuilder::uuid(
+                KVColumnTypeUtils::uuid_parse(text).ok_or_else(|| expected("uuid string"))?,
+            ),
+            (ColumnTypeCode::Uuid, _) => return Err(expected("uuid string")),
+        };
+
+        Ok(column_value)
+    }
+
+    /** Integer held by a number, which must be exact, or by a decimal string. */
+    fn integer_value<T: TryFrom<i128>>(column_name: &str, kind: &Kind) -> Result<T, Status> {
+        let integer = match kind {
+            Kind::NumberValue(number)
+                if number.fract() == 0.0 && number.abs() <= MAX_EXACT_INTEGER =>
+            {
+                *number as i128
+            }
+            Kind::NumberValue(number) if number.fract() != 0.0 => {
+                return Err(Self::invalid_column_value(
+                    column_name,
+                    format!("number {} is not an integer", number),
+                ));
+            }
+            Kind::NumberValue(number) => {
+                return Err(Self::invalid_column_value(
+                    column_name,
+                    format!(
+                        "number {:e} is not exact in a double, pass it as a decimal string",
+                        number
+                    ),
+                ));
+            }
+            Kind::StringValue(text) => text.parse::<i128>().map_err(|_| {
+                Self::invalid_column_value(
+                    column_name,
+                    format!("string {:?} is not a decimal integer", text),
+                )
+            })?,
+            _ => {
+                return Err(Self::invalid_column_value(
+                    column_name,
+                    "expected integer number or decimal string",
+                ));
+            }
+        };
+
+        T::try_from(integer).map_err(|_| {
+            Self::invalid_column_value(
+                column_name,
+                format!(
+                    "{} is out of range of {}",
+                    integer,
+                    std::any::type_name::<T>()
+                ),
+            )
+        })
     }
 }
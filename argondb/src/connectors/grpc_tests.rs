@@ -0,0 +1,565 @@
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use libargonconnector_grpc::argondb_service_definition::{
+    AlterTableChange, AlterTableRequest, ColumnRetention, CreateTableRequest,
+    CreateTableRequestColumn, CreateTableRequestColumnType, InsertMutationsRequest,
+    PrimaryKeyMarker, ReadRowRequest, ScanTableRequest, ScanTableResponse,
+    alter_table_change::Change as AlterTableChangeKind, argon_db_server::ArgonDb,
+};
+use libargondb::{
+    DbCtx,
+    kv::{
+        KVColumnValueBuilder, KVTableName, KVTableOptions, column_type::ColumnTypeCode,
+        schema::KVColumnRetention,
+    },
+};
+use prost_types::{ListValue, Value, value::Kind};
+use tokio_stream::StreamExt;
+use tonic::Request;
+
+use crate::{
+    connectors::grpc::{ArgonDbHandlers, GrpcHandlerUtils},
+    ops::{CreateTableOp, CreateTableOpColumn, InsertIntoOp},
+    test_utils::{TestDbCtx, test_db_ctx},
+};
+
+/**
+ * Table `scanned` with a bytes primary key `id` holding rows of ids `[0]` up to `[row_count - 1]`.
+ */
+async fn scanned_table(name: &str, row_count: u8) -> TestDbCtx {
+    let db_ctx = test_db_ctx(name);
+
+    let column = |column_name: &str, column_type| CreateTableOpColumn {
+        column_name: column_name.to_string(),
+        column_type,
+        retention: KVColumnRetention::default(),
+        nullable: true,
+        default_value: None,
+    };
+
+    CreateTableOp {
+        table_name: "scanned".to_string(),
+        columns: vec![
+            column("id", ColumnTypeCode::Bytes),
+            column("value", ColumnTypeCode::Text),
+        ],
+        primary_key: vec!["id".to_string()],
+        table_options: KVTableOptions::default(),
+    }
+    .execute(&db_ctx)
+    .await
+    .unwrap();
+
+    for id in 0..row_count {
+        InsertIntoOp {
+            table_name: "scanned".to_string(),
+            values: vec![
+                ("id".to_string(), KVColumnValueBuilder::bytes(vec![id])),
+                (
+                    "value".to_string(),
+                    KVColumnValueBuilder::text(id.to_string()),
+                ),
+            ],
+        }
+        .execute(&db_ctx)
+        .await
+        .unwrap();
+    }
+
+    db_ctx
+}
+
+fn scan_request() -> ScanTableRequest {
+    ScanTableRequest {
+        table_name: "scanned".to_string(),
+        ..Default::default()
+    }
+}
+
+async fn scan(db_ctx: &Arc<DbCtx>, request: ScanTableRequest) -> Vec<ScanTableResponse> {
+    let handlers = ArgonDbHandlers::new(db_ctx.clone());
+
+    handlers
+        .scan_table(Request::new(request))
+        .await
+        .unwrap()
+        .into_inner()
+        .map(Result::unwrap)
+        .collect()
+        .await
+}
+
+fn row_ids(responses: &[ScanTableResponse]) -> Vec<u8> {
+    responses
+        .iter()
+        .flat_map(|response| &response.rows)
+        .map(|row| match &row.values["id"].kind {
+            Some(Kind::StringValue(encoded)) => {
+                let id = BASE64_STANDARD.decode(encoded).unwrap();
+                assert_eq!(id.len(), 1);
+                id[0]
+            }
+            kind => panic!("unexpected id value {:?}", kind),
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_scan_table_batches_rows() {
+    let db_ctx = scanned_table("grpc-scan-batches", 150).await;
+
+    let responses = scan(&db_ctx, scan_request()).await;
+
+    let batch_sizes = responses
+        .iter()
+        .map(|response| response.rows.len())
+        .collect::<Vec<_>>();
+    assert_eq!(batch_sizes, vec![64, 64, 22]);
+    assert!(
+        responses
+            .iter()
+            .all(|response| response.resume_token.is_none())
+    );
+    assert_eq!(row_ids(&responses), (0..150).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_scan_table_limit_stops_at_last_row() {
+    let db_ctx = scanned_table("grpc-scan-limit", 150).await;
+
+    let responses = scan(
+        &db_ctx,
+        ScanTableRequest {
+            limit: Some(64),
+            ..scan_request()
+        },
+    )
+    .await;
+
+    // Batch holding the last row carries the resume token
+    assert_eq!(responses.len(), 1);
+    assert_eq!(row_ids(&responses), (0..64).collect::<Vec<_>>());
+
+    let resume_token = responses[0].resume_token.as_ref().unwrap();
+    assert!(resume_token.exclusive);
+    assert_eq!(
+        resume_token.values["id"].kind,
+        Some(Kind::StringValue(BASE64_STANDARD.encode([63])))
+    );
+}
+
+#[tokio::test]
+async fn test_scan_table_resumes_bytes_primary_keys() {
+    let db_ctx = scanned_table("grpc-scan-resume", 5).await;
+
+    for reverse in [false, true] {
+        let mut ids = vec![];
+        let mut resume_token: Option<PrimaryKeyMarker> = None;
+        let mut pages = 0;
+
+        loop {
+            let responses = scan(
+                &db_ctx,
+                ScanTableRequest {
+                    limit: Some(2),
+                    reverse,
+                    resume_token: resume_token.take(),
+                    ..scan_request()
+                },
+            )
+            .await;
+            pages += 1;
+
+            ids.extend(row_ids(&responses));
+            resume_token = responses
+                .last()
+                .and_then(|response| response.resume_token.clone());
+
+            if resume_token.is_none() {
+                break;
+            }
+        }
+
+        let mut expected_ids = (0..5).collect::<Vec<_>>();
+        if reverse {
+            expected_ids.reverse();
+        }
+        assert_eq!(ids, expected_ids);
+        assert_eq!(pages, 3);
+    }
+}
+
+#[tokio::test]
+async fn test_scan_table_resume_token_of_exhausted_scan() {
+    let db_ctx = scanned_table("grpc-scan-exhausted", 4).await;
+
+    let responses = scan(
+        &db_ctx,
+        ScanTableRequest {
+            limit: Some(4),
+            ..scan_request()
+        },
+    )
+    .await;
+    assert_eq!(row_ids(&responses), vec![0, 1, 2, 3]);
+
+    // Scan doesn't read past the limit, the next page finds no rows
+    let responses = scan(
+        &db_ctx,
+        ScanTableRequest {
+            limit: Some(4),
+            resume_token: responses[0].resume_token.clone(),
+            ..scan_request()
+        },
+    )
+    .await;
+    assert!(row_ids(&responses).is_empty());
+    assert!(
+        responses
+            .iter()
+            .all(|response| response.resume_token.is_none())
+    );
+
+    // Token with a value that is not base64 is rejected
+    let status = ArgonDbHandlers::new(db_ctx.clone())
+        .scan_table(Request::new(ScanTableRequest {
+            resume_token: Some(PrimaryKeyMarker {
+                values: HashMap::from([(
+                    "id".to_string(),
+                    Value {
+                        kind: Some(Kind::StringValue("not base64!".to_string())),
+                    },
+                )]),
+                exclusive: true,
+            }),
+            ..scan_request()
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn test_scan_table_rejects_markers_of_other_columns() {
+    let db_ctx = scanned_table("grpc-scan-marker-columns", 1).await;
+
+    let marker = |column_name: &str| PrimaryKeyMarker {
+        values: HashMap::from([
+            (
+                "id".to_string(),
+                Value {
+                    kind: Some(Kind::StringValue(BASE64_STANDARD.encode([0]))),
+                },
+            ),
+            (
+                column_name.to_string(),
+                Value {
+                    kind: Some(Kind::StringValue("0".to_string())),
+                },
+            ),
+        ]),
+        exclusive: false,
+    };
+
+    for column_name in ["value", "missing"] {
+        let status = ArgonDbHandlers::new(db_ctx.clone())
+            .scan_table(Request::new(ScanTableRequest {
+                from: Some(marker(column_name)),
+                ..scan_request()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+}
+
+fn value(kind: Kind) -> Value {
+    Value { kind: Some(kind) }
+}
+
+fn number_list(numbers: &[f64]) -> Value {
+    value(Kind::ListValue(ListValue {
+        values: numbers
+            .iter()
+            .map(|number| value(Kind::NumberValue(*number)))
+            .collect(),
+    }))
+}
+
+/** Values of every column type in the form they are read back in. */
+fn typed_values() -> Vec<(ColumnTypeCode, Value)> {
+    vec![
+        (
+            ColumnTypeCode::Bytes,
+            value(Kind::StringValue(BASE64_STANDARD.encode([0, 1, 255]))),
+        ),
+        (
+            ColumnTypeCode::Text,
+            value(Kind::StringValue("text".to_string())),
+        ),
+        (ColumnTypeCode::U16, value(Kind::NumberValue(65_535.0))),
+        (ColumnTypeCode::U16Array, number_list(&[1.0, 0.0, 65_535.0])),
+        (
+            ColumnTypeCode::I32,
+            value(Kind::NumberValue(-2_147_483_648.0)),
+        ),
+        (
+            ColumnTypeCode::I64,
+            value(Kind::StringValue("-9223372036854775808".to_string())),
+        ),
+        (
+            ColumnTypeCode::U32,
+            value(Kind::NumberValue(4_294_967_295.0)),
+        ),
+        (
+            ColumnTypeCode::U64,
+            value(Kind::StringValue("18446744073709551615".to_string())),
+        ),
+        (ColumnTypeCode::F64, value(Kind::NumberValue(-1.5))),
+        (ColumnTypeCode::Bool, value(Kind::BoolValue(true))),
+        (
+            ColumnTypeCode::Timestamp,
+            value(Kind::NumberValue(1_700_000_000_000.0)),
+        ),
+        (
+            ColumnTypeCode::Uuid,
+            value(Kind::StringValue(
+                "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            )),
+        ),
+    ]
+}
+
+fn column_name(column_type: ColumnTypeCode) -> String {
+    format!("{:?}", column_type).to_lowercase()
+}
+
+#[tokio::test]
+async fn test_values_of_every_column_type_read_back() {
+    let db_ctx = test_db_ctx("grpc-typed-values");
+    let handlers = ArgonDbHandlers::new(db_ctx.clone());
+
+    let mut columns = vec![CreateTableOpColumn {
+        column_name: "id".to_string(),
+        column_type: ColumnTypeCode::Bytes,
+        retention: KVColumnRetention::default(),
+        nullable: false,
+        default_value: None,
+    }];
+    columns.extend(
+        typed_values()
+            .into_iter()
+            .map(|(column_type, _)| CreateTableOpColumn {
+                column_name: column_name(column_type),
+                column_type,
+                retention: KVColumnRetention::default(),
+                nullable: true,
+                default_value: None,
+            }),
+    );
+
+    CreateTableOp {
+        table_name: "typed".to_string(),
+        columns,
+        primary_key: vec!["id".to_string()],
+        table_options: KVTableOptions::default(),
+    }
+    .execute(&db_ctx)
+    .await
+    .unwrap();
+
+    let id = value(Kind::StringValue(BASE64_STANDARD.encode([1])));
+    let mut values: HashMap<String, Value> = typed_values()
+        .into_iter()
+        .map(|(column_type, value)| (column_name(column_type), value))
+        .collect();
+    values.insert("id".to_string(), id.clone());
+
+    handlers
+        .insert_mutations(Request::new(InsertMutationsRequest {
+            table_name: "typed".to_string(),
+            values: values.clone(),
+        }))
+        .await
+        .unwrap();
+
+    let response = handlers
+        .read_row(Request::new(ReadRowRequest {
+            table_name: "typed".to_string(),
+            primary_key_values: HashMap::from([("id".to_string(), id)]),
+            max_versions: Some(1),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert_eq!(response.values, values);
+    for (column_name, value) in &values {
+        let versions = &response.versions[column_name].versions;
+
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].value.as_ref(), Some(value));
+    }
+}
+
+#[test]
+fn test_invalid_values_of_every_column_type() {
+    let invalid_values = [
+        (
+            ColumnTypeCode::Bytes,
+            value(Kind::StringValue("not base64!".to_string())),
+            "expected base64 encoded string",
+        ),
+        (
+            ColumnTypeCode::Text,
+            value(Kind::NumberValue(1.0)),
+            "expected string",
+        ),
+        (
+            ColumnTypeCode::U16,
+            value(Kind::NumberValue(65_536.0)),
+            "65536 is out of range of u16",
+        ),
+        (
+            ColumnTypeCode::U16Array,
+            number_list(&[1.0, -1.0]),
+            "-1 is out of range of u16",
+        ),
+        (
+            ColumnTypeCode::U16Array,
+            value(Kind::NumberValue(1.0)),
+            "expected list of integers",
+        ),
+        (
+            ColumnTypeCode::I32,
+            value(Kind::NumberValue(1.5)),
+            "number 1.5 is not an integer",
+        ),
+        (
+            ColumnTypeCode::I64,
+            value(Kind::NumberValue(9_007_199_254_740_994.0)),
+            "is not exact in a double",
+        ),
+        (
+            ColumnTypeCode::U32,
+            value(Kind::StringValue("12a".to_string())),
+            "string \"12a\" is not a decimal integer",
+        ),
+        (
+            ColumnTypeCode::U64,
+            value(Kind::BoolValue(true)),
+            "expected integer number or decimal string",
+        ),
+        (
+            ColumnTypeCode::F64,
+            value(Kind::StringValue("1.5".to_string())),
+            "expected number",
+        ),
+        (
+            ColumnTypeCode::Bool,
+            value(Kind::NumberValue(1.0)),
+            "expected bool",
+        ),
+        (
+            ColumnTypeCode::Timestamp,
+            value(Kind::StringValue("9223372036854775808".to_string())),
+            "9223372036854775808 is out of range of i64",
+        ),
+        (
+            ColumnTypeCode::Uuid,
+            value(Kind::StringValue("123e4567".to_string())),
+            "expected uuid string",
+        ),
+        (
+            ColumnTypeCode::Text,
+            Value { kind: None },
+            "expected a value",
+        ),
+    ];
+
+    for (column_type, value, description) in invalid_values {
+        let Err(status) = GrpcHandlerUtils::value_to_typed_column_value("col", column_type, &value)
+        else {
+            panic!("{:?} accepted {:?}", column_type, value);
+        };
+
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert!(
+            status
+                .message()
+                .starts_with("invalid value for column col - "),
+            "{}",
+            status.message()
+        );
+        assert!(
+            status.message().contains(description),
+            "{:?}: {}",
+            column_type,
+            status.message()
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_added_columns_get_table_default_retention() {
+    let db_ctx = test_db_ctx("grpc-alter-retention");
+    let handlers = ArgonDbHandlers::new(db_ctx.clone());
+
+    let column = |column_name: &str, retention| CreateTableRequestColumn {
+        column_name: column_name.to_string(),
+        column_type: CreateTableRequestColumnType::Text as i32,
+        retention,
+        ..Default::default()
+    };
+    let retention = |max_versions| ColumnRetention {
+        max_versions,
+        ..Default::default()
+    };
+
+    handlers
+        .create_table(Request::new(CreateTableRequest {
+            table_name: "retained".to_string(),
+            columns: vec![column("id", None)],
+            primary_key: vec!["id".to_string()],
+            default_retention: Some(retention(3)),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+
+    handlers
+        .alter_table(Request::new(AlterTableRequest {
+            table_name: "retained".to_string(),
+            changes: vec![
+                AlterTableChange {
+                    change: Some(AlterTableChangeKind::AddColumn(column("defaulted", None))),
+                },
+                AlterTableChange {
+                    change: Some(AlterTableChangeKind::AddColumn(column(
+                        "own",
+                        Some(retention(5)),
+                    ))),
+                },
+            ],
+        }))
+        .await
+        .unwrap();
+
+    let table_schema = db_ctx
+        .catalog
+        .lookup_table_by_name(&KVTableName::from_str("retained").unwrap())
+        .unwrap()
+        .table_schema();
+    let max_versions = |column_name| {
+        table_schema
+            .lookup_by_name(column_name)
+            .unwrap()
+            .retention
+            .max_versions
+    };
+
+    assert_eq!(max_versions("defaulted"), 3);
+    assert_eq!(max_versions("own"), 5);
+}
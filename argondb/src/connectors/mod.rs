@@ -1 +1,4 @@
 pub mod grpc;
+
+#[cfg(test)]
+mod grpc_tests;
@@ -1,29 +1,41 @@
 use libargondb::{
-    ArgonFs, ArgonFsConfig, Catalog, DbCtx,
+    ArgonFs, Catalog, DbCtx,
     kv::{
-        KVColumnFilter, KVInstance, KVInstanceStateSnapshot, KVPrimaryKeyMarker, KVRangeScan,
-        KVTable, KVTableId, KVTableName, KVTableSchema,
-        column_type::{ColumnTypeCode, ColumnTypeText, ColumnTypeU16, ColumnTypeU16Array},
-        config::KVConfig,
-        schema::KVColumnSchema,
+        KVColumnFilter, KVCompactionOptions, KVInstance, KVInstanceStateSnapshot,
+        KVPrimaryKeyMarker, KVRangeScan, KVRow, KVTable, KVTableId, KVTableName, KVTableOptions,
+        KVTableSchema,
+        column_type::{
+            ColumnTypeBool, ColumnTypeBytes, ColumnTypeCode, ColumnTypeText, ColumnTypeU16,
+            ColumnTypeU16Array, ColumnTypeU64,
+        },
+        commit_log::KVCommitLogRecord,
+        schema::{KVColumnRetention, KVColumnSchema},
     },
     persistence::BoxPersistenceLayer,
 };
 use smol::block_on;
-use std::{str::FromStr, sync::Arc, thread};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+    sync::Arc,
+    thread,
+    time::Duration,
+};
 
 use crate::{
+    config::ArgonDbConfig,
     errors::{CriticalError, CriticalResult, OrCriticalError},
+    ops::delete_argonsys_columns,
     system_tables::{
-        ArgonsysColumnsColumns, ArgonsysTablesColumns, SystemTableIds, SystemTableNames,
-        SystemTableSchemas,
+        ArgonsysColumnsColumns, ArgonsysTablesColumns, CompactionStrategyNames, SystemTableIds,
+        SystemTableNames, SystemTableSchemas,
     },
 };
 
-pub fn run_init_thread() -> CriticalResult<Arc<DbCtx>> {
+pub fn run_init_thread(config: ArgonDbConfig) -> CriticalResult<Arc<DbCtx>> {
     println!("spawning init thread");
 
-    let thread_handle = thread::spawn(init_thread);
+    let thread_handle = thread::spawn(move || init_thread(&config));
 
     let init_thread_result = thread_handle
         .join()
@@ -38,38 +50,73 @@ pub fn run_init_thread() -> CriticalResult<Arc<DbCtx>> {
     Ok(db_ctx)
 }
 
-fn init_thread() -> CriticalResult<Arc<DbCtx>> {
+fn init_thread(config: &ArgonDbConfig) -> CriticalResult<Arc<DbCtx>> {
     println!("init thread - running initialization procedure");
 
-    let db_ctx = init_db_ctx()?;
+    let db_ctx = init_db_ctx(config)?;
     println!("init thread - database context initialized");
 
+    let commit_log_records = block_on(db_ctx.persistence.read_commit_log()).ok_or_critical_err()?;
+    println!(
+        "init thread - found {} commit log records to replay",
+        commit_log_records.len()
+    );
+
     init_system_tables(&db_ctx)?;
     println!("init thread - system tables initialized");
 
+    // System tables must be recovered first, user tables are loaded from their contents
+    let commit_log_records = replay_commit_log(&db_ctx, commit_log_records)?;
+
     init_user_tables(&db_ctx)?;
     println!("init thread - user tables initialized");
 
+    remove_dropped_tables_storage(&db_ctx)?;
+
+    let commit_log_records = replay_commit_log(&db_ctx, commit_log_records)?;
+    log_skipped_commit_log_records(&commit_log_records);
+
+    block_on(db_ctx.persistence.remove_replayed_commit_log()).ok_or_critical_err()?;
+    println!("init thread - commit log replayed");
+
     println!("init thread - initialization finished");
     Ok(db_ctx)
 }
 
-pub fn init_db_ctx() -> CriticalResult<Arc<DbCtx>> {
-    let argon_fs_config = ArgonFsConfig::default();
-    let argon_fs = ArgonFs::init(argon_fs_config).ok_or_critical_err()?;
+pub fn init_db_ctx(config: &ArgonDbConfig) -> CriticalResult<Arc<DbCtx>> {
+    let argon_fs = ArgonFs::init(config.argon_fs.clone()).ok_or_critical_err()?;
 
+    let commit_log = argon_fs.commit_log();
+    let object_id_lease_store = argon_fs.object_id_lease_store();
     let persistence: Arc<BoxPersistenceLayer> = Arc::new(Box::new(argon_fs));
 
-    let kv_config = KVConfig::default();
-    let initial_snapshot = block_on(persistence.read_instance_snapshot())
+    let kv_config = config.kv.clone();
+    let mut initial_snapshot = block_on(persistence.read_instance_snapshot())
         .ok_or_critical_err()?
         .unwrap_or_else(|| KVInstanceStateSnapshot::new());
 
+    // Snapshot is never behind the files on disk unless it was lost or written by an older version
+    let max_object_id = block_on(persistence.scan_max_object_id()).ok_or_critical_err()?;
+    if let Some(max_object_id) = max_object_id
+        && max_object_id.0 >= initial_snapshot.object_id_generator_state
+    {
+        println!(
+            "init thread - instance state snapshot is behind object id {} found on disk - advancing",
+            max_object_id
+        );
+        initial_snapshot.object_id_generator_state = max_object_id.0 + 1;
+    }
+
     println!(
         "init thread - initializing instance with state snapshot {:?}",
         initial_snapshot
     );
-    let kv_instance = Arc::new(KVInstance::new(kv_config, initial_snapshot));
+    let kv_instance = Arc::new(KVInstance::new(
+        kv_config,
+        initial_snapshot,
+        commit_log,
+        object_id_lease_store,
+    ));
 
     let catalog = Arc::new(Catalog::new());
 
@@ -88,6 +135,7 @@ pub fn init_system_tables(db_ctx: &DbCtx) -> CriticalResult<()> {
         &SystemTableIds::ARGONSYS_TABLES,
         &SystemTableNames::ARGONSYS_TABLES,
         SystemTableSchemas::schema_argonsys_tables()?,
+        KVTableOptions::default(),
     )?;
 
     add_table(
@@ -95,6 +143,7 @@ pub fn init_system_tables(db_ctx: &DbCtx) -> CriticalResult<()> {
         &SystemTableIds::ARGONSYS_COLUMNS,
         &SystemTableNames::ARGONSYS_COLUMNS,
         SystemTableSchemas::schema_argonsys_columns()?,
+        KVTableOptions::default(),
     )?;
 
     Ok(())
@@ -104,31 +153,99 @@ pub fn init_user_tables(db_ctx: &DbCtx) -> CriticalResult<()> {
     let user_tables = block_on(scan_user_tables(db_ctx))?;
     println!("init thread - found {} user tables", user_tables.len());
 
-    for (table_id, table_name, primary_key) in user_tables {
+    for (table_id, table_name, primary_key, table_options) in user_tables {
         println!("init thread - processing user table {}", table_name);
 
-        let columns = block_on(scan_user_table_columns(db_ctx, &table_id))?;
-        let columns = columns
-            .into_iter()
-            .map(|(column_id, column_name, column_type)| KVColumnSchema {
-                column_id,
-                column_name,
-                column_type,
-            })
-            .collect();
+        let (columns, retired_column_ids) = block_on(scan_user_table_columns(db_ctx, &table_id))?;
+
+        let table_schema = KVTableSchema::build(columns, primary_key.into_vec())
+            .and_then(|table_schema| table_schema.with_retired_column_ids(retired_column_ids))
+            .ok_or_critical_err()?;
 
-        let table_schema =
-            KVTableSchema::build(columns, primary_key.into_vec()).ok_or_critical_err()?;
+        add_table(db_ctx, &table_id, &table_name, table_schema, table_options)?;
+    }
+
+    Ok(())
+}
+
+/**
+ * Removes files and column rows of tables whose drop or truncation was interrupted before those
+ * were removed.
+ */
+pub(crate) fn remove_dropped_tables_storage(db_ctx: &DbCtx) -> CriticalResult<()> {
+    let table_ids = block_on(db_ctx.persistence.scan_table_ids()).ok_or_critical_err()?;
+
+    for table_id in table_ids {
+        if db_ctx.catalog.lookup_table_by_id(&table_id).is_some() {
+            continue;
+        }
 
-        add_table(db_ctx, &table_id, &table_name, table_schema)?;
+        println!(
+            "init thread - removing storage of dropped table[table_id={}]",
+            table_id.as_ref()
+        );
+        block_on(db_ctx.persistence.remove_table_storage(&table_id)).ok_or_critical_err()?;
+    }
+
+    for table_id in block_on(scan_dropped_tables_column_table_ids(db_ctx))? {
+        let table_id = KVTableId::from_str(&table_id).ok_or_critical_err()?;
+
+        println!(
+            "init thread - removing columns of dropped table[table_id={}]",
+            table_id.as_ref()
+        );
+        block_on(delete_argonsys_columns(db_ctx, &table_id)).map_err(|e| {
+            CriticalError::from_msg(format!(
+                "failed to remove columns of dropped table - {:?}",
+                e
+            ))
+        })?;
     }
 
     Ok(())
 }
 
-async fn scan_user_tables(
+/** IDs of tables which are not loaded, yet rows of their columns are left in system tables. */
+async fn scan_dropped_tables_column_table_ids(db_ctx: &DbCtx) -> CriticalResult<BTreeSet<String>> {
+    let argonsys_columns = db_ctx
+        .catalog
+        .lookup_table_by_name(&SystemTableNames::ARGONSYS_COLUMNS)
+        .ok_or(CriticalError::from_msg("argonsys columns critical error"))?;
+    let table_schema = argonsys_columns.table_schema();
+
+    let table_id_column_id = table_schema
+        .lookup_by_name(ArgonsysColumnsColumns::TABLE_ID)
+        .ok_or(CriticalError::from_msg("argonsys columns critical error"))?
+        .column_id;
+
+    let mut scan = argonsys_columns
+        .scan(KVRangeScan::new(
+            KVTableSchema::clone(&table_schema),
+            KVPrimaryKeyMarker::Start,
+            KVPrimaryKeyMarker::End,
+            KVColumnFilter::Columns(vec![table_id_column_id]),
+        ))
+        .await
+        .ok_or_critical_err()?;
+
+    let mut table_ids = BTreeSet::new();
+    while let Some(row) = scan.next_row().await.ok_or_critical_err()? {
+        let table_id_str = row
+            .column_deserialized::<ColumnTypeText>(ArgonsysColumnsColumns::TABLE_ID)
+            .ok_or_critical_err()?;
+        let table_id = KVTableId::from_str(&table_id_str).ok_or_critical_err()?;
+
+        if db_ctx.catalog.lookup_table_by_id(&table_id).is_none() {
+            table_ids.insert(table_id_str);
+        }
+    }
+
+    Ok(table_ids)
+}
+
+pub(crate) async fn scan_user_tables(
     db_ctx: &DbCtx,
-) -> CriticalResult<Vec<(KVTableId<'_>, KVTableName<'_>, Box<[u16]>)>> {
+) -> CriticalResult<Vec<(KVTableId<'_>, KVTableName<'_>, Box<[u16]>, KVTableOptions)>> {
     let argonsys_tables = db_ctx
         .catalog
         .lookup_table_by_name(&SystemTableNames::ARGONSYS_TABLES)
@@ -136,7 +253,7 @@ async fn scan_user_tables(
 
     let mut scan = argonsys_tables
         .scan(KVRangeScan::new(
-            argonsys_tables.table_schema.clone(),
+            KVTableSchema::clone(&argonsys_tables.table_schema()),
             KVPrimaryKeyMarker::Start,
             KVPrimaryKeyMarker::End,
             KVColumnFilter::All,
@@ -144,7 +261,7 @@ async fn scan_user_tables(
         .await
         .ok_or_critical_err()?;
 
-    let mut user_tables = Vec::<(KVTableId, KVTableName, Box<[u16]>)>::new();
+    let mut user_tables = Vec::<(KVTableId, KVTableName, Box<[u16]>, KVTableOptions)>::new();
     while let Some(row) = scan.next_row().await.ok_or_critical_err()? {
         let table_id_str = row
             .column_deserialized::<ColumnTypeText>(ArgonsysTablesColumns::TABLE_ID)
@@ -160,16 +277,18 @@ async fn scan_user_tables(
             .column_deserialized::<ColumnTypeU16Array>(ArgonsysTablesColumns::PRIMARY_KEY)
             .ok_or_critical_err()?;
 
-        user_tables.push((table_id, table_name, primary_key));
+        let table_options = read_table_options(&row)?;
+
+        user_tables.push((table_id, table_name, primary_key, table_options));
     }
 
     Ok(user_tables)
 }
 
-async fn scan_user_table_columns(
+pub(crate) async fn scan_user_table_columns(
     db_ctx: &DbCtx,
     table_id: &KVTableId<'_>,
-) -> CriticalResult<Vec<(u16, String, ColumnTypeCode)>> {
+) -> CriticalResult<(Vec<KVColumnSchema>, Vec<u16>)> {
     let argonsys_columns = db_ctx
         .catalog
         .lookup_table_by_name(&SystemTableNames::ARGONSYS_COLUMNS)
@@ -178,7 +297,7 @@ async fn scan_user_table_columns(
     // TODO: Should be replaced with more advanced query
     let mut scan = argonsys_columns
         .scan(KVRangeScan::new(
-            argonsys_columns.table_schema.clone(),
+            KVTableSchema::clone(&argonsys_columns.table_schema()),
             KVPrimaryKeyMarker::Start,
             KVPrimaryKeyMarker::End,
             KVColumnFilter::All,
@@ -186,7 +305,8 @@ async fn scan_user_table_columns(
         .await
         .ok_or_critical_err()?;
 
-    let mut user_table_columns = Vec::<(u16, String, ColumnTypeCode)>::new();
+    let mut user_table_columns = Vec::<KVColumnSchema>::new();
+    let mut retired_column_ids = Vec::<u16>::new();
     while let Some(row) = scan.next_row().await.ok_or_critical_err()? {
         let table_id_str = row
             .column_deserialized::<ColumnTypeText>(ArgonsysColumnsColumns::TABLE_ID)
@@ -207,12 +327,181 @@ async fn scan_user_table_columns(
         let column_type_code =
             ColumnTypeCode::try_from(column_type_code as u8).ok_or_critical_err()?;
 
-        if table_id.eq(&column_table_id) {
-            user_table_columns.push((column_id, column_name_str, column_type_code));
+        let retention = read_column_retention(&row)?;
+        let (nullable, default_value) = read_column_constraints(&row)?;
+
+        let retired = row.has_cell_by_name(ArgonsysColumnsColumns::RETIRED)
+            && row
+                .column_deserialized::<ColumnTypeBool>(ArgonsysColumnsColumns::RETIRED)
+                .ok_or_critical_err()?;
+
+        if !table_id.eq(&column_table_id) {
+            continue;
+        }
+
+        if retired {
+            retired_column_ids.push(column_id);
+        } else {
+            user_table_columns.push(KVColumnSchema {
+                column_id,
+                column_name: column_name_str,
+                column_type: column_type_code,
+                retention,
+                nullable,
+                default_value,
+            });
+        }
+    }
+
+    Ok((user_table_columns, retired_column_ids))
+}
+
+fn read_table_options(row: &KVRow) -> CriticalResult<KVTableOptions> {
+    let mut table_options = KVTableOptions {
+        default_retention: read_table_default_retention(row)?,
+        ..Default::default()
+    };
+
+    if !row.has_cell_by_name(ArgonsysTablesColumns::COMPACTION_STRATEGY) {
+        return Ok(table_options);
+    }
+
+    let compaction_strategy = row
+        .column_deserialized::<ColumnTypeText>(ArgonsysTablesColumns::COMPACTION_STRATEGY)
+        .ok_or_critical_err()?;
+
+    table_options.compaction = match compaction_strategy.as_str() {
+        CompactionStrategyNames::SIZE_TIERED => KVCompactionOptions::SizeTiered,
+        CompactionStrategyNames::LEVELED => KVCompactionOptions::Leveled,
+        CompactionStrategyNames::TIME_WINDOW => {
+            let window_seconds = row
+                .column_deserialized::<ColumnTypeU64>(
+                    ArgonsysTablesColumns::COMPACTION_WINDOW_SECONDS,
+                )
+                .ok_or_critical_err()?;
+
+            KVCompactionOptions::TimeWindow {
+                window: Duration::from_secs(window_seconds),
+            }
+        }
+        _ => return Err(CriticalError::from_msg("unknown compaction strategy")),
+    };
+
+    Ok(table_options)
+}
+
+fn read_table_default_retention(row: &KVRow) -> CriticalResult<KVColumnRetention> {
+    let mut retention = KVColumnRetention::default();
+
+    if row.has_cell_by_name(ArgonsysTablesColumns::DEFAULT_MAX_VERSIONS) {
+        retention.max_versions = row
+            .column_deserialized::<ColumnTypeU16>(ArgonsysTablesColumns::DEFAULT_MAX_VERSIONS)
+            .ok_or_critical_err()?;
+    }
+
+    if row.has_cell_by_name(ArgonsysTablesColumns::DEFAULT_MIN_VERSIONS) {
+        retention.min_versions = row
+            .column_deserialized::<ColumnTypeU16>(ArgonsysTablesColumns::DEFAULT_MIN_VERSIONS)
+            .ok_or_critical_err()?;
+    }
+
+    if row.has_cell_by_name(ArgonsysTablesColumns::DEFAULT_TTL_SECONDS) {
+        let ttl_seconds = row
+            .column_deserialized::<ColumnTypeU64>(ArgonsysTablesColumns::DEFAULT_TTL_SECONDS)
+            .ok_or_critical_err()?;
+
+        if ttl_seconds > 0 {
+            retention.ttl = Some(Duration::from_secs(ttl_seconds));
+        }
+    }
+
+    Ok(retention)
+}
+
+fn read_column_retention(row: &KVRow) -> CriticalResult<KVColumnRetention> {
+    let mut retention = KVColumnRetention::default();
+
+    if row.has_cell_by_name(ArgonsysColumnsColumns::MAX_VERSIONS) {
+        retention.max_versions = row
+            .column_deserialized::<ColumnTypeU16>(ArgonsysColumnsColumns::MAX_VERSIONS)
+            .ok_or_critical_err()?;
+    }
+
+    if row.has_cell_by_name(ArgonsysColumnsColumns::MIN_VERSIONS) {
+        retention.min_versions = row
+            .column_deserialized::<ColumnTypeU16>(ArgonsysColumnsColumns::MIN_VERSIONS)
+            .ok_or_critical_err()?;
+    }
+
+    if row.has_cell_by_name(ArgonsysColumnsColumns::TTL_SECONDS) {
+        let ttl_seconds = row
+            .column_deserialized::<ColumnTypeU64>(ArgonsysColumnsColumns::TTL_SECONDS)
+            .ok_or_critical_err()?;
+
+        if ttl_seconds > 0 {
+            retention.ttl = Some(Duration::from_secs(ttl_seconds));
         }
     }
 
-    Ok(user_table_columns)
+    Ok(retention)
+}
+
+fn read_column_constraints(row: &KVRow) -> CriticalResult<(bool, Option<Box<[u8]>>)> {
+    let nullable = if row.has_cell_by_name(ArgonsysColumnsColumns::NULLABLE) {
+        row.column_deserialized::<ColumnTypeBool>(ArgonsysColumnsColumns::NULLABLE)
+            .ok_or_critical_err()?
+    } else {
+        true
+    };
+
+    let default_value = if row.has_cell_by_name(ArgonsysColumnsColumns::DEFAULT_VALUE) {
+        Some(
+            row.column_deserialized::<ColumnTypeBytes>(ArgonsysColumnsColumns::DEFAULT_VALUE)
+                .ok_or_critical_err()?,
+        )
+    } else {
+        None
+    };
+
+    Ok((nullable, default_value))
+}
+
+/**
+ * Re-inserts commit log records into memtables of loaded tables. Replayed mutations are logged
+ * again, so the old log can be removed afterwards. Records of tables not loaded yet are returned.
+ */
+pub fn replay_commit_log(
+    db_ctx: &DbCtx,
+    records: Vec<KVCommitLogRecord>,
+) -> CriticalResult<Vec<KVCommitLogRecord>> {
+    let mut remaining_records = vec![];
+
+    for record in records {
+        let Some(table) = db_ctx.catalog.lookup_table_by_id(&record.table_id) else {
+            remaining_records.push(record);
+            continue;
+        };
+
+        block_on(table.replay_batch(&record.mutations, &record.range_tombstones))
+            .ok_or_critical_err()?;
+    }
+
+    Ok(remaining_records)
+}
+
+/** Logs records left after the replay, these belong to dropped tables or tables never created. */
+fn log_skipped_commit_log_records(records: &[KVCommitLogRecord]) {
+    let mut skipped_counts = BTreeMap::<&str, usize>::new();
+    for record in records {
+        *skipped_counts.entry(record.table_id.as_ref()).or_default() += 1;
+    }
+
+    for (table_id, skipped_count) in skipped_counts {
+        println!(
+            "init thread - skipped {} commit log records of unknown table {}",
+            skipped_count, table_id
+        );
+    }
 }
 
 pub fn add_table(
@@ -220,6 +509,7 @@ pub fn add_table(
     table_id: &KVTableId,
     table_name: &KVTableName,
     table_schema: KVTableSchema,
+    table_options: KVTableOptions,
 ) -> CriticalResult<()> {
     let scan_result = block_on(
         db_ctx
@@ -233,9 +523,10 @@ pub fn add_table(
         table_id.to_owned(),
         table_name.to_owned(),
         table_schema,
+        table_options,
         sstables,
     ));
-    table.open();
+    table.open().ok_or_critical_err()?;
 
     db_ctx.catalog.add_table(table);
 
@@ -244,17 +535,16 @@ pub fn add_table(
 
 #[cfg(test)]
 mod tests {
-    use crate::ops::{CreateTableOp, CreateTableOpColumn};
+    use crate::{
+        ops::{CreateTableOp, CreateTableOpColumn},
+        test_utils::test_db_ctx,
+    };
 
     use super::*;
 
     #[test]
     pub fn test_skiplist_columns() {
-        let db_ctx = init_db_ctx().unwrap();
-        println!("init thread - database context initialized");
-
-        init_system_tables(&db_ctx).unwrap();
-        println!("init thread - system tables initialized");
+        let db_ctx = test_db_ctx("skiplist_columns");
 
         block_on(
             CreateTableOp {
@@ -263,13 +553,20 @@ mod tests {
                     CreateTableOpColumn {
                         column_name: "id".to_string(),
                         column_type: ColumnTypeCode::Text,
+                        retention: KVColumnRetention::default(),
+                        nullable: true,
+                        default_value: None,
                     },
                     CreateTableOpColumn {
                         column_name: "value".to_string(),
                         column_type: ColumnTypeCode::U16,
+                        retention: KVColumnRetention::default(),
+                        nullable: true,
+                        default_value: None,
                     },
                 ],
                 primary_key: vec!["id".to_string()],
+                table_options: KVTableOptions::default(),
             }
             .execute(&db_ctx),
         )
@@ -281,14 +578,30 @@ mod tests {
             .unwrap();
 
         let mut iter = block_on(table_argonsys_columns.scan(KVRangeScan::new(
-            table_argonsys_columns.table_schema.clone(),
+            KVTableSchema::clone(&table_argonsys_columns.table_schema()),
             KVPrimaryKeyMarker::Start,
             KVPrimaryKeyMarker::End,
             KVColumnFilter::All,
         )))
         .unwrap();
 
-        dbg!(block_on(iter.next_row()).unwrap());
-        dbg!(block_on(iter.next_row()).unwrap());
+        let mut columns = vec![];
+        while let Some(row) = block_on(iter.next_row()).unwrap() {
+            columns.push((
+                row.column_deserialized::<ColumnTypeText>(ArgonsysColumnsColumns::COLUMN_NAME)
+                    .unwrap(),
+                row.column_deserialized::<ColumnTypeU16>(ArgonsysColumnsColumns::COLUMN_TYPE)
+                    .unwrap(),
+            ));
+        }
+
+        // Columns of the created table are read back from the memtable
+        assert_eq!(
+            columns,
+            vec![
+                ("id".to_string(), ColumnTypeCode::Text as u16),
+                ("value".to_string(), ColumnTypeCode::U16 as u16),
+            ]
+        );
     }
 }
@@ -0,0 +1,261 @@
+use std::time::Duration;
+
+use libargondb::kv::{
+    KVColumnFilter, KVColumnValueBuilder, KVCompactionOptions, KVPrimaryKeyMarker, KVRangeScan,
+    KVTableOptions, KVTableSchema,
+    column_type::{ColumnTypeCode, ColumnTypeSerialize, ColumnTypeU16},
+    schema::KVColumnRetention,
+};
+use smol::block_on;
+
+use crate::{
+    init::{remove_dropped_tables_storage, scan_user_table_columns, scan_user_tables},
+    ops::{CreateTableOp, CreateTableOpColumn, InsertIntoOp},
+    system_tables::{ArgonsysColumnsColumns, SystemTableNames},
+    test_utils::test_db_ctx,
+};
+
+#[test]
+fn test_column_projection() {
+    let db_ctx = test_db_ctx("column-projection");
+
+    block_on(
+        CreateTableOp {
+            table_name: "projected".to_string(),
+            columns: vec![
+                CreateTableOpColumn {
+                    column_name: "id".to_string(),
+                    column_type: ColumnTypeCode::Text,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
+                },
+                CreateTableOpColumn {
+                    column_name: "value".to_string(),
+                    column_type: ColumnTypeCode::U16,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
+                },
+            ],
+            primary_key: vec!["id".to_string()],
+            table_options: KVTableOptions::default(),
+        }
+        .execute(&db_ctx),
+    )
+    .unwrap();
+
+    let table_argonsys_columns = db_ctx
+        .catalog
+        .lookup_table_by_name(&SystemTableNames::ARGONSYS_COLUMNS)
+        .unwrap();
+
+    let schema = table_argonsys_columns.table_schema();
+    let column_name_id = schema
+        .lookup_by_name(ArgonsysColumnsColumns::COLUMN_NAME)
+        .unwrap()
+        .column_id;
+
+    let mut iter = block_on(table_argonsys_columns.scan(KVRangeScan::new(
+        KVTableSchema::clone(&schema),
+        KVPrimaryKeyMarker::Start,
+        KVPrimaryKeyMarker::End,
+        KVColumnFilter::Columns(vec![column_name_id]),
+    )))
+    .unwrap();
+
+    let row = block_on(iter.next_row()).unwrap().unwrap();
+    assert!(row.has_cell(column_name_id));
+    assert!(!row.has_cell_by_name(ArgonsysColumnsColumns::COLUMN_TYPE));
+}
+
+#[test]
+fn test_table_options_read_back() {
+    let db_ctx = test_db_ctx("table-options");
+
+    let compactions = [
+        ("size_tiered", KVCompactionOptions::SizeTiered),
+        ("leveled", KVCompactionOptions::Leveled),
+        (
+            "time_window",
+            KVCompactionOptions::TimeWindow {
+                window: Duration::from_secs(3600),
+            },
+        ),
+    ];
+
+    for (table_name, compaction) in compactions {
+        block_on(
+            CreateTableOp {
+                table_name: table_name.to_string(),
+                columns: vec![CreateTableOpColumn {
+                    column_name: "id".to_string(),
+                    column_type: ColumnTypeCode::Text,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
+                }],
+                primary_key: vec!["id".to_string()],
+                table_options: KVTableOptions {
+                    compaction,
+                    ..Default::default()
+                },
+            }
+            .execute(&db_ctx),
+        )
+        .unwrap();
+    }
+
+    let default_retention = KVColumnRetention {
+        max_versions: 3,
+        min_versions: 1,
+        ttl: Some(Duration::from_secs(60)),
+    };
+    block_on(
+        CreateTableOp {
+            table_name: "retained".to_string(),
+            columns: vec![CreateTableOpColumn {
+                column_name: "id".to_string(),
+                column_type: ColumnTypeCode::Text,
+                retention: KVColumnRetention::default(),
+                nullable: true,
+                default_value: None,
+            }],
+            primary_key: vec!["id".to_string()],
+            table_options: KVTableOptions {
+                default_retention,
+                ..Default::default()
+            },
+        }
+        .execute(&db_ctx),
+    )
+    .unwrap();
+
+    // Row of a table created before compaction and default retention columns existed
+    block_on(
+        InsertIntoOp {
+            table_name: SystemTableNames::ARGONSYS_TABLES.to_string(),
+            values: vec![
+                (
+                    "table_id".into(),
+                    KVColumnValueBuilder::text("legacy".into()),
+                ),
+                (
+                    "table_name".into(),
+                    KVColumnValueBuilder::text("legacy".into()),
+                ),
+                (
+                    "primary_key".into(),
+                    KVColumnValueBuilder::u16_array(vec![1]),
+                ),
+            ],
+        }
+        .execute(&db_ctx),
+    )
+    .unwrap();
+
+    let user_tables = block_on(scan_user_tables(&db_ctx)).unwrap();
+    let table_options = |table_name: &str| {
+        user_tables
+            .iter()
+            .find(|(_, name, _, _)| name.to_string() == table_name)
+            .map(|(_, _, _, table_options)| *table_options)
+            .unwrap()
+    };
+
+    for (table_name, compaction) in compactions {
+        assert_eq!(table_options(table_name).compaction, compaction);
+    }
+    assert_eq!(
+        table_options("retained").default_retention,
+        default_retention
+    );
+    assert_eq!(table_options("legacy"), KVTableOptions::default());
+}
+
+#[test]
+fn test_column_constraints_read_back() {
+    let db_ctx = test_db_ctx("column-constraints");
+
+    let column = |column_name: &str, nullable, default_value| CreateTableOpColumn {
+        column_name: column_name.to_string(),
+        column_type: ColumnTypeCode::U16,
+        retention: KVColumnRetention::default(),
+        nullable,
+        default_value,
+    };
+
+    let table = block_on(
+        CreateTableOp {
+            table_name: "constrained".to_string(),
+            columns: vec![
+                column("id", true, None),
+                column("nullable", true, None),
+                column("not_null", false, None),
+                column("defaulted", false, Some(KVColumnValueBuilder::u16(7))),
+            ],
+            primary_key: vec!["id".to_string()],
+            table_options: KVTableOptions::default(),
+        }
+        .execute(&db_ctx),
+    )
+    .unwrap();
+
+    let (columns, retired_column_ids) =
+        block_on(scan_user_table_columns(&db_ctx, &table.table_id)).unwrap();
+    assert!(retired_column_ids.is_empty());
+
+    let constraints = |column_name: &str| {
+        columns
+            .iter()
+            .find(|column| column.column_name == column_name)
+            .map(|column| (column.nullable, column.default_value.clone()))
+            .unwrap()
+    };
+
+    // Primary key columns are never null
+    assert_eq!(constraints("id"), (false, None));
+    assert_eq!(constraints("nullable"), (true, None));
+    assert_eq!(constraints("not_null"), (false, None));
+    assert_eq!(
+        constraints("defaulted"),
+        (false, Some(ColumnTypeU16::serialize(7).unwrap()))
+    );
+}
+
+#[test]
+fn test_dropped_table_columns_removed() {
+    let db_ctx = test_db_ctx("dropped-table-columns");
+
+    let create_table = |table_name: &str| {
+        block_on(
+            CreateTableOp {
+                table_name: table_name.to_string(),
+                columns: vec![CreateTableOpColumn {
+                    column_name: "id".to_string(),
+                    column_type: ColumnTypeCode::U16,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
+                }],
+                primary_key: vec!["id".to_string()],
+                table_options: KVTableOptions::default(),
+            }
+            .execute(&db_ctx),
+        )
+        .unwrap()
+    };
+
+    let dropped = create_table("dropped");
+    let kept = create_table("kept");
+
+    // Table row is gone, while a crash left the rows of its columns behind
+    db_ctx.catalog.remove_table(&dropped.table_id).unwrap();
+    remove_dropped_tables_storage(&db_ctx).unwrap();
+
+    let (columns, _) = block_on(scan_user_table_columns(&db_ctx, &dropped.table_id)).unwrap();
+    assert!(columns.is_empty());
+
+    let (columns, _) = block_on(scan_user_table_columns(&db_ctx, &kept.table_id)).unwrap();
+    assert_eq!(columns.len(), 1);
+}
@@ -1,3 +1,4 @@
+mod config;
 mod connectors;
 mod errors;
 mod exit;
@@ -8,9 +9,17 @@ mod signals_handler;
 mod supervisor;
 mod system_tables;
 
+#[cfg(test)]
+mod config_tests;
+#[cfg(test)]
+mod init_tests;
+#[cfg(test)]
+mod test_utils;
+
 use libargondb::{ArgonFsMemtableFlusher, SSTableCompactor};
 
 use crate::{
+    config::ArgonDbConfig,
     connectors::grpc::init_connector_grpc,
     errors::{OkOrAbort, OrCriticalError},
     init::run_init_thread,
@@ -21,15 +30,22 @@ use crate::{
 
 fn main() {
     println!("argondb is starting");
-    let db_ctx = run_init_thread().ok_or_abort();
+    let config = ArgonDbConfig::load().ok_or_abort();
+    config.print();
+
+    let db_ctx = run_init_thread(config.clone()).ok_or_abort();
 
     let memtable_flusher_handle = ArgonFsMemtableFlusher::new(db_ctx.clone());
 
-    let sstable_compactor_handle = SSTableCompactor::new(db_ctx.clone());
+    let sstable_compactor_handle = SSTableCompactor::new(db_ctx.clone(), config.compactor.clone());
 
-    let connector_handle = init_connector_grpc(db_ctx.clone())
-        .ok_or_critical_err()
-        .ok_or_abort();
+    let connector_handle = init_connector_grpc(
+        db_ctx.clone(),
+        sstable_compactor_handle.client(),
+        &config.grpc,
+    )
+    .ok_or_critical_err()
+    .ok_or_abort();
 
     let system_ctx = SystemCtx {
         db_ctx: db_ctx.clone(),
@@ -0,0 +1,195 @@
+use std::{str::FromStr, sync::Arc};
+
+use libargondb::{
+    DbCtx,
+    kv::{
+        KVAlterSchemaError, KVTable, KVTableName, KVTableSchema,
+        schema::{KVColumnSchema, KVTableSchemaBuildError},
+    },
+};
+
+use crate::ops::{
+    CreateTableOpColumn,
+    create_table::{argonsys_column_row, insert_argonsys_column_rows},
+};
+
+#[derive(Debug)]
+pub enum AlterTableOpError {
+    InvalidTableName,
+    TableNotFound,
+    ColumnNotFound,
+    ColumnAlreadyExists,
+    PrimaryKeyColumnDrop,
+    PrimaryKeyChanged,
+    InvalidRetention,
+    InvalidDefaultValue,
+    /** Rows written before the column was added would violate the constraint. */
+    NotNullWithoutDefault,
+    TooManyColumns,
+    SchemaError,
+    PersistFailed,
+}
+
+pub enum AlterTableOpColumnChange {
+    Add(CreateTableOpColumn),
+    Drop {
+        column_name: String,
+    },
+    Rename {
+        column_name: String,
+        new_column_name: String,
+    },
+}
+
+/**
+ * Changes are applied in order and become visible at once, existing Argonfiles are left as they
+ * are. Added columns get IDs never used by the table, dropped columns keep their IDs retired so
+ * their cells are skipped by reads and purged by compaction.
+ */
+pub struct AlterTableOp {
+    pub table_name: String,
+    pub changes: Vec<AlterTableOpColumnChange>,
+}
+
+impl AlterTableOp {
+    pub async fn execute(&self, db_ctx: &DbCtx) -> Result<Arc<KVTable>, AlterTableOpError> {
+        let table_name = KVTableName::from_str(&self.table_name)
+            .map_err(|_| AlterTableOpError::InvalidTableName)?;
+
+        let table = db_ctx
+            .catalog
+            .lookup_table_by_name(&table_name)
+            .ok_or(AlterTableOpError::TableNotFound)?;
+
+        table
+            .alter_schema(
+                |table_schema| self.apply_changes(table_schema),
+                |prev_schema, next_schema| {
+                    Self::persist_columns(db_ctx, &table, prev_schema, next_schema)
+                },
+            )
+            .await
+            .map_err(|e| match e {
+                KVAlterSchemaError::PrimaryKeyChanged => AlterTableOpError::PrimaryKeyChanged,
+                KVAlterSchemaError::Alter(e) => e,
+            })?;
+
+        Ok(table)
+    }
+
+    /**
+     * Writes rows of renamed and added columns anew and marks dropped ones as retired. Rows are
+     * written with a single commit log record before the schema is swapped, so a failed alter
+     * leaves both unchanged.
+     */
+    async fn persist_columns(
+        db_ctx: &DbCtx,
+        table: &KVTable,
+        prev_schema: Arc<KVTableSchema>,
+        next_schema: Arc<KVTableSchema>,
+    ) -> Result<(), AlterTableOpError> {
+        let mut column_rows = vec![];
+        for column in &next_schema.columns {
+            let is_unchanged = prev_schema
+                .lookup_by_column_id(column.column_id)
+                .is_some_and(|prev_column| prev_column.column_name == column.column_name);
+
+            if !is_unchanged {
+                column_rows.push(argonsys_column_row(&table.table_id, column, false));
+            }
+        }
+
+        for column in &prev_schema.columns {
+            if next_schema.is_retired(column.column_id) {
+                column_rows.push(argonsys_column_row(&table.table_id, column, true));
+            }
+        }
+
+        insert_argonsys_column_rows(db_ctx, &column_rows)
+            .await
+            .map_err(|_| AlterTableOpError::PersistFailed)
+    }
+
+    fn apply_changes(
+        &self,
+        table_schema: &KVTableSchema,
+    ) -> Result<KVTableSchema, AlterTableOpError> {
+        let mut columns = table_schema.columns.clone();
+        let mut retired_column_ids = table_schema.retired_column_ids.clone();
+        let mut next_column_id = table_schema.next_column_id();
+
+        let position = |columns: &Vec<KVColumnSchema>, column_name: &str| {
+            columns
+                .iter()
+                .position(|column| column.column_name == column_name)
+        };
+
+        for change in &self.changes {
+            match change {
+                AlterTableOpColumnChange::Add(column) => {
+                    if position(&columns, &column.column_name).is_some() {
+                        return Err(AlterTableOpError::ColumnAlreadyExists);
+                    }
+
+                    if !column.retention.is_valid() {
+                        return Err(AlterTableOpError::InvalidRetention);
+                    }
+
+                    if !column.nullable && column.default_value.is_none() {
+                        return Err(AlterTableOpError::NotNullWithoutDefault);
+                    }
+
+                    let column_id = next_column_id.ok_or(AlterTableOpError::TooManyColumns)?;
+                    next_column_id = column_id.checked_add(1);
+
+                    let default_value = column
+                        .default_value
+                        .as_ref()
+                        .map(|value| value.serialize())
+                        .transpose()
+                        .map_err(|_| AlterTableOpError::InvalidDefaultValue)?;
+
+                    columns.push(KVColumnSchema {
+                        column_id,
+                        column_name: column.column_name.clone(),
+                        column_type: column.column_type,
+                        retention: column.retention,
+                        nullable: column.nullable,
+                        default_value,
+                    });
+                }
+                AlterTableOpColumnChange::Drop { column_name } => {
+                    let idx =
+                        position(&columns, column_name).ok_or(AlterTableOpError::ColumnNotFound)?;
+                    let column_id = columns[idx].column_id;
+
+                    if table_schema.primary_key.contains(&column_id) {
+                        return Err(AlterTableOpError::PrimaryKeyColumnDrop);
+                    }
+
+                    columns.remove(idx);
+                    retired_column_ids.push(column_id);
+                }
+                AlterTableOpColumnChange::Rename {
+                    column_name,
+                    new_column_name,
+                } => {
+                    if position(&columns, new_column_name).is_some() {
+                        return Err(AlterTableOpError::ColumnAlreadyExists);
+                    }
+
+                    let idx =
+                        position(&columns, column_name).ok_or(AlterTableOpError::ColumnNotFound)?;
+                    columns[idx].column_name = new_column_name.clone();
+                }
+            }
+        }
+
+        KVTableSchema::build(columns, table_schema.primary_key.clone())
+            .and_then(|table_schema| table_schema.with_retired_column_ids(retired_column_ids))
+            .map_err(|e| match e {
+                KVTableSchemaBuildError::ColumnCountExceeded => AlterTableOpError::TooManyColumns,
+                _ => AlterTableOpError::SchemaError,
+            })
+    }
+}
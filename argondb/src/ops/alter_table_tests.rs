@@ -0,0 +1,186 @@
+use std::sync::Arc;
+
+use libargondb::{
+    DbCtx,
+    kv::{
+        KVColumnValueBuilder, KVTable, KVTableOptions, KVTableSchema, column_type::ColumnTypeCode,
+        schema::KVColumnRetention,
+    },
+};
+use smol::block_on;
+
+use crate::{
+    init::scan_user_table_columns,
+    ops::{
+        AlterTableOp, AlterTableOpColumnChange, AlterTableOpError, CreateTableOp,
+        CreateTableOpColumn,
+    },
+    test_utils::{TestDbCtx, test_db_ctx},
+};
+
+fn column(column_name: &str, nullable: bool) -> CreateTableOpColumn {
+    CreateTableOpColumn {
+        column_name: column_name.to_string(),
+        column_type: ColumnTypeCode::Text,
+        retention: KVColumnRetention::default(),
+        nullable,
+        default_value: None,
+    }
+}
+
+/** Table `altered` keyed by `id`, with columns `a` and `b`. */
+fn altered_table(name: &str) -> (TestDbCtx, Arc<KVTable>) {
+    let db_ctx = test_db_ctx(name);
+
+    let table = block_on(
+        CreateTableOp {
+            table_name: "altered".to_string(),
+            columns: vec![column("id", false), column("a", true), column("b", true)],
+            primary_key: vec!["id".to_string()],
+            table_options: KVTableOptions::default(),
+        }
+        .execute(&db_ctx),
+    )
+    .unwrap();
+
+    (db_ctx, table)
+}
+
+fn alter(db_ctx: &DbCtx, changes: Vec<AlterTableOpColumnChange>) -> Result<(), AlterTableOpError> {
+    block_on(
+        AlterTableOp {
+            table_name: "altered".to_string(),
+            changes,
+        }
+        .execute(db_ctx),
+    )
+    .map(|_| ())
+}
+
+fn column_names(table_schema: &KVTableSchema) -> Vec<(u16, String)> {
+    table_schema
+        .columns
+        .iter()
+        .map(|column| (column.column_id, column.column_name.clone()))
+        .collect()
+}
+
+/** Schema of the table as it is loaded on startup. */
+fn reloaded_schema(db_ctx: &DbCtx, table: &KVTable) -> KVTableSchema {
+    let (columns, retired_column_ids) =
+        block_on(scan_user_table_columns(db_ctx, &table.table_id)).unwrap();
+
+    KVTableSchema::build(columns, table.table_schema().primary_key.clone())
+        .and_then(|table_schema| table_schema.with_retired_column_ids(retired_column_ids))
+        .unwrap()
+}
+
+#[test]
+fn test_alter_table_adds_drops_and_renames_columns() {
+    let (db_ctx, table) = altered_table("alter-table-changes");
+
+    alter(
+        &db_ctx,
+        vec![
+            AlterTableOpColumnChange::Drop {
+                column_name: "a".to_string(),
+            },
+            AlterTableOpColumnChange::Rename {
+                column_name: "b".to_string(),
+                new_column_name: "a".to_string(),
+            },
+            AlterTableOpColumnChange::Add(column("c", true)),
+        ],
+    )
+    .unwrap();
+
+    // Dropped column ID is retired and never given to added columns
+    let table_schema = table.table_schema();
+    assert_eq!(
+        column_names(&table_schema),
+        vec![
+            (1, "id".to_string()),
+            (3, "a".to_string()),
+            (4, "c".to_string())
+        ]
+    );
+    assert_eq!(table_schema.retired_column_ids, vec![2]);
+
+    let reloaded = reloaded_schema(&db_ctx, &table);
+    assert_eq!(column_names(&reloaded), column_names(&table_schema));
+    assert_eq!(reloaded.retired_column_ids, vec![2]);
+
+    // Retired ID stays retired when a column of its name is added again
+    alter(
+        &db_ctx,
+        vec![AlterTableOpColumnChange::Add(column("b", true))],
+    )
+    .unwrap();
+    let reloaded = reloaded_schema(&db_ctx, &table);
+    assert_eq!(reloaded.lookup_by_name("b").unwrap().column_id, 5);
+    assert_eq!(reloaded.retired_column_ids, vec![2]);
+}
+
+#[test]
+fn test_failed_alter_table_leaves_schema() {
+    let (db_ctx, table) = altered_table("alter-table-failed");
+    let table_schema = table.table_schema();
+
+    let failed_changes = [
+        (
+            vec![
+                AlterTableOpColumnChange::Add(column("c", true)),
+                AlterTableOpColumnChange::Drop {
+                    column_name: "id".to_string(),
+                },
+            ],
+            "primary key drop",
+        ),
+        (
+            vec![AlterTableOpColumnChange::Add(column("c", false))],
+            "not null without default",
+        ),
+        (
+            vec![AlterTableOpColumnChange::Rename {
+                column_name: "a".to_string(),
+                new_column_name: "b".to_string(),
+            }],
+            "existing column name",
+        ),
+        (
+            vec![AlterTableOpColumnChange::Drop {
+                column_name: "missing".to_string(),
+            }],
+            "missing column",
+        ),
+    ];
+
+    for (changes, description) in failed_changes {
+        assert!(alter(&db_ctx, changes).is_err(), "{} accepted", description);
+    }
+
+    assert_eq!(
+        column_names(&table.table_schema()),
+        column_names(&table_schema)
+    );
+    assert_eq!(
+        column_names(&reloaded_schema(&db_ctx, &table)),
+        column_names(&table_schema)
+    );
+
+    // Not null columns may be added with a default
+    alter(
+        &db_ctx,
+        vec![AlterTableOpColumnChange::Add(CreateTableOpColumn {
+            default_value: Some(KVColumnValueBuilder::text("c".to_string())),
+            ..column("c", false)
+        })],
+    )
+    .unwrap();
+    let added_column = reloaded_schema(&db_ctx, &table)
+        .lookup_by_name("c")
+        .cloned()
+        .unwrap();
+    assert!(!added_column.nullable);
+    assert!(added_column.default_value.is_some());
+}
@@ -3,14 +3,16 @@ use std::{collections::BTreeMap, str::FromStr, sync::Arc, vec};
 use libargondb::{
     DbCtx,
     kv::{
-        KVColumnValueBuilder, KVTable, KVTableId, KVTableName, KVTableSchema,
-        column_type::ColumnTypeCode, schema::KVColumnSchema,
+        KVColumnValue, KVColumnValueBuilder, KVCompactionOptions, KVTable, KVTableId, KVTableName,
+        KVTableOptions, KVTableSchema,
+        column_type::ColumnTypeCode,
+        schema::{KVColumnRetention, KVColumnSchema},
     },
 };
 
 use crate::{
-    ops::insert_into::InsertIntoOp,
-    system_tables::{self, SystemTableNames},
+    ops::insert_into::{InsertIntoOp, InsertOpError},
+    system_tables::{CompactionStrategyNames, SystemTableNames},
 };
 
 #[derive(Debug)]
@@ -21,12 +23,18 @@ pub enum CreateTableOpError {
     PrimaryKeyMissing,
     PrimaryKeyColumnsCountExceeded,
     PrimaryKeyInvalidColumn,
+    InvalidRetention,
+    InvalidTableOptions,
+    InvalidDefaultValue,
+    PrimaryKeyDefaultValue,
+    PersistFailed,
 }
 
 pub struct CreateTableOp {
     pub table_name: String,
     pub columns: Vec<CreateTableOpColumn>,
     pub primary_key: Vec<String>,
+    pub table_options: KVTableOptions,
 }
 
 impl CreateTableOp {
@@ -46,16 +54,45 @@ impl CreateTableOp {
             return Err(CreateTableOpError::PrimaryKeyColumnsCountExceeded);
         }
 
+        if !self.table_options.default_retention.is_valid()
+            || self
+                .columns
+                .iter()
+                .any(|column| !column.retention.is_valid())
+        {
+            return Err(CreateTableOpError::InvalidRetention);
+        }
+
+        if !self.table_options.is_valid() {
+            return Err(CreateTableOpError::InvalidTableOptions);
+        }
+
         let mut next_column_id = 1u16;
         let mut columns = Vec::<KVColumnSchema>::new();
         let mut column_name_map = BTreeMap::<String, u16>::new();
         for column in &self.columns {
             let column_id = next_column_id;
+            let is_primary_key = self.primary_key.contains(&column.column_name);
+
+            // Primary key columns are always set, so a default would never be read
+            if is_primary_key && column.default_value.is_some() {
+                return Err(CreateTableOpError::PrimaryKeyDefaultValue);
+            }
+
+            let default_value = column
+                .default_value
+                .as_ref()
+                .map(|value| value.serialize())
+                .transpose()
+                .map_err(|_| CreateTableOpError::InvalidDefaultValue)?;
 
             columns.push(KVColumnSchema {
                 column_id,
                 column_name: column.column_name.clone(),
                 column_type: column.column_type,
+                retention: column.retention,
+                nullable: column.nullable && !is_primary_key,
+                default_value,
             });
             column_name_map.insert(column.column_name.clone(), column_id);
 
@@ -81,57 +118,25 @@ impl CreateTableOp {
             table_id.clone(),
             table_name.clone(),
             table_schema,
+            self.table_options,
             vec![],
         ));
-        table.open();
-
-        InsertIntoOp {
-            table_name: SystemTableNames::ARGONSYS_TABLES.to_string(),
-            values: vec![
-                (
-                    "table_id".into(),
-                    KVColumnValueBuilder::text(table_id.to_string()),
-                ),
-                (
-                    "table_name".into(),
-                    KVColumnValueBuilder::text(table_name.to_string()),
-                ),
-                (
-                    "primary_key".into(),
-                    KVColumnValueBuilder::u16_array(primary_key),
-                ),
-            ],
-        }
-        .execute(db_ctx)
-        .await
-        .unwrap();
-
-        for column in columns {
-            InsertIntoOp {
-                table_name: SystemTableNames::ARGONSYS_COLUMNS.to_string(),
-                values: vec![
-                    (
-                        "table_id".into(),
-                        KVColumnValueBuilder::text(table_id.to_string()),
-                    ),
-                    (
-                        "column_id".into(),
-                        KVColumnValueBuilder::u16(column.column_id),
-                    ),
-                    (
-                        "column_name".into(),
-                        KVColumnValueBuilder::text(column.column_name),
-                    ),
-                    (
-                        "column_type".into(),
-                        KVColumnValueBuilder::u16(column.column_type as u16),
-                    ),
-                ],
-            }
+        table
+            .open()
+            .map_err(|_| CreateTableOpError::PersistFailed)?;
+
+        argonsys_table_row(&table_id, &table_name, primary_key, self.table_options)
             .execute(db_ctx)
             .await
             .unwrap();
-        }
+
+        let column_rows: Vec<InsertIntoOp> = columns
+            .iter()
+            .map(|column| argonsys_column_row(&table_id, column, false))
+            .collect();
+        insert_argonsys_column_rows(db_ctx, &column_rows)
+            .await
+            .unwrap();
 
         db_ctx.catalog.add_table(table.clone());
 
@@ -142,4 +147,151 @@ impl CreateTableOp {
 pub struct CreateTableOpColumn {
     pub column_name: String,
     pub column_type: ColumnTypeCode,
+    pub retention: KVColumnRetention,
+    /** Ignored for primary key columns, those are never null. */
+    pub nullable: bool,
+    pub default_value: Option<Box<dyn KVColumnValue + Send + Sync + 'static>>,
+}
+
+/** Row of `_argonsys_tables` describing the table. */
+pub(super) fn argonsys_table_row(
+    table_id: &KVTableId<'_>,
+    table_name: &KVTableName<'_>,
+    primary_key: Vec<u16>,
+    table_options: KVTableOptions,
+) -> InsertIntoOp {
+    let (compaction_strategy, compaction_window_seconds) = match table_options.compaction {
+        KVCompactionOptions::SizeTiered => (CompactionStrategyNames::SIZE_TIERED, 0),
+        KVCompactionOptions::Leveled => (CompactionStrategyNames::LEVELED, 0),
+        KVCompactionOptions::TimeWindow { window } => {
+            (CompactionStrategyNames::TIME_WINDOW, window.as_secs())
+        }
+    };
+
+    InsertIntoOp {
+        table_name: SystemTableNames::ARGONSYS_TABLES.to_string(),
+        values: vec![
+            (
+                "table_id".into(),
+                KVColumnValueBuilder::text(table_id.to_string()),
+            ),
+            (
+                "table_name".into(),
+                KVColumnValueBuilder::text(table_name.to_string()),
+            ),
+            (
+                "primary_key".into(),
+                KVColumnValueBuilder::u16_array(primary_key),
+            ),
+            (
+                "compaction_strategy".into(),
+                KVColumnValueBuilder::text(compaction_strategy.to_string()),
+            ),
+            (
+                "compaction_window_seconds".into(),
+                KVColumnValueBuilder::u64(compaction_window_seconds),
+            ),
+            (
+                "default_max_versions".into(),
+                KVColumnValueBuilder::u16(table_options.default_retention.max_versions),
+            ),
+            (
+                "default_min_versions".into(),
+                KVColumnValueBuilder::u16(table_options.default_retention.min_versions),
+            ),
+            (
+                "default_ttl_seconds".into(),
+                KVColumnValueBuilder::u64(
+                    table_options
+                        .default_retention
+                        .ttl
+                        .map_or(0, |ttl| ttl.as_secs()),
+                ),
+            ),
+        ],
+    }
+}
+
+/** Row of `_argonsys_columns` describing the column, replaces the previous one of the column. */
+pub(super) fn argonsys_column_row(
+    table_id: &KVTableId<'_>,
+    column: &KVColumnSchema,
+    retired: bool,
+) -> InsertIntoOp {
+    let mut values: Vec<(String, Box<dyn KVColumnValue + Send + Sync + 'static>)> = vec![
+        (
+            "table_id".into(),
+            KVColumnValueBuilder::text(table_id.to_string()),
+        ),
+        (
+            "column_id".into(),
+            KVColumnValueBuilder::u16(column.column_id),
+        ),
+        (
+            "column_name".into(),
+            KVColumnValueBuilder::text(column.column_name.clone()),
+        ),
+        (
+            "column_type".into(),
+            KVColumnValueBuilder::u16(column.column_type as u16),
+        ),
+        (
+            "max_versions".into(),
+            KVColumnValueBuilder::u16(column.retention.max_versions),
+        ),
+        (
+            "min_versions".into(),
+            KVColumnValueBuilder::u16(column.retention.min_versions),
+        ),
+        (
+            "ttl_seconds".into(),
+            KVColumnValueBuilder::u64(column.retention.ttl.map_or(0, |ttl| ttl.as_secs())),
+        ),
+        (
+            "nullable".into(),
+            KVColumnValueBuilder::bool(column.nullable),
+        ),
+        ("retired".into(), KVColumnValueBuilder::bool(retired)),
+    ];
+
+    if let Some(default_value) = &column.default_value {
+        values.push((
+            "default_value".into(),
+            KVColumnValueBuilder::bytes(default_value.to_vec()),
+        ));
+    }
+
+    InsertIntoOp {
+        table_name: SystemTableNames::ARGONSYS_COLUMNS.to_string(),
+        values,
+    }
+}
+
+/** Writes rows of `_argonsys_columns` with a single commit log record - all of them or none. */
+pub(super) async fn insert_argonsys_column_rows(
+    db_ctx: &DbCtx,
+    column_rows: &[InsertIntoOp],
+) -> Result<(), InsertOpError> {
+    let argonsys_columns = db_ctx
+        .catalog
+        .lookup_table_by_name(&SystemTableNames::ARGONSYS_COLUMNS)
+        .ok_or(InsertOpError::TableNotFound)?;
+
+    let mut mutations = vec![];
+    for column_row in column_rows {
+        mutations.extend(column_row.prepare_batch(&argonsys_columns)?);
+    }
+
+    if mutations.is_empty() {
+        return Ok(());
+    }
+
+    argonsys_columns
+        .insert_mutations(&mutations)
+        .await
+        .map_err(|e| {
+            println!("insert failed - {}", e);
+
+            InsertOpError::InsertFailed
+        })
 }
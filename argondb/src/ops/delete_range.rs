@@ -0,0 +1,145 @@
+use std::{
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use libargondb::{
+    DbCtx,
+    kv::{
+        KVColumnValue, KVPrimaryKeyMarker, KVRangeTombstone, KVRuntimeErrorKind, KVTableName,
+        KVTableSchema,
+        primary_key::{KVPrimaryKeySchema, PrimaryKeyBuilder},
+    },
+};
+
+#[derive(Debug)]
+pub enum DeleteRangeOpError {
+    InvalidTableName,
+    TableNotFound,
+    InvalidColumnName,
+    MissingPrimaryKey,
+    InvalidValue,
+    WriteStalled,
+    DeleteFailed,
+}
+
+/** Bound on leading primary key columns - trailing columns may be omitted. */
+pub struct DeleteRangeOpBound {
+    pub primary_key_values: Vec<(String, Box<dyn KVColumnValue + Send + Sync + 'static>)>,
+    pub exclusive: bool,
+}
+
+impl DeleteRangeOpBound {
+    /**
+     * Maps the bound to a range bound of the table. Bounds setting only leading primary key
+     * columns become prefix bounds.
+     */
+    pub fn to_marker(
+        &self,
+        schema: &KVTableSchema,
+    ) -> Result<KVPrimaryKeyMarker, DeleteRangeOpError> {
+        let pk_schema = KVPrimaryKeySchema::from_table_schema(schema);
+        let mut pk_builder = PrimaryKeyBuilder::new(&pk_schema);
+        let mut column_count = 0;
+
+        for column_id in &schema.primary_key {
+            let column_schema = schema.lookup_by_column_id(*column_id).unwrap();
+
+            let Some((_, value)) = self
+                .primary_key_values
+                .iter()
+                .find(|(column_name, _)| *column_name == column_schema.column_name)
+            else {
+                break;
+            };
+
+            let value = value
+                .serialize()
+                .map_err(|_| DeleteRangeOpError::InvalidValue)?;
+
+            pk_builder.add_value(&value);
+            column_count += 1;
+        }
+
+        if column_count == 0 {
+            return Err(DeleteRangeOpError::MissingPrimaryKey);
+        }
+
+        // Values of non primary key columns, or of columns following an omitted one
+        if self.primary_key_values.len() != column_count {
+            return Err(DeleteRangeOpError::InvalidColumnName);
+        }
+
+        if column_count == schema.primary_key.len() && !self.exclusive {
+            let primary_key = pk_builder
+                .build()
+                .map_err(|_| DeleteRangeOpError::MissingPrimaryKey)?;
+
+            Ok(KVPrimaryKeyMarker::Key(primary_key))
+        } else {
+            Ok(KVPrimaryKeyMarker::Prefix {
+                prefix: pk_builder.build_prefix(),
+                inclusive: !self.exclusive,
+            })
+        }
+    }
+}
+
+/**
+ * Unset bounds leave the range open on that side. Timestamp 0 is replaced with the current time.
+ */
+pub struct DeleteRangeOp {
+    pub table_name: String,
+    pub from: Option<DeleteRangeOpBound>,
+    pub to: Option<DeleteRangeOpBound>,
+    pub timestamp: u64,
+}
+
+impl DeleteRangeOp {
+    pub async fn execute(&self, db_ctx: &DbCtx) -> Result<(), DeleteRangeOpError> {
+        let table_name = KVTableName::from_str(&self.table_name)
+            .map_err(|_| DeleteRangeOpError::InvalidTableName)?;
+
+        let table = db_ctx
+            .catalog
+            .lookup_table_by_name(&table_name)
+            .ok_or(DeleteRangeOpError::TableNotFound)?;
+
+        let table_schema = table.table_schema();
+        let from = match &self.from {
+            Some(bound) => bound.to_marker(&table_schema)?,
+            None => KVPrimaryKeyMarker::Start,
+        };
+        let to = match &self.to {
+            Some(bound) => bound.to_marker(&table_schema)?,
+            None => KVPrimaryKeyMarker::End,
+        };
+
+        let timestamp = if self.timestamp == 0 {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64
+        } else {
+            self.timestamp
+        };
+
+        table
+            .delete_range(KVRangeTombstone {
+                from,
+                to,
+                timestamp,
+            })
+            .await
+            .map_err(|e| match e.kind() {
+                KVRuntimeErrorKind::WriteStalled => DeleteRangeOpError::WriteStalled,
+                _ => {
+                    println!("delete range failed - {}", e);
+
+                    DeleteRangeOpError::DeleteFailed
+                }
+            })?;
+
+        Ok(())
+    }
+}
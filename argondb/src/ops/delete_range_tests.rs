@@ -0,0 +1,216 @@
+use std::str::FromStr;
+
+use libargondb::{
+    DbCtx,
+    kv::{
+        KVColumnFilter, KVColumnValueBuilder, KVPrimaryKeyMarker, KVRangeScan, KVRowScan,
+        KVTableName, KVTableOptions, KVTableSchema,
+        column_type::{ColumnTypeCode, ColumnTypeSerialize, ColumnTypeText, ColumnTypeU16},
+        primary_key::{KVPrimaryKeySchema, PrimaryKeyBuilder},
+        schema::KVColumnRetention,
+    },
+};
+
+use crate::{
+    ops::{
+        CreateTableOp, CreateTableOpColumn, DeleteRangeOp, DeleteRangeOpBound, DeleteRangeOpError,
+        InsertIntoOp,
+    },
+    test_utils::{TestDbCtx, test_db_ctx},
+};
+
+const TENANTS: [&str; 3] = ["a", "b", "c"];
+const IDS: [u16; 3] = [1, 2, 3];
+
+/** Table `ranged` keyed by `tenant` and `id`, with a row for every tenant and id. */
+async fn ranged_table(name: &str) -> TestDbCtx {
+    let db_ctx = test_db_ctx(name);
+
+    let column = |column_name: &str, column_type| CreateTableOpColumn {
+        column_name: column_name.to_string(),
+        column_type,
+        retention: KVColumnRetention::default(),
+        nullable: true,
+        default_value: None,
+    };
+
+    CreateTableOp {
+        table_name: "ranged".to_string(),
+        columns: vec![
+            column("tenant", ColumnTypeCode::Text),
+            column("id", ColumnTypeCode::U16),
+            column("value", ColumnTypeCode::Text),
+        ],
+        primary_key: vec!["tenant".to_string(), "id".to_string()],
+        table_options: KVTableOptions::default(),
+    }
+    .execute(&db_ctx)
+    .await
+    .unwrap();
+
+    for tenant in TENANTS {
+        for id in IDS {
+            InsertIntoOp {
+                table_name: "ranged".to_string(),
+                values: vec![
+                    (
+                        "tenant".to_string(),
+                        KVColumnValueBuilder::text(tenant.into()),
+                    ),
+                    ("id".to_string(), KVColumnValueBuilder::u16(id)),
+                    (
+                        "value".to_string(),
+                        KVColumnValueBuilder::text(id.to_string()),
+                    ),
+                ],
+            }
+            .execute(&db_ctx)
+            .await
+            .unwrap();
+        }
+    }
+
+    db_ctx
+}
+
+fn bound(tenant: &str, id: Option<u16>, exclusive: bool) -> DeleteRangeOpBound {
+    let mut primary_key_values = vec![(
+        "tenant".to_string(),
+        KVColumnValueBuilder::text(tenant.into()),
+    )];
+    if let Some(id) = id {
+        primary_key_values.push(("id".to_string(), KVColumnValueBuilder::u16(id)));
+    }
+
+    DeleteRangeOpBound {
+        primary_key_values,
+        exclusive,
+    }
+}
+
+async fn delete_range(
+    db_ctx: &DbCtx,
+    from: Option<DeleteRangeOpBound>,
+    to: Option<DeleteRangeOpBound>,
+) -> Result<(), DeleteRangeOpError> {
+    DeleteRangeOp {
+        table_name: "ranged".to_string(),
+        from,
+        to,
+        timestamp: 0,
+    }
+    .execute(db_ctx)
+    .await
+}
+
+async fn row_keys(db_ctx: &DbCtx) -> Vec<(String, u16)> {
+    let table = db_ctx
+        .catalog
+        .lookup_table_by_name(&KVTableName::from_str("ranged").unwrap())
+        .unwrap();
+
+    let mut iter = table
+        .scan(KVRangeScan::new(
+            KVTableSchema::clone(&table.table_schema()),
+            KVPrimaryKeyMarker::Start,
+            KVPrimaryKeyMarker::End,
+            KVColumnFilter::All,
+        ))
+        .await
+        .unwrap();
+
+    let mut keys = vec![];
+    while let Some(row) = iter.next_row().await.unwrap() {
+        keys.push((
+            row.column_deserialized::<ColumnTypeText>("tenant").unwrap(),
+            row.column_deserialized::<ColumnTypeU16>("id").unwrap(),
+        ));
+    }
+
+    keys
+}
+
+async fn has_row(db_ctx: &DbCtx, tenant: &str, id: u16) -> bool {
+    let table = db_ctx
+        .catalog
+        .lookup_table_by_name(&KVTableName::from_str("ranged").unwrap())
+        .unwrap();
+    let table_schema = table.table_schema();
+
+    let pk_schema = KVPrimaryKeySchema::from_table_schema(&table_schema);
+    let mut pk_builder = PrimaryKeyBuilder::new(&pk_schema);
+    pk_builder.add_value(&ColumnTypeText::serialize(tenant).unwrap());
+    pk_builder.add_value(&ColumnTypeU16::serialize(id).unwrap());
+
+    let mut iter = table
+        .scan(KVRowScan::new(
+            KVTableSchema::clone(&table_schema),
+            pk_builder.build().unwrap(),
+            KVColumnFilter::All,
+        ))
+        .await
+        .unwrap();
+
+    iter.next_row().await.unwrap().is_some()
+}
+
+fn keys(keys: &[(&str, u16)]) -> Vec<(String, u16)> {
+    keys.iter()
+        .map(|(tenant, id)| (tenant.to_string(), *id))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_delete_range_of_prefix_and_key_bounds() {
+    let db_ctx = ranged_table("delete-range-bounds").await;
+
+    // Every row of tenant "a" after id 1, up to row ("b", 2) inclusive
+    delete_range(
+        &db_ctx,
+        Some(bound("a", Some(1), true)),
+        Some(bound("b", Some(2), false)),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        row_keys(&db_ctx).await,
+        keys(&[("a", 1), ("b", 3), ("c", 1), ("c", 2), ("c", 3)])
+    );
+    assert!(!has_row(&db_ctx, "b", 1).await);
+    assert!(has_row(&db_ctx, "b", 3).await);
+
+    // Prefix bounds cover all rows of the tenant
+    delete_range(&db_ctx, Some(bound("c", None, false)), None)
+        .await
+        .unwrap();
+
+    assert_eq!(row_keys(&db_ctx).await, keys(&[("a", 1), ("b", 3)]));
+    assert!(!has_row(&db_ctx, "c", 2).await);
+}
+
+#[tokio::test]
+async fn test_delete_range_rejects_invalid_bounds() {
+    let db_ctx = ranged_table("delete-range-invalid").await;
+
+    let id_only = DeleteRangeOpBound {
+        primary_key_values: vec![("id".to_string(), KVColumnValueBuilder::u16(1))],
+        exclusive: false,
+    };
+    assert!(matches!(
+        delete_range(&db_ctx, Some(id_only), None).await,
+        Err(DeleteRangeOpError::MissingPrimaryKey)
+    ));
+
+    let mut non_key = bound("a", None, false);
+    non_key
+        .primary_key_values
+        .push(("value".to_string(), KVColumnValueBuilder::text("1".into())));
+    assert!(matches!(
+        delete_range(&db_ctx, Some(non_key), None).await,
+        Err(DeleteRangeOpError::InvalidColumnName)
+    ));
+
+    // Rejected deletes leave the rows in place
+    assert_eq!(row_keys(&db_ctx).await.len(), TENANTS.len() * IDS.len());
+}
@@ -0,0 +1,124 @@
+use std::str::FromStr;
+
+use libargondb::{
+    DbCtx,
+    kv::{KVColumnValueBuilder, KVTable, KVTableId, KVTableName},
+    persistence::PersistenceError,
+};
+
+use crate::{
+    ops::{
+        DeleteRangeOp, DeleteRangeOpBound, DeleteRangeOpError, MutateRowOp, MutateRowOpMutation,
+    },
+    system_tables::{SystemTableIds, SystemTableNames},
+};
+
+#[derive(Debug)]
+pub enum DropTableOpError {
+    InvalidTableName,
+    TableNotFound,
+    SystemTable,
+    PersistFailed,
+}
+
+/**
+ * Removes the table from the catalog and its rows from system tables. Files of the table are
+ * removed once scans, flushes and compactions of it that are in progress finish.
+ */
+pub struct DropTableOp {
+    pub table_name: String,
+}
+
+impl DropTableOp {
+    pub async fn execute(&self, db_ctx: &DbCtx) -> Result<(), DropTableOpError> {
+        let table_name = KVTableName::from_str(&self.table_name)
+            .map_err(|_| DropTableOpError::InvalidTableName)?;
+
+        let table = db_ctx
+            .catalog
+            .lookup_table_by_name(&table_name)
+            .ok_or(DropTableOpError::TableNotFound)?;
+
+        if is_system_table(&table.table_id) {
+            return Err(DropTableOpError::SystemTable);
+        }
+
+        // Concurrent drops and truncates of the table race for its removal, only one proceeds
+        let table = db_ctx
+            .catalog
+            .remove_table(&table.table_id)
+            .ok_or(DropTableOpError::TableNotFound)?;
+
+        // Table is gone once its row is deleted, column rows left by a crash are removed on startup
+        let row_deleted = MutateRowOp {
+            table_name: SystemTableNames::ARGONSYS_TABLES.to_string(),
+            primary_key_values: vec![(
+                "table_id".into(),
+                KVColumnValueBuilder::text(table.table_id.to_string()),
+            )],
+            mutations: vec![MutateRowOpMutation::DeleteRow { timestamp: 0 }],
+        }
+        .execute(db_ctx)
+        .await
+        .is_ok();
+
+        if !row_deleted {
+            // Table wasn't discarded yet, it is usable again as if the drop never happened
+            db_ctx.catalog.add_table(table);
+
+            return Err(DropTableOpError::PersistFailed);
+        }
+
+        delete_argonsys_columns(db_ctx, &table.table_id)
+            .await
+            .map_err(|_| DropTableOpError::PersistFailed)?;
+
+        discard_table(db_ctx, &table)
+            .await
+            .map_err(|_| DropTableOpError::PersistFailed)
+    }
+}
+
+pub(super) fn is_system_table(table_id: &KVTableId<'_>) -> bool {
+    [
+        SystemTableIds::ARGONSYS_TABLES,
+        SystemTableIds::ARGONSYS_COLUMNS,
+    ]
+    .contains(table_id)
+}
+
+/** Deletes rows of all columns of the table from `_argonsys_columns`. */
+pub(crate) async fn delete_argonsys_columns(
+    db_ctx: &DbCtx,
+    table_id: &KVTableId<'_>,
+) -> Result<(), DeleteRangeOpError> {
+    let table_id_bound = || DeleteRangeOpBound {
+        primary_key_values: vec![(
+            "table_id".into(),
+            KVColumnValueBuilder::text(table_id.to_string()),
+        )],
+        exclusive: false,
+    };
+
+    DeleteRangeOp {
+        table_name: SystemTableNames::ARGONSYS_COLUMNS.to_string(),
+        from: Some(table_id_bound()),
+        to: Some(table_id_bound()),
+        timestamp: 0,
+    }
+    .execute(db_ctx)
+    .await
+}
+
+/**
+ * Discards the table removed from system tables and removes its files. Commit log entries of
+ * the table are skipped on replay, files left by a crash in between are removed on startup.
+ */
+pub(super) async fn discard_table(db_ctx: &DbCtx, table: &KVTable) -> Result<(), PersistenceError> {
+    table.discard().await;
+
+    db_ctx
+        .persistence
+        .remove_table_storage(&table.table_id)
+        .await
+}
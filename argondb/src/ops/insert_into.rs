@@ -7,7 +7,7 @@ use std::{
 use libargondb::{
     DbCtx,
     kv::{
-        KVColumnValue, KVTable, KVTableName,
+        KVColumnValue, KVRuntimeErrorKind, KVTable, KVTableName,
         mutation::{MutationType, StructuredMutation},
         primary_key::{KVPrimaryKeySchema, PrimaryKeyBuilder},
     },
@@ -18,7 +18,11 @@ pub enum InsertOpError {
     InvalidTableName,
     InvalidColumnName,
     MissingPrimaryKey,
+    /** Carries name of the column which is not null and has no default. */
+    MissingNotNullColumn(String),
     TableNotFound,
+    WriteStalled,
+    InsertFailed,
 }
 
 pub struct InsertIntoOp {
@@ -36,21 +40,28 @@ impl InsertIntoOp {
             .lookup_table_by_name(&table_name)
             .ok_or(InsertOpError::TableNotFound)?;
 
-        let (prepared_values, primary_key) = self.prepare(&table)?;
-        let mutations = self.prepare_mutations(&prepared_values, &primary_key)?;
-        self.execute_insertions(&table, &mutations).await;
+        let mutations = self.prepare_batch(&table)?;
+        self.execute_insertions(&table, &mutations).await
+    }
 
-        Ok(())
+    /** Mutations of the row, for ops writing it together with other rows of the table. */
+    pub(super) fn prepare_batch(
+        &self,
+        table: &KVTable,
+    ) -> Result<Vec<StructuredMutation>, InsertOpError> {
+        let (prepared_values, primary_key) = self.prepare(table)?;
+        self.prepare_mutations(&prepared_values, &primary_key)
     }
 
     fn prepare(
         &self,
         table: &KVTable,
     ) -> Result<(Vec<PreparedColumnValue>, Box<[u8]>), InsertOpError> {
+        let table_schema = table.table_schema();
         let mut prepared_values = Vec::<PreparedColumnValue>::new();
 
         for (column_name, column_value) in &self.values {
-            let Some(column_schema) = table.table_schema.lookup_by_name(column_name) else {
+            let Some(column_schema) = table_schema.lookup_by_name(column_name) else {
                 return Err(InsertOpError::InvalidColumnName);
             };
 
@@ -62,9 +73,9 @@ impl InsertIntoOp {
 
         prepared_values.sort_by(|a, b| a.column_id.cmp(&b.column_id));
 
-        let pk_schema = KVPrimaryKeySchema::from_table_schema(&table.table_schema);
+        let pk_schema = KVPrimaryKeySchema::from_table_schema(&table_schema);
         let mut pk_builder = PrimaryKeyBuilder::new(&pk_schema);
-        for column_id in &table.table_schema.primary_key {
+        for column_id in &table_schema.primary_key {
             let Ok(idx) = prepared_values
                 .binary_search_by(|prepared_val| prepared_val.column_id.cmp(column_id))
             else {
@@ -73,7 +84,27 @@ impl InsertIntoOp {
 
             pk_builder.add_value(&prepared_values[idx].value);
         }
-        let primary_key = pk_builder.build();
+        let primary_key = pk_builder
+            .build()
+            .map_err(|_| InsertOpError::MissingPrimaryKey)?;
+
+        // Cells left unset read as the column default, columns without one must be nullable
+        for column_schema in &table_schema.columns {
+            if column_schema.nullable || column_schema.default_value.is_some() {
+                continue;
+            }
+
+            if prepared_values
+                .binary_search_by(|prepared_val| {
+                    prepared_val.column_id.cmp(&column_schema.column_id)
+                })
+                .is_err()
+            {
+                return Err(InsertOpError::MissingNotNullColumn(
+                    column_schema.column_name.clone(),
+                ));
+            }
+        }
 
         Ok((prepared_values, primary_key))
     }
@@ -109,24 +140,22 @@ impl InsertIntoOp {
         Ok(mutations)
     }
 
-    async fn execute_insertions(&self, table: &Arc<KVTable>, mutations: &Vec<StructuredMutation>) {
-        // TODO: Generally speaking, this code handling table state and getting new state after flush could be handled in a better way
-        table.insert_mutations(mutations).await.unwrap();
-        // let mut table_state = table.load_state();
-        // for mutation in mutations {
-        //     loop {
-        //         match table_state.current_memtable.insert_mutation(&mutation) {
-        //             Ok(_) => {
-        //                 break;
-        //             }
-        //             Err(MemtableInsertError::SizeExceeded)
-        //             | Err(MemtableInsertError::ReadOnlyMode) => {
-        //                 table.flush_current_memtable().await;
-        //                 table_state = table.load_state();
-        //             }
-        //         }
-        //     }
-        // }
+    async fn execute_insertions(
+        &self,
+        table: &Arc<KVTable>,
+        mutations: &Vec<StructuredMutation>,
+    ) -> Result<(), InsertOpError> {
+        table
+            .insert_mutations(mutations)
+            .await
+            .map_err(|e| match e.kind() {
+                KVRuntimeErrorKind::WriteStalled => InsertOpError::WriteStalled,
+                _ => {
+                    println!("insert failed - {}", e);
+
+                    InsertOpError::InsertFailed
+                }
+            })
     }
 }
 
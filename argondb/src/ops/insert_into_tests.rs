@@ -0,0 +1,44 @@
+use libargondb::{
+    DbCtx,
+    kv::{KVColumnValue, KVColumnValueBuilder},
+};
+use smol::block_on;
+
+use crate::{
+    ops::{InsertIntoOp, InsertOpError},
+    test_utils::constrained_table,
+};
+
+fn insert(db_ctx: &DbCtx, column_names: &[&str]) -> Result<(), InsertOpError> {
+    let mut values: Vec<(String, Box<dyn KVColumnValue + Send + Sync + 'static>)> =
+        vec![("id".into(), KVColumnValueBuilder::text("a".to_string()))];
+    for column_name in column_names {
+        values.push((
+            column_name.to_string(),
+            KVColumnValueBuilder::text(column_name.to_string()),
+        ));
+    }
+
+    block_on(
+        InsertIntoOp {
+            table_name: "constrained".to_string(),
+            values,
+        }
+        .execute(db_ctx),
+    )
+}
+
+#[test]
+fn test_insert_requires_not_null_columns_without_default() {
+    let db_ctx = constrained_table("insert-not-null");
+
+    let result = insert(&db_ctx, &["note", "level"]);
+    assert!(matches!(
+        result,
+        Err(InsertOpError::MissingNotNullColumn(column_name)) if column_name == "name"
+    ));
+
+    // Nullable and defaulted columns may be left unset
+    insert(&db_ctx, &["name"]).unwrap();
+    insert(&db_ctx, &["name", "note", "level"]).unwrap();
+}
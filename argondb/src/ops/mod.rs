@@ -1,8 +1,36 @@
+mod alter_table;
 mod create_table;
+mod delete_range;
+mod drop_table;
 mod insert_into;
+mod mutate_row;
+mod truncate_table;
 
+#[cfg(test)]
+mod alter_table_tests;
+#[cfg(test)]
+mod delete_range_tests;
+#[cfg(test)]
+mod insert_into_tests;
+#[cfg(test)]
+mod mutate_row_tests;
+
+pub use alter_table::AlterTableOp;
+pub use alter_table::AlterTableOpColumnChange;
+pub use alter_table::AlterTableOpError;
 pub use create_table::CreateTableOp;
 pub use create_table::CreateTableOpColumn;
 pub use create_table::CreateTableOpError;
+pub use delete_range::DeleteRangeOp;
+pub use delete_range::DeleteRangeOpBound;
+pub use delete_range::DeleteRangeOpError;
+pub use drop_table::DropTableOp;
+pub use drop_table::DropTableOpError;
+pub(crate) use drop_table::delete_argonsys_columns;
 pub use insert_into::InsertIntoOp;
 pub use insert_into::InsertOpError;
+pub use mutate_row::MutateRowOp;
+pub use mutate_row::MutateRowOpError;
+pub use mutate_row::MutateRowOpMutation;
+pub use truncate_table::TruncateTableOp;
+pub use truncate_table::TruncateTableOpError;
@@ -0,0 +1,317 @@
+use std::{
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use libargondb::{
+    DbCtx,
+    kv::{
+        KVColumnFilter, KVColumnValue, KVRowScan, KVRuntimeErrorKind, KVTable, KVTableName,
+        KVTableSchema,
+        mutation::{MutationType, StructuredMutation},
+        primary_key::{KVPrimaryKeySchema, PrimaryKeyBuilder},
+    },
+};
+
+#[derive(Debug)]
+pub enum MutateRowOpError {
+    InvalidTableName,
+    TableNotFound,
+    InvalidColumnName,
+    MissingPrimaryKey,
+    PrimaryKeyColumnMutation,
+    InvalidValue,
+    /** Carries name of the column which is not null and has no default. */
+    MissingNotNullColumn(String),
+    WriteStalled,
+    MutationFailed,
+}
+
+/** Timestamp 0 is replaced with the current time. */
+pub enum MutateRowOpMutation {
+    PutCell {
+        column_name: String,
+        timestamp: u64,
+        value: Box<dyn KVColumnValue + Send + Sync + 'static>,
+    },
+    DeleteCell {
+        column_name: String,
+        timestamp: u64,
+    },
+    DeleteColumn {
+        column_name: String,
+        timestamp: u64,
+    },
+    DeleteRow {
+        timestamp: u64,
+    },
+}
+
+/** Serialized values of primary key columns by column id. */
+type PrimaryKeyValues = Vec<(u16, Box<[u8]>)>;
+
+pub struct MutateRowOp {
+    pub table_name: String,
+    pub primary_key_values: Vec<(String, Box<dyn KVColumnValue + Send + Sync + 'static>)>,
+    pub mutations: Vec<MutateRowOpMutation>,
+}
+
+impl MutateRowOp {
+    pub async fn execute(&self, db_ctx: &DbCtx) -> Result<(), MutateRowOpError> {
+        let table_name = KVTableName::from_str(&self.table_name)
+            .map_err(|_| MutateRowOpError::InvalidTableName)?;
+
+        let table = db_ctx
+            .catalog
+            .lookup_table_by_name(&table_name)
+            .ok_or(MutateRowOpError::TableNotFound)?;
+
+        let (primary_key, primary_key_values) = self.prepare_primary_key(&table)?;
+        let mutations = self.prepare_mutations(&table, &primary_key, &primary_key_values)?;
+        self.check_created_row(&table, &primary_key).await?;
+
+        // Whole batch goes to a single memtable with a single commit log record - row mutation is atomic
+        table
+            .insert_mutations(&mutations)
+            .await
+            .map_err(|e| match e.kind() {
+                KVRuntimeErrorKind::WriteStalled => MutateRowOpError::WriteStalled,
+                _ => {
+                    println!("mutate row failed - {}", e);
+
+                    MutateRowOpError::MutationFailed
+                }
+            })?;
+
+        Ok(())
+    }
+
+    /** Mutations of the row, for ops writing it together with other rows of the table. */
+    pub(super) fn prepare_batch(
+        &self,
+        table: &KVTable,
+    ) -> Result<Vec<StructuredMutation>, MutateRowOpError> {
+        let (primary_key, primary_key_values) = self.prepare_primary_key(table)?;
+        self.prepare_mutations(table, &primary_key, &primary_key_values)
+    }
+
+    /**
+     * Puts creating the row must set not null columns without default, same as an insert. Row is
+     * created when it doesn't exist yet or when the mutations delete it first.
+     */
+    async fn check_created_row(
+        &self,
+        table: &KVTable,
+        primary_key: &[u8],
+    ) -> Result<(), MutateRowOpError> {
+        let mut put_column_names = vec![];
+        let mut deletes_row = false;
+        for mutation in &self.mutations {
+            match mutation {
+                MutateRowOpMutation::PutCell { column_name, .. } => {
+                    put_column_names.push(column_name);
+                }
+                MutateRowOpMutation::DeleteRow { .. } => deletes_row = true,
+                _ => {}
+            }
+        }
+
+        if put_column_names.is_empty() {
+            return Ok(());
+        }
+
+        let table_schema = table.table_schema();
+        let Some(missing_column) = table_schema.columns.iter().find(|column_schema| {
+            !column_schema.nullable
+                && column_schema.default_value.is_none()
+                && !table_schema.primary_key.contains(&column_schema.column_id)
+                && !put_column_names.contains(&&column_schema.column_name)
+        }) else {
+            return Ok(());
+        };
+
+        if !deletes_row {
+            let mut scan = table
+                .scan(KVRowScan::new(
+                    KVTableSchema::clone(&table_schema),
+                    primary_key.into(),
+                    KVColumnFilter::Columns(table_schema.primary_key.clone()),
+                ))
+                .await
+                .map_err(|e| {
+                    println!("mutate row failed - {}", e);
+
+                    MutateRowOpError::MutationFailed
+                })?;
+
+            let row = scan.next_row().await.map_err(|e| {
+                println!("mutate row failed - {}", e);
+
+                MutateRowOpError::MutationFailed
+            })?;
+
+            if row.is_some() {
+                return Ok(());
+            }
+        }
+
+        Err(MutateRowOpError::MissingNotNullColumn(
+            missing_column.column_name.clone(),
+        ))
+    }
+
+    fn prepare_primary_key(
+        &self,
+        table: &KVTable,
+    ) -> Result<(Box<[u8]>, PrimaryKeyValues), MutateRowOpError> {
+        let schema = &table.table_schema();
+
+        let pk_schema = KVPrimaryKeySchema::from_table_schema(schema);
+        let mut pk_builder = PrimaryKeyBuilder::new(&pk_schema);
+        let mut primary_key_values = Vec::with_capacity(schema.primary_key.len());
+
+        for column_id in &schema.primary_key {
+            let column_schema = schema.lookup_by_column_id(*column_id).unwrap();
+
+            let Some((_, value)) = self
+                .primary_key_values
+                .iter()
+                .find(|(column_name, _)| *column_name == column_schema.column_name)
+            else {
+                return Err(MutateRowOpError::MissingPrimaryKey);
+            };
+
+            let value = value
+                .serialize()
+                .map_err(|_| MutateRowOpError::InvalidValue)?;
+
+            pk_builder.add_value(&value);
+            primary_key_values.push((*column_id, value));
+        }
+
+        if self.primary_key_values.len() != schema.primary_key.len() {
+            return Err(MutateRowOpError::InvalidColumnName);
+        }
+
+        let primary_key = pk_builder
+            .build()
+            .map_err(|_| MutateRowOpError::MissingPrimaryKey)?;
+
+        Ok((primary_key, primary_key_values))
+    }
+
+    fn prepare_mutations(
+        &self,
+        table: &KVTable,
+        primary_key: &[u8],
+        primary_key_values: &PrimaryKeyValues,
+    ) -> Result<Vec<StructuredMutation>, MutateRowOpError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        let resolve_timestamp = |timestamp: u64| if timestamp == 0 { now } else { timestamp };
+
+        let table_schema = table.table_schema();
+        let resolve_column_id = |column_name: &String| {
+            let column_schema = table_schema
+                .lookup_by_name(column_name)
+                .ok_or(MutateRowOpError::InvalidColumnName)?;
+
+            if table_schema.primary_key.contains(&column_schema.column_id) {
+                return Err(MutateRowOpError::PrimaryKeyColumnMutation);
+            }
+
+            Ok(column_schema)
+        };
+        // Deleted cells of a column without default would read as null
+        let resolve_deleted_column_id = |column_name: &String| {
+            let column_schema = resolve_column_id(column_name)?;
+
+            if !column_schema.nullable && column_schema.default_value.is_none() {
+                return Err(MutateRowOpError::MissingNotNullColumn(column_name.clone()));
+            }
+
+            Ok(column_schema.column_id)
+        };
+
+        let mut mutations = Vec::<StructuredMutation>::new();
+        let mut last_put_timestamp: Option<u64> = None;
+
+        for mutation in &self.mutations {
+            let (timestamp, column_id, mutation_type, value) = match mutation {
+                MutateRowOpMutation::PutCell {
+                    column_name,
+                    timestamp,
+                    value,
+                } => {
+                    let value = value
+                        .serialize()
+                        .map_err(|_| MutateRowOpError::InvalidValue)?;
+                    let timestamp = resolve_timestamp(*timestamp);
+                    last_put_timestamp = last_put_timestamp.max(Some(timestamp));
+
+                    (
+                        timestamp,
+                        resolve_column_id(column_name)?.column_id,
+                        MutationType::Put,
+                        value,
+                    )
+                }
+                MutateRowOpMutation::DeleteCell {
+                    column_name,
+                    timestamp,
+                } => (
+                    resolve_timestamp(*timestamp),
+                    resolve_deleted_column_id(column_name)?,
+                    MutationType::DeleteVersion,
+                    Box::from([]),
+                ),
+                MutateRowOpMutation::DeleteColumn {
+                    column_name,
+                    timestamp,
+                } => (
+                    resolve_timestamp(*timestamp),
+                    resolve_deleted_column_id(column_name)?,
+                    MutationType::Delete,
+                    Box::from([]),
+                ),
+                MutateRowOpMutation::DeleteRow { timestamp } => (
+                    resolve_timestamp(*timestamp),
+                    0,
+                    MutationType::DeleteRow,
+                    Box::from([]),
+                ),
+            };
+
+            mutations.push(
+                StructuredMutation::try_from(
+                    timestamp,
+                    column_id,
+                    mutation_type,
+                    primary_key.into(),
+                    value,
+                )
+                .map_err(|_| MutateRowOpError::InvalidValue)?,
+            );
+        }
+
+        // Primary key columns are stored as regular cells, written rows must contain them
+        if let Some(timestamp) = last_put_timestamp {
+            for (column_id, value) in primary_key_values {
+                mutations.push(
+                    StructuredMutation::try_from(
+                        timestamp,
+                        *column_id,
+                        MutationType::Put,
+                        primary_key.into(),
+                        value.clone(),
+                    )
+                    .map_err(|_| MutateRowOpError::InvalidValue)?,
+                );
+            }
+        }
+
+        Ok(mutations)
+    }
+}
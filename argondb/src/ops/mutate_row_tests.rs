@@ -0,0 +1,111 @@
+use libargondb::{DbCtx, kv::KVColumnValueBuilder};
+use smol::block_on;
+
+use crate::{
+    ops::{InsertIntoOp, MutateRowOp, MutateRowOpError, MutateRowOpMutation},
+    test_utils::constrained_table,
+};
+
+/** Row `existing` of table `constrained`, holding all its not null columns. */
+fn insert_existing_row(db_ctx: &DbCtx) {
+    block_on(
+        InsertIntoOp {
+            table_name: "constrained".to_string(),
+            values: vec![
+                ("id".into(), KVColumnValueBuilder::text("existing".into())),
+                ("name".into(), KVColumnValueBuilder::text("name".into())),
+            ],
+        }
+        .execute(db_ctx),
+    )
+    .unwrap();
+}
+
+fn mutate_row(
+    db_ctx: &DbCtx,
+    id: &str,
+    mutations: Vec<MutateRowOpMutation>,
+) -> Result<(), MutateRowOpError> {
+    block_on(
+        MutateRowOp {
+            table_name: "constrained".to_string(),
+            primary_key_values: vec![("id".into(), KVColumnValueBuilder::text(id.to_string()))],
+            mutations,
+        }
+        .execute(db_ctx),
+    )
+}
+
+fn put(column_name: &str) -> MutateRowOpMutation {
+    MutateRowOpMutation::PutCell {
+        column_name: column_name.to_string(),
+        timestamp: 0,
+        value: KVColumnValueBuilder::text(column_name.to_string()),
+    }
+}
+
+fn is_missing(result: Result<(), MutateRowOpError>, column_name: &str) -> bool {
+    matches!(
+        result,
+        Err(MutateRowOpError::MissingNotNullColumn(missing_column_name))
+            if missing_column_name == column_name
+    )
+}
+
+#[test]
+fn test_mutate_row_rejects_deleted_not_null_cells() {
+    let db_ctx = constrained_table("mutate-not-null-delete");
+    insert_existing_row(&db_ctx);
+
+    let delete_cell = |column_name: &str| MutateRowOpMutation::DeleteCell {
+        column_name: column_name.to_string(),
+        timestamp: 0,
+    };
+    let delete_column = |column_name: &str| MutateRowOpMutation::DeleteColumn {
+        column_name: column_name.to_string(),
+        timestamp: 0,
+    };
+
+    assert!(is_missing(
+        mutate_row(&db_ctx, "existing", vec![delete_cell("name")]),
+        "name"
+    ));
+    assert!(is_missing(
+        mutate_row(&db_ctx, "existing", vec![delete_column("name")]),
+        "name"
+    ));
+
+    // Deleted cells of nullable and defaulted columns read as null and as the default
+    mutate_row(&db_ctx, "existing", vec![delete_cell("note")]).unwrap();
+    mutate_row(&db_ctx, "existing", vec![delete_column("level")]).unwrap();
+    mutate_row(
+        &db_ctx,
+        "existing",
+        vec![MutateRowOpMutation::DeleteRow { timestamp: 0 }],
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_mutate_row_creating_row_requires_not_null_columns() {
+    let db_ctx = constrained_table("mutate-not-null-put");
+    insert_existing_row(&db_ctx);
+
+    assert!(is_missing(
+        mutate_row(&db_ctx, "created", vec![put("note")]),
+        "name"
+    ));
+    assert!(is_missing(
+        mutate_row(
+            &db_ctx,
+            "existing",
+            vec![MutateRowOpMutation::DeleteRow { timestamp: 0 }, put("note")]
+        ),
+        "name"
+    ));
+
+    // Existing rows already hold the not null columns
+    mutate_row(&db_ctx, "existing", vec![put("note")]).unwrap();
+    mutate_row(&db_ctx, "created", vec![put("name"), put("note")]).unwrap();
+    mutate_row(&db_ctx, "created", vec![put("level")]).unwrap();
+}
@@ -0,0 +1,162 @@
+use std::{str::FromStr, sync::Arc};
+
+use libargondb::{
+    DbCtx,
+    kv::{KVColumnValueBuilder, KVTable, KVTableId, KVTableName, KVTableSchema},
+};
+
+use crate::{
+    ops::{
+        InsertIntoOp, MutateRowOp, MutateRowOpMutation,
+        create_table::{argonsys_column_row, argonsys_table_row, insert_argonsys_column_rows},
+        drop_table::{delete_argonsys_columns, discard_table, is_system_table},
+    },
+    system_tables::SystemTableNames,
+};
+
+#[derive(Debug)]
+pub enum TruncateTableOpError {
+    InvalidTableName,
+    TableNotFound,
+    SystemTable,
+    SchemaError,
+    PersistFailed,
+}
+
+/**
+ * Replaces the table with an empty one of the same name, schema and options. The new table gets
+ * a new ID, so rows of the old one can't come back from its commit log entries, while its files
+ * are removed as those of a dropped table.
+ */
+pub struct TruncateTableOp {
+    pub table_name: String,
+}
+
+impl TruncateTableOp {
+    pub async fn execute(&self, db_ctx: &DbCtx) -> Result<(), TruncateTableOpError> {
+        let table_name = KVTableName::from_str(&self.table_name)
+            .map_err(|_| TruncateTableOpError::InvalidTableName)?;
+
+        let table = db_ctx
+            .catalog
+            .lookup_table_by_name(&table_name)
+            .ok_or(TruncateTableOpError::TableNotFound)?;
+
+        if is_system_table(&table.table_id) {
+            return Err(TruncateTableOpError::SystemTable);
+        }
+
+        // Empty table has no cells of retired columns left to hide
+        let table_schema = table.table_schema();
+        let next_table_schema = KVTableSchema::build(
+            table_schema.columns.clone(),
+            table_schema.primary_key.clone(),
+        )
+        .map_err(|_| TruncateTableOpError::SchemaError)?;
+
+        let next_table = Arc::new(KVTable::create(
+            db_ctx.kv_instance.clone(),
+            KVTableId::new_unique(),
+            table.table_name.clone(),
+            next_table_schema,
+            table.table_options,
+            vec![],
+        ));
+        next_table
+            .open()
+            .map_err(|_| TruncateTableOpError::PersistFailed)?;
+
+        // Concurrent drops and truncates of the table race for its removal, only one proceeds
+        if !db_ctx
+            .catalog
+            .replace_table(&table.table_id, next_table.clone())
+        {
+            return Err(TruncateTableOpError::TableNotFound);
+        }
+
+        if let Err(e) = Self::persist_next_table(db_ctx, &table, &next_table).await {
+            // Writes accepted by the new table in the meantime are lost with it
+            if !db_ctx
+                .catalog
+                .replace_table(&next_table.table_id, table.clone())
+            {
+                println!(
+                    "truncate of table {} rolled back after it was dropped",
+                    table.table_name
+                );
+            }
+
+            if let Err(e) = discard_table(db_ctx, &next_table).await {
+                println!(
+                    "failed to remove storage of table {}[table_id={}] - {}",
+                    next_table.table_name,
+                    next_table.table_id.as_ref(),
+                    e
+                );
+            }
+
+            return Err(e);
+        }
+
+        delete_argonsys_columns(db_ctx, &table.table_id)
+            .await
+            .map_err(|_| TruncateTableOpError::PersistFailed)?;
+
+        discard_table(db_ctx, &table)
+            .await
+            .map_err(|_| TruncateTableOpError::PersistFailed)
+    }
+
+    /**
+     * Writes column rows of the new table, then swaps rows of the tables in `_argonsys_tables`.
+     * Column rows left by a failure in between belong to no table and are removed on startup.
+     */
+    async fn persist_next_table(
+        db_ctx: &DbCtx,
+        table: &KVTable,
+        next_table: &KVTable,
+    ) -> Result<(), TruncateTableOpError> {
+        let column_rows: Vec<InsertIntoOp> = next_table
+            .table_schema()
+            .columns
+            .iter()
+            .map(|column| argonsys_column_row(&next_table.table_id, column, false))
+            .collect();
+        insert_argonsys_column_rows(db_ctx, &column_rows)
+            .await
+            .map_err(|_| TruncateTableOpError::PersistFailed)?;
+
+        let argonsys_tables = db_ctx
+            .catalog
+            .lookup_table_by_name(&SystemTableNames::ARGONSYS_TABLES)
+            .ok_or(TruncateTableOpError::PersistFailed)?;
+
+        let mut mutations = MutateRowOp {
+            table_name: SystemTableNames::ARGONSYS_TABLES.to_string(),
+            primary_key_values: vec![(
+                "table_id".into(),
+                KVColumnValueBuilder::text(table.table_id.to_string()),
+            )],
+            mutations: vec![MutateRowOpMutation::DeleteRow { timestamp: 0 }],
+        }
+        .prepare_batch(&argonsys_tables)
+        .map_err(|_| TruncateTableOpError::PersistFailed)?;
+
+        mutations.extend(
+            argonsys_table_row(
+                &next_table.table_id,
+                &next_table.table_name,
+                next_table.table_schema().primary_key.clone(),
+                next_table.table_options,
+            )
+            .prepare_batch(&argonsys_tables)
+            .map_err(|_| TruncateTableOpError::PersistFailed)?,
+        );
+
+        // Rows are swapped with a single commit log record, a crash leaves either of the tables
+        argonsys_tables
+            .insert_mutations(&mutations)
+            .await
+            .map_err(|_| TruncateTableOpError::PersistFailed)
+    }
+}
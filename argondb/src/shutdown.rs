@@ -35,6 +35,7 @@ fn shutdown_thread(system_ctx: SystemCtx) -> CriticalResult<()> {
     close_kv_instance_and_tables(&system_ctx.db_ctx)?;
     system_ctx.sstable_compactor_handle.close();
     system_ctx.memtable_flusher_handle.close();
+    system_ctx.db_ctx.kv_instance.commit_log().close();
 
     persist_instance_state_snapshot(&system_ctx.db_ctx)?;
 
@@ -1,5 +1,7 @@
 use libargondb::kv::{
-    KVTableId, KVTableName, KVTableSchema, column_type::ColumnTypeCode, schema::KVColumnSchema,
+    KVTableId, KVTableName, KVTableSchema,
+    column_type::ColumnTypeCode,
+    schema::{KVColumnRetention, KVColumnSchema},
 };
 
 use crate::errors::{CriticalResult, OrCriticalError};
@@ -32,16 +34,65 @@ impl SystemTableSchemas {
                     column_id: 1,
                     column_name: "table_id".to_string(),
                     column_type: ColumnTypeCode::Text,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
                 },
                 KVColumnSchema {
                     column_id: 2,
                     column_name: "table_name".to_string(),
                     column_type: ColumnTypeCode::Text,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
                 },
                 KVColumnSchema {
                     column_id: 3,
                     column_name: "primary_key".to_string(),
                     column_type: ColumnTypeCode::U16Array,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
+                },
+                KVColumnSchema {
+                    column_id: 4,
+                    column_name: "compaction_strategy".to_string(),
+                    column_type: ColumnTypeCode::Text,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
+                },
+                KVColumnSchema {
+                    column_id: 5,
+                    column_name: "compaction_window_seconds".to_string(),
+                    column_type: ColumnTypeCode::U64,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
+                },
+                KVColumnSchema {
+                    column_id: 6,
+                    column_name: "default_max_versions".to_string(),
+                    column_type: ColumnTypeCode::U16,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
+                },
+                KVColumnSchema {
+                    column_id: 7,
+                    column_name: "default_min_versions".to_string(),
+                    column_type: ColumnTypeCode::U16,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
+                },
+                KVColumnSchema {
+                    column_id: 8,
+                    column_name: "default_ttl_seconds".to_string(),
+                    column_type: ColumnTypeCode::U64,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
                 },
             ],
             vec![1],
@@ -56,21 +107,81 @@ impl SystemTableSchemas {
                     column_id: 1,
                     column_name: "table_id".to_string(),
                     column_type: ColumnTypeCode::Text,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
                 },
                 KVColumnSchema {
                     column_id: 2,
                     column_name: "column_id".to_string(),
                     column_type: ColumnTypeCode::U16,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
                 },
                 KVColumnSchema {
                     column_id: 3,
                     column_name: "column_name".to_string(),
                     column_type: ColumnTypeCode::Text,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
                 },
                 KVColumnSchema {
                     column_id: 4,
                     column_name: "column_type".to_string(),
                     column_type: ColumnTypeCode::U16,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
+                },
+                KVColumnSchema {
+                    column_id: 5,
+                    column_name: "max_versions".to_string(),
+                    column_type: ColumnTypeCode::U16,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
+                },
+                KVColumnSchema {
+                    column_id: 6,
+                    column_name: "min_versions".to_string(),
+                    column_type: ColumnTypeCode::U16,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
+                },
+                KVColumnSchema {
+                    column_id: 7,
+                    column_name: "ttl_seconds".to_string(),
+                    column_type: ColumnTypeCode::U64,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
+                },
+                KVColumnSchema {
+                    column_id: 8,
+                    column_name: "nullable".to_string(),
+                    column_type: ColumnTypeCode::Bool,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
+                },
+                KVColumnSchema {
+                    column_id: 9,
+                    column_name: "default_value".to_string(),
+                    column_type: ColumnTypeCode::Bytes,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
+                },
+                KVColumnSchema {
+                    column_id: 10,
+                    column_name: "retired".to_string(),
+                    column_type: ColumnTypeCode::Bool,
+                    retention: KVColumnRetention::default(),
+                    nullable: true,
+                    default_value: None,
                 },
             ],
             vec![1, 2],
@@ -85,6 +196,23 @@ impl ArgonsysTablesColumns {
     pub const TABLE_ID: &'static str = "table_id";
     pub const TABLE_NAME: &'static str = "table_name";
     pub const PRIMARY_KEY: &'static str = "primary_key";
+    /** Compaction columns may be missing in rows of tables created before they existed */
+    pub const COMPACTION_STRATEGY: &'static str = "compaction_strategy";
+    /** Set only for time window compaction */
+    pub const COMPACTION_WINDOW_SECONDS: &'static str = "compaction_window_seconds";
+    /** Default retention columns may be missing in rows of tables created before they existed */
+    pub const DEFAULT_MAX_VERSIONS: &'static str = "default_max_versions";
+    pub const DEFAULT_MIN_VERSIONS: &'static str = "default_min_versions";
+    /** Missing or 0 means versions never expire */
+    pub const DEFAULT_TTL_SECONDS: &'static str = "default_ttl_seconds";
+}
+
+pub struct CompactionStrategyNames;
+
+impl CompactionStrategyNames {
+    pub const SIZE_TIERED: &'static str = "size_tiered";
+    pub const LEVELED: &'static str = "leveled";
+    pub const TIME_WINDOW: &'static str = "time_window";
 }
 
 pub struct ArgonsysColumnsColumns;
@@ -94,4 +222,15 @@ impl ArgonsysColumnsColumns {
     pub const COLUMN_ID: &'static str = "column_id";
     pub const COLUMN_NAME: &'static str = "column_name";
     pub const COLUMN_TYPE: &'static str = "column_type";
+    /** Retention columns may be missing in rows of columns created before retention existed */
+    pub const MAX_VERSIONS: &'static str = "max_versions";
+    pub const MIN_VERSIONS: &'static str = "min_versions";
+    /** Missing or 0 means versions never expire */
+    pub const TTL_SECONDS: &'static str = "ttl_seconds";
+    /** Missing in rows of columns created before constraints existed, those are nullable */
+    pub const NULLABLE: &'static str = "nullable";
+    /** Serialized default value, missing when the column has no default */
+    pub const DEFAULT_VALUE: &'static str = "default_value";
+    /** Set for dropped columns, their ids are kept so they are never reused */
+    pub const RETIRED: &'static str = "retired";
 }
@@ -0,0 +1,89 @@
+use std::{ops::Deref, path::PathBuf, sync::Arc};
+
+use libargondb::{
+    DbCtx,
+    kv::{
+        KVColumnValueBuilder, KVTableOptions, column_type::ColumnTypeCode,
+        schema::KVColumnRetention,
+    },
+};
+use smol::block_on;
+
+use crate::{
+    config::ArgonDbConfig,
+    init::{init_db_ctx, init_system_tables},
+    ops::{CreateTableOp, CreateTableOpColumn},
+};
+
+/** Database context of a test. Its storage directory is removed once the context is dropped. */
+pub struct TestDbCtx {
+    db_ctx: Arc<DbCtx>,
+    storage_root: PathBuf,
+}
+
+impl Deref for TestDbCtx {
+    type Target = Arc<DbCtx>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.db_ctx
+    }
+}
+
+impl Drop for TestDbCtx {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.storage_root);
+    }
+}
+
+/** Initializes database with system tables in an empty storage directory named after the test. */
+pub fn test_db_ctx(name: &str) -> TestDbCtx {
+    let mut config = ArgonDbConfig::default();
+
+    let storage_root =
+        std::env::temp_dir().join(format!("argondb-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&storage_root);
+    config.argon_fs.fs_filesystem_config.storage_root = storage_root.clone();
+
+    let db_ctx = init_db_ctx(&config).unwrap();
+    init_system_tables(&db_ctx).unwrap();
+
+    TestDbCtx {
+        db_ctx,
+        storage_root,
+    }
+}
+
+/** Table `constrained` keyed by `id`, with a nullable, a not null and a defaulted column. */
+pub fn constrained_table(name: &str) -> TestDbCtx {
+    let db_ctx = test_db_ctx(name);
+
+    let column = |column_name: &str, nullable, default_value| CreateTableOpColumn {
+        column_name: column_name.to_string(),
+        column_type: ColumnTypeCode::Text,
+        retention: KVColumnRetention::default(),
+        nullable,
+        default_value,
+    };
+
+    block_on(
+        CreateTableOp {
+            table_name: "constrained".to_string(),
+            columns: vec![
+                column("id", false, None),
+                column("note", true, None),
+                column("name", false, None),
+                column(
+                    "level",
+                    false,
+                    Some(KVColumnValueBuilder::text("low".to_string())),
+                ),
+            ],
+            primary_key: vec!["id".to_string()],
+            table_options: KVTableOptions::default(),
+        }
+        .execute(&db_ctx),
+    )
+    .unwrap();
+
+    db_ctx
+}
@@ -2,13 +2,13 @@ use libargondb::ArgonfileReader;
 use libargondb::FsFileSystem;
 use libargondb::FsFileSystemConfig;
 use libargondb::argonfile::StatsParser;
-use libargondb::argonfile::{ArgonfileDataBlockIter, SummaryParser};
+use libargondb::argonfile::{ArgonfileDataBlockIter, RangeTombstonesParser, SummaryParser};
 use libargondb::kv::KVSSTableDataBlockIter;
 use libargondb::kv::KVTableSchema;
 use libargondb::kv::column_type::ColumnTypeCode;
 use libargondb::kv::mutation::MutationUtils;
-use libargondb::kv::primary_key::KVPrimaryKeyUtils;
-use libargondb::kv::schema::KVColumnSchema;
+use libargondb::kv::primary_key::{KVPrimaryKeyMarkerUtils, KVPrimaryKeyUtils};
+use libargondb::kv::schema::{KVColumnRetention, KVColumnSchema};
 use std::os::linux::raw::stat;
 use std::{env, fs, io::Cursor, process};
 
@@ -22,11 +22,17 @@ async fn main() {
                 column_id: 1,
                 column_name: "id".into(),
                 column_type: ColumnTypeCode::Text,
+                retention: KVColumnRetention::default(),
+                nullable: true,
+                default_value: None,
             },
             KVColumnSchema {
                 column_id: 2,
                 column_name: "value".into(),
                 column_type: ColumnTypeCode::U16,
+                retention: KVColumnRetention::default(),
+                nullable: true,
+                default_value: None,
             },
         ],
         vec![1],
@@ -60,6 +66,24 @@ async fn main() {
         "Max key: {}",
         KVPrimaryKeyUtils::debug_fmt(&schema, &stats.max_row_key).unwrap()
     );
+    println!(
+        "Timestamps: {} - {}",
+        stats.min_timestamp, stats.max_timestamp
+    );
+
+    if let Some(range_tombstones_block_ptr) = &trailer.range_tombstones_block_ptr {
+        let block = reader.read_block(range_tombstones_block_ptr).await.unwrap();
+        let range_tombstones = RangeTombstonesParser::parse(&block.data).unwrap();
+
+        for tombstone in &range_tombstones {
+            println!(
+                "Range tombstone: {} - {} @ {}",
+                KVPrimaryKeyMarkerUtils::debug_fmt(&schema, &tombstone.from).unwrap(),
+                KVPrimaryKeyMarkerUtils::debug_fmt(&schema, &tombstone.to).unwrap(),
+                tombstone.timestamp
+            );
+        }
+    }
 
     let block = reader.read_block(&trailer.summary_block_ptr).await.unwrap();
     let summary = SummaryParser::parse(&block.data).unwrap();
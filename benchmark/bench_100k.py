@@ -194,7 +194,7 @@ def scan_worker(worker_id, table, counter: Counter):
         getattr(req, "to").CopyFrom(make_marker(end_id))
 
         start = time.perf_counter()
-        rows = list(client.ScanTable(req).rows)
+        rows = [row for response in client.ScanTable(req) for row in response.rows]
         dur = time.perf_counter() - start
         latencies.append(dur)
 
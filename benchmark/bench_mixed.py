@@ -191,7 +191,7 @@ def worker(table: str):
             getattr(req, "to").CopyFrom(make_marker(end_id))
 
             start = time.perf_counter()
-            rows = list(client.ScanTable(req).rows)
+            rows = [row for response in client.ScanTable(req) for row in response.rows]
             dur = time.perf_counter() - start
 
             if ENABLE_SANITY and not rows:
@@ -54,7 +54,7 @@ def worker(worker_id: int):
         getattr(req, "to").CopyFrom(make_marker(end_id))
 
         start = time.perf_counter()
-        rows = list(client.ScanTable(req).rows)
+        rows = [row for response in client.ScanTable(req) for row in response.rows]
         latencies.append(time.perf_counter() - start)
 
         if ENABLE_SANITY_CHECKS:
@@ -5,15 +5,20 @@ use async_trait::async_trait;
 use crate::{
     ArgonFsConfig,
     argonfs::{
+        argon_fs_instance_snapshot::ArgonFsInstanceSnapshotStore,
         argon_fs_worker_pool::ArgonFsWorkerPool,
         argonfile_sstable::{ArgonfileSSTable, ArgonfileSSTableLoadError},
         block_cache::BlockCache,
+        commit_log::ArgonFsCommitLog,
         fs::{BoxFileSystem, FileSystemError},
         local_fs::FsFileSystem,
+        manifest::{ArgonFsManifest, ManifestEdit},
     },
     core::persistence::{PersistenceError, PersistenceLayer},
     kv::{
-        KVInstanceStateSnapshot, KVSSTable, KVScannable, KVTableId, ObjectId, schema::KVTableSchema,
+        KVInstanceStateSnapshot, KVObjectIdLeaseStore, KVSSTable, KVTableId, ObjectId,
+        commit_log::{KVCommitLog, KVCommitLogRecord},
+        schema::KVTableSchema,
     },
     persistence::OrPersistenceError,
 };
@@ -22,6 +27,9 @@ pub struct ArgonFs {
     block_cache: Arc<BlockCache>,
     filesystem: Arc<BoxFileSystem>,
     worker_pool: Arc<ArgonFsWorkerPool>,
+    commit_log: Arc<ArgonFsCommitLog>,
+    manifest: ArgonFsManifest,
+    instance_snapshot_store: Arc<ArgonFsInstanceSnapshotStore>,
 }
 
 impl ArgonFs {
@@ -33,14 +41,37 @@ impl ArgonFs {
             config.fs_filesystem_config.clone(),
         )));
 
-        let worker_pool = Arc::new(ArgonFsWorkerPool::new(1));
+        let worker_pool = Arc::new(ArgonFsWorkerPool::new(config.worker_pool_thread_count));
+
+        let commit_log = Arc::new(
+            smol::block_on(ArgonFsCommitLog::open(
+                config.commit_log_config.clone(),
+                filesystem.clone(),
+            ))
+            .map_err(|source| ArgonFsInitError { source })?,
+        );
+
+        let manifest = ArgonFsManifest::new(filesystem.clone());
+        let instance_snapshot_store =
+            Arc::new(ArgonFsInstanceSnapshotStore::new(filesystem.clone()));
 
         Ok(Self {
             block_cache,
             filesystem,
             worker_pool,
+            commit_log,
+            manifest,
+            instance_snapshot_store,
         })
     }
+
+    pub fn commit_log(&self) -> Arc<dyn KVCommitLog> {
+        self.commit_log.clone()
+    }
+
+    pub fn object_id_lease_store(&self) -> Arc<dyn KVObjectIdLeaseStore> {
+        self.instance_snapshot_store.clone()
+    }
 }
 
 #[async_trait]
@@ -48,56 +79,41 @@ impl PersistenceLayer for ArgonFs {
     async fn read_instance_snapshot(
         &self,
     ) -> Result<Option<KVInstanceStateSnapshot>, PersistenceError> {
-        let file_ref = self
-            .filesystem
-            .get_state_snapshot_file_ref()
-            .await
-            .ok_or_persistence_error()?;
-
-        match file_ref.open_read_only().await {
-            Ok(mut reader) => {
-                let object_id_generator_state = u64::from_le_bytes(
-                    reader
-                        .read(8)
-                        .await
-                        .ok_or_persistence_error()?
-                        .as_ref()
-                        .try_into()
-                        .unwrap(),
-                );
-
-                Ok(Some(KVInstanceStateSnapshot {
-                    object_id_generator_state,
-                }))
-            }
-            Err(e) => match e.kind() {
-                std::io::ErrorKind::NotFound => {
-                    println!("argonfs - instance snapshot file not found");
-                    Ok(None)
-                }
-                _ => Err(PersistenceError(Box::new(e))),
-            },
-        }
+        self.instance_snapshot_store.read().await
     }
 
     async fn save_instance_snapshot(
         &self,
         snapshot: KVInstanceStateSnapshot,
     ) -> Result<(), PersistenceError> {
-        let file_ref = self
+        self.instance_snapshot_store.save(&snapshot).await
+    }
+
+    async fn scan_max_object_id(&self) -> Result<Option<ObjectId>, PersistenceError> {
+        let table_ids = self
             .filesystem
-            .get_state_snapshot_file_ref()
+            .scan_table_ids()
             .await
             .ok_or_persistence_error()?;
 
-        let mut writer = file_ref.open_write_only().await.ok_or_persistence_error()?;
+        let mut max_object_id: Option<u64> = None;
+        for table_id in table_ids {
+            let catalog = self
+                .filesystem
+                .scan_table_catalog(&table_id)
+                .await
+                .ok_or_persistence_error()?;
+
+            for sstable_id in catalog.sstable_ids.iter().chain(&catalog.temp_sstable_ids) {
+                max_object_id = max_object_id.max(Some(sstable_id.0));
+            }
+        }
 
-        writer
-            .write(&u64::to_le_bytes(snapshot.object_id_generator_state))
-            .ok_or_persistence_error()?;
-        writer.flush().ok_or_persistence_error()?;
+        // Ids of memtables left in the commit log decide which of its records are replayed
+        let max_memtable_id = self.commit_log.max_replay_memtable_id().await?;
+        max_object_id = max_object_id.max(max_memtable_id.map(|memtable_id| memtable_id.0));
 
-        Ok(())
+        Ok(max_object_id.map(ObjectId))
     }
 
     async fn scan_for_sstables(
@@ -105,14 +121,16 @@ impl PersistenceLayer for ArgonFs {
         table_id: &KVTableId,
         table_schema: &KVTableSchema,
     ) -> Result<Vec<Box<dyn KVSSTable>>, PersistenceError> {
-        let sstable_refs = self
-            .filesystem
-            .scan_table_catalog(table_id, table_schema)
-            .await
-            .ok_or_persistence_error()?;
+        let sstable_ids = self.manifest.recover(table_id).await?;
 
         let mut sstables: Vec<Box<dyn KVSSTable>> = vec![];
-        for file_ref in sstable_refs {
+        for sstable_id in sstable_ids {
+            let file_ref = self
+                .filesystem
+                .get_sstable_file_ref(table_id, sstable_id)
+                .await
+                .ok_or_persistence_error()?;
+
             let argonfile_sstable = ArgonfileSSTable::load(
                 table_schema.clone(),
                 self.block_cache.clone(),
@@ -134,7 +152,7 @@ impl PersistenceLayer for ArgonFs {
     ) -> Result<Box<dyn Write + Send + Sync + 'static>, PersistenceError> {
         let file_ref = self
             .filesystem
-            .get_sstable_file_ref(table_id, sstable_id)
+            .get_sstable_temp_file_ref(table_id, sstable_id)
             .await
             .ok_or_persistence_error()?;
 
@@ -143,6 +161,27 @@ impl PersistenceLayer for ArgonFs {
         ))
     }
 
+    async fn commit_sstables(
+        &self,
+        table_id: &KVTableId,
+        added: Vec<ObjectId>,
+        removed: Vec<ObjectId>,
+    ) -> Result<(), PersistenceError> {
+        let edit = ManifestEdit { added, removed };
+
+        self.manifest.commit(table_id, &edit).await?;
+
+        // Renames of committed SSTables interrupted by a crash are finished on recovery
+        for sstable_id in edit.added {
+            self.filesystem
+                .publish_sstable_file(table_id, sstable_id)
+                .await
+                .ok_or_persistence_error()?;
+        }
+
+        Ok(())
+    }
+
     async fn open_sstable(
         &self,
         table_id: &KVTableId,
@@ -167,6 +206,20 @@ impl PersistenceLayer for ArgonFs {
         Ok(Box::new(argonfile_sstable))
     }
 
+    async fn scan_table_ids(&self) -> Result<Vec<KVTableId<'static>>, PersistenceError> {
+        self.filesystem
+            .scan_table_ids()
+            .await
+            .ok_or_persistence_error()
+    }
+
+    async fn remove_table_storage(&self, table_id: &KVTableId) -> Result<(), PersistenceError> {
+        self.filesystem
+            .remove_table_dir(table_id)
+            .await
+            .ok_or_persistence_error()
+    }
+
     async fn remove_compacted_sstables(
         &self,
         table_id: &KVTableId,
@@ -184,18 +237,50 @@ impl PersistenceLayer for ArgonFs {
 
         Ok(())
     }
+
+    async fn remove_uncommitted_sstables(
+        &self,
+        table_id: &KVTableId,
+        sstable_ids: Vec<ObjectId>,
+    ) -> Result<(), PersistenceError> {
+        for sstable_id in sstable_ids {
+            let file_ref = self
+                .filesystem
+                .get_sstable_temp_file_ref(table_id, sstable_id)
+                .await
+                .ok_or_persistence_error()?;
+
+            file_ref.remove().await.ok_or_persistence_error()?;
+        }
+
+        Ok(())
+    }
+
+    async fn read_commit_log(&self) -> Result<Vec<KVCommitLogRecord>, PersistenceError> {
+        self.commit_log.read_replay_records().await
+    }
+
+    async fn remove_replayed_commit_log(&self) -> Result<(), PersistenceError> {
+        self.commit_log.remove_replayed_segments().await
+    }
 }
 
 #[derive(Debug)]
-pub struct ArgonFsInitError {}
+pub struct ArgonFsInitError {
+    source: PersistenceError,
+}
 
 impl Display for ArgonFsInitError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "ArgonFsInitError")
+        write!(f, "ArgonFsInitError source: {}", self.source)
     }
 }
 
-impl std::error::Error for ArgonFsInitError {}
+impl std::error::Error for ArgonFsInitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
 
 #[derive(Debug)]
 pub enum ArgonFsError {
@@ -0,0 +1,97 @@
+use std::{io::Write, sync::Arc};
+
+use crate::{
+    argonfs::fs::BoxFileSystem,
+    core::persistence::{OrPersistenceError, PersistenceError},
+    kv::{KVInstanceStateSnapshot, KVObjectIdLeaseStore, KVRuntimeError, KVRuntimeErrorKind},
+};
+
+/**
+ * Instance state snapshot is saved on clean shutdown and whenever a new object id lease is taken.
+ * Snapshot file is replaced atomically, a crash leaves either the old or the new snapshot.
+ */
+pub struct ArgonFsInstanceSnapshotStore {
+    filesystem: Arc<BoxFileSystem>,
+}
+
+impl ArgonFsInstanceSnapshotStore {
+    pub fn new(filesystem: Arc<BoxFileSystem>) -> Self {
+        Self { filesystem }
+    }
+
+    pub async fn read(&self) -> Result<Option<KVInstanceStateSnapshot>, PersistenceError> {
+        let file_ref = self
+            .filesystem
+            .get_state_snapshot_file_ref()
+            .await
+            .ok_or_persistence_error()?;
+
+        match file_ref.open_read_only().await {
+            Ok(mut reader) => {
+                let object_id_generator_state = u64::from_le_bytes(
+                    reader
+                        .read(8)
+                        .await
+                        .ok_or_persistence_error()?
+                        .as_ref()
+                        .try_into()
+                        .unwrap(),
+                );
+
+                Ok(Some(KVInstanceStateSnapshot {
+                    object_id_generator_state,
+                }))
+            }
+            Err(e) => match e.kind() {
+                std::io::ErrorKind::NotFound => {
+                    println!("argonfs - instance snapshot file not found");
+                    Ok(None)
+                }
+                _ => Err(PersistenceError(Box::new(e))),
+            },
+        }
+    }
+
+    pub async fn save(&self, snapshot: &KVInstanceStateSnapshot) -> Result<(), PersistenceError> {
+        let file_ref = self
+            .filesystem
+            .get_state_snapshot_temp_file_ref()
+            .await
+            .ok_or_persistence_error()?;
+
+        let mut writer = file_ref.open_write_only().await.ok_or_persistence_error()?;
+
+        writer
+            .write_all(&u64::to_le_bytes(snapshot.object_id_generator_state))
+            .ok_or_persistence_error()?;
+        writer.flush().ok_or_persistence_error()?;
+
+        self.filesystem
+            .publish_state_snapshot_file()
+            .await
+            .ok_or_persistence_error()
+    }
+}
+
+impl std::fmt::Debug for ArgonFsInstanceSnapshotStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ArgonFsInstanceSnapshotStore")
+            .finish_non_exhaustive()
+    }
+}
+
+impl KVObjectIdLeaseStore for ArgonFsInstanceSnapshotStore {
+    fn persist_lease(&self, leased_until: u64) -> Result<(), KVRuntimeError> {
+        let snapshot = KVInstanceStateSnapshot {
+            object_id_generator_state: leased_until,
+        };
+
+        smol::block_on(self.save(&snapshot)).map_err(|e| {
+            KVRuntimeError::with_msg_and_source(
+                KVRuntimeErrorKind::OperationFailure,
+                "object id lease persist failed",
+                e,
+            )
+        })
+    }
+}
@@ -1,12 +1,12 @@
 use crate::{
     argonfs::{
         argonfile::{
-            ArgonfileReader, ArgonfileReaderError, BlockPointer, block::Block, stats::StatsParser,
-            summary::SummaryParser,
+            ArgonfileReader, ArgonfileReaderError, BlockPointer, RangeTombstonesParser,
+            block::Block, stats::StatsParser, summary::SummaryParser,
         },
         fs::BoxFileRef,
     },
-    kv::ObjectId,
+    kv::{KVRangeTombstone, ObjectId},
 };
 
 use super::{stats::Stats, summary::SummaryIndex};
@@ -15,8 +15,10 @@ pub struct Argonfile {
     pub file_ref: BoxFileRef,
     pub sstable_id: ObjectId,
     pub level: u64,
+    pub file_size: u64,
     pub summary_index: SummaryIndex,
     pub stats: Stats,
+    pub range_tombstones: Vec<KVRangeTombstone>,
 }
 
 impl Argonfile {
@@ -25,6 +27,7 @@ impl Argonfile {
         let mut reader = ArgonfileReader::new(file_handle);
 
         let trailer = reader.read_trailer().await?;
+        let file_size = reader.file_size().await?;
 
         let sstable_id = trailer.sstable_id;
         let level = trailer.level;
@@ -35,12 +38,22 @@ impl Argonfile {
         let stats_block = reader.read_block(&trailer.stats_block_ptr).await?;
         let stats = StatsParser::parse(&stats_block.data)?;
 
+        let range_tombstones = match &trailer.range_tombstones_block_ptr {
+            Some(block_ptr) => {
+                let range_tombstones_block = reader.read_block(block_ptr).await?;
+                RangeTombstonesParser::parse(&range_tombstones_block.data)?
+            }
+            None => vec![],
+        };
+
         Ok(Self {
             file_ref,
             sstable_id,
             level,
+            file_size,
             stats,
             summary_index,
+            range_tombstones,
         })
     }
 
@@ -13,12 +13,13 @@ use crate::{
             checksum::{ChecksumAlgoResolver, ChecksumType},
             compression::{CompressionAlgoResolver, CompressionType},
         },
+        range_tombstones::RangeTombstonesBuilder,
         stats::StatsBuilder,
         summary::SummaryBuilder,
     },
     kv::{
-        KVFlushPreStats, KVRuntimeError, KVRuntimeErrorKind, KVSSTableBuilder, KVScanIterator,
-        KVScannable, ObjectId, memtable::Memtable, mutation::KVMutation,
+        KVFlushPreStats, KVRangeTombstone, KVRuntimeError, KVRuntimeErrorKind, KVSSTableBuilder,
+        KVScanIterator, KVScannable, ObjectId, memtable::Memtable, mutation::KVMutation,
     },
 };
 
@@ -47,7 +48,8 @@ impl ArgonfileBuilder {
             .await
             .map_err(|e| ArgonfileBuilderError::from_source(e))?;
 
-        let (mut writer, summary_block_ptr, stats_block_ptr) = orchestrator.end()?;
+        let (mut writer, summary_block_ptr, stats_block_ptr, range_tombstones_block_ptr) =
+            orchestrator.end()?;
 
         Trailer::serialize(
             &mut writer,
@@ -56,6 +58,7 @@ impl ArgonfileBuilder {
                 level: 0,
                 summary_block_ptr,
                 stats_block_ptr,
+                range_tombstones_block_ptr: Some(range_tombstones_block_ptr),
             },
         )?;
 
@@ -67,6 +70,7 @@ impl ArgonfileBuilder {
     pub async fn flush_iter<'a, W: Write + Send + Sync, I: KVScanIterator + Send + Sync>(
         writer: W,
         mut iter: I,
+        range_tombstones: &[KVRangeTombstone],
         sstable_id: ObjectId,
         level: u64,
         pre_stats: KVFlushPreStats,
@@ -85,7 +89,12 @@ impl ArgonfileBuilder {
             orchestrator.add_mutation(item.mutation()).await.unwrap();
         }
 
-        let (mut writer, summary_block_ptr, stats_block_ptr) = orchestrator.end()?;
+        for tombstone in range_tombstones {
+            orchestrator.add_range_tombstone(tombstone).await.unwrap();
+        }
+
+        let (mut writer, summary_block_ptr, stats_block_ptr, range_tombstones_block_ptr) =
+            orchestrator.end()?;
 
         Trailer::serialize(
             &mut writer,
@@ -94,6 +103,7 @@ impl ArgonfileBuilder {
                 level,
                 summary_block_ptr,
                 stats_block_ptr,
+                range_tombstones_block_ptr: Some(range_tombstones_block_ptr),
             },
         )?;
 
@@ -109,6 +119,7 @@ struct BlocksBuildingOrchestrator<'a, W: Write> {
 
     stats_builder: StatsBuilder,
     summary_builder: SummaryBuilder,
+    range_tombstones_builder: RangeTombstonesBuilder,
 
     block_builder: Option<BlockBuilder>,
     row_builder: Option<RowBuilder>,
@@ -127,6 +138,7 @@ impl<'a, W: Write> BlocksBuildingOrchestrator<'a, W> {
 
             stats_builder,
             summary_builder,
+            range_tombstones_builder: RangeTombstonesBuilder::new(),
 
             block_builder: None,
             row_builder: None,
@@ -140,16 +152,26 @@ impl<'a, W: Write> BlocksBuildingOrchestrator<'a, W> {
             ArgonfileOffsetCountingWriteWrapper<W>,
             BlockPointer,
             BlockPointer,
+            BlockPointer,
         ),
         ArgonfileBuilderError,
     > {
-        self.end_row()?;
-        self.flush_block()?;
+        // Files holding only range tombstones have no data blocks
+        if self.row_builder.is_some() {
+            self.end_row()?;
+            self.flush_block()?;
+        }
 
         let summary_block_ptr = self.summary_builder.build(&mut self.writer)?;
         let stats_block_ptr = self.stats_builder.build(&mut self.writer)?;
-
-        Ok((self.writer, summary_block_ptr, stats_block_ptr))
+        let range_tombstones_block_ptr = self.range_tombstones_builder.build(&mut self.writer)?;
+
+        Ok((
+            self.writer,
+            summary_block_ptr,
+            stats_block_ptr,
+            range_tombstones_block_ptr,
+        ))
     }
 
     fn prepare_row(&mut self, mutation: &dyn KVMutation) -> Result<(), ArgonfileBuilderError> {
@@ -264,6 +286,15 @@ impl<'a, W: Write + Send> KVSSTableBuilder for BlocksBuildingOrchestrator<'a, W>
 
         Ok(())
     }
+
+    async fn add_range_tombstone(
+        &mut self,
+        tombstone: &KVRangeTombstone,
+    ) -> Result<(), KVRuntimeError> {
+        self.range_tombstones_builder.add_range_tombstone(tombstone);
+
+        Ok(())
+    }
 }
 
 pub struct ArgonfileBuilderConfig {
@@ -20,6 +20,7 @@ impl ArgonfileReader {
     }
 
     pub async fn read_trailer(&mut self) -> Result<Trailer, ArgonfileReaderError> {
+        // Older, shorter trailers are located by their magic within the read tail
         let trailer_size = Trailer::SERIALIZED_SIZE;
 
         let buf = self
@@ -31,6 +32,10 @@ impl ArgonfileReader {
         Ok(trailer)
     }
 
+    pub async fn file_size(&mut self) -> Result<u64, ArgonfileReaderError> {
+        Ok(self.file_handle.seek(SeekFrom::End(0)).await?)
+    }
+
     pub async fn read_block(
         &mut self,
         block_ptr: &BlockPointer,
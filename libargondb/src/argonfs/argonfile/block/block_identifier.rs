@@ -3,3 +3,4 @@ pub type BlockIdentifier = [u8; 8];
 pub const BLOCK_IDENTIFIER_DATA: &BlockIdentifier = b"BLK_DATA";
 pub const BLOCK_IDENTIFIER_SUMMARY: &BlockIdentifier = b"BLK_SUMM";
 pub const BLOCK_IDENTIFIER_STATS: &BlockIdentifier = b"BLK_STAT";
+pub const BLOCK_IDENTIFIER_RANGE_TOMBSTONES: &BlockIdentifier = b"BLK_RTMB";
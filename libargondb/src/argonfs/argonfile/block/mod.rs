@@ -10,6 +10,7 @@ pub mod compression;
 pub use block::Block;
 pub use block_builder::BlockBuilder;
 pub use block_identifier::BLOCK_IDENTIFIER_DATA;
+pub use block_identifier::BLOCK_IDENTIFIER_RANGE_TOMBSTONES;
 pub use block_identifier::BLOCK_IDENTIFIER_STATS;
 pub use block_identifier::BLOCK_IDENTIFIER_SUMMARY;
 pub use block_parser::BlockParser;
@@ -6,6 +6,7 @@ mod block;
 mod parse_utils;
 
 mod error;
+mod range_tombstones;
 mod row;
 mod stats;
 mod summary;
@@ -19,6 +20,7 @@ pub use argonfile_data_block_iter::ArgonfileDataBlockIter;
 pub use argonfile_reader::ArgonfileReader;
 pub use argonfile_reader::ArgonfileReaderError;
 pub use block::BlockPointer;
+pub use range_tombstones::RangeTombstonesParser;
 pub use stats::StatsParser;
 pub use summary::SummaryParser;
 
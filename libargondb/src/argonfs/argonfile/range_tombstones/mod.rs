@@ -0,0 +1,8 @@
+mod range_tombstones_builder;
+mod range_tombstones_parser;
+
+pub use range_tombstones_builder::RangeTombstonesBuilder;
+pub use range_tombstones_parser::RangeTombstonesParser;
+
+#[cfg(test)]
+mod range_tombstones_tests;
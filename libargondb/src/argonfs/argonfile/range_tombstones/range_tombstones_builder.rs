@@ -0,0 +1,60 @@
+use crate::{
+    argonfs::argonfile::{
+        block::{
+            BLOCK_IDENTIFIER_RANGE_TOMBSTONES, BlockBuilder, BlockPointer,
+            checksum::{ChecksumAlgoResolver, ChecksumType},
+            compression::CompressionAlgoResolver,
+        },
+        error::ArgonfileBuilderError,
+        utils::ArgonfileWrite,
+    },
+    kv::KVRangeTombstone,
+};
+
+/*
+ * Range tombstones block layout:
+ * [range_tombstones_count: u64]([range tombstone])*
+ */
+pub struct RangeTombstonesBuilder {
+    tombstones: Vec<KVRangeTombstone>,
+}
+
+impl RangeTombstonesBuilder {
+    pub fn new() -> Self {
+        Self { tombstones: vec![] }
+    }
+
+    pub fn add_range_tombstone(&mut self, tombstone: &KVRangeTombstone) {
+        self.tombstones.push(tombstone.clone());
+    }
+
+    pub fn build(
+        self,
+        writer: &mut impl ArgonfileWrite,
+    ) -> Result<BlockPointer, ArgonfileBuilderError> {
+        let mut block_builder = BlockBuilder::new(0);
+
+        let mut buf = Vec::<u8>::new();
+        buf.extend_from_slice(&u64::to_le_bytes(self.tombstones.len() as u64));
+
+        for tombstone in &self.tombstones {
+            tombstone.serialize(&mut buf);
+        }
+
+        block_builder
+            .write(&buf)
+            .map_err(|_| ArgonfileBuilderError::from_msg("ArgonfileBlockWriterError"))?;
+
+        let checksum_algo = ChecksumAlgoResolver::for_checksum_type(ChecksumType::CRC32);
+        let compression_algo = CompressionAlgoResolver::for_default_compression_type();
+
+        let ptr = block_builder.build(
+            writer,
+            BLOCK_IDENTIFIER_RANGE_TOMBSTONES,
+            &checksum_algo,
+            &compression_algo,
+        )?;
+
+        Ok(ptr)
+    }
+}
@@ -0,0 +1,28 @@
+use super::super::parse_utils::ensure_min_size;
+use crate::{
+    argonfs::argonfile::error::{ArgonfileParseError, ArgonfileParseResult},
+    kv::KVRangeTombstone,
+};
+
+pub struct RangeTombstonesParser;
+
+impl RangeTombstonesParser {
+    pub fn parse(buf: &[u8]) -> ArgonfileParseResult<Vec<KVRangeTombstone>> {
+        ensure_min_size(buf.len(), 8)?;
+
+        let tombstones_count = u64::from_le_bytes(buf[0..8].try_into().unwrap());
+
+        let mut buf = &buf[8..];
+
+        // Count comes from the file, capacity is limited by what the block can hold
+        let capacity = usize::try_from(tombstones_count)
+            .unwrap_or(usize::MAX)
+            .min(buf.len() / KVRangeTombstone::MIN_SIZE);
+        let mut tombstones = Vec::with_capacity(capacity);
+        for _ in 0..tombstones_count {
+            tombstones.push(KVRangeTombstone::parse(&mut buf).map_err(|_| ArgonfileParseError)?);
+        }
+
+        Ok(tombstones)
+    }
+}
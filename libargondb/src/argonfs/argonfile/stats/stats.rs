@@ -8,7 +8,7 @@ use crate::{
         utils::{ArgonfileSizeCountingWriter, ArgonfileWrite},
     },
     kv::{
-        KVPrimaryKeyMarker, KVRangeScan,
+        KVRangeScan,
         primary_key::{KVPrimaryKeyComparator, KVPrimaryKeySchema, PrimaryKeyMarkerComparator},
     },
 };
@@ -18,41 +18,50 @@ pub struct Stats {
     pub bloom_filter: Bloom<[u8]>,
     pub min_row_key: Box<[u8]>,
     pub max_row_key: Box<[u8]>,
+    /** Files written before timestamps were tracked report the widest possible range. */
+    pub min_timestamp: u64,
+    pub max_timestamp: u64,
 }
 
 impl Stats {
     pub const MIN_SIZE_SERIALIZED: usize = 20;
+    pub const TIMESTAMPS_SIZE_SERIALIZED: usize = 16;
+
+    /** Files holding only range tombstones have no data - their row keys are empty. */
+    pub fn has_data(&self) -> bool {
+        !self.min_row_key.is_empty()
+    }
 
     pub fn is_range_scan_intersecting(
         &self,
         schema: &KVPrimaryKeySchema,
         range_scan: &KVRangeScan,
     ) -> bool {
-        let from = range_scan.from();
-        let to = range_scan.to();
-
-        // Sanity check
-        assert!(PrimaryKeyMarkerComparator::cmp(schema, from, to).unwrap() != Ordering::Greater);
+        if !self.has_data() {
+            return false;
+        }
 
-        let not_intersecting = (PrimaryKeyMarkerComparator::cmp(
+        // Bounds are monotonic over sorted keys, so the scan misses the file only if the
+        // largest key is below `from` or the smallest one above `to`
+        PrimaryKeyMarkerComparator::is_above_lower_bound(
             schema,
-            to,
-            &KVPrimaryKeyMarker::Key(self.min_row_key.clone()),
+            range_scan.from(),
+            &self.max_row_key,
         )
         .unwrap()
-            == Ordering::Less)
-            || (PrimaryKeyMarkerComparator::cmp(
+            && PrimaryKeyMarkerComparator::is_below_upper_bound(
                 schema,
-                from,
-                &KVPrimaryKeyMarker::Key(self.max_row_key.clone()),
+                range_scan.to(),
+                &self.min_row_key,
             )
             .unwrap()
-                == Ordering::Greater);
-
-        !not_intersecting
     }
 
     pub fn is_row_scan_intersecting(&self, schema: &KVPrimaryKeySchema, row_key: &[u8]) -> bool {
+        if !self.has_data() {
+            return false;
+        }
+
         let not_intersecting = (KVPrimaryKeyComparator::cmp(schema, row_key, &self.min_row_key)
             .unwrap()
             == Ordering::Less)
@@ -89,6 +98,9 @@ impl Stats {
         writer.write(&stats.max_row_key)?;
         writer.write(&bloom_filter)?;
 
+        writer.write(&u64::to_le_bytes(stats.min_timestamp))?;
+        writer.write(&u64::to_le_bytes(stats.max_timestamp))?;
+
         Ok(writer.size())
     }
 }
@@ -22,6 +22,8 @@ pub struct StatsBuilder {
     bloom: Bloom<[u8]>,
     min_key: Option<Box<[u8]>>,
     max_key: Option<Box<[u8]>>,
+    min_timestamp: u64,
+    max_timestamp: u64,
 }
 
 impl StatsBuilder {
@@ -30,8 +32,9 @@ impl StatsBuilder {
     pub fn new(pre_stats: KVFlushPreStats) -> Result<Self, ArgonfileBuilderError> {
         let mutation_count = pre_stats.mutations_count;
 
-        let bloom =
-            Bloom::new_for_fp_rate(mutation_count, Self::BLOOM_FILTER_FP_CHANCE).map_err(|e| {
+        // Files holding only range tombstones have no mutations, bloom filter needs a capacity
+        let bloom = Bloom::new_for_fp_rate(mutation_count.max(1), Self::BLOOM_FILTER_FP_CHANCE)
+            .map_err(|e| {
                 ArgonfileBuilderError::from_msg(format!("Bloom construction error: {}", e))
             })?;
 
@@ -40,6 +43,8 @@ impl StatsBuilder {
             bloom,
             min_key: None,
             max_key: None,
+            min_timestamp: u64::MAX,
+            max_timestamp: 0,
         })
     }
 
@@ -51,6 +56,9 @@ impl StatsBuilder {
         }
         self.max_key = Some(key.to_owned().into_boxed_slice());
 
+        self.min_timestamp = self.min_timestamp.min(mutation.timestamp());
+        self.max_timestamp = self.max_timestamp.max(mutation.timestamp());
+
         self.bloom.set(mutation.primary_key());
     }
 
@@ -60,18 +68,17 @@ impl StatsBuilder {
     ) -> Result<BlockPointer, ArgonfileBuilderError> {
         let mut block_builder = BlockBuilder::new(0);
 
-        let min_row_key = self.min_key.ok_or(ArgonfileBuilderError::from_msg(
-            "no min row key in stat builder",
-        ))?;
-        let max_row_key = self.max_key.ok_or(ArgonfileBuilderError::from_msg(
-            "no max row key in stat builder",
-        ))?;
+        // Empty keys mark a file without data, see `Stats::has_data`
+        let min_row_key = self.min_key.unwrap_or_default();
+        let max_row_key = self.max_key.unwrap_or_default();
 
         let stats = Stats {
             mutation_count: self.mutation_count,
             bloom_filter: self.bloom,
             min_row_key,
             max_row_key,
+            min_timestamp: self.min_timestamp,
+            max_timestamp: self.max_timestamp,
         };
 
         let buf = Vec::<u8>::new();
@@ -32,11 +32,24 @@ impl StatsParser {
         let bloom_filter =
             Bloom::<[u8]>::from_bytes(bloom_filter.to_vec()).map_err(|_| ArgonfileParseError)?;
 
+        // Timestamps were appended to the stats block later - older files don't contain them
+        let buf = &buf[bloom_filter_size..];
+        let (min_timestamp, max_timestamp) = if buf.len() >= Stats::TIMESTAMPS_SIZE_SERIALIZED {
+            (
+                u64::from_le_bytes(buf[0..8].try_into().unwrap()),
+                u64::from_le_bytes(buf[8..16].try_into().unwrap()),
+            )
+        } else {
+            (0, u64::MAX)
+        };
+
         Ok(Stats {
             mutation_count,
             bloom_filter,
             min_row_key,
             max_row_key,
+            min_timestamp,
+            max_timestamp,
         })
     }
 }
@@ -6,8 +6,8 @@ use crate::{
         utils::{ArgonfileSizeCountingWriter, ArgonfileWrite},
     },
     kv::{
-        KVPrimaryKeyMarker, KVRangeScan,
-        primary_key::{KVPrimaryKeyComparator, KVPrimaryKeySchema, PrimaryKeyMarkerComparator},
+        KVRangeScan,
+        primary_key::{KVPrimaryKeySchema, PrimaryKeyMarkerComparator},
     },
 };
 
@@ -24,42 +24,23 @@ impl SummaryIndex {
         schema: &KVPrimaryKeySchema,
         range_scan: &KVRangeScan,
     ) -> Vec<BlockPointer> {
-        let from = range_scan.from();
-        let to = range_scan.to();
-
-        let from_find = self.entries.binary_search_by(|entry| {
-            PrimaryKeyMarkerComparator::cmp(
-                schema,
-                &KVPrimaryKeyMarker::Key(entry.key.clone()),
-                from,
-            )
-            .unwrap()
+        // Entries hold the first key of each block. A block may end with keys matching `from`
+        // even when the next one starts with them, so selection starts one block earlier.
+        let first_above = self.entries.partition_point(|entry| {
+            !PrimaryKeyMarkerComparator::is_above_lower_bound(schema, range_scan.from(), &entry.key)
+                .unwrap()
         });
-
-        let start = match from_find {
-            Ok(idx) => idx,
-            Err(idx) => {
-                if idx >= self.entries.len() {
-                    self.entries.len() - 1
-                } else if idx == 0 {
-                    0
-                } else {
-                    idx - 1
-                }
-            }
-        };
-
-        let to_find = self.entries.binary_search_by(|entry| {
-            PrimaryKeyMarkerComparator::cmp(schema, &KVPrimaryKeyMarker::Key(entry.key.clone()), to)
+        let below_count = self.entries.partition_point(|entry| {
+            PrimaryKeyMarkerComparator::is_below_upper_bound(schema, range_scan.to(), &entry.key)
                 .unwrap()
         });
 
-        let end = match to_find {
-            Ok(idx) => idx,
-            Err(idx) => idx.min(self.entries.len() - 1),
-        };
+        let start = first_above.saturating_sub(1);
+        if below_count == 0 || start >= below_count {
+            return Vec::new();
+        }
 
-        self.entries[start..=end]
+        self.entries[start..below_count]
             .iter()
             .map(|entry| entry.block_ptr)
             .collect()
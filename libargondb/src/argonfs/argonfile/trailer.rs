@@ -1,13 +1,15 @@
 use super::block::BlockPointer;
-use super::parse_utils::ensure_size;
+use super::parse_utils::ensure_min_size;
 use super::{
     error::ArgonfileWriterError,
     utils::{ArgonfileSizeCountingWriter, ArgonfileWrite},
 };
-use crate::argonfs::argonfile::error::ArgonfileParseResult;
+use crate::argonfs::argonfile::error::{ArgonfileParseError, ArgonfileParseResult};
 use crate::kv::ObjectId;
 
-pub const ARGONFILE_MAGIC: &'static [u8; 8] = b"ARGNFILE";
+pub const ARGONFILE_MAGIC: &[u8; 8] = b"ARGNFIL2";
+/** Magic of files written before range tombstones, their trailer has no range tombstones block. */
+pub const ARGONFILE_MAGIC_V1: &[u8; 8] = b"ARGNFILE";
 
 #[derive(Debug)]
 pub struct Trailer {
@@ -15,13 +17,28 @@ pub struct Trailer {
     pub level: u64,
     pub summary_block_ptr: BlockPointer,
     pub stats_block_ptr: BlockPointer,
+    pub range_tombstones_block_ptr: Option<BlockPointer>,
 }
 
 impl Trailer {
-    pub const SERIALIZED_SIZE: usize = 48;
+    pub const SERIALIZED_SIZE: usize = 60;
+    pub const SERIALIZED_SIZE_V1: usize = 48;
 
+    /** Parses trailer from the tail of a file - buffer may hold bytes preceding the trailer. */
     pub fn parse(buf: &[u8]) -> ArgonfileParseResult<Trailer> {
-        ensure_size(buf.len(), Self::SERIALIZED_SIZE)?;
+        ensure_min_size(buf.len(), Self::SERIALIZED_SIZE_V1)?;
+
+        let magic = &buf[(buf.len() - 8)..];
+        let (buf, range_tombstones_block_ptr) = if magic == ARGONFILE_MAGIC_V1 {
+            (&buf[(buf.len() - Self::SERIALIZED_SIZE_V1)..], None)
+        } else if magic == ARGONFILE_MAGIC {
+            ensure_min_size(buf.len(), Self::SERIALIZED_SIZE)?;
+
+            let buf = &buf[(buf.len() - Self::SERIALIZED_SIZE)..];
+            (buf, Some(BlockPointer::parse(&buf[40..52])?))
+        } else {
+            return Err(ArgonfileParseError);
+        };
 
         let sstable_id = u64::from_le_bytes(buf[0..8].try_into().unwrap());
         let level = u64::from_le_bytes(buf[8..16].try_into().unwrap());
@@ -33,6 +50,7 @@ impl Trailer {
             level,
             stats_block_ptr,
             summary_block_ptr,
+            range_tombstones_block_ptr,
         })
     }
 
@@ -46,7 +64,16 @@ impl Trailer {
         writer.write(&u64::to_le_bytes(trailer.level))?;
         BlockPointer::serialize(&mut writer, &trailer.stats_block_ptr)?;
         BlockPointer::serialize(&mut writer, &trailer.summary_block_ptr)?;
-        writer.write(ARGONFILE_MAGIC)?;
+
+        match &trailer.range_tombstones_block_ptr {
+            Some(range_tombstones_block_ptr) => {
+                BlockPointer::serialize(&mut writer, range_tombstones_block_ptr)?;
+                writer.write(ARGONFILE_MAGIC)?;
+            }
+            None => {
+                writer.write(ARGONFILE_MAGIC_V1)?;
+            }
+        }
 
         Ok(writer.size())
     }
@@ -1,5 +1,5 @@
 use std::{
-    cmp::Ordering,
+    collections::VecDeque,
     error::Error,
     fmt::Display,
     io::{self, Write},
@@ -19,11 +19,12 @@ use crate::{
         fs::BoxFileRef,
     },
     kv::{
-        KVColumnFilter, KVPrimaryKeyMarker, KVRangeScan, KVRangeScanResult, KVRuntimeError,
-        KVSSTable, KVSSTableDataBlockIter, KVScanIterator, KVScanIteratorItem, KVScannable,
-        KVTableSchema, ObjectId, PrintIter,
+        KVColumnFilter, KVPrimaryKeyMarker, KVRangeScan, KVRangeScanResult, KVRangeTombstone,
+        KVRowScan, KVRuntimeError, KVSSTable, KVSSTableDataBlockIter, KVScanDirection,
+        KVScanIterUtils, KVScanIterator, KVScanIteratorItem, KVScannable, KVTableSchema, ObjectId,
+        PrintIter,
         mutation::MutationUtils,
-        primary_key::{KVPrimaryKeySchema, PrimaryKeyMarkerComparator},
+        primary_key::{KVPrimaryKeyComparator, KVPrimaryKeySchema, PrimaryKeyMarkerComparator},
     },
 };
 
@@ -111,7 +112,8 @@ impl KVScannable for ArgonfileSSTable {
         ))))
     }
 
-    async fn row_scan(&self, primary_key: &[u8]) -> Result<KVRangeScanResult, KVRuntimeError> {
+    async fn row_scan(&self, scan: &KVRowScan) -> Result<KVRangeScanResult, KVRuntimeError> {
+        let primary_key = scan.primary_key();
         let pk_schema = KVPrimaryKeySchema::from_table_schema(&self.schema);
 
         let is_intersecting = self
@@ -130,9 +132,9 @@ impl KVScannable for ArgonfileSSTable {
 
         let range_scan = KVRangeScan::new(
             self.schema.clone(),
-            KVPrimaryKeyMarker::Key(primary_key.to_vec().into_boxed_slice()),
-            KVPrimaryKeyMarker::Key(primary_key.to_vec().into_boxed_slice()),
-            KVColumnFilter::All,
+            KVPrimaryKeyMarker::Key(primary_key.into()),
+            KVPrimaryKeyMarker::Key(primary_key.into()),
+            scan.columns().clone(),
         );
         let iter = RangeScanIterator::new(
             self.schema.clone(),
@@ -150,6 +152,10 @@ impl KVScannable for ArgonfileSSTable {
             self.schema.clone(),
         ))))
     }
+
+    fn range_tombstones(&self) -> Vec<KVRangeTombstone> {
+        self.argonfile.range_tombstones.clone()
+    }
 }
 
 impl KVSSTable for ArgonfileSSTable {
@@ -162,6 +168,21 @@ impl KVSSTable for ArgonfileSSTable {
     fn mutation_count(&self) -> u64 {
         self.argonfile.stats.mutation_count
     }
+    fn min_row_key(&self) -> &[u8] {
+        &self.argonfile.stats.min_row_key
+    }
+    fn max_row_key(&self) -> &[u8] {
+        &self.argonfile.stats.max_row_key
+    }
+    fn min_timestamp(&self) -> u64 {
+        self.argonfile.stats.min_timestamp
+    }
+    fn max_timestamp(&self) -> u64 {
+        self.argonfile.stats.max_timestamp
+    }
+    fn size(&self) -> u64 {
+        self.argonfile.file_size
+    }
 }
 
 impl std::fmt::Display for ArgonfileSSTable {
@@ -186,6 +207,14 @@ struct RangeScanIterator<B: Buf> {
     worker_pool: Arc<ArgonFsWorkerPool>,
     from: KVPrimaryKeyMarker,
     to: KVPrimaryKeyMarker,
+    columns: KVColumnFilter,
+    direction: KVScanDirection,
+    /** Reverse scans only - rows of the last loaded block ready to be returned. */
+    reverse_queue: VecDeque<Box<dyn KVScanIteratorItem + Send + Sync>>,
+    /**
+     * Reverse scans only - lowest row of the last loaded block, it may continue in previous one.
+     */
+    reverse_carry: Vec<Box<dyn KVScanIteratorItem + Send + Sync>>,
 }
 
 impl RangeScanIterator<Box<BlockView>> {
@@ -197,10 +226,14 @@ impl RangeScanIterator<Box<BlockView>> {
         argonfile: Arc<Argonfile>,
         range_scan: &KVRangeScan,
     ) -> Self {
-        let block_ptrs = argonfile
+        let mut block_ptrs = argonfile
             .summary_index
             .get_blocks_for_range_scan(schema, range_scan);
 
+        if range_scan.direction() == KVScanDirection::Reverse {
+            block_ptrs.reverse();
+        }
+
         let mut this = Self {
             table_schema,
             schema: schema.clone(),
@@ -213,6 +246,10 @@ impl RangeScanIterator<Box<BlockView>> {
             worker_pool,
             from: range_scan.from().clone(),
             to: range_scan.to().clone(),
+            columns: range_scan.columns().clone(),
+            direction: range_scan.direction(),
+            reverse_queue: VecDeque::new(),
+            reverse_carry: vec![],
         };
 
         #[cfg(debug_assertions)]
@@ -248,6 +285,11 @@ impl RangeScanIterator<Box<BlockView>> {
     }
 
     async fn load_next_entry(&mut self) {
+        if self.direction == KVScanDirection::Reverse {
+            self.load_next_entry_reverse().await;
+            return;
+        }
+
         #[cfg(debug_assertions)]
         println!(
             "argonfile id: {}, current_block_iter: {:?}",
@@ -256,30 +298,7 @@ impl RangeScanIterator<Box<BlockView>> {
         loop {
             if let Some(iter) = &mut self.current_block_iter {
                 if let Some(entry) = iter.next() {
-                    let lower_bound = PrimaryKeyMarkerComparator::cmp_with_key(
-                        &self.schema,
-                        &self.from,
-                        entry.primary_key(),
-                    )
-                    .unwrap()
-                        != Ordering::Greater;
-
-                    let upper_bound = PrimaryKeyMarkerComparator::cmp_with_key(
-                        &self.schema,
-                        &self.to,
-                        entry.primary_key(),
-                    )
-                    .unwrap()
-                        != Ordering::Less;
-
-                    #[cfg(debug_assertions)]
-                    println!(
-                        "argonfile id: {}, entry: {}",
-                        self.argonfile.sstable_id,
-                        MutationUtils::debug_fmt(&self.table_schema, entry.mutation()).unwrap()
-                    );
-
-                    if lower_bound && upper_bound {
+                    if self.is_entry_in_scan(entry.as_ref()) {
                         self.current_entry = Some(entry);
                         break;
                     }
@@ -292,6 +311,98 @@ impl RangeScanIterator<Box<BlockView>> {
             }
         }
     }
+
+    /**
+     * Blocks are loaded from the last one and their rows are returned in reverse order. Row
+     * may span multiple blocks - the lowest row of a block is held back until the previous
+     * block is loaded, so mutations of a row are returned together and in their original order.
+     */
+    async fn load_next_entry_reverse(&mut self) {
+        loop {
+            if let Some(entry) = self.reverse_queue.pop_front() {
+                self.current_entry = Some(entry);
+                return;
+            }
+
+            if self.current_block_iter.is_none() {
+                self.load_next_iter().await;
+            }
+
+            let Some(mut iter) = self.current_block_iter.take() else {
+                if self.reverse_carry.is_empty() {
+                    self.current_entry = None;
+                    return;
+                }
+
+                self.reverse_queue.extend(self.reverse_carry.drain(..));
+                continue;
+            };
+
+            let mut entries = vec![];
+            while let Some(entry) = iter.next() {
+                if self.is_entry_in_scan(entry.as_ref()) {
+                    entries.push(entry);
+                }
+            }
+
+            let mut rows = KVScanIterUtils::group_rows(&self.schema, entries);
+
+            // Held back row has higher key than all rows of this block, unless it continues here
+            let carry = std::mem::take(&mut self.reverse_carry);
+            if !carry.is_empty() {
+                match rows.last_mut() {
+                    Some(row)
+                        if KVPrimaryKeyComparator::eq(
+                            &self.schema,
+                            row[0].primary_key(),
+                            carry[0].primary_key(),
+                        )
+                        .unwrap() =>
+                    {
+                        row.extend(carry);
+                    }
+                    _ => rows.push(carry),
+                }
+            }
+
+            if rows.is_empty() {
+                continue;
+            }
+
+            self.reverse_carry = rows.remove(0);
+            self.reverse_queue.extend(rows.into_iter().rev().flatten());
+        }
+    }
+
+    fn is_entry_in_scan(&self, entry: &(dyn KVScanIteratorItem + Send + Sync)) -> bool {
+        // Unrequested columns are dropped before key comparisons and row assembly
+        if !self.columns.matches(entry.mutation()) {
+            return false;
+        }
+
+        let lower_bound = PrimaryKeyMarkerComparator::is_above_lower_bound(
+            &self.schema,
+            &self.from,
+            entry.primary_key(),
+        )
+        .unwrap();
+
+        let upper_bound = PrimaryKeyMarkerComparator::is_below_upper_bound(
+            &self.schema,
+            &self.to,
+            entry.primary_key(),
+        )
+        .unwrap();
+
+        #[cfg(debug_assertions)]
+        println!(
+            "argonfile id: {}, entry: {}",
+            self.argonfile.sstable_id,
+            MutationUtils::debug_fmt(&self.table_schema, entry.mutation()).unwrap()
+        );
+
+        lower_bound && upper_bound
+    }
 }
 
 #[async_trait]
@@ -0,0 +1,183 @@
+use std::str::FromStr;
+
+use smol::block_on;
+
+use crate::{
+    argonfs::{ArgonFs, ArgonFsConfig, FsFileSystemConfig, argonfile::ArgonfileBuilder},
+    kv::{
+        KVColumnFilter, KVFlushPreStats, KVPrimaryKeyMarker, KVRangeScan, KVRangeScanResult,
+        KVSSTable, KVScanDirection, KVTableId, ObjectId,
+        kv_test_utils::{TestIter, test_mutation, test_primary_key, test_schema},
+        primary_key::{KVPrimaryKeySchema, PrimaryKeyBuilder},
+    },
+    persistence::PersistenceLayer,
+};
+
+const ROW_COUNT: usize = 200;
+const VERSION_COUNT: u64 = 3;
+
+fn row_id(idx: usize) -> String {
+    format!("row{:03}", idx)
+}
+
+/** Sstable of [`test_schema`] rows spread over multiple data blocks. */
+fn test_sstable(name: &str) -> Box<dyn KVSSTable> {
+    let storage_root =
+        std::env::temp_dir().join(format!("argondb-sstable-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&storage_root);
+
+    let argon_fs = ArgonFs::init(ArgonFsConfig {
+        fs_filesystem_config: FsFileSystemConfig {
+            storage_root,
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut mutations = vec![];
+    for idx in 0..ROW_COUNT {
+        for timestamp in (1..=VERSION_COUNT).rev() {
+            mutations.push(test_mutation(&row_id(idx), timestamp, &"v".repeat(64)));
+        }
+    }
+
+    let table_id = KVTableId::from_str("test_table").unwrap();
+    let sstable_id = ObjectId(1);
+
+    block_on(async {
+        let writer = argon_fs
+            .new_file_writer_for_sstable(&table_id, sstable_id)
+            .await
+            .unwrap();
+
+        ArgonfileBuilder::flush_iter(
+            writer,
+            TestIter::new(mutations),
+            &[],
+            sstable_id,
+            0,
+            KVFlushPreStats {
+                mutations_count: ROW_COUNT * VERSION_COUNT as usize,
+            },
+        )
+        .await
+        .unwrap();
+
+        argon_fs
+            .commit_sstables(&table_id, vec![sstable_id], vec![])
+            .await
+            .unwrap();
+
+        argon_fs
+            .open_sstable(&table_id, sstable_id, &test_schema())
+            .await
+            .unwrap()
+    })
+}
+
+fn scan(
+    sstable: &dyn KVSSTable,
+    from: KVPrimaryKeyMarker,
+    to: KVPrimaryKeyMarker,
+    direction: KVScanDirection,
+) -> Vec<(Box<[u8]>, u64)> {
+    let scan =
+        KVRangeScan::new(test_schema(), from, to, KVColumnFilter::All).with_direction(direction);
+
+    let KVRangeScanResult::Iter(mut iter) = block_on(sstable.range_scan(&scan)).unwrap() else {
+        return vec![];
+    };
+
+    let mut mutations = vec![];
+    while let Some(item) = block_on(iter.next_mutation()) {
+        mutations.push((item.primary_key().into(), item.mutation().timestamp()));
+    }
+
+    mutations
+}
+
+/** Mutations of given rows in scan order, versions of a row are always newest first. */
+fn expected(row_idxs: impl Iterator<Item = usize>) -> Vec<(Box<[u8]>, u64)> {
+    row_idxs
+        .flat_map(|idx| {
+            (1..=VERSION_COUNT)
+                .rev()
+                .map(move |timestamp| (test_primary_key(&row_id(idx)), timestamp))
+        })
+        .collect()
+}
+
+#[test]
+fn test_reverse_scan_spans_blocks() {
+    let sstable = test_sstable("reverse-blocks");
+
+    // Rows don't fit a single 8KB data block
+    assert!(sstable.size() > 4 * 8 * 1024);
+
+    assert_eq!(
+        scan(
+            sstable.as_ref(),
+            KVPrimaryKeyMarker::Start,
+            KVPrimaryKeyMarker::End,
+            KVScanDirection::Forward
+        ),
+        expected(0..ROW_COUNT)
+    );
+    assert_eq!(
+        scan(
+            sstable.as_ref(),
+            KVPrimaryKeyMarker::Start,
+            KVPrimaryKeyMarker::End,
+            KVScanDirection::Reverse
+        ),
+        expected((0..ROW_COUNT).rev())
+    );
+}
+
+#[test]
+fn test_reverse_scan_respects_bounds() {
+    let sstable = test_sstable("reverse-bounds");
+
+    assert_eq!(
+        scan(
+            sstable.as_ref(),
+            KVPrimaryKeyMarker::Key(test_primary_key(&row_id(37))),
+            KVPrimaryKeyMarker::Key(test_primary_key(&row_id(151))),
+            KVScanDirection::Reverse
+        ),
+        expected((37..=151).rev())
+    );
+
+    // Exclusive bounds, as used by resumed scans
+    let exclusive = |idx: usize| {
+        let pk_schema = KVPrimaryKeySchema::from_table_schema(&test_schema());
+        let mut pk_builder = PrimaryKeyBuilder::new(&pk_schema);
+        pk_builder.add_value(row_id(idx).as_bytes());
+
+        KVPrimaryKeyMarker::Prefix {
+            prefix: pk_builder.build_prefix(),
+            inclusive: false,
+        }
+    };
+    assert_eq!(
+        scan(
+            sstable.as_ref(),
+            exclusive(10),
+            exclusive(120),
+            KVScanDirection::Reverse
+        ),
+        expected((11..120).rev())
+    );
+
+    // Single row read in reverse holds all its versions
+    assert_eq!(
+        scan(
+            sstable.as_ref(),
+            KVPrimaryKeyMarker::Key(test_primary_key(&row_id(0))),
+            KVPrimaryKeyMarker::Key(test_primary_key(&row_id(0))),
+            KVScanDirection::Reverse
+        ),
+        expected(0..1)
+    );
+}
@@ -0,0 +1,156 @@
+use std::{
+    io::Write,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use async_trait::async_trait;
+
+use crate::{
+    DbCtx,
+    argonfs::{ArgonFs, ArgonFsConfig, FsFileSystemConfig},
+    catalog::Catalog,
+    core::persistence::{PersistenceError, PersistenceLayer},
+    kv::{
+        KVInstanceStateSnapshot, KVSSTable, KVTableId, KVTableSchema, ObjectId,
+        commit_log::KVCommitLogRecord,
+        config::KVConfig,
+        kv_test_utils::{TestCommitLog, test_instance_with_commit_log},
+    },
+};
+
+/** ArgonFs whose sstable commits fail while `fail_commits` is set. */
+pub struct FailingCommitsPersistence {
+    inner: ArgonFs,
+    fail_commits: Arc<AtomicBool>,
+}
+
+/** Context with ArgonFs persistence in a fresh temp directory, and the flag failing its commits. */
+pub fn test_db_ctx(name: &str, commit_log: Arc<TestCommitLog>) -> (DbCtx, Arc<AtomicBool>) {
+    let storage_root =
+        std::env::temp_dir().join(format!("argondb-argonfs-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&storage_root);
+
+    let argon_fs = ArgonFs::init(ArgonFsConfig {
+        fs_filesystem_config: FsFileSystemConfig {
+            storage_root,
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .unwrap();
+
+    let fail_commits = Arc::new(AtomicBool::new(false));
+    let db_ctx = DbCtx {
+        kv_instance: test_instance_with_commit_log(KVConfig::default(), commit_log),
+        catalog: Arc::new(Catalog::new()),
+        persistence: Arc::new(Box::new(FailingCommitsPersistence {
+            inner: argon_fs,
+            fail_commits: fail_commits.clone(),
+        })),
+    };
+
+    (db_ctx, fail_commits)
+}
+
+#[async_trait]
+impl PersistenceLayer for FailingCommitsPersistence {
+    async fn read_instance_snapshot(
+        &self,
+    ) -> Result<Option<KVInstanceStateSnapshot>, PersistenceError> {
+        self.inner.read_instance_snapshot().await
+    }
+
+    async fn save_instance_snapshot(
+        &self,
+        snapshot: KVInstanceStateSnapshot,
+    ) -> Result<(), PersistenceError> {
+        self.inner.save_instance_snapshot(snapshot).await
+    }
+
+    async fn scan_max_object_id(&self) -> Result<Option<ObjectId>, PersistenceError> {
+        self.inner.scan_max_object_id().await
+    }
+
+    async fn scan_for_sstables(
+        &self,
+        table_id: &KVTableId,
+        table_schema: &KVTableSchema,
+    ) -> Result<Vec<Box<dyn KVSSTable + 'static>>, PersistenceError> {
+        self.inner.scan_for_sstables(table_id, table_schema).await
+    }
+
+    async fn new_file_writer_for_sstable(
+        &self,
+        table_id: &KVTableId<'_>,
+        sstable_id: ObjectId,
+    ) -> Result<Box<dyn Write + Send + Sync + 'static>, PersistenceError> {
+        self.inner
+            .new_file_writer_for_sstable(table_id, sstable_id)
+            .await
+    }
+
+    async fn commit_sstables(
+        &self,
+        table_id: &KVTableId,
+        added: Vec<ObjectId>,
+        removed: Vec<ObjectId>,
+    ) -> Result<(), PersistenceError> {
+        if self.fail_commits.load(Ordering::Acquire) {
+            return Err(PersistenceError(Box::new(std::io::Error::other(
+                "commit failed",
+            ))));
+        }
+
+        self.inner.commit_sstables(table_id, added, removed).await
+    }
+
+    async fn open_sstable(
+        &self,
+        table_id: &KVTableId,
+        sstable_id: ObjectId,
+        table_schema: &KVTableSchema,
+    ) -> Result<Box<dyn KVSSTable + 'static>, PersistenceError> {
+        self.inner
+            .open_sstable(table_id, sstable_id, table_schema)
+            .await
+    }
+
+    async fn scan_table_ids(&self) -> Result<Vec<KVTableId<'static>>, PersistenceError> {
+        self.inner.scan_table_ids().await
+    }
+
+    async fn remove_table_storage(&self, table_id: &KVTableId) -> Result<(), PersistenceError> {
+        self.inner.remove_table_storage(table_id).await
+    }
+
+    async fn remove_compacted_sstables(
+        &self,
+        table_id: &KVTableId,
+        sstable_ids: Vec<ObjectId>,
+    ) -> Result<(), PersistenceError> {
+        self.inner
+            .remove_compacted_sstables(table_id, sstable_ids)
+            .await
+    }
+
+    async fn remove_uncommitted_sstables(
+        &self,
+        table_id: &KVTableId,
+        sstable_ids: Vec<ObjectId>,
+    ) -> Result<(), PersistenceError> {
+        self.inner
+            .remove_uncommitted_sstables(table_id, sstable_ids)
+            .await
+    }
+
+    async fn read_commit_log(&self) -> Result<Vec<KVCommitLogRecord>, PersistenceError> {
+        self.inner.read_commit_log().await
+    }
+
+    async fn remove_replayed_commit_log(&self) -> Result<(), PersistenceError> {
+        self.inner.remove_replayed_commit_log().await
+    }
+}
@@ -47,8 +47,9 @@ impl PageBuffer {
 
         for i in 0..blocks_total {
             let header = unsafe { headers.add(i) }.as_ptr();
+            // Memory is uninitialized, assignment would drop garbage as a previous header
             unsafe {
-                *header = PageHeader {
+                header.write(PageHeader {
                     lock: BlockLock::new(),
                     data: blocks.add(i * block_size),
                     buf_len: block_size,
@@ -59,7 +60,7 @@ impl PageBuffer {
                             None
                         },
                     },
-                }
+                });
             }
         }
 
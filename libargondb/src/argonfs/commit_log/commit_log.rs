@@ -0,0 +1,216 @@
+use std::{
+    collections::HashSet,
+    io::SeekFrom,
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+};
+
+use async_trait::async_trait;
+use flume::Sender;
+
+use crate::{
+    argonfs::{
+        commit_log::{
+            CommitLogConfig,
+            commit_log_record::{
+                CommitLogRecordSerializer, CommitLogSegmentContents, CommitLogSegmentParser,
+            },
+            commit_log_writer::{CommitLogRequest, CommitLogWriter},
+        },
+        fs::BoxFileSystem,
+    },
+    core::persistence::{OrPersistenceError, PersistenceError},
+    kv::{
+        KVRuntimeError, KVRuntimeErrorKind, ObjectId,
+        commit_log::{KVCommitLog, KVCommitLogRecord},
+    },
+};
+
+pub struct ArgonFsCommitLog {
+    sender: Sender<CommitLogRequest>,
+    writer_handle: Mutex<Option<JoinHandle<()>>>,
+    filesystem: Arc<BoxFileSystem>,
+    /** Segments left by previous run, these are replayed on startup and removed afterwards. */
+    replay_segment_ids: Vec<u64>,
+}
+
+impl ArgonFsCommitLog {
+    pub async fn open(
+        config: CommitLogConfig,
+        filesystem: Arc<BoxFileSystem>,
+    ) -> Result<Self, PersistenceError> {
+        let replay_segment_ids = filesystem
+            .scan_commit_log_segments()
+            .await
+            .ok_or_persistence_error()?;
+
+        let next_segment_id = replay_segment_ids.last().map_or(0, |id| id + 1);
+
+        let (sender, receiver) = flume::unbounded();
+        let writer = CommitLogWriter::open(config, filesystem.clone(), receiver, next_segment_id)
+            .ok_or_persistence_error()?;
+
+        let writer_handle = thread::spawn(move || writer.run());
+
+        Ok(Self {
+            sender,
+            writer_handle: Mutex::new(Some(writer_handle)),
+            filesystem,
+            replay_segment_ids,
+        })
+    }
+
+    /** Reads records of all replayed segments, skipping records of memtables released since. */
+    pub async fn read_replay_records(&self) -> Result<Vec<KVCommitLogRecord>, PersistenceError> {
+        let mut records = vec![];
+        let mut released_memtable_ids = HashSet::new();
+
+        for segment_contents in self.read_replay_segments().await? {
+            records.extend(segment_contents.records);
+            released_memtable_ids.extend(segment_contents.released_memtable_ids);
+        }
+
+        let records_count = records.len();
+        records.retain(|record| !released_memtable_ids.contains(&record.memtable_id));
+        if records.len() < records_count {
+            println!(
+                "commit log - skipped {} records of released memtables",
+                records_count - records.len()
+            );
+        }
+
+        Ok(records)
+    }
+
+    /** Returns the highest memtable id referenced by replayed segments, released ones included. */
+    pub async fn max_replay_memtable_id(&self) -> Result<Option<ObjectId>, PersistenceError> {
+        let mut max_memtable_id: Option<u64> = None;
+
+        for segment_contents in self.read_replay_segments().await? {
+            let memtable_ids = segment_contents
+                .records
+                .iter()
+                .map(|record| record.memtable_id)
+                .chain(segment_contents.released_memtable_ids);
+
+            for memtable_id in memtable_ids {
+                max_memtable_id = max_memtable_id.max(Some(memtable_id.0));
+            }
+        }
+
+        Ok(max_memtable_id.map(ObjectId))
+    }
+
+    async fn read_replay_segments(
+        &self,
+    ) -> Result<Vec<CommitLogSegmentContents>, PersistenceError> {
+        let mut segments = vec![];
+
+        for segment_id in &self.replay_segment_ids {
+            let file_ref = self
+                .filesystem
+                .get_commit_log_segment_file_ref(*segment_id)
+                .await
+                .ok_or_persistence_error()?;
+
+            let mut reader = file_ref.open_read_only().await.ok_or_persistence_error()?;
+            let segment_size = reader
+                .seek(SeekFrom::End(0))
+                .await
+                .ok_or_persistence_error()?;
+            let buf = reader
+                .seek_and_read(SeekFrom::Start(0), segment_size as usize)
+                .await
+                .ok_or_persistence_error()?;
+
+            let segment_contents = CommitLogSegmentParser::parse(*segment_id, buf.as_ref())
+                .ok_or_persistence_error()?;
+
+            println!(
+                "commit log - read {} records from segment {}",
+                segment_contents.records.len(),
+                segment_id
+            );
+            segments.push(segment_contents);
+        }
+
+        Ok(segments)
+    }
+
+    pub async fn remove_replayed_segments(&self) -> Result<(), PersistenceError> {
+        for segment_id in &self.replay_segment_ids {
+            let file_ref = self
+                .filesystem
+                .get_commit_log_segment_file_ref(*segment_id)
+                .await
+                .ok_or_persistence_error()?;
+
+            file_ref.remove().await.ok_or_persistence_error()?;
+        }
+
+        self.filesystem
+            .sync_commit_log_dir()
+            .await
+            .ok_or_persistence_error()
+    }
+}
+
+impl std::fmt::Debug for ArgonFsCommitLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ArgonFsCommitLog")
+            .field("replay_segment_ids", &self.replay_segment_ids)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl KVCommitLog for ArgonFsCommitLog {
+    async fn append(&self, record: KVCommitLogRecord) -> Result<(), KVRuntimeError> {
+        let payload = CommitLogRecordSerializer::serialize(&record);
+        let (ack, ack_receiver) = flume::bounded(1);
+
+        self.sender
+            .send(CommitLogRequest::Append {
+                payload,
+                memtable_id: record.memtable_id,
+                ack,
+            })
+            .map_err(|_| {
+                KVRuntimeError::with_msg(
+                    KVRuntimeErrorKind::OperationNotAllowed,
+                    "commit log append failed - commit log closed",
+                )
+            })?;
+
+        let result = ack_receiver.recv_async().await.map_err(|_| {
+            KVRuntimeError::with_msg(
+                KVRuntimeErrorKind::OperationFailure,
+                "commit log append failed - writer stopped",
+            )
+        })?;
+
+        result.map_err(|e| {
+            KVRuntimeError::with_msg_and_source(
+                KVRuntimeErrorKind::OperationFailure,
+                "commit log append failed",
+                e,
+            )
+        })
+    }
+
+    fn release_memtable(&self, memtable_id: ObjectId) {
+        let _ = self
+            .sender
+            .send(CommitLogRequest::ReleaseMemtable(memtable_id));
+    }
+
+    fn close(&self) {
+        let Some(writer_handle) = self.writer_handle.lock().unwrap().take() else {
+            println!("commit log already closed");
+            return;
+        };
+
+        let _ = self.sender.send(CommitLogRequest::Close);
+        writer_handle.join().unwrap();
+    }
+}
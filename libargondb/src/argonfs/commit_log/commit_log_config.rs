@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct CommitLogConfig {
+    pub sync_policy: CommitLogSyncPolicy,
+    pub segment_size: usize,
+}
+
+/**
+ * Sync policy decides when appended records are fsynced and when writers are acknowledged.
+ */
+#[derive(Debug, Clone, Copy)]
+pub enum CommitLogSyncPolicy {
+    /** Every append is fsynced on its own before being acknowledged. */
+    PerWrite,
+    /**
+     * Appends arriving within the window share a single fsync. Writers are acknowledged after it.
+     */
+    Group { window: Duration },
+    /**
+     * Appends are acknowledged once written, log is fsynced in the background at given interval.
+     * Acknowledged writes from the last interval may be lost on crash.
+     */
+    Periodic { interval: Duration },
+}
+
+const DEFAULT_SEGMENT_SIZE: usize = 32 * 1024 * 1024; // 32MB
+const DEFAULT_GROUP_COMMIT_WINDOW: Duration = Duration::from_millis(1);
+
+impl Default for CommitLogConfig {
+    fn default() -> Self {
+        Self {
+            sync_policy: CommitLogSyncPolicy::Group {
+                window: DEFAULT_GROUP_COMMIT_WINDOW,
+            },
+            segment_size: DEFAULT_SEGMENT_SIZE,
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use std::str::FromStr;
+
+use bytes::{Buf, BufMut};
+use thiserror::Error;
+
+use crate::kv::{
+    KVRangeTombstone, KVTableId, ObjectId,
+    commit_log::KVCommitLogRecord,
+    mutation::{KVMutation, MutationType, StructuredMutation},
+};
+
+/*
+ * Segment layout:
+ * [magic: 8B][segment_id: u64]([payload_size: u32][payload_crc32c: u32][payload])*
+ *
+ * Mutations payload layout:
+ * [record_type: u8][table_id_size: u8][table_id][memtable_id: u64][mutations_count: u32]
+ * ([timestamp: u64][column_id: u16][mutation_type: u8][pk_size: u16][value_size: u64][pk][value])*
+ *
+ * Records with range tombstones use the range tombstones record type and append them:
+ * [range_tombstones_count: u32]([range tombstone])*
+ *
+ * Release payload layout, written to every segment still holding records of a flushed memtable:
+ * [record_type: u8][memtable_id: u64]
+ */
+pub const COMMIT_LOG_SEGMENT_MAGIC: &[u8; 8] = b"ARGNCLOG";
+pub const COMMIT_LOG_SEGMENT_HEADER_SIZE: usize = 16;
+
+const RECORD_FRAME_HEADER_SIZE: usize = 8;
+const RECORD_TYPE_MUTATIONS: u8 = 1;
+const RECORD_TYPE_RANGE_TOMBSTONES: u8 = 2;
+const RECORD_TYPE_RELEASE: u8 = 3;
+const MUTATION_HEADER_SIZE: usize = 8 + 2 + 1 + 2 + 8;
+
+pub struct CommitLogRecordSerializer;
+
+impl CommitLogRecordSerializer {
+    pub fn serialize_segment_header(segment_id: u64) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(COMMIT_LOG_SEGMENT_HEADER_SIZE);
+
+        buf.put_slice(COMMIT_LOG_SEGMENT_MAGIC);
+        buf.put_u64_le(segment_id);
+
+        buf
+    }
+
+    pub fn serialize(record: &KVCommitLogRecord) -> Vec<u8> {
+        let mut payload = vec![];
+
+        let table_id = record.table_id.as_ref().as_bytes();
+
+        // Records without range tombstones keep the original layout
+        if record.range_tombstones.is_empty() {
+            payload.put_u8(RECORD_TYPE_MUTATIONS);
+        } else {
+            payload.put_u8(RECORD_TYPE_RANGE_TOMBSTONES);
+        }
+
+        payload.put_u8(table_id.len() as u8);
+        payload.put_slice(table_id);
+        payload.put_u64_le(record.memtable_id.0);
+        payload.put_u32_le(record.mutations.len() as u32);
+
+        for mutation in &record.mutations {
+            payload.put_u64_le(mutation.timestamp());
+            payload.put_u16_le(mutation.column_id());
+            payload.put_u8(mutation.mutation_type() as u8);
+            payload.put_u16_le(mutation.primary_key_size());
+            payload.put_u64_le(mutation.value_size());
+            payload.put_slice(mutation.primary_key());
+            payload.put_slice(mutation.value());
+        }
+
+        if !record.range_tombstones.is_empty() {
+            payload.put_u32_le(record.range_tombstones.len() as u32);
+
+            for tombstone in &record.range_tombstones {
+                tombstone.serialize(&mut payload);
+            }
+        }
+
+        Self::frame(&payload)
+    }
+
+    /** Marks records of the memtable as flushed, replay skips them. */
+    pub fn serialize_release(memtable_id: ObjectId) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(1 + 8);
+        payload.put_u8(RECORD_TYPE_RELEASE);
+        payload.put_u64_le(memtable_id.0);
+
+        Self::frame(&payload)
+    }
+
+    fn frame(payload: &[u8]) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(RECORD_FRAME_HEADER_SIZE + payload.len());
+        buf.put_u32_le(payload.len() as u32);
+        buf.put_u32_le(crc32c::crc32c(payload));
+        buf.put_slice(payload);
+
+        buf
+    }
+}
+
+/** Contents of a single segment. */
+#[derive(Debug, Default)]
+pub struct CommitLogSegmentContents {
+    pub records: Vec<KVCommitLogRecord>,
+    /** Memtables flushed after their records were written to the segment. */
+    pub released_memtable_ids: Vec<ObjectId>,
+}
+
+pub struct CommitLogSegmentParser;
+
+impl CommitLogSegmentParser {
+    /**
+     * Parses all complete records of a segment. Parsing stops at the first truncated or corrupted
+     * record - it is the tail of a write that was never acknowledged.
+     */
+    pub fn parse(
+        segment_id: u64,
+        mut buf: &[u8],
+    ) -> Result<CommitLogSegmentContents, CommitLogParseError> {
+        let mut contents = CommitLogSegmentContents::default();
+
+        if buf.remaining() < COMMIT_LOG_SEGMENT_HEADER_SIZE {
+            println!(
+                "commit log segment {} has no complete header - treating as empty",
+                segment_id
+            );
+            return Ok(contents);
+        }
+
+        let mut magic = [0u8; 8];
+        buf.copy_to_slice(&mut magic);
+        if &magic != COMMIT_LOG_SEGMENT_MAGIC {
+            return Err(CommitLogParseError::InvalidMagic(segment_id));
+        }
+
+        let header_segment_id = buf.get_u64_le();
+        if header_segment_id != segment_id {
+            return Err(CommitLogParseError::SegmentIdMismatch(segment_id));
+        }
+
+        while buf.remaining() >= RECORD_FRAME_HEADER_SIZE {
+            let payload_size = buf.get_u32_le() as usize;
+            let payload_crc = buf.get_u32_le();
+
+            if buf.remaining() < payload_size {
+                println!(
+                    "commit log segment {} ends with a truncated record - skipping it",
+                    segment_id
+                );
+                break;
+            }
+
+            let payload = &buf[..payload_size];
+            if crc32c::crc32c(payload) != payload_crc {
+                println!(
+                    "commit log segment {} contains a corrupted record - skipping the rest of segment",
+                    segment_id
+                );
+                break;
+            }
+
+            if payload.first() == Some(&RECORD_TYPE_RELEASE) {
+                contents
+                    .released_memtable_ids
+                    .push(Self::parse_release_payload(segment_id, payload)?);
+            } else {
+                contents
+                    .records
+                    .push(Self::parse_payload(segment_id, payload)?);
+            }
+            buf.advance(payload_size);
+        }
+
+        Ok(contents)
+    }
+
+    fn parse_release_payload(
+        segment_id: u64,
+        mut payload: &[u8],
+    ) -> Result<ObjectId, CommitLogParseError> {
+        if payload.remaining() != 1 + 8 {
+            return Err(CommitLogParseError::MalformedRecord(segment_id));
+        }
+
+        payload.advance(1);
+        Ok(ObjectId(payload.get_u64_le()))
+    }
+
+    fn parse_payload(
+        segment_id: u64,
+        mut payload: &[u8],
+    ) -> Result<KVCommitLogRecord, CommitLogParseError> {
+        let malformed = || CommitLogParseError::MalformedRecord(segment_id);
+
+        if payload.remaining() < 2 {
+            return Err(malformed());
+        }
+
+        let record_type = payload.get_u8();
+        if record_type != RECORD_TYPE_MUTATIONS && record_type != RECORD_TYPE_RANGE_TOMBSTONES {
+            return Err(malformed());
+        }
+
+        let table_id_size = payload.get_u8() as usize;
+        if payload.remaining() < table_id_size + 8 + 4 {
+            return Err(malformed());
+        }
+
+        let table_id = std::str::from_utf8(&payload[..table_id_size]).map_err(|_| malformed())?;
+        let table_id = KVTableId::from_str(table_id).map_err(|_| malformed())?;
+        payload.advance(table_id_size);
+
+        let memtable_id = ObjectId(payload.get_u64_le());
+        let mutations_count = payload.get_u32_le();
+
+        let mut mutations = Vec::with_capacity(mutations_count as usize);
+        for _ in 0..mutations_count {
+            if payload.remaining() < MUTATION_HEADER_SIZE {
+                return Err(malformed());
+            }
+
+            let timestamp = payload.get_u64_le();
+            let column_id = payload.get_u16_le();
+            let mutation_type =
+                MutationType::try_from(payload.get_u8()).map_err(|_| malformed())?;
+            let primary_key_size = payload.get_u16_le() as usize;
+            let value_size = payload.get_u64_le() as usize;
+
+            if payload.remaining() < primary_key_size + value_size {
+                return Err(malformed());
+            }
+
+            let primary_key: Box<[u8]> = payload[..primary_key_size].into();
+            payload.advance(primary_key_size);
+
+            let value: Box<[u8]> = payload[..value_size].into();
+            payload.advance(value_size);
+
+            let mutation = StructuredMutation::try_from(
+                timestamp,
+                column_id,
+                mutation_type,
+                primary_key,
+                value,
+            )
+            .map_err(|_| malformed())?;
+
+            mutations.push(mutation);
+        }
+
+        let mut range_tombstones = vec![];
+        if record_type == RECORD_TYPE_RANGE_TOMBSTONES {
+            if payload.remaining() < 4 {
+                return Err(malformed());
+            }
+
+            let range_tombstones_count = payload.get_u32_le();
+            for _ in 0..range_tombstones_count {
+                range_tombstones
+                    .push(KVRangeTombstone::parse(&mut payload).map_err(|_| malformed())?);
+            }
+        }
+
+        Ok(KVCommitLogRecord {
+            table_id,
+            memtable_id,
+            mutations,
+            range_tombstones,
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CommitLogParseError {
+    #[error("commit log segment {0} has invalid magic")]
+    InvalidMagic(u64),
+    #[error("commit log segment {0} header doesn't match its file name")]
+    SegmentIdMismatch(u64),
+    #[error("commit log segment {0} contains malformed record")]
+    MalformedRecord(u64),
+}
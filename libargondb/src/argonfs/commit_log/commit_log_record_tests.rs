@@ -0,0 +1,131 @@
+use std::str::FromStr;
+
+use crate::{
+    argonfs::commit_log::commit_log_record::{CommitLogRecordSerializer, CommitLogSegmentParser},
+    kv::{
+        KVPrimaryKeyMarker, KVRangeTombstone, KVTableId, ObjectId,
+        commit_log::KVCommitLogRecord,
+        mutation::{KVMutation, MutationType, StructuredMutation},
+    },
+};
+
+fn test_record(memtable_id: u64) -> KVCommitLogRecord {
+    KVCommitLogRecord {
+        table_id: KVTableId::from_str("test_table").unwrap(),
+        memtable_id: ObjectId(memtable_id),
+        mutations: vec![
+            StructuredMutation::try_from(
+                12,
+                1,
+                MutationType::Put,
+                Box::new([1, 2]),
+                Box::new([3, 4, 5]),
+            )
+            .unwrap(),
+            StructuredMutation::try_from(
+                13,
+                2,
+                MutationType::Delete,
+                Box::new([1, 2]),
+                Box::new([]),
+            )
+            .unwrap(),
+        ],
+        range_tombstones: vec![],
+    }
+}
+
+#[test]
+fn test_serializer_parser_integration() {
+    let mut segment = CommitLogRecordSerializer::serialize_segment_header(7);
+    segment.extend(CommitLogRecordSerializer::serialize(&test_record(1)));
+    segment.extend(CommitLogRecordSerializer::serialize(&test_record(2)));
+
+    let records = CommitLogSegmentParser::parse(7, &segment).unwrap().records;
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].table_id.as_ref(), "test_table");
+    assert_eq!(records[0].memtable_id, ObjectId(1));
+    assert_eq!(records[1].memtable_id, ObjectId(2));
+
+    let mutation = &records[1].mutations[0];
+    assert_eq!(mutation.timestamp(), 12);
+    assert_eq!(mutation.column_id(), 1);
+    assert_eq!(mutation.mutation_type(), MutationType::Put);
+    assert_eq!(mutation.primary_key(), &[1, 2]);
+    assert_eq!(mutation.value(), &[3, 4, 5]);
+    assert_eq!(
+        records[1].mutations[1].mutation_type(),
+        MutationType::Delete
+    );
+}
+
+#[test]
+fn test_parser_skips_torn_tail() {
+    let mut segment = CommitLogRecordSerializer::serialize_segment_header(7);
+    segment.extend(CommitLogRecordSerializer::serialize(&test_record(1)));
+
+    let torn_record = CommitLogRecordSerializer::serialize(&test_record(2));
+    segment.extend(&torn_record[..torn_record.len() - 3]);
+
+    let records = CommitLogSegmentParser::parse(7, &segment).unwrap().records;
+    assert_eq!(records.len(), 1);
+
+    let last = segment.len() - 1;
+    segment[last] ^= 0xFF;
+    segment.extend(&torn_record[torn_record.len() - 3..]);
+    let records = CommitLogSegmentParser::parse(7, &segment).unwrap().records;
+    assert_eq!(records.len(), 1);
+
+    assert!(CommitLogSegmentParser::parse(8, &segment).is_err());
+}
+
+#[test]
+fn test_range_tombstones_serializer_parser_integration() {
+    let mut record = test_record(3);
+    record.range_tombstones = vec![
+        KVRangeTombstone {
+            from: KVPrimaryKeyMarker::Start,
+            to: KVPrimaryKeyMarker::Key(Box::new([1, 2])),
+            timestamp: 14,
+        },
+        KVRangeTombstone {
+            from: KVPrimaryKeyMarker::Prefix {
+                prefix: Box::new([1, 3]),
+                inclusive: false,
+            },
+            to: KVPrimaryKeyMarker::End,
+            timestamp: 15,
+        },
+    ];
+
+    let mut segment = CommitLogRecordSerializer::serialize_segment_header(7);
+    segment.extend(CommitLogRecordSerializer::serialize(&record));
+
+    let records = CommitLogSegmentParser::parse(7, &segment).unwrap().records;
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].mutations.len(), 2);
+
+    let tombstones = &records[0].range_tombstones;
+    assert_eq!(tombstones.len(), 2);
+    assert_eq!(tombstones[0].timestamp, 14);
+    assert!(matches!(tombstones[0].from, KVPrimaryKeyMarker::Start));
+    assert!(matches!(&tombstones[0].to, KVPrimaryKeyMarker::Key(key) if key.as_ref() == [1, 2]));
+    assert!(matches!(
+        &tombstones[1].from,
+        KVPrimaryKeyMarker::Prefix { prefix, inclusive: false } if prefix.as_ref() == [1, 3]
+    ));
+    assert!(matches!(tombstones[1].to, KVPrimaryKeyMarker::End));
+}
+
+#[test]
+fn test_release_serializer_parser_integration() {
+    let mut segment = CommitLogRecordSerializer::serialize_segment_header(7);
+    segment.extend(CommitLogRecordSerializer::serialize(&test_record(1)));
+    segment.extend(CommitLogRecordSerializer::serialize(&test_record(2)));
+    segment.extend(CommitLogRecordSerializer::serialize_release(ObjectId(1)));
+
+    let contents = CommitLogSegmentParser::parse(7, &segment).unwrap();
+    assert_eq!(contents.records.len(), 2);
+    assert_eq!(contents.released_memtable_ids, vec![ObjectId(1)]);
+}
@@ -0,0 +1,353 @@
+use std::{
+    collections::HashSet,
+    io::{self, Write},
+    sync::Arc,
+    time::Instant,
+};
+
+use flume::{Receiver, RecvTimeoutError, Sender};
+
+use crate::{
+    argonfs::{
+        commit_log::{
+            CommitLogConfig, CommitLogSyncPolicy,
+            commit_log_record::{COMMIT_LOG_SEGMENT_HEADER_SIZE, CommitLogRecordSerializer},
+        },
+        fs::{AppendOnlyFileHandle, BoxFileSystem, FileSystemError},
+    },
+    kv::ObjectId,
+};
+
+pub type CommitLogAck = Result<(), Arc<io::Error>>;
+
+pub enum CommitLogRequest {
+    Append {
+        payload: Vec<u8>,
+        memtable_id: ObjectId,
+        ack: Sender<CommitLogAck>,
+    },
+    ReleaseMemtable(ObjectId),
+    Close,
+}
+
+struct CommitLogSegment {
+    segment_id: u64,
+    size: usize,
+    dirty_memtables: HashSet<ObjectId>,
+}
+
+impl CommitLogSegment {
+    fn is_clean(&self) -> bool {
+        self.dirty_memtables.is_empty()
+    }
+}
+
+/**
+ * Commit log writer owns all segment files and runs on a dedicated thread. Writers communicate
+ * with it through a request queue, which lets a single fsync acknowledge a whole group of appends.
+ */
+pub struct CommitLogWriter {
+    config: CommitLogConfig,
+    filesystem: Arc<BoxFileSystem>,
+    receiver: Receiver<CommitLogRequest>,
+
+    active_segment: CommitLogSegment,
+    active_handle: Box<dyn AppendOnlyFileHandle>,
+    closed_segments: Vec<CommitLogSegment>,
+
+    pending_acks: Vec<Sender<CommitLogAck>>,
+    is_synced: bool,
+    last_sync: Instant,
+}
+
+impl CommitLogWriter {
+    pub fn open(
+        config: CommitLogConfig,
+        filesystem: Arc<BoxFileSystem>,
+        receiver: Receiver<CommitLogRequest>,
+        segment_id: u64,
+    ) -> Result<Self, FileSystemError> {
+        let (active_segment, active_handle) = Self::create_segment(&filesystem, segment_id)?;
+
+        Ok(Self {
+            config,
+            filesystem,
+            receiver,
+
+            active_segment,
+            active_handle,
+            closed_segments: vec![],
+
+            pending_acks: vec![],
+            is_synced: true,
+            last_sync: Instant::now(),
+        })
+    }
+
+    pub fn run(mut self) {
+        loop {
+            let request = match self.config.sync_policy {
+                CommitLogSyncPolicy::Periodic { interval } => {
+                    match self.receiver.recv_deadline(self.last_sync + interval) {
+                        Ok(request) => Some(request),
+                        Err(RecvTimeoutError::Timeout) => {
+                            self.sync();
+                            continue;
+                        }
+                        Err(RecvTimeoutError::Disconnected) => None,
+                    }
+                }
+                _ => self.receiver.recv().ok(),
+            };
+
+            let Some(request) = request else {
+                break;
+            };
+
+            if !self.process_request(request) {
+                break;
+            }
+
+            match self.config.sync_policy {
+                CommitLogSyncPolicy::PerWrite => self.sync(),
+                CommitLogSyncPolicy::Group { window } => {
+                    if self.pending_acks.is_empty() {
+                        self.sync();
+                        continue;
+                    }
+
+                    let deadline = Instant::now() + window;
+                    let mut is_closing = false;
+                    while let Ok(request) = self.receiver.recv_deadline(deadline) {
+                        if !self.process_request(request) {
+                            is_closing = true;
+                            break;
+                        }
+                    }
+
+                    self.sync();
+
+                    if is_closing {
+                        break;
+                    }
+                }
+                CommitLogSyncPolicy::Periodic { interval } => {
+                    self.ack_pending(&Ok(()));
+
+                    if self.last_sync.elapsed() >= interval {
+                        self.sync();
+                    }
+                }
+            }
+        }
+
+        self.shutdown();
+        println!("commit log writer thread finished");
+    }
+
+    /** Returns false when writer should stop. */
+    fn process_request(&mut self, request: CommitLogRequest) -> bool {
+        match request {
+            CommitLogRequest::Append {
+                payload,
+                memtable_id,
+                ack,
+            } => {
+                if let Err(e) = self.active_handle.write_all(&payload) {
+                    println!("commit log - append failed: {}", e);
+                    let _ = ack.send(Err(Arc::new(e)));
+
+                    // Segment may end with a partial record now, later records would be unreadable
+                    self.sync();
+                    self.rotate_segment();
+                    return true;
+                }
+
+                self.active_segment.size += payload.len();
+                self.active_segment.dirty_memtables.insert(memtable_id);
+                self.is_synced = false;
+                self.pending_acks.push(ack);
+            }
+            CommitLogRequest::ReleaseMemtable(memtable_id) => {
+                self.release_memtable(memtable_id);
+            }
+            CommitLogRequest::Close => return false,
+        }
+
+        true
+    }
+
+    /**
+     * Segments that still hold records of other memtables get a release record, so replay skips
+     * records of the flushed memtable. Clean segments are removed instead.
+     */
+    fn release_memtable(&mut self, memtable_id: ObjectId) {
+        let release = CommitLogRecordSerializer::serialize_release(memtable_id);
+
+        for segment in &mut self.closed_segments {
+            if segment.dirty_memtables.remove(&memtable_id)
+                && !segment.is_clean()
+                && let Err(e) =
+                    Self::append_to_closed_segment(&self.filesystem, segment.segment_id, &release)
+            {
+                println!(
+                    "commit log - failed to release memtable {:?} in segment {}: {}",
+                    memtable_id, segment.segment_id, e
+                );
+            }
+        }
+
+        if self.active_segment.dirty_memtables.remove(&memtable_id)
+            && !self.active_segment.is_clean()
+        {
+            if let Err(e) = self.active_handle.write_all(&release) {
+                println!(
+                    "commit log - failed to release memtable {:?} in segment {}: {}",
+                    memtable_id, self.active_segment.segment_id, e
+                );
+            } else {
+                self.active_segment.size += release.len();
+                self.is_synced = false;
+            }
+        }
+
+        self.remove_clean_segments();
+    }
+
+    fn sync(&mut self) {
+        let result = if self.is_synced {
+            Ok(())
+        } else {
+            self.active_handle.sync().map_err(Arc::new)
+        };
+
+        if let Err(e) = &result {
+            println!("commit log - sync failed: {}", e);
+        } else {
+            self.is_synced = true;
+        }
+
+        self.last_sync = Instant::now();
+        self.ack_pending(&result);
+
+        let is_segment_full = self.active_segment.size >= self.config.segment_size;
+        let is_segment_released = self.active_segment.is_clean()
+            && self.active_segment.size > COMMIT_LOG_SEGMENT_HEADER_SIZE;
+
+        if result.is_err() || is_segment_full || is_segment_released {
+            self.rotate_segment();
+        }
+    }
+
+    fn ack_pending(&mut self, result: &CommitLogAck) {
+        for ack in self.pending_acks.drain(..) {
+            let _ = ack.send(result.clone());
+        }
+    }
+
+    fn rotate_segment(&mut self) {
+        let next_segment_id = self.active_segment.segment_id + 1;
+
+        match Self::create_segment(&self.filesystem, next_segment_id) {
+            Ok((segment, handle)) => {
+                let prev_segment = std::mem::replace(&mut self.active_segment, segment);
+                self.active_handle = handle;
+                self.is_synced = true;
+
+                self.closed_segments.push(prev_segment);
+                self.remove_clean_segments();
+            }
+            Err(e) => {
+                println!(
+                    "commit log - failed to create segment {}: {}",
+                    next_segment_id, e
+                );
+            }
+        }
+    }
+
+    fn remove_clean_segments(&mut self) {
+        let (clean, dirty) = std::mem::take(&mut self.closed_segments)
+            .into_iter()
+            .partition::<Vec<_>, _>(CommitLogSegment::is_clean);
+        self.closed_segments = dirty;
+
+        if clean.is_empty() {
+            return;
+        }
+
+        for segment in clean {
+            if let Err(e) = Self::remove_segment(&self.filesystem, segment.segment_id) {
+                println!(
+                    "commit log - failed to remove segment {}: {}",
+                    segment.segment_id, e
+                );
+            }
+        }
+
+        // Segment surviving a crash without its release records would be replayed in full
+        if let Err(e) = smol::block_on(self.filesystem.sync_commit_log_dir()) {
+            println!("commit log - failed to sync directory: {}", e);
+        }
+    }
+
+    fn shutdown(&mut self) {
+        self.sync();
+
+        if self.active_segment.is_clean() {
+            self.closed_segments.push(CommitLogSegment {
+                segment_id: self.active_segment.segment_id,
+                size: self.active_segment.size,
+                dirty_memtables: HashSet::new(),
+            });
+        }
+        self.remove_clean_segments();
+
+        if !self.closed_segments.is_empty() || !self.active_segment.is_clean() {
+            println!("commit log - closed with unflushed memtables, log will be replayed on start");
+        }
+    }
+
+    fn create_segment(
+        filesystem: &BoxFileSystem,
+        segment_id: u64,
+    ) -> Result<(CommitLogSegment, Box<dyn AppendOnlyFileHandle>), FileSystemError> {
+        let file_ref = smol::block_on(filesystem.get_commit_log_segment_file_ref(segment_id))?;
+        let mut handle = smol::block_on(file_ref.open_append())?;
+
+        handle.write_all(&CommitLogRecordSerializer::serialize_segment_header(
+            segment_id,
+        ))?;
+        handle.sync()?;
+        smol::block_on(filesystem.sync_commit_log_dir())?;
+
+        let segment = CommitLogSegment {
+            segment_id,
+            size: COMMIT_LOG_SEGMENT_HEADER_SIZE,
+            dirty_memtables: HashSet::new(),
+        };
+
+        Ok((segment, handle))
+    }
+
+    fn append_to_closed_segment(
+        filesystem: &BoxFileSystem,
+        segment_id: u64,
+        payload: &[u8],
+    ) -> Result<(), FileSystemError> {
+        let file_ref = smol::block_on(filesystem.get_commit_log_segment_file_ref(segment_id))?;
+        let mut handle = smol::block_on(file_ref.open_append())?;
+
+        handle.write_all(payload)?;
+        handle.sync()?;
+
+        Ok(())
+    }
+
+    fn remove_segment(filesystem: &BoxFileSystem, segment_id: u64) -> Result<(), FileSystemError> {
+        let file_ref = smol::block_on(filesystem.get_commit_log_segment_file_ref(segment_id))?;
+        smol::block_on(file_ref.remove())?;
+
+        Ok(())
+    }
+}
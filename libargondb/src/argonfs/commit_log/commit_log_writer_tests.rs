@@ -0,0 +1,180 @@
+use std::{str::FromStr, sync::Arc};
+
+use smol::block_on;
+
+use crate::{
+    argonfs::{
+        FsFileSystem, FsFileSystemConfig,
+        commit_log::{ArgonFsCommitLog, CommitLogConfig, CommitLogSyncPolicy},
+        fs::BoxFileSystem,
+    },
+    kv::{
+        KVTableId, ObjectId,
+        commit_log::{KVCommitLog, KVCommitLogRecord},
+        mutation::{KVMutation, MutationType, StructuredMutation},
+    },
+};
+
+fn test_filesystem(name: &str) -> Arc<BoxFileSystem> {
+    let storage_root = std::env::temp_dir().join(format!(
+        "argondb-commit-log-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&storage_root);
+
+    Arc::new(Box::new(FsFileSystem::new(FsFileSystemConfig {
+        storage_root,
+        ..Default::default()
+    })))
+}
+
+fn test_config(segment_size: usize) -> CommitLogConfig {
+    CommitLogConfig {
+        sync_policy: CommitLogSyncPolicy::PerWrite,
+        segment_size,
+    }
+}
+
+fn test_record(memtable_id: u64, timestamp: u64) -> KVCommitLogRecord {
+    KVCommitLogRecord {
+        table_id: KVTableId::from_str("test_table").unwrap(),
+        memtable_id: ObjectId(memtable_id),
+        mutations: vec![
+            StructuredMutation::try_from(
+                timestamp,
+                1,
+                MutationType::Put,
+                Box::new([1, 2]),
+                Box::new([3, 4, 5]),
+            )
+            .unwrap(),
+        ],
+        range_tombstones: vec![],
+    }
+}
+
+fn replay(filesystem: &Arc<BoxFileSystem>) -> Vec<KVCommitLogRecord> {
+    let commit_log = block_on(ArgonFsCommitLog::open(
+        test_config(1024),
+        filesystem.clone(),
+    ))
+    .unwrap();
+    let records = block_on(commit_log.read_replay_records()).unwrap();
+    commit_log.close();
+
+    records
+}
+
+#[test]
+fn test_writer_rotates_full_segments() {
+    let filesystem = test_filesystem("rotation");
+    let commit_log = block_on(ArgonFsCommitLog::open(test_config(64), filesystem.clone())).unwrap();
+
+    for timestamp in 0..4 {
+        block_on(commit_log.append(test_record(1, timestamp))).unwrap();
+    }
+    commit_log.close();
+
+    // Every record fills a segment, the last segment is empty
+    let segment_ids = block_on(filesystem.scan_commit_log_segments()).unwrap();
+    assert_eq!(segment_ids, vec![0, 1, 2, 3]);
+
+    let records = replay(&filesystem);
+    let timestamps = records
+        .iter()
+        .map(|record| record.mutations[0].timestamp())
+        .collect::<Vec<_>>();
+    assert_eq!(timestamps, vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn test_writer_removes_released_segments() {
+    let filesystem = test_filesystem("removal");
+    let commit_log = block_on(ArgonFsCommitLog::open(test_config(64), filesystem.clone())).unwrap();
+
+    block_on(commit_log.append(test_record(1, 0))).unwrap();
+    block_on(commit_log.append(test_record(2, 1))).unwrap();
+    commit_log.release_memtable(ObjectId(1));
+    commit_log.close();
+
+    let segment_ids = block_on(filesystem.scan_commit_log_segments()).unwrap();
+    assert_eq!(segment_ids, vec![1]);
+
+    let records = replay(&filesystem);
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].memtable_id, ObjectId(2));
+}
+
+#[test]
+fn test_replay_skips_released_memtables() {
+    let filesystem = test_filesystem("release");
+    let commit_log = block_on(ArgonFsCommitLog::open(
+        test_config(1024),
+        filesystem.clone(),
+    ))
+    .unwrap();
+
+    block_on(commit_log.append(test_record(1, 0))).unwrap();
+    block_on(commit_log.append(test_record(2, 1))).unwrap();
+    block_on(commit_log.append(test_record(1, 2))).unwrap();
+    commit_log.release_memtable(ObjectId(1));
+    commit_log.close();
+
+    // Segment still holds records of memtable 2, memtable 1 is skipped on replay
+    let segment_ids = block_on(filesystem.scan_commit_log_segments()).unwrap();
+    assert_eq!(segment_ids, vec![0]);
+
+    let records = replay(&filesystem);
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].memtable_id, ObjectId(2));
+    assert_eq!(records[0].mutations[0].timestamp(), 1);
+}
+
+#[test]
+fn test_replay_skips_memtables_released_in_closed_segments() {
+    let filesystem = test_filesystem("closed-release");
+    let commit_log =
+        block_on(ArgonFsCommitLog::open(test_config(128), filesystem.clone())).unwrap();
+
+    block_on(commit_log.append(test_record(1, 0))).unwrap();
+    block_on(commit_log.append(test_record(2, 1))).unwrap();
+    block_on(commit_log.append(test_record(1, 2))).unwrap();
+    commit_log.release_memtable(ObjectId(1));
+    commit_log.close();
+
+    let segment_ids = block_on(filesystem.scan_commit_log_segments()).unwrap();
+    assert_eq!(segment_ids, vec![0]);
+
+    let records = replay(&filesystem);
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].memtable_id, ObjectId(2));
+}
+
+#[test]
+fn test_max_replay_memtable_id_includes_released_memtables() {
+    let filesystem = test_filesystem("max-memtable-id");
+    let commit_log = block_on(ArgonFsCommitLog::open(
+        test_config(1024),
+        filesystem.clone(),
+    ))
+    .unwrap();
+
+    block_on(commit_log.append(test_record(1, 0))).unwrap();
+    block_on(commit_log.append(test_record(5, 1))).unwrap();
+    commit_log.release_memtable(ObjectId(5));
+    commit_log.close();
+
+    let commit_log = block_on(ArgonFsCommitLog::open(
+        test_config(1024),
+        filesystem.clone(),
+    ))
+    .unwrap();
+
+    // Reusing id of a released memtable would get records of its successor skipped on replay
+    assert_eq!(
+        block_on(commit_log.max_replay_memtable_id()).unwrap(),
+        Some(ObjectId(5))
+    );
+    commit_log.close();
+}
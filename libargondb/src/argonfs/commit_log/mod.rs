@@ -0,0 +1,13 @@
+mod commit_log;
+mod commit_log_config;
+mod commit_log_record;
+mod commit_log_writer;
+
+pub use commit_log::ArgonFsCommitLog;
+pub use commit_log_config::CommitLogConfig;
+pub use commit_log_config::CommitLogSyncPolicy;
+
+#[cfg(test)]
+mod commit_log_record_tests;
+#[cfg(test)]
+mod commit_log_writer_tests;
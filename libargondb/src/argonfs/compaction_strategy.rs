@@ -0,0 +1,406 @@
+use std::{
+    cmp::Ordering,
+    collections::BTreeMap,
+    fmt::Debug,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::kv::{
+    KVCompactionOptions, KVSSTable, KVTableOptions, KVTableSchema,
+    primary_key::{KVPrimaryKeyComparator, KVPrimaryKeySchema},
+};
+
+/** Sstables merged together by a single compaction. */
+pub struct CompactionTask {
+    pub sstables: Vec<Arc<Box<dyn KVSSTable>>>,
+    pub output_level: u64,
+    /** Output is split into files of roughly this size, at row boundaries. Single file if unset. */
+    pub max_output_file_size: Option<u64>,
+}
+
+/**
+ * Decides which sstables of a table are compacted next. Compactor keeps asking for tasks until
+ * the strategy returns none.
+ */
+pub trait CompactionStrategy: Debug + Send + Sync {
+    fn pick_compaction(
+        &self,
+        table_schema: &KVTableSchema,
+        sstables: Vec<Arc<Box<dyn KVSSTable>>>,
+    ) -> Option<CompactionTask>;
+
+    /**
+     * Task merging given sstables on operator request, `table_sstables` are all sstables of the
+     * table. Output stays at the highest input level.
+     */
+    fn manual_compaction(
+        &self,
+        _table_schema: &KVTableSchema,
+        sstables: Vec<Arc<Box<dyn KVSSTable>>>,
+        _table_sstables: Vec<Arc<Box<dyn KVSSTable>>>,
+    ) -> CompactionTask {
+        CompactionTask {
+            output_level: max_level(&sstables),
+            sstables,
+            max_output_file_size: None,
+        }
+    }
+}
+
+/** Merges a whole level into a single sstable of the next level once it holds too many files. */
+#[derive(Debug, Clone)]
+pub struct SizeTieredCompactionStrategy {
+    pub level_file_threshold: usize,
+}
+
+const DEFAULT_LEVEL_FILE_THRESHOLD: usize = 10;
+
+impl Default for SizeTieredCompactionStrategy {
+    fn default() -> Self {
+        Self {
+            level_file_threshold: DEFAULT_LEVEL_FILE_THRESHOLD,
+        }
+    }
+}
+
+impl CompactionStrategy for SizeTieredCompactionStrategy {
+    fn pick_compaction(
+        &self,
+        _table_schema: &KVTableSchema,
+        sstables: Vec<Arc<Box<dyn KVSSTable>>>,
+    ) -> Option<CompactionTask> {
+        let level_bins = bin_by_level(sstables);
+
+        for (level, sstables) in level_bins {
+            if sstables.len() > self.level_file_threshold {
+                return Some(CompactionTask {
+                    sstables,
+                    output_level: level.saturating_add(1),
+                    max_output_file_size: None,
+                });
+            }
+        }
+
+        None
+    }
+}
+
+/**
+ * Keeps sstables of each level above 0 non-overlapping, so a key is looked up in at most one
+ * sstable per level. Level 0 holds flushed memtables and is merged into level 1 once it holds
+ * `level0_file_threshold` files. Level N above 0 is compacted once its size exceeds
+ * `level_base_size * level_size_multiplier^(N-1)` - its oldest sstable is merged with the
+ * overlapping sstables of level N+1.
+ */
+#[derive(Debug, Clone)]
+pub struct LeveledCompactionStrategy {
+    pub level0_file_threshold: usize,
+    pub level_base_size: u64,
+    pub level_size_multiplier: u64,
+    pub max_level: u64,
+    pub max_file_size: u64,
+}
+
+const DEFAULT_LEVEL0_FILE_THRESHOLD: usize = 4;
+const DEFAULT_LEVEL_BASE_SIZE: u64 = 64 * 1024 * 1024; // 64MB
+const DEFAULT_LEVEL_SIZE_MULTIPLIER: u64 = 10;
+const DEFAULT_MAX_LEVEL: u64 = 6;
+const DEFAULT_MAX_FILE_SIZE: u64 = 8 * 1024 * 1024; // 8MB
+
+impl Default for LeveledCompactionStrategy {
+    fn default() -> Self {
+        Self {
+            level0_file_threshold: DEFAULT_LEVEL0_FILE_THRESHOLD,
+            level_base_size: DEFAULT_LEVEL_BASE_SIZE,
+            level_size_multiplier: DEFAULT_LEVEL_SIZE_MULTIPLIER,
+            max_level: DEFAULT_MAX_LEVEL,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+        }
+    }
+}
+
+impl LeveledCompactionStrategy {
+    fn level_target_size(&self, level: u64) -> u64 {
+        let exponent = u32::try_from(level.saturating_sub(1)).unwrap_or(u32::MAX);
+
+        self.level_base_size
+            .saturating_mul(self.level_size_multiplier.saturating_pow(exponent))
+    }
+}
+
+impl CompactionStrategy for LeveledCompactionStrategy {
+    fn pick_compaction(
+        &self,
+        table_schema: &KVTableSchema,
+        sstables: Vec<Arc<Box<dyn KVSSTable>>>,
+    ) -> Option<CompactionTask> {
+        let pk_schema = KVPrimaryKeySchema::from_table_schema(table_schema);
+        let level_bins = bin_by_level(sstables);
+
+        // Level with the highest ratio of its size to the target size is compacted first
+        let mut picked: Option<(f64, u64)> = None;
+        for (level, sstables) in &level_bins {
+            if *level >= self.max_level {
+                continue;
+            }
+
+            let score = if *level == 0 {
+                sstables.len() as f64 / self.level0_file_threshold.max(1) as f64
+            } else {
+                let level_size: u64 = sstables.iter().map(|sstable| sstable.size()).sum();
+                level_size as f64 / self.level_target_size(*level).max(1) as f64
+            };
+
+            if score >= 1.0 && picked.is_none_or(|(picked_score, _)| score > picked_score) {
+                picked = Some((score, *level));
+            }
+        }
+
+        let (_, level) = picked?;
+
+        let mut inputs = if level == 0 {
+            level_bins[&0].clone()
+        } else {
+            let oldest = level_bins[&level]
+                .iter()
+                .min_by_key(|sstable| sstable.sstable_id().0)?;
+            vec![oldest.clone()]
+        };
+
+        if let Some((min_row_key, max_row_key)) = key_range(&pk_schema, &inputs)
+            && let Some(next_level_sstables) = level_bins.get(&(level + 1))
+        {
+            for sstable in next_level_sstables {
+                if overlaps(&pk_schema, sstable, &min_row_key, &max_row_key) {
+                    inputs.push(sstable.clone());
+                }
+            }
+        }
+
+        Some(CompactionTask {
+            sstables: inputs,
+            output_level: level + 1,
+            max_output_file_size: Some(self.max_file_size),
+        })
+    }
+
+    /**
+     * Level 0 is reserved for flushed memtables, its sstables are merged into level 1 at least.
+     * Sstables of the output level overlapping the merged sstables are merged too, so the output
+     * level stays non-overlapping.
+     */
+    fn manual_compaction(
+        &self,
+        table_schema: &KVTableSchema,
+        mut sstables: Vec<Arc<Box<dyn KVSSTable>>>,
+        table_sstables: Vec<Arc<Box<dyn KVSSTable>>>,
+    ) -> CompactionTask {
+        let pk_schema = KVPrimaryKeySchema::from_table_schema(table_schema);
+        let output_level = max_level(&sstables).max(1);
+
+        if let Some((min_row_key, max_row_key)) = key_range(&pk_schema, &sstables) {
+            for sstable in table_sstables {
+                let is_input = sstables
+                    .iter()
+                    .any(|input| input.sstable_id() == sstable.sstable_id());
+
+                if sstable.level() == output_level
+                    && !is_input
+                    && overlaps(&pk_schema, &sstable, &min_row_key, &max_row_key)
+                {
+                    sstables.push(sstable);
+                }
+            }
+        }
+
+        CompactionTask {
+            output_level,
+            sstables,
+            max_output_file_size: Some(self.max_file_size),
+        }
+    }
+}
+
+/**
+ * Groups sstables into time windows by their newest mutation. Sstables of the current window are
+ * merged once `window_file_threshold` of them pile up, each past window is merged into a single
+ * sstable of level 1 - data of a window is not rewritten again once the window is over. Past
+ * windows are never left at level 0, as level 0 sstables count towards write stalls.
+ */
+#[derive(Debug, Clone)]
+pub struct TimeWindowCompactionStrategy {
+    pub window: Duration,
+    pub window_file_threshold: usize,
+}
+
+const DEFAULT_WINDOW_FILE_THRESHOLD: usize = 4;
+
+impl TimeWindowCompactionStrategy {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            window_file_threshold: DEFAULT_WINDOW_FILE_THRESHOLD,
+        }
+    }
+
+    fn window_of(&self, timestamp: u64) -> u64 {
+        let window_millis = u64::try_from(self.window.as_millis())
+            .unwrap_or(u64::MAX)
+            .max(1);
+
+        timestamp / window_millis
+    }
+}
+
+impl CompactionStrategy for TimeWindowCompactionStrategy {
+    fn pick_compaction(
+        &self,
+        _table_schema: &KVTableSchema,
+        sstables: Vec<Arc<Box<dyn KVSSTable>>>,
+    ) -> Option<CompactionTask> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+        let current_window = self.window_of(u64::try_from(now).unwrap_or(u64::MAX));
+
+        let mut window_bins = BTreeMap::<u64, Vec<Arc<Box<dyn KVSSTable>>>>::new();
+        for sstable in sstables {
+            window_bins
+                .entry(self.window_of(sstable.max_timestamp()))
+                .or_default()
+                .push(sstable);
+        }
+
+        for (window, sstables) in window_bins {
+            let is_compaction_needed = if window < current_window {
+                sstables.len() >= 2 || sstables.iter().any(|sstable| sstable.level() == 0)
+            } else {
+                sstables.len() >= self.window_file_threshold.max(2)
+            };
+
+            if is_compaction_needed {
+                return Some(CompactionTask {
+                    sstables,
+                    output_level: 1,
+                    max_output_file_size: None,
+                });
+            }
+        }
+
+        None
+    }
+}
+
+/** Settings of strategies shared by all tables, tables select only the strategy to use. */
+#[derive(Debug, Clone)]
+pub struct CompactionStrategyConfig {
+    pub size_tiered: SizeTieredCompactionStrategy,
+    pub leveled: LeveledCompactionStrategy,
+    pub time_window_file_threshold: usize,
+}
+
+impl Default for CompactionStrategyConfig {
+    fn default() -> Self {
+        Self {
+            size_tiered: SizeTieredCompactionStrategy::default(),
+            leveled: LeveledCompactionStrategy::default(),
+            time_window_file_threshold: DEFAULT_WINDOW_FILE_THRESHOLD,
+        }
+    }
+}
+
+pub struct CompactionStrategyResolver;
+
+impl CompactionStrategyResolver {
+    pub fn for_table_options(
+        table_options: &KVTableOptions,
+        config: &CompactionStrategyConfig,
+    ) -> Box<dyn CompactionStrategy> {
+        match table_options.compaction {
+            KVCompactionOptions::SizeTiered => Box::new(config.size_tiered.clone()),
+            KVCompactionOptions::Leveled => Box::new(config.leveled.clone()),
+            KVCompactionOptions::TimeWindow { window } => Box::new(TimeWindowCompactionStrategy {
+                window,
+                window_file_threshold: config.time_window_file_threshold,
+            }),
+        }
+    }
+}
+
+fn max_level(sstables: &[Arc<Box<dyn KVSSTable>>]) -> u64 {
+    sstables
+        .iter()
+        .map(|sstable| sstable.level())
+        .max()
+        .unwrap_or(0)
+}
+
+type KeyRange = (Box<[u8]>, Box<[u8]>);
+
+fn bin_by_level(
+    sstables: Vec<Arc<Box<dyn KVSSTable>>>,
+) -> BTreeMap<u64, Vec<Arc<Box<dyn KVSSTable>>>> {
+    let mut level_bins = BTreeMap::<u64, Vec<Arc<Box<dyn KVSSTable>>>>::new();
+
+    for sstable in sstables {
+        level_bins.entry(sstable.level()).or_default().push(sstable);
+    }
+
+    level_bins
+}
+
+/** Smallest key range covering all sstables with data. */
+fn key_range(
+    pk_schema: &KVPrimaryKeySchema,
+    sstables: &[Arc<Box<dyn KVSSTable>>],
+) -> Option<KeyRange> {
+    let mut range: Option<(&[u8], &[u8])> = None;
+
+    for sstable in sstables {
+        if sstable.min_row_key().is_empty() {
+            continue;
+        }
+
+        range = Some(match range {
+            None => (sstable.min_row_key(), sstable.max_row_key()),
+            Some((min_row_key, max_row_key)) => (
+                min_key(pk_schema, min_row_key, sstable.min_row_key()),
+                max_key(pk_schema, max_row_key, sstable.max_row_key()),
+            ),
+        });
+    }
+
+    range.map(|(min_row_key, max_row_key)| (min_row_key.into(), max_row_key.into()))
+}
+
+fn overlaps(
+    pk_schema: &KVPrimaryKeySchema,
+    sstable: &Arc<Box<dyn KVSSTable>>,
+    min_row_key: &[u8],
+    max_row_key: &[u8],
+) -> bool {
+    if sstable.min_row_key().is_empty() {
+        return false;
+    }
+
+    KVPrimaryKeyComparator::cmp(pk_schema, sstable.min_row_key(), max_row_key).unwrap()
+        != Ordering::Greater
+        && KVPrimaryKeyComparator::cmp(pk_schema, sstable.max_row_key(), min_row_key).unwrap()
+            != Ordering::Less
+}
+
+fn min_key<'a>(pk_schema: &KVPrimaryKeySchema, a: &'a [u8], b: &'a [u8]) -> &'a [u8] {
+    match KVPrimaryKeyComparator::cmp(pk_schema, a, b).unwrap() {
+        Ordering::Greater => b,
+        _ => a,
+    }
+}
+
+fn max_key<'a>(pk_schema: &KVPrimaryKeySchema, a: &'a [u8], b: &'a [u8]) -> &'a [u8] {
+    match KVPrimaryKeyComparator::cmp(pk_schema, a, b).unwrap() {
+        Ordering::Less => b,
+        _ => a,
+    }
+}
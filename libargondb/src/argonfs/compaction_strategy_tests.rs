@@ -0,0 +1,273 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    argonfs::{
+        CompactionStrategy, CompactionStrategyConfig, CompactionStrategyResolver,
+        LeveledCompactionStrategy, SizeTieredCompactionStrategy, TimeWindowCompactionStrategy,
+    },
+    kv::{
+        KVCompactionOptions, KVSSTable, KVTableOptions,
+        kv_test_utils::{TestSSTable, test_schema},
+    },
+};
+
+fn sstables(sstables: Vec<TestSSTable>) -> Vec<Arc<Box<dyn KVSSTable>>> {
+    sstables
+        .into_iter()
+        .map(|sstable| Arc::new(sstable.boxed()))
+        .collect()
+}
+
+fn sstable_ids(sstables: &[Arc<Box<dyn KVSSTable>>]) -> Vec<u64> {
+    let mut sstable_ids: Vec<u64> = sstables
+        .iter()
+        .map(|sstable| sstable.sstable_id().0)
+        .collect();
+    sstable_ids.sort_unstable();
+
+    sstable_ids
+}
+
+fn leveled() -> LeveledCompactionStrategy {
+    LeveledCompactionStrategy {
+        level0_file_threshold: 2,
+        level_base_size: 100,
+        level_size_multiplier: 10,
+        max_level: 3,
+        max_file_size: 50,
+    }
+}
+
+#[test]
+fn test_size_tiered_merges_level_over_threshold() {
+    let strategy = SizeTieredCompactionStrategy {
+        level_file_threshold: 2,
+    };
+
+    let level_full = sstables(vec![
+        TestSSTable::new(1, 0, "a", "b"),
+        TestSSTable::new(2, 0, "c", "d"),
+        TestSSTable::new(3, 1, "a", "z"),
+    ]);
+    assert!(
+        strategy
+            .pick_compaction(&test_schema(), level_full)
+            .is_none()
+    );
+
+    let level_over_threshold = sstables(vec![
+        TestSSTable::new(1, 0, "a", "b"),
+        TestSSTable::new(2, 0, "c", "d"),
+        TestSSTable::new(3, 0, "e", "f"),
+        TestSSTable::new(4, 1, "a", "z"),
+    ]);
+    let task = strategy
+        .pick_compaction(&test_schema(), level_over_threshold)
+        .unwrap();
+
+    // Whole level is merged into a single sstable of the next level
+    assert_eq!(sstable_ids(&task.sstables), vec![1, 2, 3]);
+    assert_eq!(task.output_level, 1);
+    assert_eq!(task.max_output_file_size, None);
+}
+
+#[test]
+fn test_leveled_merges_level0_with_overlapping_level1() {
+    let strategy = leveled();
+
+    let task = strategy
+        .pick_compaction(
+            &test_schema(),
+            sstables(vec![
+                TestSSTable::new(1, 0, "b", "c"),
+                TestSSTable::new(2, 0, "e", "f"),
+                TestSSTable::new(3, 1, "a", "b"),
+                TestSSTable::new(4, 1, "d", "d"),
+                TestSSTable::new(5, 1, "g", "h"),
+            ]),
+        )
+        .unwrap();
+
+    // Level 1 sstables within the key range of the whole level 0 are rewritten
+    assert_eq!(sstable_ids(&task.sstables), vec![1, 2, 3, 4]);
+    assert_eq!(task.output_level, 1);
+    assert_eq!(task.max_output_file_size, Some(50));
+}
+
+#[test]
+fn test_leveled_merges_oldest_sstable_of_oversized_level() {
+    let strategy = leveled();
+
+    let under_target_size = sstables(vec![
+        TestSSTable::new(1, 1, "a", "c").with_size(40),
+        TestSSTable::new(2, 1, "d", "f").with_size(40),
+    ]);
+    assert!(
+        strategy
+            .pick_compaction(&test_schema(), under_target_size)
+            .is_none()
+    );
+
+    let task = strategy
+        .pick_compaction(
+            &test_schema(),
+            sstables(vec![
+                TestSSTable::new(3, 1, "d", "f").with_size(60),
+                TestSSTable::new(2, 1, "a", "c").with_size(60),
+                TestSSTable::new(4, 2, "b", "b").with_size(200),
+                TestSSTable::new(5, 2, "e", "e").with_size(200),
+            ]),
+        )
+        .unwrap();
+
+    // Only the overlapping sstables of the next level are rewritten
+    assert_eq!(sstable_ids(&task.sstables), vec![2, 4]);
+    assert_eq!(task.output_level, 2);
+}
+
+#[test]
+fn test_leveled_picks_level_furthest_over_target() {
+    let strategy = leveled();
+
+    let task = strategy
+        .pick_compaction(
+            &test_schema(),
+            sstables(vec![
+                TestSSTable::new(1, 0, "a", "b"),
+                TestSSTable::new(2, 0, "c", "d"),
+                TestSSTable::new(3, 1, "e", "f").with_size(500),
+                TestSSTable::new(4, 3, "a", "z").with_size(1_000_000),
+            ]),
+        )
+        .unwrap();
+
+    // Level 1 is 5 times over its target, level 0 is just at its threshold, level 3 is the last
+    assert_eq!(sstable_ids(&task.sstables), vec![3]);
+    assert_eq!(task.output_level, 2);
+
+    let last_level_only = sstables(vec![TestSSTable::new(4, 3, "a", "z").with_size(1_000_000)]);
+    assert!(
+        strategy
+            .pick_compaction(&test_schema(), last_level_only)
+            .is_none()
+    );
+}
+
+#[test]
+fn test_leveled_manual_compaction_leaves_level0() {
+    let table_sstables = sstables(vec![
+        TestSSTable::new(1, 0, "a", "b"),
+        TestSSTable::new(2, 0, "c", "d"),
+    ]);
+    let task = leveled().manual_compaction(&test_schema(), table_sstables.clone(), table_sstables);
+
+    assert_eq!(task.output_level, 1);
+    assert_eq!(task.max_output_file_size, Some(50));
+}
+
+#[test]
+fn test_leveled_manual_compaction_merges_overlapping_output_level() {
+    let table_sstables = sstables(vec![
+        TestSSTable::new(1, 0, "c", "f"),
+        TestSSTable::new(2, 1, "a", "d"),
+        TestSSTable::new(3, 1, "e", "e"),
+        TestSSTable::new(4, 1, "g", "h"),
+        TestSSTable::new(5, 2, "a", "z"),
+    ]);
+    let in_range = table_sstables
+        .iter()
+        .filter(|sstable| sstable.sstable_id().0 <= 2)
+        .cloned()
+        .collect();
+
+    let task = leveled().manual_compaction(&test_schema(), in_range, table_sstables);
+
+    // Output would overlap sstable 3 otherwise, sstables of other levels are left alone
+    assert_eq!(sstable_ids(&task.sstables), vec![1, 2, 3]);
+    assert_eq!(task.output_level, 1);
+}
+
+#[test]
+fn test_time_window_merges_past_windows_and_full_current_window() {
+    let strategy = TimeWindowCompactionStrategy::new(Duration::from_hours(1));
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    let hour = Duration::from_hours(1).as_millis() as u64;
+    let window_start = now - now % hour;
+
+    let current_window = |sstable_ids: &[u64]| -> Vec<TestSSTable> {
+        sstable_ids
+            .iter()
+            .map(|sstable_id| {
+                TestSSTable::new(*sstable_id, 0, "a", "z").with_timestamps(window_start, now)
+            })
+            .collect()
+    };
+
+    // Current window is merged only once enough sstables pile up
+    assert!(
+        strategy
+            .pick_compaction(&test_schema(), sstables(current_window(&[1, 2, 3])))
+            .is_none()
+    );
+    let task = strategy
+        .pick_compaction(&test_schema(), sstables(current_window(&[1, 2, 3, 4])))
+        .unwrap();
+    assert_eq!(sstable_ids(&task.sstables), vec![1, 2, 3, 4]);
+
+    // Past window is merged into a single sstable, sstables of other windows are left alone
+    let mut window_sstables = current_window(&[1, 2, 3]);
+    window_sstables.extend([
+        TestSSTable::new(4, 1, "a", "z").with_timestamps(0, hour - 1),
+        TestSSTable::new(5, 0, "a", "z").with_timestamps(hour, 2 * hour - 1),
+        TestSSTable::new(6, 0, "a", "z").with_timestamps(hour + 1, hour + 2),
+    ]);
+    let task = strategy
+        .pick_compaction(&test_schema(), sstables(window_sstables))
+        .unwrap();
+    assert_eq!(sstable_ids(&task.sstables), vec![5, 6]);
+    assert_eq!(task.output_level, 1);
+
+    // Single sstable of a past window still leaves level 0, where it would count towards stalls
+    let mut window_sstables = current_window(&[1, 2, 3]);
+    window_sstables.push(TestSSTable::new(4, 0, "a", "z").with_timestamps(0, hour - 1));
+    let task = strategy
+        .pick_compaction(&test_schema(), sstables(window_sstables))
+        .unwrap();
+    assert_eq!(sstable_ids(&task.sstables), vec![4]);
+    assert_eq!(task.output_level, 1);
+}
+
+#[test]
+fn test_resolver_selects_strategy_of_table() {
+    let config = CompactionStrategyConfig::default();
+    let strategy = |compaction| {
+        format!(
+            "{:?}",
+            CompactionStrategyResolver::for_table_options(
+                &KVTableOptions {
+                    compaction,
+                    ..Default::default()
+                },
+                &config,
+            )
+        )
+    };
+
+    assert!(strategy(KVCompactionOptions::SizeTiered).starts_with("SizeTieredCompactionStrategy"));
+    assert!(strategy(KVCompactionOptions::Leveled).starts_with("LeveledCompactionStrategy"));
+    assert!(
+        strategy(KVCompactionOptions::TimeWindow {
+            window: Duration::from_hours(1)
+        })
+        .starts_with("TimeWindowCompactionStrategy")
+    );
+
+    // Tables without selected strategy keep the strategy of tables created before strategies
+    assert!(strategy(KVCompactionOptions::default()).starts_with("SizeTieredCompactionStrategy"));
+}
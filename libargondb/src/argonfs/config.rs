@@ -1,12 +1,16 @@
 use std::path::PathBuf;
 
-use crate::argonfs::{block_cache::BlockCacheConfig, local_fs::FsFileSystemConfig};
+use crate::argonfs::{
+    block_cache::BlockCacheConfig, commit_log::CommitLogConfig, local_fs::FsFileSystemConfig,
+};
 
 #[derive(Debug, Clone)]
 pub struct ArgonFsConfig {
     pub fs_filesystem_config: FsFileSystemConfig,
     pub block_cache_page_size: usize,
     pub block_cache_pages_count: usize,
+    pub commit_log_config: CommitLogConfig,
+    pub worker_pool_thread_count: usize,
 }
 
 impl Default for ArgonFsConfig {
@@ -16,6 +20,10 @@ impl Default for ArgonFsConfig {
 
             block_cache_page_size: 1 << 13,   // page size = 8KB
             block_cache_pages_count: 1 << 15, // total pages size = 256MB
+
+            commit_log_config: CommitLogConfig::default(),
+
+            worker_pool_thread_count: 1,
         }
     }
 }
@@ -42,3 +42,8 @@ impl AsRef<[u8]> for ReadData {
 
 #[async_trait]
 pub trait WriteOnlyFileHandle: Write + Send + Sync {}
+
+pub trait AppendOnlyFileHandle: Write + Send + Sync {
+    /** Blocks until all written data reaches durable storage. */
+    fn sync(&mut self) -> Result<(), io::Error>;
+}
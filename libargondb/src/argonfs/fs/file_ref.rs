@@ -1,6 +1,6 @@
 use std::io;
 
-use super::file_handle::{ReadOnlyFileHandle, WriteOnlyFileHandle};
+use super::file_handle::{AppendOnlyFileHandle, ReadOnlyFileHandle, WriteOnlyFileHandle};
 use async_trait::async_trait;
 
 #[async_trait]
@@ -9,6 +9,8 @@ pub trait FileRef: Send + Sync {
 
     async fn open_write_only(&self) -> Result<Box<dyn WriteOnlyFileHandle>, io::Error>;
 
+    async fn open_append(&self) -> Result<Box<dyn AppendOnlyFileHandle>, io::Error>;
+
     async fn remove(self: Box<Self>) -> Result<(), io::Error>;
 
     fn box_clone(&self) -> BoxFileRef;
@@ -4,15 +4,16 @@ use async_trait::async_trait;
 use thiserror::Error;
 
 use super::BoxFileRef;
-use crate::kv::{KVTableId, KVTableSchema, ObjectId};
+use crate::kv::{KVTableId, ObjectId};
 
 #[async_trait]
 pub trait FileSystem {
+    async fn scan_table_ids(&self) -> Result<Vec<KVTableId<'static>>, FileSystemError>;
+
     async fn scan_table_catalog(
         &self,
         table_id: &KVTableId,
-        table_schema: &KVTableSchema,
-    ) -> Result<Vec<BoxFileRef>, FileSystemError>;
+    ) -> Result<TableCatalog, FileSystemError>;
 
     async fn get_sstable_file_ref(
         &self,
@@ -20,7 +21,68 @@ pub trait FileSystem {
         sstable_id: ObjectId,
     ) -> Result<BoxFileRef, FileSystemError>;
 
+    /** File the sstable is written to before it is published under its final name. */
+    async fn get_sstable_temp_file_ref(
+        &self,
+        table_id: &KVTableId,
+        sstable_id: ObjectId,
+    ) -> Result<BoxFileRef, FileSystemError>;
+
+    /**
+     * Renames temporary file of the sstable to its final name. The rename is durable once it
+     * returns.
+     */
+    async fn publish_sstable_file(
+        &self,
+        table_id: &KVTableId,
+        sstable_id: ObjectId,
+    ) -> Result<(), FileSystemError>;
+
+    /**
+     * Removes directory of the table with all its files. The removal is durable once it returns.
+     */
+    async fn remove_table_dir(&self, table_id: &KVTableId) -> Result<(), FileSystemError>;
+
+    async fn get_manifest_file_ref(
+        &self,
+        table_id: &KVTableId,
+    ) -> Result<BoxFileRef, FileSystemError>;
+
+    async fn get_manifest_temp_file_ref(
+        &self,
+        table_id: &KVTableId,
+    ) -> Result<BoxFileRef, FileSystemError>;
+
+    /**
+     * Replaces manifest of the table with its temporary file. The rename is durable once it
+     * returns.
+     */
+    async fn publish_manifest_file(&self, table_id: &KVTableId) -> Result<(), FileSystemError>;
+
     async fn get_state_snapshot_file_ref(&self) -> Result<BoxFileRef, FileSystemError>;
+
+    async fn get_state_snapshot_temp_file_ref(&self) -> Result<BoxFileRef, FileSystemError>;
+
+    /** Replaces state snapshot with its temporary file. The rename is durable once it returns. */
+    async fn publish_state_snapshot_file(&self) -> Result<(), FileSystemError>;
+
+    async fn scan_commit_log_segments(&self) -> Result<Vec<u64>, FileSystemError>;
+
+    async fn get_commit_log_segment_file_ref(
+        &self,
+        segment_id: u64,
+    ) -> Result<BoxFileRef, FileSystemError>;
+
+    /** Syncs the commit log directory, so created and removed segments survive a crash. */
+    async fn sync_commit_log_dir(&self) -> Result<(), FileSystemError>;
+}
+
+/** Files found in a table directory. */
+#[derive(Debug, Default)]
+pub struct TableCatalog {
+    pub manifest_exists: bool,
+    pub sstable_ids: Vec<ObjectId>,
+    pub temp_sstable_ids: Vec<ObjectId>,
 }
 
 pub type BoxFileSystem = Box<dyn FileSystem + Send + Sync>;
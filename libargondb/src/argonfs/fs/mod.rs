@@ -2,6 +2,7 @@ mod file_handle;
 mod file_ref;
 mod file_system;
 
+pub use file_handle::AppendOnlyFileHandle;
 pub use file_handle::FileHandleError;
 pub use file_handle::ReadData;
 pub use file_handle::ReadOnlyFileHandle;
@@ -11,3 +12,4 @@ pub use file_ref::FileRef;
 pub use file_system::BoxFileSystem;
 pub use file_system::FileSystem;
 pub use file_system::FileSystemError;
+pub use file_system::TableCatalog;
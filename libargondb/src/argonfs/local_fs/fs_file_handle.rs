@@ -7,7 +7,9 @@ use std::{
 use async_trait::async_trait;
 use memmap::MmapOptions;
 
-use crate::argonfs::fs::{FileHandleError, ReadData, ReadOnlyFileHandle, WriteOnlyFileHandle};
+use crate::argonfs::fs::{
+    AppendOnlyFileHandle, FileHandleError, ReadData, ReadOnlyFileHandle, WriteOnlyFileHandle,
+};
 
 pub struct FsReadOnlyFileHandle {
     file: File,
@@ -107,6 +109,39 @@ impl Write for FsWriteOnlyFileHandle {
         self.file.write(buf)
     }
 
+    fn flush(&mut self) -> std::io::Result<()> {
+        // Flushed file must be durable - SSTables are only registered after their writer is flushed
+        self.file.flush()?;
+        self.file.sync_all()
+    }
+}
+
+pub struct FsAppendOnlyFileHandle {
+    file: File,
+}
+
+impl FsAppendOnlyFileHandle {
+    pub fn new(path: &PathBuf) -> Result<Self, std::io::Error> {
+        let parent_path = path.parent().unwrap();
+        std::fs::create_dir_all(parent_path)?;
+
+        let file = OpenOptions::new().append(true).create(true).open(path)?;
+
+        Ok(Self { file })
+    }
+}
+
+impl AppendOnlyFileHandle for FsAppendOnlyFileHandle {
+    fn sync(&mut self) -> std::io::Result<()> {
+        self.file.sync_data()
+    }
+}
+
+impl Write for FsAppendOnlyFileHandle {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.file.write(buf)
+    }
+
     fn flush(&mut self) -> std::io::Result<()> {
         self.file.flush()
     }
@@ -6,8 +6,10 @@ use std::{
 use async_trait::async_trait;
 
 use crate::argonfs::{
-    fs::{BoxFileRef, FileRef, ReadOnlyFileHandle, WriteOnlyFileHandle},
-    local_fs::fs_file_handle::{FsReadOnlyFileHandle, FsWriteOnlyFileHandle},
+    fs::{AppendOnlyFileHandle, BoxFileRef, FileRef, ReadOnlyFileHandle, WriteOnlyFileHandle},
+    local_fs::fs_file_handle::{
+        FsAppendOnlyFileHandle, FsReadOnlyFileHandle, FsWriteOnlyFileHandle,
+    },
 };
 
 #[derive(Clone)]
@@ -33,6 +35,10 @@ impl FileRef for FsFileRef {
         Ok(Box::new(FsWriteOnlyFileHandle::new(&self.path)?))
     }
 
+    async fn open_append(&self) -> Result<Box<dyn AppendOnlyFileHandle>, io::Error> {
+        Ok(Box::new(FsAppendOnlyFileHandle::new(&self.path)?))
+    }
+
     async fn remove(self: Box<Self>) -> Result<(), io::Error> {
         fs::remove_file(self.path)
     }
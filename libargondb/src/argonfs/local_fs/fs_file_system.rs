@@ -1,14 +1,18 @@
-use std::{fs, path::Path};
+use std::{
+    fs::{self, File},
+    path::Path,
+    str::FromStr,
+};
 
 use async_trait::async_trait;
 use crossbeam::queue::ArrayQueue;
 
 use crate::{
     argonfs::{
-        fs::{BoxFileRef, FileSystem, FileSystemError},
+        fs::{BoxFileRef, FileSystem, FileSystemError, TableCatalog},
         local_fs::{FsFileSystemConfig, fs_file_ref::FsFileRef, fs_path_factory::FsPathFactory},
     },
-    kv::{KVTableId, KVTableSchema, ObjectId},
+    kv::{KVTableId, ObjectId},
 };
 
 pub struct FsFileSystem {
@@ -29,29 +33,62 @@ impl FsFileSystem {
 
 #[async_trait]
 impl FileSystem for FsFileSystem {
+    async fn scan_table_ids(&self) -> Result<Vec<KVTableId<'static>>, FileSystemError> {
+        let tables_root = self.ctx.path_factory.tables_root();
+        let mut table_ids = vec![];
+
+        if tables_root.exists() {
+            for entry in fs::read_dir(tables_root)? {
+                let entry = entry?;
+
+                if entry.file_type()?.is_dir()
+                    && let Some(table_id) = entry
+                        .file_name()
+                        .to_str()
+                        .and_then(|name| KVTableId::from_str(name).ok())
+                {
+                    table_ids.push(table_id.to_owned());
+                }
+            }
+        }
+
+        Ok(table_ids)
+    }
+
     async fn scan_table_catalog(
         &self,
         table_id: &KVTableId,
-        table_schema: &KVTableSchema,
-    ) -> Result<Vec<BoxFileRef>, FileSystemError> {
+    ) -> Result<TableCatalog, FileSystemError> {
         let table_dir = self.ctx.path_factory.table_dir(table_id);
-        let mut refs: Vec<BoxFileRef> = vec![];
+        let mut catalog = TableCatalog {
+            manifest_exists: self.ctx.path_factory.manifest_file(table_id).exists(),
+            ..Default::default()
+        };
 
         if table_dir.exists() {
             let dir_entries = fs::read_dir(table_dir)?;
 
             for entry in dir_entries {
-                let entry = entry?;
+                let path = entry?.path();
 
-                if let Some(extension) = entry.path().extension()
-                    && extension == "argonfile"
-                {
-                    refs.push(Box::new(FsFileRef::new(&entry.path())));
+                let Some(sstable_id) = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.parse::<u64>().ok())
+                    .map(ObjectId)
+                else {
+                    continue;
+                };
+
+                match path.extension().and_then(|extension| extension.to_str()) {
+                    Some("argonfile") => catalog.sstable_ids.push(sstable_id),
+                    Some("tmp") => catalog.temp_sstable_ids.push(sstable_id),
+                    _ => {}
                 }
             }
         }
 
-        Ok(refs)
+        Ok(catalog)
     }
 
     async fn get_sstable_file_ref(
@@ -64,11 +101,143 @@ impl FileSystem for FsFileSystem {
         Ok(Box::new(FsFileRef::new(&file_path)))
     }
 
+    async fn get_sstable_temp_file_ref(
+        &self,
+        table_id: &KVTableId,
+        sstable_id: ObjectId,
+    ) -> Result<BoxFileRef, FileSystemError> {
+        let file_path = self
+            .ctx
+            .path_factory
+            .sstable_temp_file(table_id, sstable_id);
+
+        Ok(Box::new(FsFileRef::new(&file_path)))
+    }
+
+    async fn publish_sstable_file(
+        &self,
+        table_id: &KVTableId,
+        sstable_id: ObjectId,
+    ) -> Result<(), FileSystemError> {
+        rename_durable(
+            &self
+                .ctx
+                .path_factory
+                .sstable_temp_file(table_id, sstable_id),
+            &self.ctx.path_factory.sstable_file(table_id, sstable_id),
+        )
+    }
+
+    async fn remove_table_dir(&self, table_id: &KVTableId) -> Result<(), FileSystemError> {
+        let table_dir = self.ctx.path_factory.table_dir(table_id);
+
+        if table_dir.exists() {
+            fs::remove_dir_all(&table_dir)?;
+
+            if let Some(dir) = table_dir.parent() {
+                File::open(dir)?.sync_all()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_manifest_file_ref(
+        &self,
+        table_id: &KVTableId,
+    ) -> Result<BoxFileRef, FileSystemError> {
+        let file_path = self.ctx.path_factory.manifest_file(table_id);
+
+        Ok(Box::new(FsFileRef::new(&file_path)))
+    }
+
+    async fn get_manifest_temp_file_ref(
+        &self,
+        table_id: &KVTableId,
+    ) -> Result<BoxFileRef, FileSystemError> {
+        let file_path = self.ctx.path_factory.manifest_temp_file(table_id);
+
+        Ok(Box::new(FsFileRef::new(&file_path)))
+    }
+
+    async fn publish_manifest_file(&self, table_id: &KVTableId) -> Result<(), FileSystemError> {
+        rename_durable(
+            &self.ctx.path_factory.manifest_temp_file(table_id),
+            &self.ctx.path_factory.manifest_file(table_id),
+        )
+    }
+
     async fn get_state_snapshot_file_ref(&self) -> Result<BoxFileRef, FileSystemError> {
         let file_path = self.ctx.path_factory.state_snapshot_file();
 
         Ok(Box::new(FsFileRef::new(&file_path)))
     }
+
+    async fn get_state_snapshot_temp_file_ref(&self) -> Result<BoxFileRef, FileSystemError> {
+        let file_path = self.ctx.path_factory.state_snapshot_temp_file();
+
+        Ok(Box::new(FsFileRef::new(&file_path)))
+    }
+
+    async fn publish_state_snapshot_file(&self) -> Result<(), FileSystemError> {
+        rename_durable(
+            &self.ctx.path_factory.state_snapshot_temp_file(),
+            &self.ctx.path_factory.state_snapshot_file(),
+        )
+    }
+
+    async fn scan_commit_log_segments(&self) -> Result<Vec<u64>, FileSystemError> {
+        let commit_log_dir = self.ctx.path_factory.commit_log_dir();
+        let mut segment_ids: Vec<u64> = vec![];
+
+        if commit_log_dir.exists() {
+            let dir_entries = fs::read_dir(commit_log_dir)?;
+
+            for entry in dir_entries {
+                let path = entry?.path();
+
+                if let Some(extension) = path.extension()
+                    && extension == "commitlog"
+                    && let Some(segment_id) = path
+                        .file_stem()
+                        .and_then(|stem| stem.to_str())
+                        .and_then(|stem| stem.parse::<u64>().ok())
+                {
+                    segment_ids.push(segment_id);
+                }
+            }
+        }
+
+        segment_ids.sort_unstable();
+
+        Ok(segment_ids)
+    }
+
+    async fn get_commit_log_segment_file_ref(
+        &self,
+        segment_id: u64,
+    ) -> Result<BoxFileRef, FileSystemError> {
+        let file_path = self.ctx.path_factory.commit_log_segment_file(segment_id);
+
+        Ok(Box::new(FsFileRef::new(&file_path)))
+    }
+
+    async fn sync_commit_log_dir(&self) -> Result<(), FileSystemError> {
+        File::open(self.ctx.path_factory.commit_log_dir())?.sync_all()?;
+
+        Ok(())
+    }
+}
+
+/** Renames the file and syncs its directory, so the rename survives a crash. */
+fn rename_durable(from: &Path, to: &Path) -> Result<(), FileSystemError> {
+    fs::rename(from, to)?;
+
+    if let Some(dir) = to.parent() {
+        File::open(dir)?.sync_all()?;
+    }
+
+    Ok(())
 }
 
 struct FsFileSystemCtx {
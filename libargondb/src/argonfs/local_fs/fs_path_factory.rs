@@ -33,7 +33,33 @@ impl FsPathFactory {
             .join(format!("{}.argonfile", sstable_id.0))
     }
 
+    pub fn sstable_temp_file(&self, table_id: &KVTableId, sstable_id: ObjectId) -> PathBuf {
+        self.table_dir(table_id)
+            .join(format!("{}.tmp", sstable_id.0))
+    }
+
+    pub fn manifest_file(&self, table_id: &KVTableId) -> PathBuf {
+        self.table_dir(table_id).join("MANIFEST")
+    }
+
+    pub fn manifest_temp_file(&self, table_id: &KVTableId) -> PathBuf {
+        self.table_dir(table_id).join("MANIFEST.tmp")
+    }
+
     pub fn state_snapshot_file(&self) -> PathBuf {
         self.config.storage_root.join("_state_snapshot")
     }
+
+    pub fn state_snapshot_temp_file(&self) -> PathBuf {
+        self.config.storage_root.join("_state_snapshot.tmp")
+    }
+
+    pub fn commit_log_dir(&self) -> PathBuf {
+        self.config.storage_root.join("commit_log")
+    }
+
+    pub fn commit_log_segment_file(&self, segment_id: u64) -> PathBuf {
+        self.commit_log_dir()
+            .join(format!("{}.commitlog", segment_id))
+    }
 }
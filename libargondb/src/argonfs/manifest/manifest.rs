@@ -0,0 +1,241 @@
+use std::{
+    collections::BTreeMap,
+    io::{ErrorKind, SeekFrom, Write},
+    sync::Arc,
+};
+
+use async_lock::Mutex;
+
+use crate::{
+    argonfs::{
+        fs::{BoxFileSystem, FileRef},
+        manifest::manifest_record::{ManifestEdit, ManifestParser, ManifestSerializer},
+    },
+    core::persistence::{OrPersistenceError, PersistenceError},
+    kv::{KVTableId, ObjectId},
+};
+
+/**
+ * Append-only log of changes of the set of live sstables, one per table. An edit is committed once
+ * its append is synced - sstable files not referenced by committed edits are never loaded.
+ */
+pub struct ArgonFsManifest {
+    filesystem: Arc<BoxFileSystem>,
+    /**
+     * Flushes and compactions commit their edits from different threads. Holds tables whose last
+     * append failed, with the size of their manifest before it.
+     */
+    torn_manifests: Mutex<BTreeMap<String, u64>>,
+}
+
+impl ArgonFsManifest {
+    pub fn new(filesystem: Arc<BoxFileSystem>) -> Self {
+        Self {
+            filesystem,
+            torn_manifests: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /**
+     * Returns live sstables of the table and brings its directory in line with the manifest.
+     * Committed sstables which were not renamed into place yet are published, files of interrupted
+     * flushes and of already compacted sstables are removed. Tables created before manifests were
+     * introduced adopt all sstable files found in their directory.
+     */
+    pub async fn recover(
+        &self,
+        table_id: &KVTableId<'_>,
+    ) -> Result<Vec<ObjectId>, PersistenceError> {
+        let mut torn_manifests = self.torn_manifests.lock().await;
+
+        let catalog = self
+            .filesystem
+            .scan_table_catalog(table_id)
+            .await
+            .ok_or_persistence_error()?;
+
+        let live_sstable_ids = if catalog.manifest_exists {
+            let manifest_size = torn_manifests.get(table_id.as_ref()).copied();
+            ManifestParser::live_sstables(&self.read_edits(table_id, manifest_size).await?)
+        } else {
+            println!(
+                "argonfs - table {} has no manifest - adopting {} sstables found in its directory",
+                table_id.as_ref(),
+                catalog.sstable_ids.len()
+            );
+            catalog.sstable_ids.clone()
+        };
+
+        for sstable_id in &catalog.temp_sstable_ids {
+            if live_sstable_ids.contains(sstable_id) && !catalog.sstable_ids.contains(sstable_id) {
+                self.filesystem
+                    .publish_sstable_file(table_id, *sstable_id)
+                    .await
+                    .ok_or_persistence_error()?;
+                continue;
+            }
+
+            println!(
+                "argonfs - removing unfinished sstable {} of table {}",
+                sstable_id.0,
+                table_id.as_ref()
+            );
+            self.filesystem
+                .get_sstable_temp_file_ref(table_id, *sstable_id)
+                .await
+                .ok_or_persistence_error()?
+                .remove()
+                .await
+                .ok_or_persistence_error()?;
+        }
+
+        for sstable_id in &catalog.sstable_ids {
+            if live_sstable_ids.contains(sstable_id) {
+                continue;
+            }
+
+            println!(
+                "argonfs - removing orphaned sstable {} of table {}",
+                sstable_id.0,
+                table_id.as_ref()
+            );
+            self.filesystem
+                .get_sstable_file_ref(table_id, *sstable_id)
+                .await
+                .ok_or_persistence_error()?
+                .remove()
+                .await
+                .ok_or_persistence_error()?;
+        }
+
+        // Edits of previous runs are squashed, so the manifest doesn't grow across restarts
+        self.write_manifest(
+            table_id,
+            &ManifestEdit {
+                added: live_sstable_ids.clone(),
+                removed: vec![],
+            },
+        )
+        .await?;
+        torn_manifests.remove(table_id.as_ref());
+
+        Ok(live_sstable_ids)
+    }
+
+    /**
+     * Appends the edit to the manifest of the table. A failed append may leave a torn frame, which
+     * would hide all later edits from the parser, so the manifest is rewritten before the next one.
+     */
+    pub async fn commit(
+        &self,
+        table_id: &KVTableId<'_>,
+        edit: &ManifestEdit,
+    ) -> Result<(), PersistenceError> {
+        let mut torn_manifests = self.torn_manifests.lock().await;
+
+        if let Some(manifest_size) = torn_manifests.get(table_id.as_ref()).copied() {
+            let edits = self.read_edits(table_id, Some(manifest_size)).await?;
+            self.write_manifest(
+                table_id,
+                &ManifestEdit {
+                    added: ManifestParser::live_sstables(&edits),
+                    removed: vec![],
+                },
+            )
+            .await?;
+            torn_manifests.remove(table_id.as_ref());
+        }
+
+        let file_ref = self
+            .filesystem
+            .get_manifest_file_ref(table_id)
+            .await
+            .ok_or_persistence_error()?;
+
+        // Tables created during this run have no manifest until their first commit
+        let mut reader = match file_ref.open_read_only().await {
+            Ok(reader) => reader,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                return self.write_manifest(table_id, edit).await;
+            }
+            Err(e) => return Err(PersistenceError(Box::new(e))),
+        };
+        let manifest_size = reader
+            .seek(SeekFrom::End(0))
+            .await
+            .ok_or_persistence_error()?;
+
+        let result = Self::append_edit(file_ref.as_ref(), edit).await;
+        if result.is_err() {
+            torn_manifests.insert(table_id.to_string(), manifest_size);
+        }
+
+        result
+    }
+
+    async fn append_edit(
+        file_ref: &dyn FileRef,
+        edit: &ManifestEdit,
+    ) -> Result<(), PersistenceError> {
+        let mut writer = file_ref.open_append().await.ok_or_persistence_error()?;
+        writer
+            .write_all(&ManifestSerializer::serialize_edit(edit))
+            .ok_or_persistence_error()?;
+        writer.sync().ok_or_persistence_error()
+    }
+
+    /** Parses edits within the first `manifest_size` bytes, the whole manifest when `None`. */
+    async fn read_edits(
+        &self,
+        table_id: &KVTableId<'_>,
+        manifest_size: Option<u64>,
+    ) -> Result<Vec<ManifestEdit>, PersistenceError> {
+        let file_ref = self
+            .filesystem
+            .get_manifest_file_ref(table_id)
+            .await
+            .ok_or_persistence_error()?;
+
+        let mut reader = file_ref.open_read_only().await.ok_or_persistence_error()?;
+        let manifest_size = match manifest_size {
+            Some(manifest_size) => manifest_size,
+            None => reader
+                .seek(SeekFrom::End(0))
+                .await
+                .ok_or_persistence_error()?,
+        };
+        let buf = reader
+            .seek_and_read(SeekFrom::Start(0), manifest_size as usize)
+            .await
+            .ok_or_persistence_error()?;
+
+        ManifestParser::parse(buf.as_ref()).ok_or_persistence_error()
+    }
+
+    /** Atomically replaces the manifest with a new one holding only the given edit. */
+    async fn write_manifest(
+        &self,
+        table_id: &KVTableId<'_>,
+        edit: &ManifestEdit,
+    ) -> Result<(), PersistenceError> {
+        let file_ref = self
+            .filesystem
+            .get_manifest_temp_file_ref(table_id)
+            .await
+            .ok_or_persistence_error()?;
+
+        let mut writer = file_ref.open_write_only().await.ok_or_persistence_error()?;
+        writer
+            .write_all(&ManifestSerializer::serialize_header())
+            .ok_or_persistence_error()?;
+        writer
+            .write_all(&ManifestSerializer::serialize_edit(edit))
+            .ok_or_persistence_error()?;
+        writer.flush().ok_or_persistence_error()?;
+
+        self.filesystem
+            .publish_manifest_file(table_id)
+            .await
+            .ok_or_persistence_error()
+    }
+}
@@ -0,0 +1,140 @@
+use bytes::{Buf, BufMut};
+use thiserror::Error;
+
+use crate::kv::ObjectId;
+
+/*
+ * Manifest layout:
+ * [magic: 8B]([payload_size: u32][payload_crc32c: u32][payload])*
+ *
+ * Edit payload layout:
+ * [added_count: u32]([sstable_id: u64])*[removed_count: u32]([sstable_id: u64])*
+ */
+pub const MANIFEST_MAGIC: &[u8; 8] = b"ARGNMANI";
+pub const MANIFEST_HEADER_SIZE: usize = 8;
+
+const RECORD_FRAME_HEADER_SIZE: usize = 8;
+
+/** Change of the set of live sstables of a table, applied atomically. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEdit {
+    pub added: Vec<ObjectId>,
+    pub removed: Vec<ObjectId>,
+}
+
+pub struct ManifestSerializer;
+
+impl ManifestSerializer {
+    pub fn serialize_header() -> Vec<u8> {
+        MANIFEST_MAGIC.to_vec()
+    }
+
+    pub fn serialize_edit(edit: &ManifestEdit) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(8 + 8 * (edit.added.len() + edit.removed.len()));
+
+        payload.put_u32_le(edit.added.len() as u32);
+        for sstable_id in &edit.added {
+            payload.put_u64_le(sstable_id.0);
+        }
+
+        payload.put_u32_le(edit.removed.len() as u32);
+        for sstable_id in &edit.removed {
+            payload.put_u64_le(sstable_id.0);
+        }
+
+        let mut buf = Vec::with_capacity(RECORD_FRAME_HEADER_SIZE + payload.len());
+        buf.put_u32_le(payload.len() as u32);
+        buf.put_u32_le(crc32c::crc32c(&payload));
+        buf.put_slice(&payload);
+
+        buf
+    }
+}
+
+pub struct ManifestParser;
+
+impl ManifestParser {
+    /**
+     * Parses all complete edits of a manifest. Parsing stops at the first truncated or corrupted
+     * edit - it is the tail of an append that was never committed.
+     */
+    pub fn parse(mut buf: &[u8]) -> Result<Vec<ManifestEdit>, ManifestParseError> {
+        if buf.remaining() < MANIFEST_HEADER_SIZE {
+            return Err(ManifestParseError::InvalidMagic);
+        }
+
+        let mut magic = [0u8; 8];
+        buf.copy_to_slice(&mut magic);
+        if &magic != MANIFEST_MAGIC {
+            return Err(ManifestParseError::InvalidMagic);
+        }
+
+        let mut edits = vec![];
+
+        while buf.remaining() >= RECORD_FRAME_HEADER_SIZE {
+            let payload_size = buf.get_u32_le() as usize;
+            let payload_crc = buf.get_u32_le();
+
+            if buf.remaining() < payload_size {
+                println!("manifest ends with a truncated edit - skipping it");
+                break;
+            }
+
+            let payload = &buf[..payload_size];
+            if crc32c::crc32c(payload) != payload_crc {
+                println!("manifest contains a corrupted edit - skipping the rest of manifest");
+                break;
+            }
+
+            edits.push(Self::parse_edit(payload)?);
+            buf.advance(payload_size);
+        }
+
+        Ok(edits)
+    }
+
+    /** Returns sstables live after applying all edits, in order they were added. */
+    pub fn live_sstables(edits: &[ManifestEdit]) -> Vec<ObjectId> {
+        let mut live: Vec<ObjectId> = vec![];
+
+        for edit in edits {
+            live.retain(|sstable_id| !edit.removed.contains(sstable_id));
+
+            for sstable_id in &edit.added {
+                if !live.contains(sstable_id) {
+                    live.push(*sstable_id);
+                }
+            }
+        }
+
+        live
+    }
+
+    fn parse_edit(mut payload: &[u8]) -> Result<ManifestEdit, ManifestParseError> {
+        let added = Self::parse_sstable_ids(&mut payload)?;
+        let removed = Self::parse_sstable_ids(&mut payload)?;
+
+        Ok(ManifestEdit { added, removed })
+    }
+
+    fn parse_sstable_ids(payload: &mut &[u8]) -> Result<Vec<ObjectId>, ManifestParseError> {
+        if payload.remaining() < 4 {
+            return Err(ManifestParseError::MalformedEdit);
+        }
+
+        let count = payload.get_u32_le() as usize;
+        if payload.remaining() < count * 8 {
+            return Err(ManifestParseError::MalformedEdit);
+        }
+
+        Ok((0..count).map(|_| ObjectId(payload.get_u64_le())).collect())
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ManifestParseError {
+    #[error("manifest has invalid magic")]
+    InvalidMagic,
+    #[error("manifest contains malformed edit")]
+    MalformedEdit,
+}
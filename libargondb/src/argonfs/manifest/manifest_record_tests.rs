@@ -0,0 +1,57 @@
+use crate::{
+    argonfs::manifest::manifest_record::{
+        ManifestEdit, ManifestParseError, ManifestParser, ManifestSerializer,
+    },
+    kv::ObjectId,
+};
+
+fn test_edit(added: &[u64], removed: &[u64]) -> ManifestEdit {
+    ManifestEdit {
+        added: added.iter().copied().map(ObjectId).collect(),
+        removed: removed.iter().copied().map(ObjectId).collect(),
+    }
+}
+
+#[test]
+fn test_serializer_parser_integration() {
+    let mut manifest = ManifestSerializer::serialize_header();
+    manifest.extend(ManifestSerializer::serialize_edit(&test_edit(&[1], &[])));
+    manifest.extend(ManifestSerializer::serialize_edit(&test_edit(&[2], &[])));
+    manifest.extend(ManifestSerializer::serialize_edit(&test_edit(
+        &[3],
+        &[1, 2],
+    )));
+
+    let edits = ManifestParser::parse(&manifest).unwrap();
+
+    assert_eq!(edits.len(), 3);
+    assert_eq!(edits[2], test_edit(&[3], &[1, 2]));
+    assert_eq!(ManifestParser::live_sstables(&edits), vec![ObjectId(3)]);
+}
+
+#[test]
+fn test_parser_skips_uncommitted_tail() {
+    let mut manifest = ManifestSerializer::serialize_header();
+    manifest.extend(ManifestSerializer::serialize_edit(&test_edit(&[1, 2], &[])));
+
+    let compaction_edit = ManifestSerializer::serialize_edit(&test_edit(&[3], &[1, 2]));
+    manifest.extend(&compaction_edit[..compaction_edit.len() - 1]);
+
+    let edits = ManifestParser::parse(&manifest).unwrap();
+    assert_eq!(
+        ManifestParser::live_sstables(&edits),
+        vec![ObjectId(1), ObjectId(2)]
+    );
+
+    let mut corrupted = ManifestSerializer::serialize_header();
+    corrupted.extend(ManifestSerializer::serialize_edit(&test_edit(&[1], &[])));
+    let last = corrupted.len() - 1;
+    corrupted[last] ^= 0xff;
+
+    assert!(ManifestParser::parse(&corrupted).unwrap().is_empty());
+
+    assert!(matches!(
+        ManifestParser::parse(b"ARGNFILE"),
+        Err(ManifestParseError::InvalidMagic)
+    ));
+}
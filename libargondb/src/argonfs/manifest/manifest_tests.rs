@@ -0,0 +1,357 @@
+use std::{
+    io::{self, Write},
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use async_trait::async_trait;
+use smol::block_on;
+
+use crate::{
+    argonfs::{
+        FsFileSystem, FsFileSystemConfig,
+        fs::{
+            AppendOnlyFileHandle, BoxFileRef, BoxFileSystem, FileRef, FileSystem, FileSystemError,
+            ReadOnlyFileHandle, TableCatalog, WriteOnlyFileHandle,
+        },
+        manifest::{ArgonFsManifest, ManifestEdit},
+    },
+    kv::{KVTableId, ObjectId},
+};
+
+fn test_filesystem(name: &str) -> Arc<BoxFileSystem> {
+    Arc::new(Box::new(local_filesystem(name)))
+}
+
+fn local_filesystem(name: &str) -> FsFileSystem {
+    let storage_root =
+        std::env::temp_dir().join(format!("argondb-manifest-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&storage_root);
+
+    FsFileSystem::new(FsFileSystemConfig {
+        storage_root,
+        ..Default::default()
+    })
+}
+
+/** Local filesystem whose manifest appends write half of their data and fail while `torn` is set. */
+struct TornAppendFileSystem {
+    inner: FsFileSystem,
+    torn: Arc<AtomicBool>,
+}
+
+struct TornAppendFileRef {
+    inner: BoxFileRef,
+    torn: Arc<AtomicBool>,
+}
+
+struct TornAppendFileHandle {
+    inner: Box<dyn AppendOnlyFileHandle>,
+    torn: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl FileSystem for TornAppendFileSystem {
+    async fn scan_table_ids(&self) -> Result<Vec<KVTableId<'static>>, FileSystemError> {
+        self.inner.scan_table_ids().await
+    }
+
+    async fn scan_table_catalog(
+        &self,
+        table_id: &KVTableId,
+    ) -> Result<TableCatalog, FileSystemError> {
+        self.inner.scan_table_catalog(table_id).await
+    }
+
+    async fn get_sstable_file_ref(
+        &self,
+        table_id: &KVTableId,
+        sstable_id: ObjectId,
+    ) -> Result<BoxFileRef, FileSystemError> {
+        self.inner.get_sstable_file_ref(table_id, sstable_id).await
+    }
+
+    async fn get_sstable_temp_file_ref(
+        &self,
+        table_id: &KVTableId,
+        sstable_id: ObjectId,
+    ) -> Result<BoxFileRef, FileSystemError> {
+        self.inner
+            .get_sstable_temp_file_ref(table_id, sstable_id)
+            .await
+    }
+
+    async fn publish_sstable_file(
+        &self,
+        table_id: &KVTableId,
+        sstable_id: ObjectId,
+    ) -> Result<(), FileSystemError> {
+        self.inner.publish_sstable_file(table_id, sstable_id).await
+    }
+
+    async fn remove_table_dir(&self, table_id: &KVTableId) -> Result<(), FileSystemError> {
+        self.inner.remove_table_dir(table_id).await
+    }
+
+    async fn get_manifest_file_ref(
+        &self,
+        table_id: &KVTableId,
+    ) -> Result<BoxFileRef, FileSystemError> {
+        Ok(Box::new(TornAppendFileRef {
+            inner: self.inner.get_manifest_file_ref(table_id).await?,
+            torn: self.torn.clone(),
+        }))
+    }
+
+    async fn get_manifest_temp_file_ref(
+        &self,
+        table_id: &KVTableId,
+    ) -> Result<BoxFileRef, FileSystemError> {
+        self.inner.get_manifest_temp_file_ref(table_id).await
+    }
+
+    async fn publish_manifest_file(&self, table_id: &KVTableId) -> Result<(), FileSystemError> {
+        self.inner.publish_manifest_file(table_id).await
+    }
+
+    async fn get_state_snapshot_file_ref(&self) -> Result<BoxFileRef, FileSystemError> {
+        self.inner.get_state_snapshot_file_ref().await
+    }
+
+    async fn get_state_snapshot_temp_file_ref(&self) -> Result<BoxFileRef, FileSystemError> {
+        self.inner.get_state_snapshot_temp_file_ref().await
+    }
+
+    async fn publish_state_snapshot_file(&self) -> Result<(), FileSystemError> {
+        self.inner.publish_state_snapshot_file().await
+    }
+
+    async fn scan_commit_log_segments(&self) -> Result<Vec<u64>, FileSystemError> {
+        self.inner.scan_commit_log_segments().await
+    }
+
+    async fn get_commit_log_segment_file_ref(
+        &self,
+        segment_id: u64,
+    ) -> Result<BoxFileRef, FileSystemError> {
+        self.inner.get_commit_log_segment_file_ref(segment_id).await
+    }
+
+    async fn sync_commit_log_dir(&self) -> Result<(), FileSystemError> {
+        self.inner.sync_commit_log_dir().await
+    }
+}
+
+#[async_trait]
+impl FileRef for TornAppendFileRef {
+    async fn open_read_only(&self) -> Result<Box<dyn ReadOnlyFileHandle>, io::Error> {
+        self.inner.open_read_only().await
+    }
+
+    async fn open_write_only(&self) -> Result<Box<dyn WriteOnlyFileHandle>, io::Error> {
+        self.inner.open_write_only().await
+    }
+
+    async fn open_append(&self) -> Result<Box<dyn AppendOnlyFileHandle>, io::Error> {
+        Ok(Box::new(TornAppendFileHandle {
+            inner: self.inner.open_append().await?,
+            torn: self.torn.clone(),
+        }))
+    }
+
+    async fn remove(self: Box<Self>) -> Result<(), io::Error> {
+        self.inner.remove().await
+    }
+
+    fn box_clone(&self) -> BoxFileRef {
+        Box::new(TornAppendFileRef {
+            inner: self.inner.box_clone(),
+            torn: self.torn.clone(),
+        })
+    }
+}
+
+impl Write for TornAppendFileHandle {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !self.torn.load(Ordering::Acquire) {
+            return self.inner.write(buf);
+        }
+
+        self.inner.write_all(&buf[..buf.len() / 2])?;
+        self.inner.flush()?;
+        Err(io::Error::other("torn append"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl AppendOnlyFileHandle for TornAppendFileHandle {
+    fn sync(&mut self) -> Result<(), io::Error> {
+        self.inner.sync()
+    }
+}
+
+fn ids(ids: &[u64]) -> Vec<ObjectId> {
+    ids.iter().copied().map(ObjectId).collect()
+}
+
+async fn create_files(filesystem: &BoxFileSystem, table_id: &KVTableId<'_>, sstable_ids: &[u64]) {
+    for sstable_id in sstable_ids {
+        filesystem
+            .get_sstable_file_ref(table_id, ObjectId(*sstable_id))
+            .await
+            .unwrap()
+            .open_write_only()
+            .await
+            .unwrap();
+    }
+}
+
+async fn create_temp_files(
+    filesystem: &BoxFileSystem,
+    table_id: &KVTableId<'_>,
+    sstable_ids: &[u64],
+) {
+    for sstable_id in sstable_ids {
+        filesystem
+            .get_sstable_temp_file_ref(table_id, ObjectId(*sstable_id))
+            .await
+            .unwrap()
+            .open_write_only()
+            .await
+            .unwrap();
+    }
+}
+
+/** Sstable and temporary sstable ids found in the table directory, sorted. */
+async fn files(filesystem: &BoxFileSystem, table_id: &KVTableId<'_>) -> (Vec<u64>, Vec<u64>) {
+    let catalog = filesystem.scan_table_catalog(table_id).await.unwrap();
+
+    let sorted = |ids: Vec<ObjectId>| {
+        let mut ids: Vec<_> = ids.into_iter().map(|id| id.0).collect();
+        ids.sort();
+        ids
+    };
+
+    (
+        sorted(catalog.sstable_ids),
+        sorted(catalog.temp_sstable_ids),
+    )
+}
+
+#[test]
+fn test_recover_adopts_sstables_without_manifest() {
+    let filesystem = test_filesystem("adopt");
+    let table_id = KVTableId::from_str("test_table").unwrap();
+    let manifest = ArgonFsManifest::new(filesystem.clone());
+
+    block_on(async {
+        create_files(&filesystem, &table_id, &[1, 2]).await;
+        create_temp_files(&filesystem, &table_id, &[3]).await;
+
+        let mut live = manifest.recover(&table_id).await.unwrap();
+        live.sort_by_key(|id| id.0);
+        assert_eq!(live, ids(&[1, 2]));
+
+        // Files of interrupted flushes are removed, adopted sstables are written to the manifest
+        assert_eq!(files(&filesystem, &table_id).await, (vec![1, 2], vec![]));
+        assert!(
+            filesystem
+                .scan_table_catalog(&table_id)
+                .await
+                .unwrap()
+                .manifest_exists
+        );
+    });
+}
+
+#[test]
+fn test_recover_follows_committed_edits() {
+    let filesystem = test_filesystem("edits");
+    let table_id = KVTableId::from_str("test_table").unwrap();
+    let manifest = ArgonFsManifest::new(filesystem.clone());
+
+    block_on(async {
+        create_files(&filesystem, &table_id, &[1, 2]).await;
+        manifest
+            .commit(
+                &table_id,
+                &ManifestEdit {
+                    added: ids(&[1, 2]),
+                    removed: vec![],
+                },
+            )
+            .await
+            .unwrap();
+
+        // Compaction of sstable 1 into 3 committed before 3 was renamed into place and before 1
+        // was removed, followed by an uncommitted flush of sstable 4
+        create_temp_files(&filesystem, &table_id, &[3, 4]).await;
+        manifest
+            .commit(
+                &table_id,
+                &ManifestEdit {
+                    added: ids(&[3]),
+                    removed: ids(&[1]),
+                },
+            )
+            .await
+            .unwrap();
+
+        let mut live = manifest.recover(&table_id).await.unwrap();
+        live.sort_by_key(|id| id.0);
+        assert_eq!(live, ids(&[2, 3]));
+        assert_eq!(files(&filesystem, &table_id).await, (vec![2, 3], vec![]));
+
+        // Squashed manifest recovers the same sstables on the next start
+        let mut live = manifest.recover(&table_id).await.unwrap();
+        live.sort_by_key(|id| id.0);
+        assert_eq!(live, ids(&[2, 3]));
+    });
+}
+
+#[test]
+fn test_commits_after_torn_append_are_recovered() {
+    let torn = Arc::new(AtomicBool::new(false));
+    let filesystem: Arc<BoxFileSystem> = Arc::new(Box::new(TornAppendFileSystem {
+        inner: local_filesystem("torn"),
+        torn: torn.clone(),
+    }));
+    let table_id = KVTableId::from_str("test_table").unwrap();
+    let manifest = ArgonFsManifest::new(filesystem.clone());
+
+    let commit = |added: &[u64]| {
+        let edit = ManifestEdit {
+            added: ids(added),
+            removed: vec![],
+        };
+        let (manifest, table_id) = (&manifest, &table_id);
+
+        async move { manifest.commit(table_id, &edit).await }
+    };
+
+    block_on(async {
+        create_files(&filesystem, &table_id, &[1, 2, 3, 4]).await;
+        commit(&[1]).await.unwrap();
+        commit(&[2]).await.unwrap();
+
+        torn.store(true, Ordering::Release);
+        assert!(commit(&[3]).await.is_err());
+        torn.store(false, Ordering::Release);
+
+        // Edit following the torn frame is not hidden by it, the failed edit is not committed
+        commit(&[4]).await.unwrap();
+
+        let mut live = ArgonFsManifest::new(filesystem.clone())
+            .recover(&table_id)
+            .await
+            .unwrap();
+        live.sort_by_key(|id| id.0);
+        assert_eq!(live, ids(&[1, 2, 4]));
+    });
+}
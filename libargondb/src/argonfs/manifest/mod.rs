@@ -0,0 +1,10 @@
+mod manifest;
+mod manifest_record;
+
+pub use manifest::ArgonFsManifest;
+pub use manifest_record::ManifestEdit;
+
+#[cfg(test)]
+mod manifest_record_tests;
+#[cfg(test)]
+mod manifest_tests;
@@ -1,9 +1,21 @@
 use std::{
     sync::Arc,
     thread::{self, JoinHandle},
+    time::Duration,
 };
 
-use crate::{DbCtx, argonfs::argonfile::ArgonfileBuilder, kv::memtable::KVMemtableFlushRequest};
+use crate::{
+    DbCtx,
+    argonfs::argonfile::ArgonfileBuilder,
+    core::persistence::{OrPersistenceError, PersistenceError},
+    kv::{
+        KVSSTable,
+        memtable::{KVMemtableFlushRequest, Memtable},
+    },
+};
+
+/** Delay before a failed flush is retried, so a persistent I/O error doesn't keep the flusher busy. */
+const FLUSH_RETRY_DELAY: Duration = Duration::from_secs(1);
 
 pub struct ArgonFsMemtableFlusher {}
 
@@ -25,7 +37,7 @@ fn memtable_flusher_thread(db_ctx: Arc<DbCtx>) {
     println!("memtable flusher thread finished");
 }
 
-async fn process_flush_request(db_ctx: &DbCtx, request: KVMemtableFlushRequest) {
+pub(super) async fn process_flush_request(db_ctx: &DbCtx, request: KVMemtableFlushRequest) {
     let memtable = request.memtable;
     let table = memtable.table();
 
@@ -43,6 +55,20 @@ async fn process_flush_request(db_ctx: &DbCtx, request: KVMemtableFlushRequest)
         std::hint::spin_loop();
     }
 
+    let storage_guard = table.try_storage_guard();
+
+    // Memtables of dropped tables are discarded, their log entries are skipped on replay anyway
+    if storage_guard.is_none() {
+        println!(
+            "flush cancelled for memtable[object_id={}] of dropped table {}[table_id={}]",
+            memtable.object_id,
+            table.table_name,
+            table_id.as_ref()
+        );
+        db_ctx.kv_instance.commit_log().release_memtable(object_id);
+        return;
+    }
+
     if !memtable.is_flush_needed() {
         println!(
             "flush not needed for memtable[object_id={}] of table {}[table_id={}]",
@@ -50,26 +76,66 @@ async fn process_flush_request(db_ctx: &DbCtx, request: KVMemtableFlushRequest)
             table.table_name,
             table_id.as_ref()
         );
+        db_ctx.kv_instance.commit_log().release_memtable(object_id);
         return;
     }
 
+    let sstable = match flush_memtable(db_ctx, &memtable).await {
+        Ok(sstable) => sstable,
+        Err(e) => {
+            // Memtable stays readable and keeps its log entries until a retry succeeds
+            println!(
+                "flush failed for memtable[object_id={}] of table {}[table_id={}] - {}",
+                memtable.object_id,
+                table.table_name,
+                table_id.as_ref(),
+                e
+            );
+            thread::sleep(FLUSH_RETRY_DELAY);
+
+            if let Err(e) = db_ctx.kv_instance.request_memtable_flush(memtable.clone()) {
+                println!(
+                    "flush retry of memtable[object_id={}] abandoned, its log entries are replayed on startup - {}",
+                    memtable.object_id, e
+                );
+            }
+            return;
+        }
+    };
+
+    table.replace_flushed_memtable_with_sstable(memtable.clone(), Arc::new(sstable));
+
+    // SSTable file is synced once its writer is flushed, log entries are no longer needed
+    db_ctx.kv_instance.commit_log().release_memtable(object_id);
+}
+
+/** Writes the memtable into a committed sstable. Safe to repeat after a failure at any step. */
+async fn flush_memtable(
+    db_ctx: &DbCtx,
+    memtable: &Arc<Memtable>,
+) -> Result<Box<dyn KVSSTable>, PersistenceError> {
+    let table = memtable.table();
+    let table_id = &table.table_id;
+    let object_id = memtable.object_id;
+
     let writer = db_ctx
         .persistence
         .new_file_writer_for_sstable(table_id, object_id)
-        .await
-        .unwrap();
+        .await?;
 
     ArgonfileBuilder::flush_memtable(writer, memtable.clone())
         .await
-        .unwrap();
+        .ok_or_persistence_error()?;
 
-    let sstable = db_ctx
+    db_ctx
         .persistence
-        .open_sstable(table_id, object_id, &table.table_schema)
-        .await
-        .unwrap();
+        .commit_sstables(table_id, vec![object_id], vec![])
+        .await?;
 
-    table.replace_flushed_memtable_with_sstable(memtable.clone(), Arc::new(sstable));
+    db_ctx
+        .persistence
+        .open_sstable(table_id, object_id, &table.table_schema())
+        .await
 }
 
 pub struct ArgonFsMemtableFlusherHandle {
@@ -0,0 +1,84 @@
+use std::{
+    sync::{Arc, atomic::Ordering},
+    thread,
+    time::Duration,
+};
+
+use smol::block_on;
+
+use crate::{
+    argonfs::{argonfs_test_utils::test_db_ctx, memtable_flusher::process_flush_request},
+    kv::kv_test_utils::{TestCommitLog, test_mutation, test_table},
+};
+
+#[test]
+fn test_memtable_of_dropped_table_is_discarded() {
+    let commit_log = Arc::new(TestCommitLog::default());
+    let (db_ctx, _) = test_db_ctx("flusher-dropped", commit_log.clone());
+
+    let table = test_table(db_ctx.kv_instance.clone(), vec![]);
+    block_on(table.insert_mutations(&vec![test_mutation("a", 1, "value")])).unwrap();
+
+    // Discard keeps waiting for the scan holding the storage guard
+    let storage_guard = block_on(table.storage_guard());
+    let discarding_table = table.clone();
+    let discard = thread::spawn(move || block_on(discarding_table.discard()));
+
+    while !table.is_dropped() {
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    let flush_request = db_ctx
+        .kv_instance
+        .get_memtable_flush_queue_iter()
+        .next()
+        .unwrap();
+    let memtable_id = flush_request.memtable.object_id;
+
+    // Flush of the memtable doesn't wait for the discard
+    block_on(process_flush_request(&db_ctx, flush_request));
+
+    assert_eq!(
+        *commit_log.released_memtable_ids.lock().unwrap(),
+        vec![memtable_id]
+    );
+    assert!(
+        block_on(db_ctx.persistence.scan_table_ids())
+            .unwrap()
+            .is_empty()
+    );
+
+    drop(storage_guard);
+    discard.join().unwrap();
+}
+
+#[test]
+fn test_failed_flush_is_retried() {
+    let commit_log = Arc::new(TestCommitLog::default());
+    let (db_ctx, fail_commits) = test_db_ctx("flusher-retried", commit_log.clone());
+
+    let table = test_table(db_ctx.kv_instance.clone(), vec![]);
+    block_on(table.insert_mutations(&vec![test_mutation("a", 1, "value")])).unwrap();
+    block_on(table.request_current_memtable_flush()).unwrap();
+
+    let mut flush_queue = db_ctx.kv_instance.get_memtable_flush_queue_iter();
+    let flush_request = flush_queue.next().unwrap();
+    let memtable_id = flush_request.memtable.object_id;
+
+    // Memtable keeps its log entries and is queued again
+    fail_commits.store(true, Ordering::Release);
+    block_on(process_flush_request(&db_ctx, flush_request));
+    assert!(commit_log.released_memtable_ids.lock().unwrap().is_empty());
+    assert!(table.list_sstables().is_empty());
+
+    fail_commits.store(false, Ordering::Release);
+    let flush_request = flush_queue.next().unwrap();
+    assert_eq!(flush_request.memtable.object_id, memtable_id);
+    block_on(process_flush_request(&db_ctx, flush_request));
+
+    assert_eq!(
+        *commit_log.released_memtable_ids.lock().unwrap(),
+        vec![memtable_id]
+    );
+    assert_eq!(table.list_sstables().len(), 1);
+}
@@ -1,21 +1,50 @@
 mod argon_fs;
+mod argon_fs_instance_snapshot;
 mod argon_fs_worker_pool;
 pub mod argonfile;
 mod argonfile_sstable;
 mod block_cache;
+mod commit_log;
+mod compaction_strategy;
 mod config;
 mod fs;
 mod local_fs;
+mod manifest;
 mod memtable_flusher;
+mod rate_limiter;
 mod sstable_compactor;
 
 pub use argon_fs::ArgonFs;
 pub use argon_fs::ArgonFsError;
 pub use argonfile::ArgonfileReader;
+pub use commit_log::CommitLogConfig;
+pub use commit_log::CommitLogSyncPolicy;
+pub use compaction_strategy::CompactionStrategy;
+pub use compaction_strategy::CompactionStrategyConfig;
+pub use compaction_strategy::CompactionStrategyResolver;
+pub use compaction_strategy::CompactionTask;
+pub use compaction_strategy::LeveledCompactionStrategy;
+pub use compaction_strategy::SizeTieredCompactionStrategy;
+pub use compaction_strategy::TimeWindowCompactionStrategy;
 pub use config::ArgonFsConfig;
 pub use local_fs::FsFileSystem;
 pub use local_fs::FsFileSystemConfig;
 pub use memtable_flusher::ArgonFsMemtableFlusher;
 pub use memtable_flusher::ArgonFsMemtableFlusherHandle;
 pub use sstable_compactor::SSTableCompactor;
+pub use sstable_compactor::SSTableCompactorClient;
+pub use sstable_compactor::SSTableCompactorConfig;
 pub use sstable_compactor::SSTableCompactorHandle;
+
+#[cfg(test)]
+mod argonfile_sstable_tests;
+#[cfg(test)]
+pub(crate) mod argonfs_test_utils;
+#[cfg(test)]
+mod compaction_strategy_tests;
+#[cfg(test)]
+mod memtable_flusher_tests;
+#[cfg(test)]
+mod rate_limiter_tests;
+#[cfg(test)]
+mod sstable_compactor_tests;
@@ -0,0 +1,106 @@
+use std::{
+    io::{self, Write},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/**
+ * Token bucket limiting throughput of writes shared by all writers it is attached to. Bucket holds
+ * at most one second worth of bytes, so writers resuming after idle time can't burst above that.
+ */
+/** Longest sleep between checks of the cancel flag. */
+const MAX_SLEEP_SLICE: Duration = Duration::from_millis(50);
+
+#[derive(Debug)]
+pub struct IoRateLimiter {
+    bytes_per_second: u64,
+    state: Mutex<IoRateLimiterState>,
+}
+
+#[derive(Debug)]
+struct IoRateLimiterState {
+    /** Negative when writes taken ahead of time are still being paid off. */
+    available_bytes: f64,
+    refilled_at: Instant,
+}
+
+impl IoRateLimiter {
+    pub fn new(bytes_per_second: u64) -> Self {
+        Self {
+            bytes_per_second: bytes_per_second.max(1),
+            state: Mutex::new(IoRateLimiterState {
+                available_bytes: bytes_per_second as f64,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /**
+     * Blocks calling thread until given number of bytes may be written, or until the cancel flag
+     * is set. Bytes are taken from the bucket either way.
+     */
+    pub fn acquire(&self, bytes: usize, cancel_flag: &AtomicBool) {
+        let wait_time = {
+            let mut state = self.state.lock().unwrap();
+            let rate = self.bytes_per_second as f64;
+
+            let now = Instant::now();
+            let refilled_bytes = now.duration_since(state.refilled_at).as_secs_f64() * rate;
+            state.available_bytes = (state.available_bytes + refilled_bytes).min(rate);
+            state.refilled_at = now;
+
+            state.available_bytes -= bytes as f64;
+
+            if state.available_bytes >= 0.0 {
+                Duration::ZERO
+            } else {
+                Duration::from_secs_f64(-state.available_bytes / rate)
+            }
+        };
+
+        let wake_at = Instant::now() + wait_time;
+        while !cancel_flag.load(Ordering::SeqCst) {
+            let remaining = wake_at.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+
+            thread::sleep(remaining.min(MAX_SLEEP_SLICE));
+        }
+    }
+}
+
+/** Writer charging its writes to the rate limiter. Writes go unthrottled once cancelled. */
+pub struct RateLimitedWriter<W: Write> {
+    inner: W,
+    rate_limiter: Arc<IoRateLimiter>,
+    cancel_flag: Arc<AtomicBool>,
+}
+
+impl<W: Write> RateLimitedWriter<W> {
+    pub fn new(inner: W, rate_limiter: Arc<IoRateLimiter>, cancel_flag: Arc<AtomicBool>) -> Self {
+        Self {
+            inner,
+            rate_limiter,
+            cancel_flag,
+        }
+    }
+}
+
+impl<W: Write> Write for RateLimitedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Short writes are charged only for what they wrote
+        let written = self.inner.write(buf)?;
+        self.rate_limiter.acquire(written, &self.cancel_flag);
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,98 @@
+use std::{
+    io::{self, Write},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::argonfs::rate_limiter::{IoRateLimiter, RateLimitedWriter};
+
+fn acquire_time(rate_limiter: &IoRateLimiter, bytes: usize) -> Duration {
+    let started_at = Instant::now();
+    rate_limiter.acquire(bytes, &AtomicBool::new(false));
+    started_at.elapsed()
+}
+
+#[test]
+fn test_bucket_holds_one_second_of_bytes() {
+    let rate_limiter = IoRateLimiter::new(10_000);
+
+    // Bucket starts full
+    assert!(acquire_time(&rate_limiter, 10_000) < Duration::from_millis(50));
+
+    // Empty bucket is refilled at the configured rate
+    let wait_time = acquire_time(&rate_limiter, 2_000);
+    assert!(wait_time >= Duration::from_millis(150));
+    assert!(wait_time < Duration::from_secs(1));
+
+    // Idle time doesn't let writers burst above one second worth of bytes
+    thread::sleep(Duration::from_millis(1_200));
+    assert!(acquire_time(&rate_limiter, 10_000) < Duration::from_millis(50));
+    assert!(acquire_time(&rate_limiter, 1_000) >= Duration::from_millis(50));
+}
+
+#[test]
+fn test_writer_passes_through_limited_writes() {
+    let rate_limiter = Arc::new(IoRateLimiter::new(1_000));
+    let mut writer = RateLimitedWriter::new(
+        vec![],
+        rate_limiter.clone(),
+        Arc::new(AtomicBool::new(false)),
+    );
+
+    writer.write_all(&[1; 1_000]).unwrap();
+    writer.flush().unwrap();
+
+    // Bytes of the writer were taken from the shared bucket
+    assert!(acquire_time(&rate_limiter, 100) >= Duration::from_millis(50));
+}
+
+/** Writer accepting at most a fixed number of bytes per write. */
+struct ShortWriter(usize);
+
+impl Write for ShortWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(buf.len().min(self.0))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_writer_charges_written_bytes() {
+    let rate_limiter = Arc::new(IoRateLimiter::new(1_000));
+    let mut writer = RateLimitedWriter::new(
+        ShortWriter(100),
+        rate_limiter.clone(),
+        Arc::new(AtomicBool::new(false)),
+    );
+
+    assert_eq!(writer.write(&[1; 1_000]).unwrap(), 100);
+
+    // Bytes left unwritten are still available
+    assert!(acquire_time(&rate_limiter, 900) < Duration::from_millis(50));
+}
+
+#[test]
+fn test_cancelled_acquire_stops_waiting() {
+    let rate_limiter = Arc::new(IoRateLimiter::new(1_000));
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+
+    let waiting_flag = cancel_flag.clone();
+    let waiting_limiter = rate_limiter.clone();
+    let waiting = thread::spawn(move || {
+        let started_at = Instant::now();
+        waiting_limiter.acquire(60_000, &waiting_flag);
+        started_at.elapsed()
+    });
+
+    thread::sleep(Duration::from_millis(100));
+    cancel_flag.store(true, Ordering::SeqCst);
+
+    assert!(waiting.join().unwrap() < Duration::from_secs(1));
+}
@@ -1,185 +1,891 @@
 use std::{
-    collections::BTreeMap,
+    cmp,
+    collections::{BTreeSet, BinaryHeap},
     sync::{
-        Arc,
+        Arc, Condvar, Mutex,
         atomic::{AtomicBool, Ordering},
     },
-    thread, time, u64,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
+use async_trait::async_trait;
+
 use crate::{
     DbCtx,
     argonfile::ArgonfileBuilder,
+    argonfs::{
+        compaction_strategy::{
+            CompactionStrategyConfig, CompactionStrategyResolver, CompactionTask,
+        },
+        rate_limiter::{IoRateLimiter, RateLimitedWriter},
+    },
+    core::persistence::{OrPersistenceError, PersistenceError},
     kv::{
         KVColumnFilter, KVFlushPreStats, KVMergeScanIter, KVPrimaryKeyMarker, KVRangeScan,
-        KVRangeScanResult, KVSSTable, KVTable, ShadowingIter, primary_key::KVPrimaryKeySchema,
+        KVRangeScanResult, KVRangeTombstone, KVRuntimeError, KVSSTable, KVScanDirection,
+        KVScanIterator, KVScanIteratorItem, KVTable, KVTableId, KVTableSchema, ObjectId,
+        ShadowingIter,
+        primary_key::{KVPrimaryKeyComparator, KVPrimaryKeySchema, PrimaryKeyMarkerComparator},
     },
 };
 
-const LEVEL_COMPACTION_THRESHOLD: usize = 10;
+#[derive(Debug, Clone)]
+pub struct SSTableCompactorConfig {
+    /**
+     * Minimal age of a tombstone before compaction may drop it. Mutations with timestamps older
+     * than an already collected tombstone, which arrive later, are not shadowed anymore.
+     */
+    pub tombstone_gc_grace_period: Duration,
+    /** Number of compactions running in parallel, each of a different table. */
+    pub worker_count: usize,
+    /** Interval of checking all tables for compactions to run. */
+    pub scan_interval: Duration,
+    /** Limit of bytes per second written by all compactions together. Unlimited if unset. */
+    pub write_rate_limit: Option<u64>,
+    pub strategy_config: CompactionStrategyConfig,
+}
+
+/** Approximate per mutation size within an Argonfile, on top of its primary key and value. */
+const MUTATION_OVERHEAD_SIZE: u64 = 16;
+
+const DEFAULT_TOMBSTONE_GC_GRACE_PERIOD: Duration = Duration::from_hours(1);
+const DEFAULT_WORKER_COUNT: usize = 2;
+const DEFAULT_SCAN_INTERVAL: Duration = Duration::from_secs(15);
+const DEFAULT_WRITE_RATE_LIMIT: u64 = 64 * 1024 * 1024; // 64MB/s
+
+impl Default for SSTableCompactorConfig {
+    fn default() -> Self {
+        Self {
+            tombstone_gc_grace_period: DEFAULT_TOMBSTONE_GC_GRACE_PERIOD,
+            worker_count: DEFAULT_WORKER_COUNT,
+            scan_interval: DEFAULT_SCAN_INTERVAL,
+            write_rate_limit: Some(DEFAULT_WRITE_RATE_LIMIT),
+            strategy_config: CompactionStrategyConfig::default(),
+        }
+    }
+}
 
 pub struct SSTableCompactor;
 
 impl SSTableCompactor {
-    pub fn new(db_ctx: Arc<DbCtx>) -> SSTableCompactorHandle {
-        let close_flag = Arc::new(AtomicBool::new(false));
+    pub fn new(db_ctx: Arc<DbCtx>, config: SSTableCompactorConfig) -> SSTableCompactorHandle {
+        let ctx = Arc::new(CompactorCtx {
+            db_ctx,
+            rate_limiter: config
+                .write_rate_limit
+                .map(|bytes_per_second| Arc::new(IoRateLimiter::new(bytes_per_second))),
+            worker_count: config.worker_count.max(1),
+            config,
+            close_flag: Arc::new(AtomicBool::new(false)),
+            queue: Mutex::new(CompactionQueue {
+                jobs: BinaryHeap::new(),
+                queued_tables: BTreeSet::new(),
+                running_tables: BTreeSet::new(),
+            }),
+            jobs_condvar: Condvar::new(),
+            close_condvar: Condvar::new(),
+        });
+
+        let mut handles = vec![];
+
+        let scheduler_ctx = ctx.clone();
+        handles.push(thread::spawn(move || {
+            compaction_scheduler_thread(&scheduler_ctx);
+        }));
+
+        for _ in 0..ctx.worker_count {
+            let worker_ctx = ctx.clone();
+            handles.push(thread::spawn(move || compaction_worker_thread(&worker_ctx)));
+        }
+
+        SSTableCompactorHandle { ctx, handles }
+    }
+}
+
+struct CompactorCtx {
+    db_ctx: Arc<DbCtx>,
+    config: SSTableCompactorConfig,
+    worker_count: usize,
+    rate_limiter: Option<Arc<IoRateLimiter>>,
+
+    /**
+     * Set on shutdown - running compactions are abandoned, their outputs are never committed, and
+     * their writes stop waiting for the rate limiter.
+     */
+    close_flag: Arc<AtomicBool>,
+    queue: Mutex<CompactionQueue>,
+    jobs_condvar: Condvar,
+    close_condvar: Condvar,
+}
+
+pub(super) struct CompactionQueue {
+    pub(super) jobs: BinaryHeap<CompactionJob>,
+    /** Tables with a queued automatic compaction. */
+    pub(super) queued_tables: BTreeSet<KVTableId<'static>>,
+    /** Tables being compacted - compactions of a single table never run in parallel. */
+    pub(super) running_tables: BTreeSet<KVTableId<'static>>,
+}
+
+impl CompactionQueue {
+    fn has_runnable_job(&self) -> bool {
+        self.jobs
+            .iter()
+            .any(|job| !self.running_tables.contains(&job.table.table_id))
+    }
+
+    /** Pops the highest priority job of a table which is not being compacted. */
+    pub(super) fn pop_runnable_job(&mut self) -> Option<CompactionJob> {
+        let mut skipped_jobs = vec![];
+
+        let job = loop {
+            let Some(job) = self.jobs.pop() else {
+                break None;
+            };
 
-        let thread_close_flag = close_flag.clone();
-        let handle = thread::spawn(move || sstable_compactor_thread(db_ctx, thread_close_flag));
+            if self.running_tables.contains(&job.table.table_id) {
+                skipped_jobs.push(job);
+                continue;
+            }
+
+            break Some(job);
+        };
+
+        self.jobs.extend(skipped_jobs);
 
-        SSTableCompactorHandle {
-            close_flag,
-            handles: vec![handle],
+        if let Some(job) = &job {
+            if job.manual.is_none() {
+                self.queued_tables.remove(&job.table.table_id);
+            }
+            self.running_tables.insert(job.table.table_id.clone());
         }
+
+        job
+    }
+}
+
+/**
+ * Table with a pending compaction. Manual compactions run first, then tables with higher read
+ * amplification.
+ */
+pub(super) struct CompactionJob {
+    pub(super) table: Arc<KVTable>,
+    /** Number of sstables a read of the table may need to consult. */
+    pub(super) read_amplification: usize,
+    pub(super) manual: Option<ManualCompaction>,
+}
+
+/** Compaction requested through `SSTableCompactorClient`. */
+pub(super) struct ManualCompaction {
+    pub(super) from: KVPrimaryKeyMarker,
+    pub(super) to: KVPrimaryKeyMarker,
+    pub(super) result_tx: flume::Sender<bool>,
+}
+
+impl CompactionJob {
+    fn priority(&self) -> (bool, usize) {
+        (self.manual.is_some(), self.read_amplification)
     }
 }
 
-fn sstable_compactor_thread(db_ctx: Arc<DbCtx>, close_flag: Arc<AtomicBool>) {
-    while !close_flag.load(Ordering::SeqCst) {
-        scan_tables_for_compaction(&db_ctx);
-        thread::sleep(time::Duration::from_secs(15));
+impl PartialEq for CompactionJob {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority() == other.priority()
     }
+}
+
+impl Eq for CompactionJob {}
 
-    println!("sstable compactor thread finished");
+impl PartialOrd for CompactionJob {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
-fn scan_tables_for_compaction(db_ctx: &Arc<DbCtx>) {
-    let tables = db_ctx.catalog.list_tables();
+impl Ord for CompactionJob {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.priority().cmp(&other.priority())
+    }
+}
 
-    for table in tables {
-        let mut level_bins = BTreeMap::<u64, Vec<Arc<Box<dyn KVSSTable>>>>::new();
+impl CompactorCtx {
+    fn is_closed(&self) -> bool {
+        self.close_flag.load(Ordering::SeqCst)
+    }
+
+    /** Queues the table if its compaction strategy has work for it. */
+    fn schedule(&self, table: Arc<KVTable>) {
+        let is_scheduled = |queue: &CompactionQueue| {
+            queue.queued_tables.contains(&table.table_id)
+                || queue.running_tables.contains(&table.table_id)
+        };
 
+        if is_scheduled(&self.queue.lock().unwrap()) {
+            return;
+        }
+
+        let strategy = CompactionStrategyResolver::for_table_options(
+            &table.table_options,
+            &self.config.strategy_config,
+        );
         let sstables = table.list_sstables();
-        for sstable in sstables {
-            let level = sstable.level();
+        let read_amplification = sstables.len();
 
-            if !level_bins.contains_key(&level) {
-                level_bins.insert(level, vec![]);
-            }
+        if strategy
+            .pick_compaction(&table.table_schema(), sstables)
+            .is_none()
+        {
+            return;
+        }
+
+        let mut queue = self.queue.lock().unwrap();
+        if is_scheduled(&queue) {
+            return;
+        }
+
+        queue.queued_tables.insert(table.table_id.clone());
+        queue.jobs.push(CompactionJob {
+            table,
+            read_amplification,
+            manual: None,
+        });
+        self.jobs_condvar.notify_all();
+    }
+
+    fn schedule_manual(&self, table: Arc<KVTable>, manual: ManualCompaction) {
+        let read_amplification = table.list_sstables().len();
+
+        let mut queue = self.queue.lock().unwrap();
+        if self.is_closed() {
+            return;
+        }
+
+        queue.jobs.push(CompactionJob {
+            table,
+            read_amplification,
+            manual: Some(manual),
+        });
+        self.jobs_condvar.notify_all();
+    }
+
+    /** Blocks until a job can run. Returns none once the compactor is closed. */
+    fn next_job(&self) -> Option<CompactionJob> {
+        let queue = self.queue.lock().unwrap();
+        let mut queue = self
+            .jobs_condvar
+            .wait_while(queue, |queue| {
+                !queue.has_runnable_job() && !self.is_closed()
+            })
+            .unwrap();
 
-            level_bins.get_mut(&level).unwrap().push(sstable);
+        if self.is_closed() {
+            return None;
         }
 
-        for (level, sstables) in level_bins {
-            if sstables.len() > LEVEL_COMPACTION_THRESHOLD {
+        queue.pop_runnable_job()
+    }
+
+    fn finish_job(&self, table_id: &KVTableId<'static>) {
+        self.queue.lock().unwrap().running_tables.remove(table_id);
+
+        // Jobs of the table could be waiting for this one to finish
+        self.jobs_condvar.notify_all();
+    }
+
+    fn close(&self) {
+        self.close_flag.store(true, Ordering::SeqCst);
+
+        // Taking the lock ensures no thread is between checking the flag and starting to wait
+        let mut queue = self.queue.lock().unwrap();
+        // Dropping queued manual compactions notifies their requesters
+        queue.jobs.clear();
+        self.jobs_condvar.notify_all();
+        self.close_condvar.notify_all();
+    }
+}
+
+fn compaction_scheduler_thread(ctx: &CompactorCtx) {
+    while !ctx.is_closed() {
+        for table in ctx.db_ctx.catalog.list_tables() {
+            ctx.schedule(table);
+        }
+
+        let queue = ctx.queue.lock().unwrap();
+        let _queue = ctx
+            .close_condvar
+            .wait_timeout_while(queue, ctx.config.scan_interval, |_| !ctx.is_closed())
+            .unwrap();
+    }
+
+    println!("sstable compactor scheduler thread finished");
+}
+
+fn compaction_worker_thread(ctx: &CompactorCtx) {
+    while let Some(job) = ctx.next_job() {
+        let table = job.table;
+        let strategy = CompactionStrategyResolver::for_table_options(
+            &table.table_options,
+            &ctx.config.strategy_config,
+        );
+
+        // Task is picked only now - sstables of the table could change while the job was queued
+        let task = match &job.manual {
+            Some(manual) => sstables_in_range(&table, &manual.from, &manual.to).map(|sstables| {
+                strategy.manual_compaction(&table.table_schema(), sstables, table.list_sstables())
+            }),
+            None => strategy.pick_compaction(&table.table_schema(), table.list_sstables()),
+        };
+
+        let compacted = match task {
+            Some(task) => {
                 println!(
-                    "[SSTable Compactor] For table {} level {} compaction threshold reached - compacting...",
-                    table.table_name, level
+                    "[SSTable Compactor] For table {} compacting {} sstables into level {}...",
+                    table.table_name,
+                    task.sstables.len(),
+                    task.output_level
                 );
 
-                let new_level = if level < u64::MAX {
-                    level + 1
-                } else {
-                    u64::MAX
-                };
+                match smol::block_on(compact_sstables(ctx, table.clone(), task)) {
+                    Ok(compacted) => {
+                        println!("[SSTable Compactor] compaction finished");
+                        compacted
+                    }
+                    Err(e) => {
+                        println!(
+                            "[SSTable Compactor] compaction of table {} failed - {}",
+                            table.table_name, e
+                        );
+                        false
+                    }
+                }
+            }
+            None => false,
+        };
 
-                smol::block_on(compact_sstables(db_ctx, new_level, table.clone(), sstables));
+        ctx.finish_job(&table.table_id);
 
-                println!("[SSTable Compactor] compaction finished");
-            }
+        if let Some(manual) = job.manual {
+            // Requester may have stopped waiting
+            let _ = manual.result_tx.send(compacted);
+        }
+
+        // Picking the same sstables again would loop forever, failed compactions wait for next scan
+        if compacted {
+            ctx.schedule(table);
         }
     }
+
+    println!("sstable compactor worker thread finished");
+}
+
+/**
+ * Sstables of the table holding rows within the range. Returns none if there are none. Sstables
+ * with keys which can't be compared against the range are left out.
+ */
+pub(super) fn sstables_in_range(
+    table: &KVTable,
+    from: &KVPrimaryKeyMarker,
+    to: &KVPrimaryKeyMarker,
+) -> Option<Vec<Arc<Box<dyn KVSSTable>>>> {
+    let pk_schema = KVPrimaryKeySchema::from_table_schema(&table.table_schema());
+
+    let sstables: Vec<_> = table
+        .list_sstables()
+        .into_iter()
+        .filter(|sstable| {
+            // Sstables holding only range tombstones are merged with any range
+            if sstable.min_row_key().is_empty() {
+                return true;
+            }
+
+            let in_range = || -> Result<bool, KVRuntimeError> {
+                Ok(PrimaryKeyMarkerComparator::is_above_lower_bound(
+                    &pk_schema,
+                    from,
+                    sstable.max_row_key(),
+                )? && PrimaryKeyMarkerComparator::is_below_upper_bound(
+                    &pk_schema,
+                    to,
+                    sstable.min_row_key(),
+                )?)
+            };
+
+            in_range().unwrap_or_else(|e| {
+                println!(
+                    "[SSTable Compactor] skipping sstable {} of table {} - {}",
+                    sstable.sstable_id(),
+                    table.table_name,
+                    e
+                );
+                false
+            })
+        })
+        .collect();
+
+    (!sstables.is_empty()).then_some(sstables)
 }
 
+/**
+ * Returns whether compacted sstables were replaced with the output. Outputs of a compaction which
+ * fails before its commit are removed, any left behind by a later failure are removed on startup.
+ */
 async fn compact_sstables(
-    db_ctx: &Arc<DbCtx>,
-    new_level: u64,
+    ctx: &CompactorCtx,
     table: Arc<KVTable>,
-    sstables: Vec<Arc<Box<dyn KVSSTable>>>,
-) {
-    let pk_schema = KVPrimaryKeySchema::from_table_schema(&table.table_schema);
-    let mut merge_iter = KVMergeScanIter::new(pk_schema.clone());
+    task: CompactionTask,
+) -> Result<bool, PersistenceError> {
+    let db_ctx = &ctx.db_ctx;
+
+    let Some(_storage_guard) = table.try_storage_guard() else {
+        return Ok(false);
+    };
+
+    let mut new_sstable_ids = vec![];
+    let written = write_outputs(ctx, &table, &task, &mut new_sstable_ids).await;
+
+    if !matches!(written, Ok(true)) {
+        // Outputs left behind by a failed removal are removed on startup
+        if let Err(e) = db_ctx
+            .persistence
+            .remove_uncommitted_sstables(&table.table_id, new_sstable_ids)
+            .await
+        {
+            println!(
+                "[SSTable Compactor] failed to remove outputs of unfinished compaction - {}",
+                e
+            );
+        }
+
+        return written.map(|_| false);
+    }
+
+    if new_sstable_ids.is_empty() {
+        println!("[SSTable Compactor] compaction output is empty - no sstable written");
+    }
+
+    let sstables = task.sstables;
+    let sstable_ids: Vec<_> = sstables
+        .iter()
+        .map(|sstable| sstable.sstable_id())
+        .collect();
+
+    // Output replaces compacted sstables atomically - a crash leaves either of them on disk. Outputs
+    // of a failed commit may be in the manifest already, so they are left for startup to sort out
+    db_ctx
+        .persistence
+        .commit_sstables(
+            &table.table_id,
+            new_sstable_ids.clone(),
+            sstable_ids.clone(),
+        )
+        .await?;
+
+    let mut new_sstables = vec![];
+    for object_id in new_sstable_ids {
+        new_sstables.push(Arc::new(
+            db_ctx
+                .persistence
+                .open_sstable(&table.table_id, object_id, &table.table_schema())
+                .await?,
+        ));
+    }
+
+    if table
+        .replace_compacted_sstables(&sstables, new_sstables)
+        .is_err()
+    {
+        return Ok(false);
+    }
+
+    // Files of compacted sstables left behind are removed on startup
+    match db_ctx
+        .persistence
+        .remove_compacted_sstables(&table.table_id, sstable_ids)
+        .await
+    {
+        Ok(()) => println!("[SSTable Compactor] old SSTables successfully removed"),
+        Err(e) => println!("[SSTable Compactor] failed to remove old SSTables - {e}"),
+    }
+
+    Ok(true)
+}
+
+/**
+ * Writes compacted sstables into uncommitted outputs, pushing ids of outputs as they are created.
+ * Returns false if the compaction was cancelled.
+ */
+async fn write_outputs(
+    ctx: &CompactorCtx,
+    table: &KVTable,
+    task: &CompactionTask,
+    new_sstable_ids: &mut Vec<ObjectId>,
+) -> Result<bool, PersistenceError> {
+    let db_ctx = &ctx.db_ctx;
+    let config = &ctx.config;
+    let sstables = &task.sstables;
+
+    // Cells of columns retired by now are purged from the output
+    let table_schema = table.table_schema();
+
+    let pk_schema = KVPrimaryKeySchema::from_table_schema(&table_schema);
+    let mut merge_iter = KVMergeScanIter::new(pk_schema.clone(), KVScanDirection::Forward);
 
     let mut pre_stats_builder = MergePreStatsBuilder::new();
 
-    for sstable in &sstables {
+    for sstable in sstables {
         pre_stats_builder.add_sstable(sstable.as_ref().as_ref());
 
         let scan_result = sstable
             .range_scan(&KVRangeScan::new(
-                table.table_schema.clone(),
+                KVTableSchema::clone(&table_schema),
                 KVPrimaryKeyMarker::Start,
                 KVPrimaryKeyMarker::End,
                 KVColumnFilter::All,
             ))
             .await
-            .unwrap();
+            .ok_or_persistence_error()?;
 
         if let KVRangeScanResult::Iter(iter) = scan_result {
             merge_iter.add_iter(iter);
         }
     }
 
-    let object_id = db_ctx.kv_instance.generate_compacted_sstable_id();
-    let writer = db_ctx
-        .persistence
-        .new_file_writer_for_sstable(&table.table_id, object_id)
-        .await
-        .unwrap();
-
-    ArgonfileBuilder::flush_iter(
-        writer,
-        ShadowingIter::new(merge_iter, pk_schema.clone()).await,
-        object_id,
-        new_level,
-        pre_stats_builder.build(),
+    let tombstone_gc_before = tombstone_gc_horizon(config, table, sstables);
+
+    let range_tombstones: Vec<KVRangeTombstone> = sstables
+        .iter()
+        .flat_map(|sstable| sstable.range_tombstones())
+        .collect();
+    let retained_range_tombstones: Vec<KVRangeTombstone> = range_tombstones
+        .iter()
+        .filter(|tombstone| !is_range_tombstone_collectable(config, table, sstables, tombstone))
+        .cloned()
+        .collect();
+
+    let mut iter = ShadowingIter::retaining_tombstones(
+        merge_iter,
+        &table_schema,
+        tombstone_gc_before,
+        range_tombstones,
     )
-    .await
-    .unwrap();
+    .await;
 
-    let sstable = db_ctx
-        .persistence
-        .open_sstable(&table.table_id, object_id, &table.table_schema)
-        .await
-        .unwrap();
+    let pre_stats = pre_stats_builder.build_partition(task.max_output_file_size);
 
-    if let Ok(()) = table.replace_compacted_sstables(&sstables, Arc::new(sstable)) {
-        let sstable_ids = sstables
-            .iter()
-            .map(|sstable| sstable.sstable_id())
-            .collect();
+    // All compacted data may have been deleted - there is nothing to write then
+    while iter.peek_mutation().is_some()
+        || (new_sstable_ids.is_empty() && !retained_range_tombstones.is_empty())
+    {
+        let object_id = db_ctx
+            .kv_instance
+            .generate_compacted_sstable_id()
+            .ok_or_persistence_error()?;
 
-        db_ctx
+        let mut writer = db_ctx
             .persistence
-            .remove_compacted_sstables(&table.table_id, sstable_ids)
-            .await
-            .unwrap();
+            .new_file_writer_for_sstable(&table.table_id, object_id)
+            .await?;
+
+        // Range tombstones are carried over by the first output, they apply regardless of its keys
+        let range_tombstones = if new_sstable_ids.is_empty() {
+            &retained_range_tombstones[..]
+        } else {
+            &[]
+        };
+
+        // Output is removed if the compaction doesn't get to commit it
+        new_sstable_ids.push(object_id);
+
+        if let Some(rate_limiter) = &ctx.rate_limiter {
+            writer = Box::new(RateLimitedWriter::new(
+                writer,
+                rate_limiter.clone(),
+                ctx.close_flag.clone(),
+            ));
+        }
 
-        println!("[SSTable Compactor] old SSTables successfully removed");
+        let partition_pre_stats = KVFlushPreStats {
+            mutations_count: pre_stats.mutations_count,
+        };
+
+        let flushed = ArgonfileBuilder::flush_iter(
+            writer,
+            OutputPartitionIter::new(&mut iter, task.max_output_file_size, &ctx.close_flag),
+            range_tombstones,
+            object_id,
+            task.output_level,
+            partition_pre_stats,
+        )
+        .await;
+
+        if ctx.is_closed() {
+            println!(
+                "[SSTable Compactor] compaction of table {} cancelled",
+                table.table_name
+            );
+
+            return Ok(false);
+        }
+
+        flushed.ok_or_persistence_error()?;
     }
+
+    Ok(true)
+}
+
+/**
+ * Returns timestamp before which tombstones of compacted sstables can be dropped. Tombstone is
+ * collectable when its grace period has passed and no data outside of the compaction is old
+ * enough to be shadowed by it.
+ */
+pub(super) fn tombstone_gc_horizon(
+    config: &SSTableCompactorConfig,
+    table: &KVTable,
+    sstables: &[Arc<Box<dyn KVSSTable>>],
+) -> u64 {
+    let pk_schema = KVPrimaryKeySchema::from_table_schema(&table.table_schema());
+
+    let mut min_row_key: Option<&[u8]> = None;
+    let mut max_row_key: Option<&[u8]> = None;
+
+    for sstable in sstables {
+        if sstable.min_row_key().is_empty() {
+            continue;
+        }
+
+        let is_lower = |key: &[u8]| {
+            KVPrimaryKeyComparator::cmp(&pk_schema, sstable.min_row_key(), key).unwrap()
+                == cmp::Ordering::Less
+        };
+        if min_row_key.is_none_or(is_lower) {
+            min_row_key = Some(sstable.min_row_key());
+        }
+
+        let is_greater = |key: &[u8]| {
+            KVPrimaryKeyComparator::cmp(&pk_schema, sstable.max_row_key(), key).unwrap()
+                == cmp::Ordering::Greater
+        };
+        if max_row_key.is_none_or(is_greater) {
+            max_row_key = Some(sstable.max_row_key());
+        }
+    }
+
+    let (Some(min_row_key), Some(max_row_key)) = (min_row_key, max_row_key) else {
+        return 0;
+    };
+
+    let Ok(min_timestamp_outside) = table.min_timestamp_outside(
+        sstables,
+        &KVPrimaryKeyMarker::Key(min_row_key.into()),
+        &KVPrimaryKeyMarker::Key(max_row_key.into()),
+    ) else {
+        return 0;
+    };
+
+    min_timestamp_outside.min(grace_period_horizon(config))
+}
+
+/**
+ * Range tombstone is dropped under the same rules as other tombstones, except that data outside
+ * of the compaction is checked within the tombstone range instead of the compacted key range.
+ */
+pub(super) fn is_range_tombstone_collectable(
+    config: &SSTableCompactorConfig,
+    table: &KVTable,
+    sstables: &[Arc<Box<dyn KVSSTable>>],
+    tombstone: &KVRangeTombstone,
+) -> bool {
+    let Ok(min_timestamp_outside) =
+        table.min_timestamp_outside(sstables, &tombstone.from, &tombstone.to)
+    else {
+        return false;
+    };
+
+    tombstone.timestamp < min_timestamp_outside.min(grace_period_horizon(config))
+}
+
+fn grace_period_horizon(config: &SSTableCompactorConfig) -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+
+    now.saturating_sub(config.tombstone_gc_grace_period.as_millis() as u64)
 }
 
 pub struct SSTableCompactorHandle {
-    close_flag: Arc<AtomicBool>,
+    ctx: Arc<CompactorCtx>,
     handles: Vec<thread::JoinHandle<()>>,
 }
 
 impl SSTableCompactorHandle {
+    pub fn client(&self) -> SSTableCompactorClient {
+        SSTableCompactorClient {
+            ctx: self.ctx.clone(),
+        }
+    }
+
     pub fn close(self) {
-        self.close_flag.store(true, Ordering::SeqCst);
+        self.ctx.close();
 
         for handle in self.handles {
-            handle.join().unwrap();
+            if handle.join().is_err() {
+                println!("sstable compactor thread panicked");
+            }
         }
     }
 }
 
-struct MergePreStatsBuilder {
+/** Submits compactions requested by operators to the running compactor. */
+#[derive(Clone)]
+pub struct SSTableCompactorClient {
+    ctx: Arc<CompactorCtx>,
+}
+
+impl SSTableCompactorClient {
+    /**
+     * Compacts all sstables of the table holding rows within the range, whether its compaction
+     * strategy considers compaction due or not. Returns once compaction finishes, with whether
+     * there were sstables to compact and they were replaced with the output.
+     */
+    pub async fn compact_table(
+        &self,
+        table: Arc<KVTable>,
+        from: KVPrimaryKeyMarker,
+        to: KVPrimaryKeyMarker,
+    ) -> bool {
+        let (result_tx, result_rx) = flume::bounded(1);
+
+        self.ctx.schedule_manual(
+            table,
+            ManualCompaction {
+                from,
+                to,
+                result_tx,
+            },
+        );
+
+        result_rx.recv_async().await.unwrap_or(false)
+    }
+}
+
+/**
+ * Ends iteration once mutations returned so far reach the maximum output file size. Mutations of a
+ * row are never split between outputs, so outputs of a compaction don't overlap. Iteration also
+ * ends early once the cancel flag is set.
+ */
+pub(super) struct OutputPartitionIter<'a, T: KVScanIterator + Send + Sync> {
+    inner: &'a mut T,
+    max_size: Option<u64>,
+    size: u64,
+    last_primary_key: Option<Box<[u8]>>,
+    cancel_flag: &'a AtomicBool,
+}
+
+impl<'a, T: KVScanIterator + Send + Sync> OutputPartitionIter<'a, T> {
+    pub(super) fn new(
+        inner: &'a mut T,
+        max_size: Option<u64>,
+        cancel_flag: &'a AtomicBool,
+    ) -> Self {
+        Self {
+            inner,
+            max_size,
+            size: 0,
+            last_primary_key: None,
+            cancel_flag,
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.cancel_flag.load(Ordering::SeqCst) || self.is_partition_full()
+    }
+
+    fn is_partition_full(&self) -> bool {
+        let (Some(max_size), Some(last_primary_key)) = (self.max_size, &self.last_primary_key)
+        else {
+            return false;
+        };
+
+        self.size >= max_size
+            && self
+                .inner
+                .peek_mutation()
+                .is_some_and(|item| item.primary_key() != last_primary_key.as_ref())
+    }
+}
+
+#[async_trait]
+impl<T: KVScanIterator + Send + Sync> KVScanIterator for OutputPartitionIter<'_, T> {
+    async fn next_mutation(&mut self) -> Option<Box<dyn KVScanIteratorItem + Send + Sync>> {
+        if self.is_finished() {
+            return None;
+        }
+
+        let item = self.inner.next_mutation().await?;
+
+        let mutation = item.mutation();
+        self.size +=
+            MUTATION_OVERHEAD_SIZE + u64::from(mutation.primary_key_size()) + mutation.value_size();
+
+        if self
+            .last_primary_key
+            .as_deref()
+            .is_none_or(|last_primary_key| last_primary_key != item.primary_key())
+        {
+            self.last_primary_key = Some(item.primary_key().into());
+        }
+
+        Some(item)
+    }
+
+    fn peek_mutation(&self) -> Option<&Box<dyn KVScanIteratorItem + Send + Sync>> {
+        if self.is_finished() {
+            return None;
+        }
+
+        self.inner.peek_mutation()
+    }
+}
+
+pub(super) struct MergePreStatsBuilder {
     mutation_count: u64,
+    size: u64,
 }
 
 impl MergePreStatsBuilder {
-    fn new() -> Self {
-        Self { mutation_count: 0 }
+    pub(super) fn new() -> Self {
+        Self {
+            mutation_count: 0,
+            size: 0,
+        }
     }
 
-    fn add_sstable(&mut self, sstable: &dyn KVSSTable) {
+    pub(super) fn add_sstable(&mut self, sstable: &dyn KVSSTable) {
         self.mutation_count += sstable.mutation_count();
+        self.size += sstable.size();
     }
 
-    fn build(self) -> KVFlushPreStats {
+    /**
+     * Stats of a single output partition. Mutation count of partitions capped at
+     * `max_output_file_size` is estimated from the average size of compacted mutations.
+     */
+    pub(super) fn build_partition(self, max_output_file_size: Option<u64>) -> KVFlushPreStats {
+        let mutation_count = match max_output_file_size {
+            Some(max_size) if self.size > max_size => {
+                let estimate = u128::from(self.mutation_count) * u128::from(max_size)
+                    / u128::from(self.size)
+                    + 1;
+
+                u64::try_from(estimate)
+                    .unwrap_or(u64::MAX)
+                    .min(self.mutation_count)
+            }
+            _ => self.mutation_count,
+        };
+
         KVFlushPreStats {
-            mutations_count: self.mutation_count as usize,
+            mutations_count: mutation_count as usize,
         }
     }
 }
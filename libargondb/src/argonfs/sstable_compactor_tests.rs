@@ -0,0 +1,415 @@
+use std::{
+    collections::{BTreeSet, BinaryHeap},
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use smol::block_on;
+
+use crate::{
+    argonfs::{
+        SSTableCompactor, SSTableCompactorConfig,
+        argonfs_test_utils::test_db_ctx,
+        memtable_flusher::process_flush_request,
+        sstable_compactor::{
+            CompactionJob, CompactionQueue, ManualCompaction, MergePreStatsBuilder,
+            OutputPartitionIter, is_range_tombstone_collectable, sstables_in_range,
+            tombstone_gc_horizon,
+        },
+    },
+    kv::{
+        KVInstance, KVPrimaryKeyMarker, KVRangeTombstone, KVSSTable, KVScanIterator, KVTable,
+        KVTableId, KVTableName, KVTableOptions,
+        config::KVConfig,
+        kv_test_utils::{
+            TestCommitLog, TestIter, TestSSTable, test_instance, test_mutation, test_primary_key,
+            test_schema, test_table,
+        },
+        mutation::{MutationType, StructuredMutation},
+    },
+};
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+fn config(tombstone_gc_grace_period: Duration) -> SSTableCompactorConfig {
+    SSTableCompactorConfig {
+        tombstone_gc_grace_period,
+        ..Default::default()
+    }
+}
+
+fn compacted_sstables(table: &KVTable, sstable_ids: &[u64]) -> Vec<Arc<Box<dyn KVSSTable>>> {
+    table
+        .list_sstables()
+        .into_iter()
+        .filter(|sstable| sstable_ids.contains(&sstable.sstable_id().0))
+        .collect()
+}
+
+#[test]
+fn test_tombstone_gc_horizon_respects_overlapping_data() {
+    let table = test_table(
+        test_instance(KVConfig::default()),
+        vec![
+            TestSSTable::new(1, 1, "b", "d")
+                .with_timestamps(100, 200)
+                .boxed(),
+            TestSSTable::new(2, 1, "c", "f")
+                .with_timestamps(50, 60)
+                .boxed(),
+            TestSSTable::new(3, 1, "x", "z")
+                .with_timestamps(10, 20)
+                .boxed(),
+        ],
+    );
+
+    // Older data of an overlapping sstable must not be resurrected by dropped tombstones
+    let sstables = compacted_sstables(&table, &[1]);
+    assert_eq!(
+        tombstone_gc_horizon(&config(Duration::ZERO), &table, &sstables),
+        50
+    );
+
+    // Data outside of the compacted key range is ignored
+    let sstables = compacted_sstables(&table, &[1, 2]);
+    let horizon = tombstone_gc_horizon(&config(Duration::ZERO), &table, &sstables);
+    assert!(horizon > 1_000);
+}
+
+#[test]
+fn test_tombstone_gc_horizon_respects_grace_period_and_memtables() {
+    let table = test_table(
+        test_instance(KVConfig::default()),
+        vec![
+            TestSSTable::new(1, 1, "b", "d")
+                .with_timestamps(100, 200)
+                .boxed(),
+        ],
+    );
+    let sstables = compacted_sstables(&table, &[1]);
+
+    let grace_period = Duration::from_hours(1);
+    let horizon = tombstone_gc_horizon(&config(grace_period), &table, &sstables);
+    assert!(horizon <= now() - grace_period.as_millis() as u64);
+    assert!(horizon > now() - 2 * grace_period.as_millis() as u64);
+
+    // Memtables may hold data of any key
+    let mutation = StructuredMutation::try_from(
+        30,
+        2,
+        MutationType::Put,
+        test_primary_key("z"),
+        Box::new([]),
+    )
+    .unwrap();
+    block_on(table.insert_mutations(&vec![mutation])).unwrap();
+    assert_eq!(
+        tombstone_gc_horizon(&config(grace_period), &table, &sstables),
+        30
+    );
+
+    // SSTables without rows hold nothing to compare against
+    assert_eq!(tombstone_gc_horizon(&config(grace_period), &table, &[]), 0);
+}
+
+#[test]
+fn test_range_tombstone_collectable_within_its_range() {
+    let table = test_table(
+        test_instance(KVConfig::default()),
+        vec![
+            TestSSTable::new(1, 1, "b", "d")
+                .with_timestamps(100, 200)
+                .boxed(),
+            TestSSTable::new(2, 1, "x", "z")
+                .with_timestamps(10, 20)
+                .boxed(),
+        ],
+    );
+    let sstables = compacted_sstables(&table, &[1]);
+    let tombstone = |from: &str, to: &str, timestamp| KVRangeTombstone {
+        from: KVPrimaryKeyMarker::Key(test_primary_key(from)),
+        to: KVPrimaryKeyMarker::Key(test_primary_key(to)),
+        timestamp,
+    };
+    let collectable = |grace_period, tombstone: &KVRangeTombstone| {
+        is_range_tombstone_collectable(&config(grace_period), &table, &sstables, tombstone)
+    };
+
+    // Nothing outside of the compaction lies within the range
+    assert!(collectable(Duration::ZERO, &tombstone("b", "c", 150)));
+
+    // Older data of a sstable within the range would be resurrected
+    assert!(!collectable(Duration::ZERO, &tombstone("b", "y", 150)));
+    assert!(collectable(Duration::ZERO, &tombstone("b", "y", 5)));
+
+    // Tombstones within the grace period are kept regardless
+    let grace_period = Duration::from_hours(1);
+    assert!(!collectable(
+        grace_period,
+        &tombstone("b", "c", now() - 1_000)
+    ));
+    assert!(collectable(
+        grace_period,
+        &tombstone("b", "c", now() - 2 * grace_period.as_millis() as u64)
+    ));
+}
+
+/** Row ids and timestamps of mutations returned by each output partition. */
+fn partitions(
+    iter: &mut TestIter,
+    max_size: Option<u64>,
+    cancel_flag: &AtomicBool,
+) -> Vec<Vec<(Box<[u8]>, u64)>> {
+    let mut partitions = vec![];
+
+    while iter.peek_mutation().is_some() {
+        let mut partition_iter = OutputPartitionIter::new(iter, max_size, cancel_flag);
+        let mut partition = vec![];
+        while let Some(item) = block_on(partition_iter.next_mutation()) {
+            partition.push((item.primary_key().into(), item.mutation().timestamp()));
+        }
+
+        if partition.is_empty() {
+            break;
+        }
+        partitions.push(partition);
+    }
+
+    partitions
+}
+
+#[test]
+fn test_output_partitions_split_at_row_boundaries() {
+    let mutations = || {
+        vec![
+            test_mutation("a", 20, "value"),
+            test_mutation("a", 10, "value"),
+            test_mutation("b", 10, "value"),
+            test_mutation("c", 10, "value"),
+        ]
+    };
+    let row = |id: &str, timestamps: &[u64]| -> Vec<(Box<[u8]>, u64)> {
+        timestamps
+            .iter()
+            .map(|timestamp| (test_primary_key(id), *timestamp))
+            .collect()
+    };
+    let cancel_flag = AtomicBool::new(false);
+
+    // Every mutation exceeds the maximum size, versions of a row still stay together
+    assert_eq!(
+        partitions(&mut TestIter::new(mutations()), Some(1), &cancel_flag),
+        vec![row("a", &[20, 10]), row("b", &[10]), row("c", &[10])]
+    );
+
+    assert_eq!(
+        partitions(&mut TestIter::new(mutations()), None, &cancel_flag),
+        vec![[row("a", &[20, 10]), row("b", &[10]), row("c", &[10])].concat()]
+    );
+
+    // Cancelled compaction writes nothing more
+    cancel_flag.store(true, Ordering::SeqCst);
+    assert!(partitions(&mut TestIter::new(mutations()), None, &cancel_flag).is_empty());
+}
+
+#[test]
+fn test_partition_pre_stats_estimate_mutations_of_partition() {
+    let pre_stats = |max_output_file_size| {
+        let mut pre_stats_builder = MergePreStatsBuilder::new();
+        for sstable_id in 1..=10 {
+            pre_stats_builder
+                .add_sstable(&TestSSTable::new(sstable_id, 1, "a", "z").with_size(100));
+        }
+
+        pre_stats_builder
+            .build_partition(max_output_file_size)
+            .mutations_count
+    };
+
+    assert_eq!(pre_stats(None), 10);
+    assert_eq!(pre_stats(Some(5_000)), 10);
+    assert_eq!(pre_stats(Some(300)), 4);
+}
+
+fn named_table(instance: &Arc<KVInstance>, table_name: &str) -> Arc<KVTable> {
+    Arc::new(KVTable::create(
+        instance.clone(),
+        KVTableId::from_str(table_name).unwrap(),
+        KVTableName::from_str(table_name).unwrap(),
+        test_schema(),
+        KVTableOptions::default(),
+        vec![],
+    ))
+}
+
+fn automatic_job(table: &Arc<KVTable>, read_amplification: usize) -> CompactionJob {
+    CompactionJob {
+        table: table.clone(),
+        read_amplification,
+        manual: None,
+    }
+}
+
+fn queue(jobs: Vec<CompactionJob>) -> CompactionQueue {
+    CompactionQueue {
+        queued_tables: jobs.iter().map(|job| job.table.table_id.clone()).collect(),
+        jobs: BinaryHeap::from(jobs),
+        running_tables: BTreeSet::new(),
+    }
+}
+
+fn popped_table(queue: &mut CompactionQueue) -> Option<String> {
+    queue
+        .pop_runnable_job()
+        .map(|job| job.table.table_name.to_string())
+}
+
+#[test]
+fn test_queue_pops_tables_by_read_amplification() {
+    let instance = test_instance(KVConfig::default());
+    let (table_a, table_b, table_c) = (
+        named_table(&instance, "table_a"),
+        named_table(&instance, "table_b"),
+        named_table(&instance, "table_c"),
+    );
+
+    let mut queue = queue(vec![
+        automatic_job(&table_a, 3),
+        automatic_job(&table_b, 10),
+        automatic_job(&table_c, 5),
+    ]);
+
+    assert_eq!(popped_table(&mut queue).as_deref(), Some("table_b"));
+    assert_eq!(popped_table(&mut queue).as_deref(), Some("table_c"));
+    assert!(queue.running_tables.contains(&table_b.table_id));
+    assert!(!queue.queued_tables.contains(&table_b.table_id));
+    assert!(queue.queued_tables.contains(&table_a.table_id));
+
+    // Table being compacted is skipped, its job stays queued
+    queue.running_tables.remove(&table_c.table_id);
+    queue.running_tables.insert(table_a.table_id.clone());
+    assert_eq!(popped_table(&mut queue), None);
+    assert_eq!(queue.jobs.len(), 1);
+
+    queue.running_tables.remove(&table_a.table_id);
+    assert_eq!(popped_table(&mut queue).as_deref(), Some("table_a"));
+}
+
+#[test]
+fn test_queue_pops_manual_jobs_first() {
+    let instance = test_instance(KVConfig::default());
+    let (table_a, table_b) = (
+        named_table(&instance, "table_a"),
+        named_table(&instance, "table_b"),
+    );
+    let (result_tx, _result_rx) = flume::unbounded();
+
+    let mut queue = queue(vec![
+        automatic_job(&table_a, 3),
+        automatic_job(&table_b, 10),
+    ]);
+    queue.jobs.push(CompactionJob {
+        table: table_a.clone(),
+        read_amplification: 3,
+        manual: Some(ManualCompaction {
+            from: KVPrimaryKeyMarker::Start,
+            to: KVPrimaryKeyMarker::End,
+            result_tx,
+        }),
+    });
+
+    let job = queue.pop_runnable_job().unwrap();
+    assert_eq!(job.table.table_id, table_a.table_id);
+    assert!(job.manual.is_some());
+
+    // Automatic job of the table stays queued, but waits for the manual one to finish
+    assert!(queue.queued_tables.contains(&table_a.table_id));
+    assert_eq!(popped_table(&mut queue).as_deref(), Some("table_b"));
+    assert_eq!(popped_table(&mut queue), None);
+
+    queue.running_tables.remove(&table_a.table_id);
+    assert_eq!(popped_table(&mut queue).as_deref(), Some("table_a"));
+    assert!(queue.queued_tables.is_empty());
+}
+
+#[test]
+fn test_manual_compaction_selects_sstables_in_range() {
+    let table = test_table(
+        test_instance(KVConfig::default()),
+        vec![
+            TestSSTable::new(1, 1, "a", "c").boxed(),
+            TestSSTable::new(2, 1, "d", "f").boxed(),
+            TestSSTable::new(3, 1, "x", "z").boxed(),
+        ],
+    );
+    let key = |id: &str| KVPrimaryKeyMarker::Key(test_primary_key(id));
+    let selected = |from: &KVPrimaryKeyMarker, to: &KVPrimaryKeyMarker| {
+        sstables_in_range(&table, from, to).map(|sstables| {
+            let mut sstable_ids: Vec<_> = sstables
+                .iter()
+                .map(|sstable| sstable.sstable_id().0)
+                .collect();
+            sstable_ids.sort_unstable();
+            sstable_ids
+        })
+    };
+
+    assert_eq!(selected(&key("b"), &key("e")), Some(vec![1, 2]));
+    assert_eq!(
+        selected(&KVPrimaryKeyMarker::Start, &KVPrimaryKeyMarker::End),
+        Some(vec![1, 2, 3])
+    );
+    assert_eq!(selected(&key("g"), &key("w")), None);
+}
+
+#[test]
+fn test_failed_compaction_keeps_sstables() {
+    let commit_log = Arc::new(TestCommitLog::default());
+    let (db_ctx, fail_commits) = test_db_ctx("compactor-failed", commit_log);
+    let db_ctx = Arc::new(db_ctx);
+
+    let table = test_table(db_ctx.kv_instance.clone(), vec![]);
+    let mut flush_queue = db_ctx.kv_instance.get_memtable_flush_queue_iter();
+    for id in ["a", "b"] {
+        block_on(table.insert_mutations(&vec![test_mutation(id, 1, "value")])).unwrap();
+        block_on(table.request_current_memtable_flush()).unwrap();
+        block_on(process_flush_request(&db_ctx, flush_queue.next().unwrap()));
+    }
+    assert_eq!(table.list_sstables().len(), 2);
+
+    let compactor = SSTableCompactor::new(
+        db_ctx.clone(),
+        SSTableCompactorConfig {
+            scan_interval: Duration::from_secs(3_600),
+            ..Default::default()
+        },
+    );
+    let client = compactor.client();
+    let compact = || {
+        block_on(client.compact_table(
+            table.clone(),
+            KVPrimaryKeyMarker::Start,
+            KVPrimaryKeyMarker::End,
+        ))
+    };
+
+    fail_commits.store(true, Ordering::Release);
+    assert!(!compact());
+    assert_eq!(table.list_sstables().len(), 2);
+
+    // Failed job doesn't keep the table marked as being compacted
+    fail_commits.store(false, Ordering::Release);
+    assert!(compact());
+    assert_eq!(table.list_sstables().len(), 1);
+
+    compactor.close();
+}
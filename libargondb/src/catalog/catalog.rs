@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use super::catalog_state::CatalogState;
 use crate::{
-    kv::{KVTable, KVTableName},
+    kv::{KVTable, KVTableId, KVTableName},
     utils::rcu::RCU,
 };
 
@@ -25,12 +25,40 @@ impl Catalog {
         });
     }
 
+    /** Returns the removed table, `None` when it was removed already. */
+    pub fn remove_table(&self, table_id: &KVTableId) -> Option<Arc<KVTable>> {
+        let mut removed_table = None;
+
+        self.state.mutate_blocking(|current_state| {
+            removed_table = current_state.lookup_table_by_id(table_id);
+
+            current_state.remove_table(table_id)
+        });
+
+        removed_table
+    }
+
+    /** Swaps the table for another one at once, returns false when it was removed already. */
+    pub fn replace_table(&self, table_id: &KVTableId, table: Arc<KVTable>) -> bool {
+        self.state.mutate_blocking(move |current_state| {
+            let new_state = current_state.remove_table(table_id)?.add_table(table);
+
+            Some(new_state)
+        })
+    }
+
     pub fn lookup_table_by_name(&self, table_name: &KVTableName) -> Option<Arc<KVTable>> {
         let catalog_state = self.state.load();
 
         catalog_state.lookup_table_by_name(table_name)
     }
 
+    pub fn lookup_table_by_id(&self, table_id: &KVTableId) -> Option<Arc<KVTable>> {
+        let catalog_state = self.state.load();
+
+        catalog_state.lookup_table_by_id(table_id)
+    }
+
     pub fn list_tables(&self) -> Vec<Arc<KVTable>> {
         let catalog_state = self.state.load();
 
@@ -1,6 +1,6 @@
 use std::{collections::BTreeMap, sync::Arc};
 
-use crate::kv::{KVTable, KVTableName};
+use crate::kv::{KVTable, KVTableId, KVTableName};
 
 pub struct CatalogState {
     tables: Vec<Arc<KVTable>>,
@@ -28,6 +28,25 @@ impl CatalogState {
         }
     }
 
+    /** Returns state without the table, `None` when it isn't part of this state. */
+    pub fn remove_table(&self, table_id: &KVTableId) -> Option<Self> {
+        let idx = self
+            .tables
+            .iter()
+            .position(|table| table.table_id.eq(table_id))?;
+
+        let mut tables = self.tables.clone();
+        let table = tables.remove(idx);
+
+        let mut table_name_map = self.table_name_map.clone();
+        table_name_map.remove(&table.table_name);
+
+        Some(Self {
+            tables,
+            table_name_map,
+        })
+    }
+
     pub fn list_tables(&self) -> Vec<Arc<KVTable>> {
         self.tables.clone()
     }
@@ -37,4 +56,11 @@ impl CatalogState {
             .get(table_name)
             .map(|table_ref| table_ref.clone())
     }
+
+    pub fn lookup_table_by_id(&self, table_id: &KVTableId) -> Option<Arc<KVTable>> {
+        self.tables
+            .iter()
+            .find(|table| table.table_id.eq(table_id))
+            .cloned()
+    }
 }
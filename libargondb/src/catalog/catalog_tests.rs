@@ -0,0 +1,92 @@
+use std::{str::FromStr, sync::Arc};
+
+use super::{Catalog, catalog_state::CatalogState};
+use crate::kv::{
+    KVTable, KVTableId, KVTableName, KVTableOptions,
+    config::KVConfig,
+    kv_test_utils::{test_instance, test_schema},
+};
+
+fn table(table_id: &str, table_name: &str) -> Arc<KVTable> {
+    Arc::new(KVTable::create(
+        test_instance(KVConfig::default()),
+        KVTableId::from_str(table_id).unwrap(),
+        KVTableName::from_str(table_name).unwrap(),
+        test_schema(),
+        KVTableOptions::default(),
+        vec![],
+    ))
+}
+
+fn table_id(table_id: &str) -> KVTableId<'static> {
+    KVTableId::from_str(table_id).unwrap()
+}
+
+fn table_name(table_name: &str) -> KVTableName<'static> {
+    KVTableName::from_str(table_name).unwrap()
+}
+
+#[test]
+fn test_state_remove_table() {
+    let state = CatalogState::empty()
+        .add_table(table("id_a", "table_a"))
+        .add_table(table("id_b", "table_b"));
+
+    let removed_state = state.remove_table(&table_id("id_a")).unwrap();
+    assert!(
+        removed_state
+            .lookup_table_by_id(&table_id("id_a"))
+            .is_none()
+    );
+    assert!(
+        removed_state
+            .lookup_table_by_name(&table_name("table_a"))
+            .is_none()
+    );
+    assert_eq!(removed_state.list_tables().len(), 1);
+
+    // Original state is left untouched
+    assert!(state.lookup_table_by_id(&table_id("id_a")).is_some());
+
+    assert!(removed_state.remove_table(&table_id("id_a")).is_none());
+}
+
+#[test]
+fn test_remove_table() {
+    let catalog = Catalog::new();
+    catalog.add_table(table("id_a", "table_a"));
+    catalog.add_table(table("id_b", "table_b"));
+
+    let removed_table = catalog.remove_table(&table_id("id_a")).unwrap();
+    assert_eq!(removed_table.table_id, table_id("id_a"));
+    assert!(catalog.lookup_table_by_id(&table_id("id_a")).is_none());
+    assert!(
+        catalog
+            .lookup_table_by_name(&table_name("table_a"))
+            .is_none()
+    );
+    assert!(catalog.lookup_table_by_id(&table_id("id_b")).is_some());
+
+    assert!(catalog.remove_table(&table_id("id_a")).is_none());
+}
+
+#[test]
+fn test_replace_table() {
+    let catalog = Catalog::new();
+    catalog.add_table(table("id_a", "table_a"));
+
+    // Replacing table keeps the name, but gets a new id
+    assert!(catalog.replace_table(&table_id("id_a"), table("id_b", "table_a")));
+    assert!(catalog.lookup_table_by_id(&table_id("id_a")).is_none());
+    assert_eq!(
+        catalog
+            .lookup_table_by_name(&table_name("table_a"))
+            .unwrap()
+            .table_id,
+        table_id("id_b")
+    );
+    assert_eq!(catalog.list_tables().len(), 1);
+
+    assert!(!catalog.replace_table(&table_id("id_a"), table("id_c", "table_a")));
+    assert!(catalog.lookup_table_by_id(&table_id("id_c")).is_none());
+}
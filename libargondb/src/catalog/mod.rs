@@ -2,3 +2,6 @@ mod catalog;
 mod catalog_state;
 
 pub use catalog::Catalog;
+
+#[cfg(test)]
+mod catalog_tests;
@@ -0,0 +1,57 @@
+use crate::{
+    ensure,
+    kv::{
+        KVRuntimeError, KVRuntimeErrorKind,
+        column_type::{ColumnType, ColumnTypeCode, ColumnTypeDeserialize, ColumnTypeSerialize},
+    },
+};
+
+pub struct ColumnTypeBool;
+
+impl ColumnType for ColumnTypeBool {
+    fn eq(&self, this: &[u8], that: &[u8]) -> bool {
+        this.eq(that)
+    }
+
+    fn cmp(&self, this: &[u8], that: &[u8]) -> std::cmp::Ordering {
+        let a = Self::deserialize(this).unwrap();
+        let b = Self::deserialize(that).unwrap();
+
+        a.cmp(&b)
+    }
+
+    fn code(&self) -> ColumnTypeCode {
+        ColumnTypeCode::Bool
+    }
+}
+
+impl ColumnTypeDeserialize for ColumnTypeBool {
+    type Output = bool;
+
+    fn deserialize(buf: &[u8]) -> Result<bool, KVRuntimeError> {
+        ensure!(
+            buf.len() == 1,
+            KVRuntimeError::with_msg(
+                KVRuntimeErrorKind::DataMalformed,
+                format!("invalid buffer size - expected 1, got: {}", buf.len())
+            )
+        );
+
+        match buf[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(KVRuntimeError::with_msg(
+                KVRuntimeErrorKind::DataMalformed,
+                format!("invalid bool value: {value}"),
+            )),
+        }
+    }
+}
+
+impl ColumnTypeSerialize for ColumnTypeBool {
+    type Input<'a> = bool;
+
+    fn serialize(input: Self::Input<'_>) -> Result<Box<[u8]>, KVRuntimeError> {
+        Ok(Box::from([u8::from(input)]))
+    }
+}
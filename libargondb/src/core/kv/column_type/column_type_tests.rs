@@ -0,0 +1,107 @@
+use crate::kv::{
+    KVTableSchema,
+    column_type::{
+        ColumnTypeCode, ColumnTypeF64, ColumnTypeI64, ColumnTypeSerialize, KVColumnTypeUtils,
+    },
+    primary_key::{KVPrimaryKeyComparator, KVPrimaryKeySchema, PrimaryKeyBuilder},
+    schema::{KVColumnRetention, KVColumnSchema},
+};
+
+fn pk_schema() -> KVPrimaryKeySchema {
+    let column = |column_id: u16, column_name: &str, column_type: ColumnTypeCode| KVColumnSchema {
+        column_id,
+        column_name: column_name.into(),
+        column_type,
+        retention: KVColumnRetention::default(),
+        nullable: true,
+        default_value: None,
+    };
+
+    let table_schema = KVTableSchema::build(
+        vec![
+            column(1, "counter", ColumnTypeCode::I64),
+            column(2, "score", ColumnTypeCode::F64),
+        ],
+        vec![1, 2],
+    )
+    .unwrap();
+
+    KVPrimaryKeySchema::from_table_schema(&table_schema)
+}
+
+#[test]
+fn test_numeric_primary_key_order() {
+    let schema = pk_schema();
+
+    let key = |counter: i64, score: f64| {
+        let mut pk_builder = PrimaryKeyBuilder::new(&schema);
+        pk_builder.add_value(&ColumnTypeI64::serialize(counter).unwrap());
+        pk_builder.add_value(&ColumnTypeF64::serialize(score).unwrap());
+
+        (counter, score, pk_builder.build().unwrap())
+    };
+
+    // Little endian bytes of these values are ordered differently than the values
+    let mut keys = vec![
+        key(256, 1.5),
+        key(-1, 0.0),
+        key(1, 2.0),
+        key(i64::MIN, 0.0),
+        key(1, -0.5),
+        key(1, -2.0),
+    ];
+
+    keys.sort_by(|(_, _, this), (_, _, that)| {
+        KVPrimaryKeyComparator::cmp(&schema, this, that).unwrap()
+    });
+
+    let sorted: Vec<_> = keys
+        .into_iter()
+        .map(|(counter, score, _)| (counter, score))
+        .collect();
+
+    assert_eq!(
+        sorted,
+        vec![
+            (i64::MIN, 0.0),
+            (-1, 0.0),
+            (1, -2.0),
+            (1, -0.5),
+            (1, 2.0),
+            (256, 1.5),
+        ]
+    );
+}
+
+#[test]
+fn test_uuid_text_form() {
+    let text = "123e4567-e89b-12d3-a456-426614174000";
+
+    let uuid = KVColumnTypeUtils::uuid_parse(text).unwrap();
+    assert_eq!(KVColumnTypeUtils::uuid_fmt(&uuid), text);
+    assert_eq!(
+        KVColumnTypeUtils::uuid_parse("123e4567e89b12d3a456426614174000"),
+        Some(uuid)
+    );
+
+    assert_eq!(
+        KVColumnTypeUtils::uuid_parse("123e4567-e89b-12d3-a456-42661417400"),
+        None
+    );
+    assert_eq!(
+        KVColumnTypeUtils::uuid_parse("123e4567e-89b-12d3-a456-426614174000"),
+        None
+    );
+    assert_eq!(
+        KVColumnTypeUtils::uuid_parse("123e4567-e89b-12d3-a456-42661417400g"),
+        None
+    );
+    assert_eq!(
+        KVColumnTypeUtils::uuid_parse("+f+f+f+f+f+f+f+f+f+f+f+f+f+f+f+f"),
+        None
+    );
+    assert_eq!(
+        KVColumnTypeUtils::uuid_parse("+23e4567-e89b-12d3-a456-426614174000"),
+        None
+    );
+}
@@ -0,0 +1,53 @@
+use crate::{
+    ensure,
+    kv::{
+        KVRuntimeError, KVRuntimeErrorKind,
+        column_type::{ColumnType, ColumnTypeCode, ColumnTypeDeserialize, ColumnTypeSerialize},
+    },
+};
+
+/**
+ * Ordered by IEEE 754 total order - negative zero sorts before zero, NaNs sort beyond infinities.
+ */
+pub struct ColumnTypeF64;
+
+impl ColumnType for ColumnTypeF64 {
+    fn eq(&self, this: &[u8], that: &[u8]) -> bool {
+        this.eq(that)
+    }
+
+    fn cmp(&self, this: &[u8], that: &[u8]) -> std::cmp::Ordering {
+        let a = Self::deserialize(this).unwrap();
+        let b = Self::deserialize(that).unwrap();
+
+        a.total_cmp(&b)
+    }
+
+    fn code(&self) -> ColumnTypeCode {
+        ColumnTypeCode::F64
+    }
+}
+
+impl ColumnTypeDeserialize for ColumnTypeF64 {
+    type Output = f64;
+
+    fn deserialize(buf: &[u8]) -> Result<f64, KVRuntimeError> {
+        ensure!(
+            buf.len() == 8,
+            KVRuntimeError::with_msg(
+                KVRuntimeErrorKind::DataMalformed,
+                format!("invalid buffer size - expected 8, got: {}", buf.len())
+            )
+        );
+
+        Ok(f64::from_le_bytes(buf[0..8].try_into().unwrap()))
+    }
+}
+
+impl ColumnTypeSerialize for ColumnTypeF64 {
+    type Input<'a> = f64;
+
+    fn serialize(input: Self::Input<'_>) -> Result<Box<[u8]>, KVRuntimeError> {
+        Ok(Box::from(f64::to_le_bytes(input)))
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{
+    ensure,
+    kv::{
+        KVRuntimeError, KVRuntimeErrorKind,
+        column_type::{ColumnType, ColumnTypeCode, ColumnTypeDeserialize, ColumnTypeSerialize},
+    },
+};
+
+pub struct ColumnTypeI32;
+
+impl ColumnType for ColumnTypeI32 {
+    fn eq(&self, this: &[u8], that: &[u8]) -> bool {
+        this.eq(that)
+    }
+
+    fn cmp(&self, this: &[u8], that: &[u8]) -> std::cmp::Ordering {
+        let a = Self::deserialize(this).unwrap();
+        let b = Self::deserialize(that).unwrap();
+
+        a.cmp(&b)
+    }
+
+    fn code(&self) -> ColumnTypeCode {
+        ColumnTypeCode::I32
+    }
+}
+
+impl ColumnTypeDeserialize for ColumnTypeI32 {
+    type Output = i32;
+
+    fn deserialize(buf: &[u8]) -> Result<i32, KVRuntimeError> {
+        ensure!(
+            buf.len() == 4,
+            KVRuntimeError::with_msg(
+                KVRuntimeErrorKind::DataMalformed,
+                format!("invalid buffer size - expected 4, got: {}", buf.len())
+            )
+        );
+
+        Ok(i32::from_le_bytes(buf[0..4].try_into().unwrap()))
+    }
+}
+
+impl ColumnTypeSerialize for ColumnTypeI32 {
+    type Input<'a> = i32;
+
+    fn serialize(input: Self::Input<'_>) -> Result<Box<[u8]>, KVRuntimeError> {
+        Ok(Box::from(i32::to_le_bytes(input)))
+    }
+}
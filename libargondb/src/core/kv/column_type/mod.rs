@@ -1,14 +1,33 @@
+mod bool;
 mod bytes;
+mod f64;
+mod i32;
+mod i64;
 mod text;
+mod timestamp;
 mod u16;
 mod u16_array;
+mod u32;
+mod u64;
+mod uuid;
 
+pub use bool::ColumnTypeBool;
 pub use bytes::ColumnTypeBytes;
+pub use f64::ColumnTypeF64;
+pub use i32::ColumnTypeI32;
+pub use i64::ColumnTypeI64;
 pub use text::ColumnTypeText;
+pub use timestamp::ColumnTypeTimestamp;
 pub use u16::ColumnTypeU16;
 pub use u16_array::ColumnTypeU16Array;
+pub use u32::ColumnTypeU32;
+pub use u64::ColumnTypeU64;
+pub use uuid::ColumnTypeUuid;
 
-use std::cmp::Ordering;
+#[cfg(test)]
+mod column_type_tests;
+
+use std::{cmp::Ordering, fmt::Write};
 
 use crate::kv::{KVRuntimeError, KVRuntimeErrorKind};
 
@@ -39,6 +58,14 @@ pub enum ColumnTypeCode {
     Text = 2,
     U16 = 3,
     U16Array = 4,
+    I32 = 5,
+    I64 = 6,
+    U32 = 7,
+    U64 = 8,
+    F64 = 9,
+    Bool = 10,
+    Timestamp = 11,
+    Uuid = 12,
 }
 
 impl TryFrom<u8> for ColumnTypeCode {
@@ -50,6 +77,14 @@ impl TryFrom<u8> for ColumnTypeCode {
             2 => Ok(ColumnTypeCode::Text),
             3 => Ok(ColumnTypeCode::U16),
             4 => Ok(ColumnTypeCode::U16Array),
+            5 => Ok(ColumnTypeCode::I32),
+            6 => Ok(ColumnTypeCode::I64),
+            7 => Ok(ColumnTypeCode::U32),
+            8 => Ok(ColumnTypeCode::U64),
+            9 => Ok(ColumnTypeCode::F64),
+            10 => Ok(ColumnTypeCode::Bool),
+            11 => Ok(ColumnTypeCode::Timestamp),
+            12 => Ok(ColumnTypeCode::Uuid),
             _ => Err(KVRuntimeError::with_msg(
                 KVRuntimeErrorKind::DataMalformed,
                 format!(
@@ -65,6 +100,14 @@ static COLUMN_TYPE_BYTES: ColumnTypeBytes = ColumnTypeBytes;
 static COLUMN_TYPE_TEXT: ColumnTypeText = ColumnTypeText;
 static COLUMN_TYPE_U16: ColumnTypeU16 = ColumnTypeU16;
 static COLUMN_TYPE_U16ARR: ColumnTypeU16Array = ColumnTypeU16Array;
+static COLUMN_TYPE_I32: ColumnTypeI32 = ColumnTypeI32;
+static COLUMN_TYPE_I64: ColumnTypeI64 = ColumnTypeI64;
+static COLUMN_TYPE_U32: ColumnTypeU32 = ColumnTypeU32;
+static COLUMN_TYPE_U64: ColumnTypeU64 = ColumnTypeU64;
+static COLUMN_TYPE_F64: ColumnTypeF64 = ColumnTypeF64;
+static COLUMN_TYPE_BOOL: ColumnTypeBool = ColumnTypeBool;
+static COLUMN_TYPE_TIMESTAMP: ColumnTypeTimestamp = ColumnTypeTimestamp;
+static COLUMN_TYPE_UUID: ColumnTypeUuid = ColumnTypeUuid;
 
 impl ColumnTypeCode {
     pub fn type_for_code(code: u8) -> Result<&'static dyn ColumnType, KVRuntimeError> {
@@ -73,6 +116,14 @@ impl ColumnTypeCode {
             2 => Ok(&COLUMN_TYPE_TEXT),
             3 => Ok(&COLUMN_TYPE_U16),
             4 => Ok(&COLUMN_TYPE_U16ARR),
+            5 => Ok(&COLUMN_TYPE_I32),
+            6 => Ok(&COLUMN_TYPE_I64),
+            7 => Ok(&COLUMN_TYPE_U32),
+            8 => Ok(&COLUMN_TYPE_U64),
+            9 => Ok(&COLUMN_TYPE_F64),
+            10 => Ok(&COLUMN_TYPE_BOOL),
+            11 => Ok(&COLUMN_TYPE_TIMESTAMP),
+            12 => Ok(&COLUMN_TYPE_UUID),
             _ => Err(KVRuntimeError::with_msg(
                 KVRuntimeErrorKind::DataMalformed,
                 format!(
@@ -97,6 +148,66 @@ impl KVColumnTypeUtils {
             ColumnTypeCode::U16Array => {
                 format!("{:?}", &ColumnTypeU16Array::deserialize(value).unwrap(),)
             }
+            ColumnTypeCode::I32 => format!("{}", &ColumnTypeI32::deserialize(value).unwrap()),
+            ColumnTypeCode::I64 => format!("{}", &ColumnTypeI64::deserialize(value).unwrap()),
+            ColumnTypeCode::U32 => format!("{}", &ColumnTypeU32::deserialize(value).unwrap()),
+            ColumnTypeCode::U64 => format!("{}", &ColumnTypeU64::deserialize(value).unwrap()),
+            ColumnTypeCode::F64 => format!("{}", &ColumnTypeF64::deserialize(value).unwrap()),
+            ColumnTypeCode::Bool => format!("{}", &ColumnTypeBool::deserialize(value).unwrap()),
+            ColumnTypeCode::Timestamp => {
+                format!("{}", &ColumnTypeTimestamp::deserialize(value).unwrap())
+            }
+            ColumnTypeCode::Uuid => {
+                KVColumnTypeUtils::uuid_fmt(&ColumnTypeUuid::deserialize(value).unwrap())
+            }
+        }
+    }
+
+    /** Formats uuid bytes in the hyphenated 8-4-4-4-12 form. */
+    pub fn uuid_fmt(uuid: &[u8; 16]) -> String {
+        let mut formatted = String::with_capacity(36);
+
+        for (idx, byte) in uuid.iter().enumerate() {
+            if matches!(idx, 4 | 6 | 8 | 10) {
+                formatted.push('-');
+            }
+
+            let _ = write!(formatted, "{byte:02x}");
         }
+
+        formatted
+    }
+
+    /** Parses uuid in the hyphenated 8-4-4-4-12 form or as 32 hex digits. */
+    pub fn uuid_parse(text: &str) -> Option<[u8; 16]> {
+        let digits = match text.len() {
+            36 => {
+                let is_hyphenated = text
+                    .char_indices()
+                    .filter(|(_, c)| *c == '-')
+                    .map(|(idx, _)| idx)
+                    .eq([8, 13, 18, 23]);
+                if !is_hyphenated {
+                    return None;
+                }
+
+                text.replace('-', "")
+            }
+            32 => text.to_string(),
+            _ => return None,
+        };
+
+        // Radix parsing alone would also take a sign, as in "+f"
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let mut uuid = [0u8; 16];
+        for (idx, byte) in uuid.iter_mut().enumerate() {
+            let hex = digits.get(2 * idx..2 * idx + 2)?;
+            *byte = u8::from_str_radix(hex, 16).ok()?;
+        }
+
+        Some(uuid)
     }
 }
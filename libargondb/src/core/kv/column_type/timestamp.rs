@@ -0,0 +1,51 @@
+use crate::{
+    ensure,
+    kv::{
+        KVRuntimeError, KVRuntimeErrorKind,
+        column_type::{ColumnType, ColumnTypeCode, ColumnTypeDeserialize, ColumnTypeSerialize},
+    },
+};
+
+/** Milliseconds since the Unix epoch, negative for earlier points in time. */
+pub struct ColumnTypeTimestamp;
+
+impl ColumnType for ColumnTypeTimestamp {
+    fn eq(&self, this: &[u8], that: &[u8]) -> bool {
+        this.eq(that)
+    }
+
+    fn cmp(&self, this: &[u8], that: &[u8]) -> std::cmp::Ordering {
+        let a = Self::deserialize(this).unwrap();
+        let b = Self::deserialize(that).unwrap();
+
+        a.cmp(&b)
+    }
+
+    fn code(&self) -> ColumnTypeCode {
+        ColumnTypeCode::Timestamp
+    }
+}
+
+impl ColumnTypeDeserialize for ColumnTypeTimestamp {
+    type Output = i64;
+
+    fn deserialize(buf: &[u8]) -> Result<i64, KVRuntimeError> {
+        ensure!(
+            buf.len() == 8,
+            KVRuntimeError::with_msg(
+                KVRuntimeErrorKind::DataMalformed,
+                format!("invalid buffer size - expected 8, got: {}", buf.len())
+            )
+        );
+
+        Ok(i64::from_le_bytes(buf[0..8].try_into().unwrap()))
+    }
+}
+
+impl ColumnTypeSerialize for ColumnTypeTimestamp {
+    type Input<'a> = i64;
+
+    fn serialize(input: Self::Input<'_>) -> Result<Box<[u8]>, KVRuntimeError> {
+        Ok(Box::from(i64::to_le_bytes(input)))
+    }
+}
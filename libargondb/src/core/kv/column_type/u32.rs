@@ -0,0 +1,50 @@
+use crate::{
+    ensure,
+    kv::{
+        KVRuntimeError, KVRuntimeErrorKind,
+        column_type::{ColumnType, ColumnTypeCode, ColumnTypeDeserialize, ColumnTypeSerialize},
+    },
+};
+
+pub struct ColumnTypeU32;
+
+impl ColumnType for ColumnTypeU32 {
+    fn eq(&self, this: &[u8], that: &[u8]) -> bool {
+        this.eq(that)
+    }
+
+    fn cmp(&self, this: &[u8], that: &[u8]) -> std::cmp::Ordering {
+        let a = Self::deserialize(this).unwrap();
+        let b = Self::deserialize(that).unwrap();
+
+        a.cmp(&b)
+    }
+
+    fn code(&self) -> ColumnTypeCode {
+        ColumnTypeCode::U32
+    }
+}
+
+impl ColumnTypeDeserialize for ColumnTypeU32 {
+    type Output = u32;
+
+    fn deserialize(buf: &[u8]) -> Result<u32, KVRuntimeError> {
+        ensure!(
+            buf.len() == 4,
+            KVRuntimeError::with_msg(
+                KVRuntimeErrorKind::DataMalformed,
+                format!("invalid buffer size - expected 4, got: {}", buf.len())
+            )
+        );
+
+        Ok(u32::from_le_bytes(buf[0..4].try_into().unwrap()))
+    }
+}
+
+impl ColumnTypeSerialize for ColumnTypeU32 {
+    type Input<'a> = u32;
+
+    fn serialize(input: Self::Input<'_>) -> Result<Box<[u8]>, KVRuntimeError> {
+        Ok(Box::from(u32::to_le_bytes(input)))
+    }
+}
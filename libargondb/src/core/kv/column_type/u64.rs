@@ -0,0 +1,50 @@
+use crate::{
+    ensure,
+    kv::{
+        KVRuntimeError, KVRuntimeErrorKind,
+        column_type::{ColumnType, ColumnTypeCode, ColumnTypeDeserialize, ColumnTypeSerialize},
+    },
+};
+
+pub struct ColumnTypeU64;
+
+impl ColumnType for ColumnTypeU64 {
+    fn eq(&self, this: &[u8], that: &[u8]) -> bool {
+        this.eq(that)
+    }
+
+    fn cmp(&self, this: &[u8], that: &[u8]) -> std::cmp::Ordering {
+        let a = Self::deserialize(this).unwrap();
+        let b = Self::deserialize(that).unwrap();
+
+        a.cmp(&b)
+    }
+
+    fn code(&self) -> ColumnTypeCode {
+        ColumnTypeCode::U64
+    }
+}
+
+impl ColumnTypeDeserialize for ColumnTypeU64 {
+    type Output = u64;
+
+    fn deserialize(buf: &[u8]) -> Result<u64, KVRuntimeError> {
+        ensure!(
+            buf.len() == 8,
+            KVRuntimeError::with_msg(
+                KVRuntimeErrorKind::DataMalformed,
+                format!("invalid buffer size - expected 8, got: {}", buf.len())
+            )
+        );
+
+        Ok(u64::from_le_bytes(buf[0..8].try_into().unwrap()))
+    }
+}
+
+impl ColumnTypeSerialize for ColumnTypeU64 {
+    type Input<'a> = u64;
+
+    fn serialize(input: Self::Input<'_>) -> Result<Box<[u8]>, KVRuntimeError> {
+        Ok(Box::from(u64::to_le_bytes(input)))
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{
+    ensure,
+    kv::{
+        KVRuntimeError, KVRuntimeErrorKind,
+        column_type::{ColumnType, ColumnTypeCode, ColumnTypeDeserialize, ColumnTypeSerialize},
+    },
+};
+
+/** Stored as 16 bytes in RFC 4122 order, so byte order matches order of the text form. */
+pub struct ColumnTypeUuid;
+
+impl ColumnType for ColumnTypeUuid {
+    fn eq(&self, this: &[u8], that: &[u8]) -> bool {
+        this.eq(that)
+    }
+
+    fn cmp(&self, this: &[u8], that: &[u8]) -> std::cmp::Ordering {
+        this.cmp(that)
+    }
+
+    fn code(&self) -> ColumnTypeCode {
+        ColumnTypeCode::Uuid
+    }
+}
+
+impl ColumnTypeDeserialize for ColumnTypeUuid {
+    type Output = [u8; 16];
+
+    fn deserialize(buf: &[u8]) -> Result<[u8; 16], KVRuntimeError> {
+        ensure!(
+            buf.len() == 16,
+            KVRuntimeError::with_msg(
+                KVRuntimeErrorKind::DataMalformed,
+                format!("invalid buffer size - expected 16, got: {}", buf.len())
+            )
+        );
+
+        Ok(buf[0..16].try_into().unwrap())
+    }
+}
+
+impl ColumnTypeSerialize for ColumnTypeUuid {
+    type Input<'a> = [u8; 16];
+
+    fn serialize(input: Self::Input<'_>) -> Result<Box<[u8]>, KVRuntimeError> {
+        Ok(Box::from(input))
+    }
+}
@@ -1,6 +1,10 @@
 use crate::kv::{
     KVRuntimeError,
-    column_type::{ColumnTypeSerialize, ColumnTypeText, ColumnTypeU16, ColumnTypeU16Array},
+    column_type::{
+        ColumnTypeBool, ColumnTypeBytes, ColumnTypeF64, ColumnTypeI32, ColumnTypeI64,
+        ColumnTypeSerialize, ColumnTypeText, ColumnTypeTimestamp, ColumnTypeU16,
+        ColumnTypeU16Array, ColumnTypeU32, ColumnTypeU64, ColumnTypeUuid,
+    },
 };
 
 pub trait KVColumnValue {
@@ -10,6 +14,10 @@ pub trait KVColumnValue {
 pub struct KVColumnValueBuilder;
 
 impl KVColumnValueBuilder {
+    pub fn bytes(value: Vec<u8>) -> Box<dyn KVColumnValue + Send + Sync + 'static> {
+        Box::new(ColumnValueBytes(value))
+    }
+
     pub fn text(value: String) -> Box<dyn KVColumnValue + Send + Sync + 'static> {
         Box::new(ColumnValueText(value))
     }
@@ -21,6 +29,47 @@ impl KVColumnValueBuilder {
     pub fn u16_array(value: Vec<u16>) -> Box<dyn KVColumnValue + Send + Sync + 'static> {
         Box::new(ColumnValueU16Array(value))
     }
+
+    pub fn i32(value: i32) -> Box<dyn KVColumnValue + Send + Sync + 'static> {
+        Box::new(ColumnValueI32(value))
+    }
+
+    pub fn i64(value: i64) -> Box<dyn KVColumnValue + Send + Sync + 'static> {
+        Box::new(ColumnValueI64(value))
+    }
+
+    pub fn u32(value: u32) -> Box<dyn KVColumnValue + Send + Sync + 'static> {
+        Box::new(ColumnValueU32(value))
+    }
+
+    pub fn u64(value: u64) -> Box<dyn KVColumnValue + Send + Sync + 'static> {
+        Box::new(ColumnValueU64(value))
+    }
+
+    pub fn f64(value: f64) -> Box<dyn KVColumnValue + Send + Sync + 'static> {
+        Box::new(ColumnValueF64(value))
+    }
+
+    pub fn bool(value: bool) -> Box<dyn KVColumnValue + Send + Sync + 'static> {
+        Box::new(ColumnValueBool(value))
+    }
+
+    /** Milliseconds since the Unix epoch. */
+    pub fn timestamp(value: i64) -> Box<dyn KVColumnValue + Send + Sync + 'static> {
+        Box::new(ColumnValueTimestamp(value))
+    }
+
+    pub fn uuid(value: [u8; 16]) -> Box<dyn KVColumnValue + Send + Sync + 'static> {
+        Box::new(ColumnValueUuid(value))
+    }
+}
+
+struct ColumnValueBytes(Vec<u8>);
+
+impl KVColumnValue for ColumnValueBytes {
+    fn serialize(&self) -> Result<Box<[u8]>, KVRuntimeError> {
+        ColumnTypeBytes::serialize(&self.0)
+    }
 }
 
 struct ColumnValueText(String);
@@ -46,3 +95,67 @@ impl KVColumnValue for ColumnValueU16Array {
         ColumnTypeU16Array::serialize(&self.0)
     }
 }
+
+struct ColumnValueI32(i32);
+
+impl KVColumnValue for ColumnValueI32 {
+    fn serialize(&self) -> Result<Box<[u8]>, KVRuntimeError> {
+        ColumnTypeI32::serialize(self.0)
+    }
+}
+
+struct ColumnValueI64(i64);
+
+impl KVColumnValue for ColumnValueI64 {
+    fn serialize(&self) -> Result<Box<[u8]>, KVRuntimeError> {
+        ColumnTypeI64::serialize(self.0)
+    }
+}
+
+struct ColumnValueU32(u32);
+
+impl KVColumnValue for ColumnValueU32 {
+    fn serialize(&self) -> Result<Box<[u8]>, KVRuntimeError> {
+        ColumnTypeU32::serialize(self.0)
+    }
+}
+
+struct ColumnValueU64(u64);
+
+impl KVColumnValue for ColumnValueU64 {
+    fn serialize(&self) -> Result<Box<[u8]>, KVRuntimeError> {
+        ColumnTypeU64::serialize(self.0)
+    }
+}
+
+struct ColumnValueF64(f64);
+
+impl KVColumnValue for ColumnValueF64 {
+    fn serialize(&self) -> Result<Box<[u8]>, KVRuntimeError> {
+        ColumnTypeF64::serialize(self.0)
+    }
+}
+
+struct ColumnValueBool(bool);
+
+impl KVColumnValue for ColumnValueBool {
+    fn serialize(&self) -> Result<Box<[u8]>, KVRuntimeError> {
+        ColumnTypeBool::serialize(self.0)
+    }
+}
+
+struct ColumnValueTimestamp(i64);
+
+impl KVColumnValue for ColumnValueTimestamp {
+    fn serialize(&self) -> Result<Box<[u8]>, KVRuntimeError> {
+        ColumnTypeTimestamp::serialize(self.0)
+    }
+}
+
+struct ColumnValueUuid([u8; 16]);
+
+impl KVColumnValue for ColumnValueUuid {
+    fn serialize(&self) -> Result<Box<[u8]>, KVRuntimeError> {
+        ColumnTypeUuid::serialize(self.0)
+    }
+}
@@ -0,0 +1,35 @@
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+
+use crate::kv::{
+    KVRangeTombstone, KVRuntimeError, KVTableId, ObjectId, mutation::StructuredMutation,
+};
+
+/**
+ * Commit log makes mutations durable before they are acknowledged to the client.
+ * Every batch inserted into a memtable is appended to the log first. Log entries of a memtable
+ * can be discarded once the memtable has been flushed into a durable `SSTable`.
+ */
+#[async_trait]
+pub trait KVCommitLog: Debug + Send + Sync {
+    /**
+     * Appends record to the log. Returned future resolves once the record is durable according to
+     * configured sync policy.
+     */
+    async fn append(&self, record: KVCommitLogRecord) -> Result<(), KVRuntimeError>;
+
+    /** Marks all log entries written for given memtable as no longer needed for recovery. */
+    fn release_memtable(&self, memtable_id: ObjectId);
+
+    /** Syncs pending writes and stops the log. Appends issued after close fail. */
+    fn close(&self);
+}
+
+#[derive(Debug, Clone)]
+pub struct KVCommitLogRecord {
+    pub table_id: KVTableId<'static>,
+    pub memtable_id: ObjectId,
+    pub mutations: Vec<StructuredMutation>,
+    pub range_tombstones: Vec<KVRangeTombstone>,
+}
@@ -1,17 +1,51 @@
+use std::time::Duration;
+
 #[derive(Debug, Clone)]
 pub struct KVConfig {
     pub memtable_size: usize,
     pub mutation_max_size: usize,
+    /** Object ids reserved by a single persisted lease. */
+    pub object_id_lease_size: u64,
+    /** Memory of memtables of all tables above which the largest memtable is flushed. */
+    pub write_buffer_size: usize,
+    /** Memtables of a table waiting for flush at which its writes are delayed. */
+    pub memtable_slowdown_count: usize,
+    /** Memtables of a table waiting for flush at which its writes are blocked. */
+    pub memtable_stop_count: usize,
+    /** Level 0 sstables of a table at which its writes are delayed. */
+    pub level0_slowdown_count: usize,
+    /** Level 0 sstables of a table at which its writes are blocked. */
+    pub level0_stop_count: usize,
+    /** Delay of every write batch to a slowed down table. */
+    pub write_slowdown_delay: Duration,
+    /** Longest time a write waits for a blocked table before it's rejected. */
+    pub write_stall_timeout: Duration,
 }
 
 const DEFAULT_MEMTABLE_SIZE: usize = 1 * 1024 * 1024; // 1MB
 const DEFAULT_MUTATION_MAX_SIZE: usize = 16 * 1024; // 16kB
+const DEFAULT_OBJECT_ID_LEASE_SIZE: u64 = 1024;
+const DEFAULT_WRITE_BUFFER_SIZE: usize = 64 * 1024 * 1024; // 64MB
+const DEFAULT_MEMTABLE_SLOWDOWN_COUNT: usize = 3;
+const DEFAULT_MEMTABLE_STOP_COUNT: usize = 5;
+const DEFAULT_LEVEL0_SLOWDOWN_COUNT: usize = 20;
+const DEFAULT_LEVEL0_STOP_COUNT: usize = 36;
+const DEFAULT_WRITE_SLOWDOWN_DELAY: Duration = Duration::from_millis(2);
+const DEFAULT_WRITE_STALL_TIMEOUT: Duration = Duration::from_secs(10);
 
 impl Default for KVConfig {
     fn default() -> Self {
         KVConfig {
             memtable_size: DEFAULT_MEMTABLE_SIZE,
             mutation_max_size: DEFAULT_MUTATION_MAX_SIZE,
+            object_id_lease_size: DEFAULT_OBJECT_ID_LEASE_SIZE,
+            write_buffer_size: DEFAULT_WRITE_BUFFER_SIZE,
+            memtable_slowdown_count: DEFAULT_MEMTABLE_SLOWDOWN_COUNT,
+            memtable_stop_count: DEFAULT_MEMTABLE_STOP_COUNT,
+            level0_slowdown_count: DEFAULT_LEVEL0_SLOWDOWN_COUNT,
+            level0_stop_count: DEFAULT_LEVEL0_STOP_COUNT,
+            write_slowdown_delay: DEFAULT_WRITE_SLOWDOWN_DELAY,
+            write_stall_timeout: DEFAULT_WRITE_STALL_TIMEOUT,
         }
     }
 }
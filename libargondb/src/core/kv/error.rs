@@ -53,6 +53,10 @@ impl KVRuntimeError {
             source: Some(Box::new(source)),
         }
     }
+
+    pub fn kind(&self) -> KVRuntimeErrorKind {
+        self.kind
+    }
 }
 
 impl std::error::Error for KVRuntimeError {
@@ -85,6 +89,8 @@ pub enum KVRuntimeErrorKind {
     IndexOutOfBounds,
     DataMalformed,
     OperationNotAllowed,
+    /** Write rejected as the table stayed blocked until its deadline. */
+    WriteStalled,
 }
 
 impl std::fmt::Display for KVRuntimeErrorKind {
@@ -94,6 +100,7 @@ impl std::fmt::Display for KVRuntimeErrorKind {
             Self::IndexOutOfBounds => write!(f, "IndexOutOfBounds"),
             Self::DataMalformed => write!(f, "DataMalformed"),
             Self::OperationNotAllowed => write!(f, "OperationNotAllowed"),
+            Self::WriteStalled => write!(f, "WriteStalled"),
         }
     }
 }
@@ -3,10 +3,12 @@ use std::sync::Arc;
 
 use crate::{
     kv::{
-        KVRuntimeError, KVRuntimeErrorKind, KVTable, ObjectId,
+        KVObjectIdLeaseStore, KVRuntimeError, KVRuntimeErrorKind, KVTable, ObjectId,
+        commit_log::KVCommitLog,
         config::KVConfig,
         memtable::{KVMemtableFlushRequest, Memtable},
         object_id::ObjectIdGenerator,
+        write_buffer_manager::KVWriteBufferManager,
     },
     utils::rcu::RCU,
 };
@@ -15,14 +17,31 @@ use crate::{
 pub struct KVInstance {
     config: KVConfig,
     object_id_generator: ObjectIdGenerator,
+    commit_log: Arc<dyn KVCommitLog>,
+    write_buffer_manager: KVWriteBufferManager,
     state: RCU<KVInstanceState>,
 }
 
 impl KVInstance {
-    pub fn new(config: KVConfig, initial_state: KVInstanceStateSnapshot) -> Self {
+    pub fn new(
+        config: KVConfig,
+        initial_state: KVInstanceStateSnapshot,
+        commit_log: Arc<dyn KVCommitLog>,
+        object_id_lease_store: Arc<dyn KVObjectIdLeaseStore>,
+    ) -> Self {
+        let object_id_generator = ObjectIdGenerator::new(
+            initial_state.object_id_generator_state,
+            config.object_id_lease_size,
+            object_id_lease_store,
+        );
+
+        let write_buffer_manager = KVWriteBufferManager::new(config.write_buffer_size);
+
         Self {
             config,
-            object_id_generator: ObjectIdGenerator::new(initial_state.object_id_generator_state),
+            object_id_generator,
+            commit_log,
+            write_buffer_manager,
             state: RCU::new(Arc::new(KVInstanceState::Active {
                 memtable_flush_queue: MemtableFlushQueue::new(),
             })),
@@ -44,6 +63,18 @@ impl KVInstance {
         }
     }
 
+    pub fn config(&self) -> &KVConfig {
+        &self.config
+    }
+
+    pub fn commit_log(&self) -> &Arc<dyn KVCommitLog> {
+        &self.commit_log
+    }
+
+    pub fn write_buffer_manager(&self) -> &KVWriteBufferManager {
+        &self.write_buffer_manager
+    }
+
     pub fn get_memtable_flush_queue_iter(&self) -> impl Iterator<Item = KVMemtableFlushRequest> {
         let state = self.state.load();
 
@@ -51,8 +82,8 @@ impl KVInstance {
         Receiver::into_iter(receiver)
     }
 
-    pub fn new_memtable(&self, table: Arc<KVTable>) -> Arc<Memtable> {
-        let object_id = self.object_id_generator.next();
+    pub fn new_memtable(&self, table: Arc<KVTable>) -> Result<Arc<Memtable>, KVRuntimeError> {
+        let object_id = self.object_id_generator.next()?;
         let memtable_size = self.config.memtable_size;
 
         println!(
@@ -61,7 +92,10 @@ impl KVInstance {
             table.table_name,
             table.table_id.as_ref()
         );
-        Arc::new(Memtable::new(object_id, table, memtable_size))
+        let memtable = Arc::new(Memtable::new(object_id, table, memtable_size));
+        self.write_buffer_manager.register_memtable(&memtable);
+
+        Ok(memtable)
     }
 
     pub fn state_snapshot(&self) -> KVInstanceStateSnapshot {
@@ -93,7 +127,7 @@ impl KVInstance {
         });
     }
 
-    pub fn generate_compacted_sstable_id(&self) -> ObjectId {
+    pub fn generate_compacted_sstable_id(&self) -> Result<ObjectId, KVRuntimeError> {
         self.object_id_generator.next()
     }
 }
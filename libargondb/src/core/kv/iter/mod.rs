@@ -3,3 +3,6 @@ mod shadowing_iter;
 
 pub use print_iter::PrintIter;
 pub use shadowing_iter::ShadowingIter;
+
+#[cfg(test)]
+mod shadowing_iter_tests;
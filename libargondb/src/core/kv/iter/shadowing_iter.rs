@@ -1,28 +1,164 @@
-use std::collections::BTreeSet;
+use std::{
+    collections::{BTreeMap, BTreeSet, BinaryHeap},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use async_trait::async_trait;
 
 use crate::kv::{
-    KVScanIterator, KVScanIteratorItem,
-    primary_key::{KVPrimaryKeyComparator, KVPrimaryKeySchema},
+    KVRangeTombstone, KVScanDirection, KVScanIterator, KVScanIteratorItem, KVTableSchema,
+    KVVersionFilter,
+    mutation::{KVMutation, MutationType},
+    primary_key::{KVPrimaryKeyComparator, KVPrimaryKeySchema, PrimaryKeyMarkerComparator},
+    schema::KVColumnRetention,
 };
 
+/**
+ * Hides mutations shadowed by newer ones - versions of a column exceeding version limit, versions
+ * dropped by column retention and puts covered by tombstones. Relies on mutations of a row being
+ * ordered by timestamp descending, with tombstones preceding puts of the same timestamp. Range
+ * tombstones covering a row act as its row tombstone. Mutations of retired columns are always
+ * hidden, so compaction purges them.
+ *
+ * Range tombstones are ordered by the bound rows reach them at in the scan direction, so each
+ * row only checks the next tombstone not reached yet and the newest one reached so far.
+ */
 pub struct ShadowingIter<T: KVScanIterator + Send + Sync + 'static> {
-    current_mask: Option<(Vec<u8>, BTreeSet<u16>)>,
+    current_mask: Option<RowMask>,
     current_mutation: Option<Box<dyn KVScanIteratorItem + Send + Sync + 'static>>,
     finished: bool,
+    retain_tombstones: bool,
+    tombstone_gc_before: u64,
+    versions: KVVersionFilter,
+    retention: BTreeMap<u16, KVColumnRetention>,
+    retired_column_ids: Vec<u16>,
+    direction: KVScanDirection,
+    range_tombstones: Vec<KVRangeTombstone>,
+    /** Index of the first range tombstone not reached by rows yet. */
+    next_range_tombstone: usize,
+    /**
+     * Timestamps and indexes of reached range tombstones, newest on top. Tombstones already left
+     * behind are dropped once they get on top.
+     */
+    reached_range_tombstones: BinaryHeap<(u64, usize)>,
+    /** Reference time for retention TTL, in ms since UNIX epoch. */
+    now: u64,
     inner: T,
     schema: KVPrimaryKeySchema,
 }
 
+struct RowMask {
+    primary_key: Vec<u8>,
+    deleted_at: Option<u64>,
+    columns: BTreeMap<u16, ColumnMask>,
+}
+
+#[derive(Default)]
+struct ColumnMask {
+    deleted_at: Option<u64>,
+    deleted_versions: BTreeSet<u64>,
+    visible_versions: usize,
+}
+
 impl<T: KVScanIterator + Send + Sync + 'static> ShadowingIter<T> {
-    pub async fn new(inner: T, schema: KVPrimaryKeySchema) -> Self {
+    /** Creates iterator for reads - tombstones are consumed and never returned. */
+    pub async fn new(
+        inner: T,
+        schema: &KVTableSchema,
+        versions: KVVersionFilter,
+        direction: KVScanDirection,
+        range_tombstones: Vec<KVRangeTombstone>,
+    ) -> Self {
+        Self::create(
+            inner,
+            schema,
+            false,
+            0,
+            versions,
+            direction,
+            range_tombstones,
+        )
+        .await
+    }
+
+    /**
+     * Creates iterator for compaction - versions kept by column retention and not covered by
+     * tombstones are returned, the number of versions is limited by the retention only.
+     * Tombstones are returned as well, so they keep shadowing puts from sstables not taking part
+     * in the compaction. Tombstones older than `tombstone_gc_before` still shadow puts, but are
+     * dropped - caller guarantees nothing left outside of the compaction is old enough to be
+     * shadowed by them. Range tombstones are never returned, the caller carries them over on its
+     * own.
+     */
+    pub async fn retaining_tombstones(
+        inner: T,
+        schema: &KVTableSchema,
+        tombstone_gc_before: u64,
+        range_tombstones: Vec<KVRangeTombstone>,
+    ) -> Self {
+        Self::create(
+            inner,
+            schema,
+            true,
+            tombstone_gc_before,
+            KVVersionFilter::retained(),
+            KVScanDirection::Forward,
+            range_tombstones,
+        )
+        .await
+    }
+
+    async fn create(
+        inner: T,
+        schema: &KVTableSchema,
+        retain_tombstones: bool,
+        tombstone_gc_before: u64,
+        versions: KVVersionFilter,
+        direction: KVScanDirection,
+        range_tombstones: Vec<KVRangeTombstone>,
+    ) -> Self {
+        let pk_schema = KVPrimaryKeySchema::from_table_schema(schema);
+        let retention = schema
+            .columns
+            .iter()
+            .map(|column| (column.column_id, column.retention))
+            .collect();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+
+        // Tombstones written after the read timestamp are not visible to the read
+        let mut range_tombstones: Vec<_> = range_tombstones
+            .into_iter()
+            .filter(|tombstone| tombstone.timestamp <= versions.read_timestamp)
+            .collect();
+
+        range_tombstones.sort_by(|a, b| match direction {
+            KVScanDirection::Forward => {
+                PrimaryKeyMarkerComparator::cmp_lower_bounds(&pk_schema, &a.from, &b.from).unwrap()
+            }
+            KVScanDirection::Reverse => {
+                PrimaryKeyMarkerComparator::cmp_upper_bounds(&pk_schema, &b.to, &a.to).unwrap()
+            }
+        });
+
         let mut this = Self {
             current_mask: None,
             current_mutation: None,
             finished: false,
+            retain_tombstones,
+            tombstone_gc_before,
+            versions,
+            retention,
+            retired_column_ids: schema.retired_column_ids.clone(),
+            direction,
+            range_tombstones,
+            next_range_tombstone: 0,
+            reached_range_tombstones: BinaryHeap::new(),
+            now,
             inner,
-            schema,
+            schema: pk_schema,
         };
 
         this.fetch_next_mutation().await;
@@ -42,52 +178,159 @@ impl<T: KVScanIterator + Send + Sync + 'static> ShadowingIter<T> {
 
             self.replace_mask_if_necessary(&item);
 
-            if !self.should_be_skipped(&item) {
-                self.add_to_mask(&item);
+            if self.apply_to_mask(item.mutation()) {
                 self.current_mutation = Some(item);
                 break;
             }
         }
     }
 
-    fn should_be_skipped(
-        &self,
-        item: &Box<dyn KVScanIteratorItem + Send + Sync + 'static>,
-    ) -> bool {
-        let Some((_, columns_set)) = &self.current_mask else {
+    /** Updates mask with given mutation. Returns true when mutation should be returned. */
+    fn apply_to_mask(&mut self, mutation: &(dyn KVMutation + Send + Sync)) -> bool {
+        let Some(mask) = &mut self.current_mask else {
             unreachable!()
         };
 
-        columns_set.contains(&item.mutation().column_id())
-    }
+        let mutation_type = mutation.mutation_type();
+        if mutation_type.is_marker() {
+            return true;
+        }
 
-    fn add_to_mask(&mut self, item: &Box<dyn KVScanIteratorItem + Send + Sync + 'static>) {
-        let Some((_, columns_set)) = &mut self.current_mask else {
-            unreachable!()
-        };
+        let timestamp = mutation.timestamp();
+        if timestamp > self.versions.read_timestamp {
+            return false;
+        }
+
+        let retain_tombstone = self.retain_tombstones && timestamp >= self.tombstone_gc_before;
+
+        if mask
+            .deleted_at
+            .is_some_and(|deleted_at| timestamp <= deleted_at)
+        {
+            return false;
+        }
+
+        if mutation_type == MutationType::DeleteRow {
+            mask.deleted_at = Some(timestamp);
+            return retain_tombstone;
+        }
+
+        let column_id = mutation.column_id();
+        if self.retired_column_ids.binary_search(&column_id).is_ok() {
+            return false;
+        }
+
+        let column = mask.columns.entry(column_id).or_default();
+        if column
+            .deleted_at
+            .is_some_and(|deleted_at| timestamp <= deleted_at)
+        {
+            return false;
+        }
+
+        match mutation_type {
+            MutationType::Delete => {
+                column.deleted_at = Some(timestamp);
+                retain_tombstone
+            }
+            MutationType::DeleteVersion => {
+                let is_new = column.deleted_versions.insert(timestamp);
+                is_new && retain_tombstone
+            }
+            _ => {
+                if column.visible_versions >= self.versions.max_versions
+                    || column.deleted_versions.contains(&timestamp)
+                {
+                    return false;
+                }
 
-        columns_set.insert(item.mutation().column_id());
+                let retention = self.retention.get(&column_id).copied().unwrap_or_default();
+                if !retention.is_retained(column.visible_versions, timestamp, self.now) {
+                    return false;
+                }
+
+                column.visible_versions += 1;
+                true
+            }
+        }
     }
 
     fn replace_mask_if_necessary(
         &mut self,
         mutation: &Box<dyn KVScanIteratorItem + Send + Sync + 'static>,
     ) {
-        let Some((mask_pk, ..)) = &self.current_mask else {
+        let Some(mask) = &self.current_mask else {
             self.new_empty_mask(mutation);
             return;
         };
 
-        if !KVPrimaryKeyComparator::eq(&self.schema, mask_pk, mutation.primary_key()).unwrap() {
+        if !KVPrimaryKeyComparator::eq(&self.schema, &mask.primary_key, mutation.primary_key())
+            .unwrap()
+        {
             self.new_empty_mask(mutation);
             return;
         }
     }
 
     fn new_empty_mask(&mut self, item: &Box<dyn KVScanIteratorItem + Send + Sync + 'static>) {
-        let mask_pk = item.primary_key().to_vec();
+        let deleted_at = self.range_tombstones_deleted_at(item.primary_key());
+
+        self.current_mask = Some(RowMask {
+            primary_key: item.primary_key().to_vec(),
+            deleted_at,
+            columns: BTreeMap::new(),
+        });
+    }
+
+    /**
+     * Returns the timestamp of the newest range tombstone covering the key. Keys must come in
+     * the scan direction.
+     */
+    fn range_tombstones_deleted_at(&mut self, key: &[u8]) -> Option<u64> {
+        while let Some(tombstone) = self.range_tombstones.get(self.next_range_tombstone) {
+            if !self.is_range_tombstone_reached(tombstone, key) {
+                break;
+            }
+
+            self.reached_range_tombstones
+                .push((tombstone.timestamp, self.next_range_tombstone));
+            self.next_range_tombstone += 1;
+        }
+
+        while let Some(&(timestamp, idx)) = self.reached_range_tombstones.peek() {
+            if self.is_range_tombstone_left(&self.range_tombstones[idx], key) {
+                self.reached_range_tombstones.pop();
+                continue;
+            }
+
+            return Some(timestamp);
+        }
+
+        None
+    }
 
-        self.current_mask = Some((mask_pk, BTreeSet::new()));
+    fn is_range_tombstone_reached(&self, tombstone: &KVRangeTombstone, key: &[u8]) -> bool {
+        match self.direction {
+            KVScanDirection::Forward => {
+                PrimaryKeyMarkerComparator::is_above_lower_bound(&self.schema, &tombstone.from, key)
+            }
+            KVScanDirection::Reverse => {
+                PrimaryKeyMarkerComparator::is_below_upper_bound(&self.schema, &tombstone.to, key)
+            }
+        }
+        .unwrap()
+    }
+
+    fn is_range_tombstone_left(&self, tombstone: &KVRangeTombstone, key: &[u8]) -> bool {
+        !match self.direction {
+            KVScanDirection::Forward => {
+                PrimaryKeyMarkerComparator::is_below_upper_bound(&self.schema, &tombstone.to, key)
+            }
+            KVScanDirection::Reverse => {
+                PrimaryKeyMarkerComparator::is_above_lower_bound(&self.schema, &tombstone.from, key)
+            }
+        }
+        .unwrap()
     }
 }
 
@@ -0,0 +1,428 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::kv::{
+    KVPrimaryKeyMarker, KVRangeTombstone, KVScanDirection, KVScanIterator, KVTableSchema,
+    KVVersionFilter, ShadowingIter,
+    column_type::{ColumnTypeCode, ColumnTypeSerialize, ColumnTypeText},
+    kv_test_utils::{TestIter, test_primary_key},
+    mutation::{MutationType, StructuredMutation},
+    primary_key::{KVPrimaryKeySchema, PrimaryKeyBuilder},
+    schema::{KVColumnRetention, KVColumnSchema},
+};
+
+fn table_schema(value_retention: KVColumnRetention) -> KVTableSchema {
+    KVTableSchema::build(
+        vec![
+            KVColumnSchema {
+                column_id: 1,
+                column_name: "id".into(),
+                column_type: ColumnTypeCode::Text,
+                retention: KVColumnRetention::default(),
+                nullable: true,
+                default_value: None,
+            },
+            KVColumnSchema {
+                column_id: 2,
+                column_name: "value".into(),
+                column_type: ColumnTypeCode::Text,
+                retention: value_retention,
+                nullable: true,
+                default_value: None,
+            },
+        ],
+        vec![1],
+    )
+    .unwrap()
+}
+
+fn mutation(
+    id: &str,
+    timestamp: u64,
+    column_id: u16,
+    mutation_type: MutationType,
+) -> StructuredMutation {
+    StructuredMutation::try_from(
+        timestamp,
+        column_id,
+        mutation_type,
+        test_primary_key(id),
+        Box::new([]),
+    )
+    .unwrap()
+}
+
+async fn drain(mut iter: impl KVScanIterator) -> Vec<(u64, u16, MutationType)> {
+    let mut result = vec![];
+    while let Some(item) = iter.next_mutation().await {
+        let mutation = item.mutation();
+        result.push((
+            mutation.timestamp(),
+            mutation.column_id(),
+            mutation.mutation_type(),
+        ));
+    }
+
+    result
+}
+
+fn read(
+    schema: &KVTableSchema,
+    mutations: Vec<StructuredMutation>,
+    versions: KVVersionFilter,
+) -> Vec<(u64, u16, MutationType)> {
+    let inner = TestIter::sorted(mutations, KVScanDirection::Forward);
+
+    smol::block_on(async {
+        drain(ShadowingIter::new(inner, schema, versions, KVScanDirection::Forward, vec![]).await)
+            .await
+    })
+}
+
+fn compact(
+    schema: &KVTableSchema,
+    mutations: Vec<StructuredMutation>,
+    tombstone_gc_before: u64,
+) -> Vec<(u64, u16, MutationType)> {
+    let inner = TestIter::sorted(mutations, KVScanDirection::Forward);
+
+    smol::block_on(async {
+        drain(ShadowingIter::retaining_tombstones(inner, schema, tombstone_gc_before, vec![]).await)
+            .await
+    })
+}
+
+#[test]
+fn test_tombstones_shadow_older_puts() {
+    let table_schema = table_schema(KVColumnRetention::default());
+
+    let mutations = vec![
+        // Row "a" - column delete hides older versions, newer put stays visible
+        mutation("a", 10, 2, MutationType::Put),
+        mutation("a", 20, 2, MutationType::Delete),
+        mutation("a", 30, 2, MutationType::Put),
+        // Row "b" - version delete exposes previous version
+        mutation("b", 10, 2, MutationType::Put),
+        mutation("b", 20, 2, MutationType::Put),
+        mutation("b", 20, 2, MutationType::DeleteVersion),
+        // Row "c" - row delete with equal timestamp hides all columns
+        mutation("c", 10, 1, MutationType::Put),
+        mutation("c", 10, 2, MutationType::Put),
+        mutation("c", 10, 0, MutationType::DeleteRow),
+    ];
+
+    assert_eq!(
+        read(&table_schema, mutations.clone(), KVVersionFilter::latest()),
+        vec![(30, 2, MutationType::Put), (10, 2, MutationType::Put)]
+    );
+
+    assert_eq!(
+        compact(&table_schema, mutations.clone(), 0),
+        vec![
+            (30, 2, MutationType::Put),
+            (20, 2, MutationType::Delete),
+            (20, 2, MutationType::DeleteVersion),
+            (10, 2, MutationType::Put),
+            (10, 0, MutationType::DeleteRow),
+        ]
+    );
+
+    // Collected tombstones keep shadowing puts of the compacted data
+    assert_eq!(
+        compact(&table_schema, mutations, 15),
+        vec![
+            (30, 2, MutationType::Put),
+            (20, 2, MutationType::Delete),
+            (20, 2, MutationType::DeleteVersion),
+            (10, 2, MutationType::Put),
+        ]
+    );
+}
+
+#[test]
+fn test_range_tombstones() {
+    let table_schema = table_schema(KVColumnRetention::default());
+
+    let mutations = vec![
+        mutation("a", 10, 2, MutationType::Put),
+        mutation("b", 10, 2, MutationType::Put),
+        mutation("b", 30, 2, MutationType::Put),
+        mutation("c", 10, 2, MutationType::Put),
+        mutation("d", 10, 2, MutationType::Put),
+    ];
+
+    // Deletes rows "b" and "c" written at or before 20
+    let range_tombstones = vec![KVRangeTombstone {
+        from: KVPrimaryKeyMarker::Key(test_primary_key("b")),
+        to: KVPrimaryKeyMarker::Key(test_primary_key("c")),
+        timestamp: 20,
+    }];
+
+    let read = |versions: KVVersionFilter, gc_before: Option<u64>| {
+        let inner = TestIter::sorted(mutations.clone(), KVScanDirection::Forward);
+        let range_tombstones = range_tombstones.clone();
+
+        smol::block_on(async {
+            match gc_before {
+                Some(gc_before) => {
+                    drain(
+                        ShadowingIter::retaining_tombstones(
+                            inner,
+                            &table_schema,
+                            gc_before,
+                            range_tombstones,
+                        )
+                        .await,
+                    )
+                    .await
+                }
+                None => {
+                    drain(
+                        ShadowingIter::new(
+                            inner,
+                            &table_schema,
+                            versions,
+                            KVScanDirection::Forward,
+                            range_tombstones,
+                        )
+                        .await,
+                    )
+                    .await
+                }
+            }
+        })
+    };
+
+    let expected = vec![
+        (10, 2, MutationType::Put),
+        (30, 2, MutationType::Put),
+        (10, 2, MutationType::Put),
+    ];
+    assert_eq!(read(KVVersionFilter::latest(), None), expected);
+    assert_eq!(read(KVVersionFilter::latest(), Some(0)), expected);
+
+    // Reads before the tombstone was written still see covered rows
+    assert_eq!(
+        read(KVVersionFilter::at(15), None),
+        vec![
+            (10, 2, MutationType::Put),
+            (10, 2, MutationType::Put),
+            (10, 2, MutationType::Put),
+            (10, 2, MutationType::Put),
+        ]
+    );
+}
+
+#[test]
+fn test_overlapping_range_tombstones_in_both_directions() {
+    let table_schema = table_schema(KVColumnRetention::default());
+    let schema = KVPrimaryKeySchema::from_table_schema(&table_schema);
+    let key = |id: &str| KVPrimaryKeyMarker::Key(test_primary_key(id));
+    let tombstone = |from, to, timestamp| KVRangeTombstone {
+        from,
+        to,
+        timestamp,
+    };
+
+    let mutations = vec![
+        mutation("a", 10, 2, MutationType::Put),
+        mutation("b", 10, 2, MutationType::Put),
+        mutation("b", 30, 2, MutationType::Put),
+        mutation("c", 10, 2, MutationType::Put),
+        mutation("c", 40, 2, MutationType::Put),
+        mutation("d", 10, 2, MutationType::Put),
+        mutation("e", 10, 2, MutationType::Put),
+        mutation("f", 11, 2, MutationType::Put),
+    ];
+
+    // Nested, overlapping and open ranges, given out of order
+    let range_tombstones = vec![
+        tombstone(key("b"), key("e"), 20),
+        tombstone(
+            KVPrimaryKeyMarker::Prefix {
+                prefix: {
+                    let mut pk_builder = PrimaryKeyBuilder::new(&schema);
+                    pk_builder.add_value(&ColumnTypeText::serialize("d").unwrap());
+                    pk_builder.build_prefix()
+                },
+                inclusive: false,
+            },
+            KVPrimaryKeyMarker::End,
+            5,
+        ),
+        tombstone(key("c"), key("c"), 50),
+        tombstone(KVPrimaryKeyMarker::Start, key("a"), 15),
+    ];
+
+    let read = |direction: KVScanDirection| {
+        let inner = TestIter::sorted(mutations.clone(), direction);
+
+        smol::block_on(async {
+            drain(
+                ShadowingIter::new(
+                    inner,
+                    &table_schema,
+                    KVVersionFilter::latest(),
+                    direction,
+                    range_tombstones.clone(),
+                )
+                .await,
+            )
+            .await
+        })
+    };
+
+    // Only the put of "b" newer than its tombstone and the row after the exclusive prefix bound
+    // of an older tombstone remain
+    assert_eq!(
+        read(KVScanDirection::Forward),
+        vec![(30, 2, MutationType::Put), (11, 2, MutationType::Put)]
+    );
+    assert_eq!(
+        read(KVScanDirection::Reverse),
+        vec![(11, 2, MutationType::Put), (30, 2, MutationType::Put)]
+    );
+}
+
+#[test]
+fn test_version_filter() {
+    let table_schema = table_schema(KVColumnRetention::default());
+
+    let mutations = vec![
+        mutation("a", 10, 2, MutationType::Put),
+        mutation("a", 20, 2, MutationType::Put),
+        mutation("a", 20, 2, MutationType::DeleteVersion),
+        mutation("a", 30, 2, MutationType::Put),
+        mutation("a", 40, 2, MutationType::Put),
+    ];
+
+    assert_eq!(
+        read(&table_schema, mutations.clone(), KVVersionFilter::latest()),
+        vec![(40, 2, MutationType::Put)]
+    );
+
+    assert_eq!(
+        read(&table_schema, mutations.clone(), KVVersionFilter::at(25)),
+        vec![(10, 2, MutationType::Put)]
+    );
+
+    assert_eq!(
+        read(
+            &table_schema,
+            mutations.clone(),
+            KVVersionFilter::latest().with_max_versions(3)
+        ),
+        vec![
+            (40, 2, MutationType::Put),
+            (30, 2, MutationType::Put),
+            (10, 2, MutationType::Put)
+        ]
+    );
+
+    assert_eq!(
+        read(
+            &table_schema,
+            mutations,
+            KVVersionFilter::at(35).with_max_versions(2)
+        ),
+        vec![(30, 2, MutationType::Put), (10, 2, MutationType::Put)]
+    );
+}
+
+#[test]
+fn test_column_retention() {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    let hour_ago = now - 3_600_000;
+
+    let table_schema = table_schema(KVColumnRetention {
+        max_versions: 3,
+        min_versions: 1,
+        ttl: Some(Duration::from_secs(60)),
+    });
+
+    let mutations = vec![
+        // Row "a" - newest version outlives TTL thanks to min versions
+        mutation("a", hour_ago, 2, MutationType::Put),
+        mutation("a", hour_ago + 1, 2, MutationType::Put),
+        // Row "b" - versions over max versions are dropped even when not expired
+        mutation("b", now, 2, MutationType::Put),
+        mutation("b", now - 1, 2, MutationType::Put),
+        mutation("b", now - 2, 2, MutationType::Put),
+        mutation("b", now - 3, 2, MutationType::Put),
+    ];
+
+    let expected = vec![
+        (hour_ago + 1, 2, MutationType::Put),
+        (now, 2, MutationType::Put),
+        (now - 1, 2, MutationType::Put),
+        (now - 2, 2, MutationType::Put),
+    ];
+
+    assert_eq!(
+        read(
+            &table_schema,
+            mutations.clone(),
+            KVVersionFilter::retained()
+        ),
+        expected
+    );
+    assert_eq!(compact(&table_schema, mutations, 0), expected);
+}
+
+#[test]
+fn test_compaction_version_retention() {
+    let mutations = || {
+        (10..14)
+            .map(|timestamp| mutation("a", timestamp, 2, MutationType::Put))
+            .collect::<Vec<_>>()
+    };
+
+    // Versions are limited by retention only, reads pick them with their own version filter
+    let unlimited_schema = table_schema(KVColumnRetention::default());
+    assert_eq!(compact(&unlimited_schema, mutations(), 0).len(), 4);
+
+    let limited_schema = table_schema(KVColumnRetention {
+        max_versions: 2,
+        ..Default::default()
+    });
+    assert_eq!(
+        compact(&limited_schema, mutations(), 0),
+        vec![(13, 2, MutationType::Put), (12, 2, MutationType::Put)]
+    );
+}
+
+#[test]
+fn test_retired_columns() {
+    // Column "value" was dropped and a new column added in its place
+    let mut columns = table_schema(KVColumnRetention::default()).columns;
+    columns[1].column_id = 3;
+    let table_schema = KVTableSchema::build(columns, vec![1])
+        .unwrap()
+        .with_retired_column_ids(vec![2])
+        .unwrap();
+
+    assert_eq!(table_schema.next_column_id(), Some(4));
+    assert!(
+        KVTableSchema::build(table_schema.columns.clone(), vec![1])
+            .unwrap()
+            .with_retired_column_ids(vec![3])
+            .is_err()
+    );
+
+    let mutations = vec![
+        mutation("a", 10, 1, MutationType::Put),
+        mutation("a", 10, 2, MutationType::Put),
+        mutation("a", 12, 2, MutationType::Delete),
+        mutation("a", 11, 3, MutationType::Put),
+    ];
+
+    let expected = vec![(11, 3, MutationType::Put), (10, 1, MutationType::Put)];
+
+    assert_eq!(
+        read(&table_schema, mutations.clone(), KVVersionFilter::latest()),
+        expected
+    );
+    assert_eq!(compact(&table_schema, mutations, 0), expected);
+}
@@ -0,0 +1,278 @@
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+
+use crate::kv::{
+    KVInstance, KVInstanceStateSnapshot, KVObjectIdLeaseStore, KVRangeScan, KVRangeScanResult,
+    KVRangeTombstone, KVRowScan, KVRuntimeError, KVSSTable, KVScanDirection, KVScanIterator,
+    KVScanIteratorItem, KVScannable, KVTable, KVTableId, KVTableName, KVTableOptions,
+    KVTableSchema, ObjectId,
+    column_type::{ColumnTypeCode, ColumnTypeSerialize, ColumnTypeText},
+    commit_log::{KVCommitLog, KVCommitLogRecord},
+    config::KVConfig,
+    mutation::{KVMutation, MutationComparator, MutationType, StructuredMutation},
+    primary_key::{KVPrimaryKeySchema, PrimaryKeyBuilder},
+    schema::{KVColumnRetention, KVColumnSchema},
+};
+
+/** Commit log keeping appended records in memory. */
+#[derive(Debug, Default)]
+pub struct TestCommitLog {
+    pub records: Mutex<Vec<KVCommitLogRecord>>,
+    pub released_memtable_ids: Mutex<Vec<ObjectId>>,
+}
+
+#[async_trait]
+impl KVCommitLog for TestCommitLog {
+    async fn append(&self, record: KVCommitLogRecord) -> Result<(), KVRuntimeError> {
+        self.records.lock().unwrap().push(record);
+        Ok(())
+    }
+
+    fn release_memtable(&self, memtable_id: ObjectId) {
+        self.released_memtable_ids.lock().unwrap().push(memtable_id);
+    }
+
+    fn close(&self) {}
+}
+
+#[derive(Debug, Default)]
+pub struct TestLeaseStore;
+
+impl KVObjectIdLeaseStore for TestLeaseStore {
+    fn persist_lease(&self, _leased_until: u64) -> Result<(), KVRuntimeError> {
+        Ok(())
+    }
+}
+
+pub fn test_instance(config: KVConfig) -> Arc<KVInstance> {
+    test_instance_with_commit_log(config, Arc::new(TestCommitLog::default()))
+}
+
+pub fn test_instance_with_commit_log(
+    config: KVConfig,
+    commit_log: Arc<TestCommitLog>,
+) -> Arc<KVInstance> {
+    Arc::new(KVInstance::new(
+        config,
+        KVInstanceStateSnapshot::new(),
+        commit_log,
+        Arc::new(TestLeaseStore),
+    ))
+}
+
+/** Schema with a text primary key column `id` and a text column `value`. */
+pub fn test_schema() -> KVTableSchema {
+    KVTableSchema::build(
+        vec![
+            KVColumnSchema {
+                column_id: 1,
+                column_name: "id".into(),
+                column_type: ColumnTypeCode::Text,
+                retention: KVColumnRetention::default(),
+                nullable: true,
+                default_value: None,
+            },
+            KVColumnSchema {
+                column_id: 2,
+                column_name: "value".into(),
+                column_type: ColumnTypeCode::Text,
+                retention: KVColumnRetention::default(),
+                nullable: true,
+                default_value: None,
+            },
+        ],
+        vec![1],
+    )
+    .unwrap()
+}
+
+/** Primary key of [`test_schema`] row. */
+pub fn test_primary_key(id: &str) -> Box<[u8]> {
+    let pk_schema = KVPrimaryKeySchema::from_table_schema(&test_schema());
+
+    let mut pk_builder = PrimaryKeyBuilder::new(&pk_schema);
+    pk_builder.add_value(&ColumnTypeText::serialize(id).unwrap());
+    pk_builder.build().unwrap()
+}
+
+/** Put of `value` column of [`test_schema`] row. */
+pub fn test_mutation(id: &str, timestamp: u64, value: &str) -> StructuredMutation {
+    StructuredMutation::try_from(
+        timestamp,
+        2,
+        MutationType::Put,
+        test_primary_key(id),
+        ColumnTypeText::serialize(value).unwrap(),
+    )
+    .unwrap()
+}
+
+/** Opened table of [`test_schema`] holding given sstables. */
+pub fn test_table(instance: Arc<KVInstance>, sstables: Vec<Box<dyn KVSSTable>>) -> Arc<KVTable> {
+    let table = Arc::new(KVTable::create(
+        instance,
+        KVTableId::from_str("test_table").unwrap(),
+        KVTableName::from_str("test_table").unwrap(),
+        test_schema(),
+        KVTableOptions::default(),
+        sstables,
+    ));
+    table.open().unwrap();
+
+    table
+}
+
+/** SSTable stats without any data, scans of it are always empty. */
+#[derive(Debug, Clone)]
+pub struct TestSSTable {
+    pub sstable_id: ObjectId,
+    pub level: u64,
+    pub min_row_key: Box<[u8]>,
+    pub max_row_key: Box<[u8]>,
+    pub min_timestamp: u64,
+    pub max_timestamp: u64,
+    pub size: u64,
+}
+
+impl TestSSTable {
+    /** SSTable of [`test_schema`] rows between given ids. */
+    pub fn new(sstable_id: u64, level: u64, min_id: &str, max_id: &str) -> Self {
+        Self {
+            sstable_id: ObjectId(sstable_id),
+            level,
+            min_row_key: test_primary_key(min_id),
+            max_row_key: test_primary_key(max_id),
+            min_timestamp: 0,
+            max_timestamp: 0,
+            size: 1,
+        }
+    }
+
+    pub fn with_timestamps(mut self, min_timestamp: u64, max_timestamp: u64) -> Self {
+        self.min_timestamp = min_timestamp;
+        self.max_timestamp = max_timestamp;
+        self
+    }
+
+    pub fn with_size(mut self, size: u64) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn boxed(self) -> Box<dyn KVSSTable> {
+        Box::new(self)
+    }
+}
+
+impl Display for TestSSTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TestSSTable(sstable_id={})", self.sstable_id)
+    }
+}
+
+#[async_trait]
+impl KVScannable for TestSSTable {
+    async fn range_scan(&self, _scan: &KVRangeScan) -> Result<KVRangeScanResult, KVRuntimeError> {
+        Ok(KVRangeScanResult::Empty)
+    }
+
+    async fn row_scan(&self, _scan: &KVRowScan) -> Result<KVRangeScanResult, KVRuntimeError> {
+        Ok(KVRangeScanResult::Empty)
+    }
+
+    fn range_tombstones(&self) -> Vec<KVRangeTombstone> {
+        vec![]
+    }
+}
+
+impl KVSSTable for TestSSTable {
+    fn level(&self) -> u64 {
+        self.level
+    }
+
+    fn sstable_id(&self) -> ObjectId {
+        self.sstable_id
+    }
+
+    fn mutation_count(&self) -> u64 {
+        1
+    }
+
+    fn min_row_key(&self) -> &[u8] {
+        &self.min_row_key
+    }
+
+    fn max_row_key(&self) -> &[u8] {
+        &self.max_row_key
+    }
+
+    fn min_timestamp(&self) -> u64 {
+        self.min_timestamp
+    }
+
+    fn max_timestamp(&self) -> u64 {
+        self.max_timestamp
+    }
+
+    fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+/** Scanned item holding the mutation. */
+pub struct TestIterItem(pub StructuredMutation);
+
+impl KVScanIteratorItem for TestIterItem {
+    fn primary_key(&self) -> &[u8] {
+        self.0.primary_key()
+    }
+
+    fn mutation(&self) -> &(dyn KVMutation + Send + Sync) {
+        &self.0
+    }
+}
+
+/** Iterator returning given mutations, in the order they were passed. */
+pub struct TestIter {
+    items: VecDeque<Box<dyn KVScanIteratorItem + Send + Sync>>,
+}
+
+impl TestIter {
+    pub fn new(mutations: Vec<StructuredMutation>) -> Self {
+        Self {
+            items: mutations
+                .into_iter()
+                .map(|mutation| {
+                    Box::new(TestIterItem(mutation)) as Box<dyn KVScanIteratorItem + Send + Sync>
+                })
+                .collect(),
+        }
+    }
+
+    /** Iterator returning given mutations of [`test_schema`] rows in order of the scan direction. */
+    pub fn sorted(mut mutations: Vec<StructuredMutation>, direction: KVScanDirection) -> Self {
+        let pk_schema = KVPrimaryKeySchema::from_table_schema(&test_schema());
+        mutations.sort_by(|a, b| {
+            MutationComparator::cmp_in_direction(&pk_schema, a, b, direction).unwrap()
+        });
+
+        Self::new(mutations)
+    }
+}
+
+#[async_trait]
+impl KVScanIterator for TestIter {
+    async fn next_mutation(&mut self) -> Option<Box<dyn KVScanIteratorItem + Send + Sync>> {
+        self.items.pop_front()
+    }
+
+    fn peek_mutation(&self) -> Option<&Box<dyn KVScanIteratorItem + Send + Sync>> {
+        self.items.front()
+    }
+}
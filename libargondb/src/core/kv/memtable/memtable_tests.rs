@@ -0,0 +1,110 @@
+use smol::block_on;
+
+use crate::kv::{
+    KVColumnFilter, KVPrimaryKeyMarker, KVRangeScan, KVRangeScanResult, KVScanDirection,
+    KVScannable, KVTableSchema, ObjectId,
+    config::KVConfig,
+    kv_test_utils::{test_instance, test_mutation, test_primary_key, test_table},
+    memtable::Memtable,
+};
+
+fn test_memtable() -> Memtable {
+    let memtable = Memtable::new(
+        ObjectId(1),
+        test_table(test_instance(KVConfig::default()), vec![]),
+        1 << 20,
+    );
+
+    // Inserted out of order, skiplist keeps mutations sorted
+    let write = memtable.reserve_write(1024).unwrap();
+    for (id, timestamp) in [("c", 1), ("a", 1), ("b", 2), ("c", 3), ("a", 2), ("b", 1)] {
+        write.insert(&test_mutation(id, timestamp, "value"));
+    }
+    drop(write);
+
+    memtable
+}
+
+fn scan(
+    memtable: &Memtable,
+    from: KVPrimaryKeyMarker,
+    to: KVPrimaryKeyMarker,
+    direction: KVScanDirection,
+) -> Vec<(Box<[u8]>, u64)> {
+    let scan = KVRangeScan::new(
+        KVTableSchema::clone(&memtable.table().table_schema()),
+        from,
+        to,
+        KVColumnFilter::All,
+    )
+    .with_direction(direction);
+
+    let KVRangeScanResult::Iter(mut iter) = block_on(memtable.range_scan(&scan)).unwrap() else {
+        return vec![];
+    };
+
+    let mut mutations = vec![];
+    while let Some(item) = block_on(iter.next_mutation()) {
+        mutations.push((item.primary_key().into(), item.mutation().timestamp()));
+    }
+
+    mutations
+}
+
+fn expected(mutations: &[(&str, u64)]) -> Vec<(Box<[u8]>, u64)> {
+    mutations
+        .iter()
+        .map(|(id, timestamp)| (test_primary_key(id), *timestamp))
+        .collect()
+}
+
+#[test]
+fn test_reverse_scan_returns_rows_in_descending_order() {
+    let memtable = test_memtable();
+
+    assert_eq!(
+        scan(
+            &memtable,
+            KVPrimaryKeyMarker::Start,
+            KVPrimaryKeyMarker::End,
+            KVScanDirection::Forward
+        ),
+        expected(&[("a", 2), ("a", 1), ("b", 2), ("b", 1), ("c", 3), ("c", 1)])
+    );
+
+    // Mutations of a row keep their order, newest first
+    assert_eq!(
+        scan(
+            &memtable,
+            KVPrimaryKeyMarker::Start,
+            KVPrimaryKeyMarker::End,
+            KVScanDirection::Reverse
+        ),
+        expected(&[("c", 3), ("c", 1), ("b", 2), ("b", 1), ("a", 2), ("a", 1)])
+    );
+}
+
+#[test]
+fn test_reverse_scan_respects_bounds() {
+    let memtable = test_memtable();
+
+    assert_eq!(
+        scan(
+            &memtable,
+            KVPrimaryKeyMarker::Key(test_primary_key("a")),
+            KVPrimaryKeyMarker::Key(test_primary_key("b")),
+            KVScanDirection::Reverse
+        ),
+        expected(&[("b", 2), ("b", 1), ("a", 2), ("a", 1)])
+    );
+
+    assert_eq!(
+        scan(
+            &memtable,
+            KVPrimaryKeyMarker::Key(test_primary_key("bb")),
+            KVPrimaryKeyMarker::End,
+            KVScanDirection::Reverse
+        ),
+        expected(&[("c", 3), ("c", 1)])
+    );
+}
@@ -3,19 +3,23 @@ mod flush_request;
 mod lock;
 mod skiplist;
 
+#[cfg(test)]
+mod memtable_tests;
+
 pub use flush_pre_stats::KVFlushPreStats;
 pub use flush_request::KVMemtableFlushRequest;
 
 use crate::kv::{
-    KVColumnFilter, KVPrimaryKeyMarker, KVRangeScanResult, KVRuntimeErrorKind, KVSSTableBuilder,
-    KVScanIterator, KVScanIteratorItem, KVTable,
+    KVColumnFilter, KVPrimaryKeyMarker, KVRangeScanResult, KVRangeTombstone, KVRuntimeErrorKind,
+    KVSSTableBuilder, KVScanDirection, KVScanIterUtils, KVScanIterator, KVScanIteratorItem,
+    KVTable, KVTableSchema,
     error::KVRuntimeError,
     iter::PrintIter,
     memtable::lock::MemtableLock,
     mutation::{KVMutation, MutationComparator, MutationUtils, StructuredMutation},
     object_id::ObjectId,
     primary_key::KVPrimaryKeySchema,
-    scan::{KVRangeScan, KVScannable},
+    scan::{KVRangeScan, KVRowScan, KVScannable},
 };
 use async_trait::async_trait;
 use skiplist::{Entry, Skiplist};
@@ -23,8 +27,8 @@ use std::{
     mem::replace,
     ops::Deref,
     sync::{
-        Arc,
-        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
     },
 };
 
@@ -33,21 +37,28 @@ pub struct Memtable {
     pub object_id: ObjectId,
     table: Arc<KVTable>,
     inner: Skiplist,
+    range_tombstones: RwLock<Vec<KVRangeTombstone>>,
     size_limit: usize,
     size: AtomicUsize,
+    /** Size accounted in the write buffer as being flushed. */
+    flushing_size: AtomicUsize,
+    min_timestamp: AtomicU64,
     lock: MemtableLock,
 }
 
 impl Memtable {
     pub fn new(object_id: ObjectId, table: Arc<KVTable>, size_limit: usize) -> Self {
-        let primary_key_schema = KVPrimaryKeySchema::from_table_schema(&table.table_schema);
+        let primary_key_schema = KVPrimaryKeySchema::from_table_schema(&table.table_schema());
 
         Self {
             object_id,
             table,
             inner: Skiplist::new(primary_key_schema),
+            range_tombstones: RwLock::new(vec![]),
             size_limit,
             size: AtomicUsize::new(0),
+            flushing_size: AtomicUsize::new(0),
+            min_timestamp: AtomicU64::new(u64::MAX),
             lock: MemtableLock::new(),
         }
     }
@@ -60,21 +71,18 @@ impl Memtable {
         self
     }
 
-    pub fn insert_mutation(
-        &self,
-        mutation: &StructuredMutation,
-    ) -> Result<(), MemtableInsertError> {
-        assert!(!MutationUtils::is_marker(mutation));
-
+    /**
+     * Reserves space for a batch of mutations and holds write access until returned guard is
+     * dropped. Memtable cannot be flushed while guard is alive.
+     */
+    pub fn reserve_write(&self, size: usize) -> Result<MemtableWrite<'_>, MemtableInsertError> {
         self.lock
             .obtain_write_access()
             .map_err(|_| MemtableInsertError::ReadOnlyMode)?;
 
-        let mutation_size = mutation.size();
-
         loop {
             let memtable_size = self.size.load(Ordering::Acquire);
-            let new_memtable_size = memtable_size + mutation_size;
+            let new_memtable_size = memtable_size + size;
 
             if new_memtable_size > self.size_limit {
                 self.lock.enable_read_only_mode();
@@ -88,21 +96,22 @@ impl Memtable {
                 Ordering::Acquire,
                 Ordering::Relaxed,
             ) {
-                self.inner.insert(mutation.clone());
+                self.table.instance.write_buffer_manager().reserve(size);
 
-                self.lock.release_write_access();
-
-                #[cfg(debug_assertions)]
-                println!(
-                    "[Memtable id: {}] inserted mutation {}",
-                    self.object_id,
-                    MutationUtils::debug_fmt(&self.table.table_schema, mutation).unwrap()
-                );
-                return Ok(());
+                return Ok(MemtableWrite { memtable: self });
             }
         }
     }
 
+    /** Returns the lowest timestamp of inserted mutations, `u64::MAX` for empty memtable. */
+    pub fn min_timestamp(&self) -> u64 {
+        self.min_timestamp.load(Ordering::Acquire)
+    }
+
+    pub fn size(&self) -> usize {
+        self.size.load(Ordering::Acquire)
+    }
+
     pub fn is_flush_needed(&self) -> bool {
         self.size.load(Ordering::SeqCst) > 0
     }
@@ -115,35 +124,8 @@ impl Memtable {
         self.lock.is_flush_ready()
     }
 
-    fn get_range_iterator<'a>(
-        &'a self,
-        scan: &KVRangeScan,
-    ) -> Result<MemtableScanResultsIterInner<'a>, KVRuntimeError> {
-        let from = scan.from();
-        let to = scan.to();
-
-        match (from, to) {
-            (KVPrimaryKeyMarker::Start, KVPrimaryKeyMarker::End) => {
-                Ok(Box::new(self.inner.range(None, None).into_iter()))
-            }
-            (KVPrimaryKeyMarker::Start, KVPrimaryKeyMarker::Key(pk)) => Ok(Box::new(
-                self.inner.range(None, Some(pk.clone())).into_iter(),
-            )),
-            (KVPrimaryKeyMarker::Key(pk), KVPrimaryKeyMarker::End) => Ok(Box::new(
-                self.inner.range(Some(pk.clone()), None).into_iter(),
-            )),
-            (KVPrimaryKeyMarker::Key(pk_from), KVPrimaryKeyMarker::Key(pk_to)) => Ok(Box::new(
-                self.inner
-                    .range(Some(pk_from.clone()), Some(pk_to.clone()))
-                    .into_iter(),
-            )),
-            _ => {
-                return Err(KVRuntimeError::with_msg(
-                    KVRuntimeErrorKind::DataMalformed,
-                    "invalid scan range",
-                ));
-            }
-        }
+    fn get_range_iterator<'a>(&'a self, scan: &KVRangeScan) -> MemtableScanResultsIterInner<'a> {
+        Box::new(self.inner.range(scan.from().clone(), scan.to().clone()))
     }
 
     pub async fn flush(
@@ -166,7 +148,7 @@ impl Memtable {
 
         let scan = self
             .range_scan(&KVRangeScan::new(
-                self.table.table_schema.clone(),
+                KVTableSchema::clone(&self.table.table_schema()),
                 KVPrimaryKeyMarker::Start,
                 KVPrimaryKeyMarker::End,
                 KVColumnFilter::All,
@@ -184,6 +166,10 @@ impl Memtable {
             sstable_builder.add_mutation(item.mutation()).await.unwrap();
         }
 
+        for tombstone in self.range_tombstones() {
+            sstable_builder.add_range_tombstone(&tombstone).await?;
+        }
+
         Ok(())
     }
 
@@ -194,6 +180,14 @@ impl Memtable {
         );
 
         self.lock.enable_read_only_mode();
+
+        let size = self.size.load(Ordering::Acquire);
+        let flushing_size = self.flushing_size.swap(size, Ordering::AcqRel);
+        self.table
+            .instance
+            .write_buffer_manager()
+            .mark_flushing(size - flushing_size);
+
         self.table.instance.request_memtable_flush(self.clone())?;
 
         Ok(())
@@ -206,6 +200,15 @@ impl Memtable {
     }
 }
 
+impl Drop for Memtable {
+    fn drop(&mut self) {
+        self.table.instance.write_buffer_manager().free(
+            self.size.load(Ordering::Acquire),
+            self.flushing_size.load(Ordering::Acquire),
+        );
+    }
+}
+
 impl std::fmt::Display for Memtable {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Memtable(object_id={})", self.object_id)
@@ -215,28 +218,79 @@ impl std::fmt::Display for Memtable {
 #[async_trait]
 impl KVScannable for Memtable {
     async fn range_scan(&self, scan: &KVRangeScan) -> Result<KVRangeScanResult, KVRuntimeError> {
-        let iter: Box<dyn Iterator<Item = Entry<'_>> + Send + Sync> =
-            self.get_range_iterator(scan)?;
+        let table_schema = self.table.table_schema();
+        let iter: Box<dyn Iterator<Item = Entry<'_>> + Send + Sync> = self.get_range_iterator(scan);
         Ok(KVRangeScanResult::Iter(Box::new(PrintIter::new(
             format!("Memtable id={}", self.object_id),
-            MemtableScanResultsIter::new(iter),
-            self.table.table_schema.clone(),
+            MemtableScanResultsIter::new(
+                iter,
+                scan.columns(),
+                scan.direction(),
+                &KVPrimaryKeySchema::from_table_schema(&table_schema),
+            ),
+            KVTableSchema::clone(&table_schema),
         ))))
     }
 
-    async fn row_scan(&self, primary_key: &[u8]) -> Result<KVRangeScanResult, KVRuntimeError> {
+    async fn row_scan(&self, scan: &KVRowScan) -> Result<KVRangeScanResult, KVRuntimeError> {
+        let table_schema = self.table.table_schema();
         let iter = self.get_range_iterator(&KVRangeScan::new(
-            self.table.table_schema.clone(),
-            KVPrimaryKeyMarker::Key(primary_key.to_vec().into_boxed_slice()),
-            KVPrimaryKeyMarker::Key(primary_key.to_vec().into_boxed_slice()),
-            KVColumnFilter::All,
-        ))?;
+            KVTableSchema::clone(&table_schema),
+            KVPrimaryKeyMarker::Key(scan.primary_key().into()),
+            KVPrimaryKeyMarker::Key(scan.primary_key().into()),
+            scan.columns().clone(),
+        ));
         Ok(KVRangeScanResult::Iter(Box::new(PrintIter::new(
             format!("Memtable id={}", self.object_id),
-            MemtableScanResultsIter::new(iter),
-            self.table.table_schema.clone(),
+            MemtableScanResultsIter::new(
+                iter,
+                scan.columns(),
+                KVScanDirection::Forward,
+                &KVPrimaryKeySchema::from_table_schema(&table_schema),
+            ),
+            KVTableSchema::clone(&table_schema),
         ))))
     }
+
+    fn range_tombstones(&self) -> Vec<KVRangeTombstone> {
+        self.range_tombstones.read().unwrap().clone()
+    }
+}
+
+pub struct MemtableWrite<'a> {
+    memtable: &'a Memtable,
+}
+
+impl MemtableWrite<'_> {
+    pub fn insert(&self, mutation: &StructuredMutation) {
+        assert!(!MutationUtils::is_marker(mutation));
+
+        self.memtable
+            .min_timestamp
+            .fetch_min(mutation.timestamp(), Ordering::AcqRel);
+        self.memtable.inner.insert(mutation.clone());
+
+        #[cfg(debug_assertions)]
+        println!(
+            "[Memtable id: {}] inserted mutation {}",
+            self.memtable.object_id,
+            MutationUtils::debug_fmt(&self.memtable.table.table_schema(), mutation).unwrap()
+        );
+    }
+
+    pub fn insert_range_tombstone(&self, tombstone: &KVRangeTombstone) {
+        self.memtable
+            .range_tombstones
+            .write()
+            .unwrap()
+            .push(tombstone.clone());
+    }
+}
+
+impl Drop for MemtableWrite<'_> {
+    fn drop(&mut self) {
+        self.memtable.lock.release_write_access();
+    }
 }
 
 #[derive(Debug)]
@@ -254,15 +308,34 @@ struct MemtableScanResultsIter {
 }
 
 impl MemtableScanResultsIter {
-    fn new<'a>(iter: MemtableScanResultsIterInner<'a>) -> Self {
+    /**
+     * Scanned mutations are copied out of the skiplist up front. Skiplist links point forward
+     * only, so reverse scans reorder the copied rows instead of walking the skiplist backwards.
+     */
+    fn new(
+        iter: MemtableScanResultsIterInner<'_>,
+        columns: &KVColumnFilter,
+        direction: KVScanDirection,
+        schema: &KVPrimaryKeySchema,
+    ) -> Self {
         let mut mutations: Vec<Box<dyn KVScanIteratorItem + Send + Sync>> = vec![];
 
         for entry in iter {
+            if !columns.matches(MutationUtils::as_dyn(&*entry)) {
+                continue;
+            }
+
             mutations.push(Box::new(MemtableScanResultsIterItem {
                 mutation: entry.deref().clone(),
             }));
         }
 
+        if direction == KVScanDirection::Reverse {
+            let mut rows = KVScanIterUtils::group_rows(schema, mutations);
+            rows.reverse();
+            mutations = rows.into_iter().flatten().collect();
+        }
+
         let mut inner_iter = mutations.into_iter();
         let current_item = inner_iter.next();
 
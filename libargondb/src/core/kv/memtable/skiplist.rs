@@ -19,7 +19,7 @@ use crossbeam::{
 
 use crate::kv::{
     mutation::{KVMutation, MutationComparator, StructuredMutation},
-    primary_key::{KVPrimaryKeyComparator, KVPrimaryKeySchema},
+    primary_key::{KVPrimaryKeyMarker, KVPrimaryKeySchema, PrimaryKeyMarkerComparator},
 };
 
 const HEIGHT_BITS: usize = 5;
@@ -49,8 +49,8 @@ pub struct Entry<'a> {
 
 pub struct Iter<'a> {
     parent: &'a Skiplist,
-    from: Option<Box<[u8]>>,
-    to: Option<Box<[u8]>>,
+    from: KVPrimaryKeyMarker,
+    to: KVPrimaryKeyMarker,
     current: Option<Entry<'a>>,
 }
 
@@ -195,7 +195,7 @@ impl Skiplist {
         entry.node.ref_count_decrement();
     }
 
-    pub fn range(&self, from: Option<Box<[u8]>>, to: Option<Box<[u8]>>) -> Iter<'_> {
+    pub fn range(&self, from: KVPrimaryKeyMarker, to: KVPrimaryKeyMarker) -> Iter<'_> {
         Iter {
             parent: self,
             from,
@@ -411,7 +411,7 @@ impl Skiplist {
 
     fn search_bound_lower_pk<'a>(
         &'a self,
-        bound: Bound<&[u8]>,
+        from: &KVPrimaryKeyMarker,
         guard: &'a Guard,
     ) -> Option<NodeRef<'a>> {
         'retry: loop {
@@ -438,19 +438,12 @@ impl Skiplist {
                         }
                     }
 
-                    let is_above = match bound {
-                        Bound::Unbounded => true,
-                        Bound::Excluded(key) => {
-                            KVPrimaryKeyComparator::cmp(&self.schema, &node.data.primary_key(), key)
-                                .unwrap()
-                                .is_gt()
-                        }
-                        Bound::Included(key) => {
-                            KVPrimaryKeyComparator::cmp(&self.schema, &node.data.primary_key(), key)
-                                .unwrap()
-                                .is_ge()
-                        }
-                    };
+                    let is_above = PrimaryKeyMarkerComparator::is_above_lower_bound(
+                        &self.schema,
+                        from,
+                        node.data.primary_key(),
+                    )
+                    .unwrap();
                     if is_above {
                         result = Some(node);
                         break;
@@ -573,12 +566,7 @@ impl<'a> Iterator for Iter<'a> {
                     self.parent
                         .next_node(entry.node.as_tower(), &entry.node.data, guard)
                 }
-                None => match &self.from {
-                    Some(key) => self
-                        .parent
-                        .search_bound_lower_pk(Bound::Included(key), guard),
-                    None => self.parent.search_bound_lower_pk(Bound::Unbounded, guard),
-                },
+                None => self.parent.search_bound_lower_pk(&self.from, guard),
             };
 
             if let Some(node_ref) = next_node {
@@ -596,19 +584,15 @@ impl<'a> Iterator for Iter<'a> {
 
         self.current = next;
 
-        if let Some(entry) = &self.current {
-            if let Some(end_bound) = &self.to {
-                if KVPrimaryKeyComparator::cmp(
-                    &self.parent.schema,
-                    entry.node.data.primary_key(),
-                    end_bound,
-                )
-                .unwrap()
-                .is_ge()
-                {
-                    self.current = None;
-                }
-            }
+        if let Some(entry) = &self.current
+            && !PrimaryKeyMarkerComparator::is_below_upper_bound(
+                &self.parent.schema,
+                &self.to,
+                entry.node.data.primary_key(),
+            )
+            .unwrap()
+        {
+            self.current = None;
         }
 
         self.current.clone()
@@ -1,5 +1,6 @@
 pub mod column_type;
 mod column_value;
+pub mod commit_log;
 pub mod config;
 mod error;
 mod instance;
@@ -9,12 +10,15 @@ pub mod memtable;
 pub mod mutation;
 mod object_id;
 pub mod primary_key;
+mod range_tombstone;
 mod row;
+mod row_filter;
 mod scan;
 mod scan_iter;
 pub mod schema;
 mod sstable;
 mod table;
+mod write_buffer_manager;
 
 pub use sstable::{KVSSTableBuilder, KVSSTableDataBlockIter, KVSSTableReader};
 
@@ -28,25 +32,52 @@ pub use iter::PrintIter;
 pub use iter::ShadowingIter;
 pub use limits::KVLimits;
 pub use memtable::KVFlushPreStats;
+pub use object_id::KVObjectIdLeaseStore;
 pub use object_id::ObjectId;
 pub use object_id::ObjectIdGenerator;
 pub use primary_key::KVPrimaryKeyMarker;
+pub use range_tombstone::KVRangeTombstone;
 pub use row::KVRow;
+pub use row_filter::KVCompareOp;
+pub use row_filter::KVRowFilter;
 pub use scan::KVColumnFilter;
 pub use scan::KVRangeScan;
 pub use scan::KVRangeScanResult;
 pub use scan::KVRowScan;
+pub use scan::KVScanDirection;
 pub use scan::KVScanIterator;
 pub use scan::KVScanIteratorItem;
 pub use scan::KVScannable;
+pub use scan::KVVersionFilter;
 pub use scan_iter::KVMergeScanIter;
 pub use scan_iter::KVRowIter;
+pub(crate) use scan_iter::KVScanIterUtils;
 pub use schema::KVTableSchema;
 pub use sstable::KVSSTable;
 pub use sstable::KVSSTableBlockPtr;
+pub use table::KVAlterSchemaError;
+pub use table::KVCompactionOptions;
 pub use table::KVTable;
 pub use table::KVTableId;
 pub use table::KVTableIdConversionError;
 pub use table::KVTableName;
 pub use table::KVTableNameConversionError;
+pub use table::KVTableOptions;
 pub use table::KVTableState;
+pub use table::KVTableStorageGuard;
+pub use table::KVWriteStall;
+pub use write_buffer_manager::KVWriteBufferManager;
+pub use write_buffer_manager::KVWriteBufferStats;
+
+#[cfg(test)]
+pub(crate) mod kv_test_utils;
+#[cfg(test)]
+mod primary_key_tests;
+#[cfg(test)]
+mod row_filter_tests;
+#[cfg(test)]
+mod row_tests;
+#[cfg(test)]
+mod scan_iter_tests;
+#[cfg(test)]
+mod write_buffer_manager_tests;
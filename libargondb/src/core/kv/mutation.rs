@@ -1,5 +1,5 @@
 use crate::kv::{
-    KVTableSchema,
+    KVScanDirection, KVTableSchema,
     column_type::KVColumnTypeUtils,
     error::KVRuntimeError,
     primary_key::{KVPrimaryKeyComparator, KVPrimaryKeySchema, KVPrimaryKeyUtils},
@@ -23,12 +23,20 @@ pub trait KVMutation {
     fn value(&self) -> &[u8];
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, NoUninit, CheckedBitPattern)]
+/**
+ * Tombstones shadow puts of the same row with timestamp lower or equal to their own:
+ * - `Delete` removes all versions of a single column,
+ * - `DeleteVersion` removes only the version of a column written at exactly the same timestamp,
+ * - `DeleteRow` removes all columns of a row, its column id is always 0.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, NoUninit, CheckedBitPattern)]
 #[repr(u8)]
 pub enum MutationType {
     Start = 1,
     Put = 2,
     Delete = 4,
+    DeleteVersion = 8,
+    DeleteRow = 16,
     End = 128,
 }
 
@@ -40,6 +48,8 @@ impl TryFrom<u8> for MutationType {
             1 => Ok(MutationType::Start),
             2 => Ok(MutationType::Put),
             4 => Ok(MutationType::Delete),
+            8 => Ok(MutationType::DeleteVersion),
+            16 => Ok(MutationType::DeleteRow),
             128 => Ok(MutationType::End),
             _ => Err(()),
         }
@@ -52,6 +62,8 @@ impl std::fmt::Display for MutationType {
             Self::Start => write!(f, "Start"),
             Self::Put => write!(f, "Put"),
             Self::Delete => write!(f, "Delete"),
+            Self::DeleteVersion => write!(f, "DeleteVersion"),
+            Self::DeleteRow => write!(f, "DeleteRow"),
             Self::End => write!(f, "End"),
         }
     }
@@ -65,15 +77,25 @@ impl MutationType {
             _ => false,
         }
     }
+
+    pub fn is_tombstone(&self) -> bool {
+        matches!(
+            *self,
+            MutationType::Delete | MutationType::DeleteVersion | MutationType::DeleteRow
+        )
+    }
+}
+
+/** Types with higher discriminant go first, so tombstones precede puts with the same timestamp. */
+impl Ord for MutationType {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (*other as u8).cmp(&(*self as u8))
+    }
 }
 
 impl PartialOrd for MutationType {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match (self.clone() as u8).cmp(&(other.clone() as u8)) {
-            Ordering::Greater => Some(Ordering::Less),
-            Ordering::Equal => Some(Ordering::Equal),
-            Ordering::Less => Some(Ordering::Greater),
-        }
+        Some(self.cmp(other))
     }
 }
 
@@ -120,6 +142,23 @@ impl MutationComparator {
         Ok(this.mutation_type().cmp(&that.mutation_type()))
     }
 
+    /** Orders rows by primary key in given direction, mutations of a single row as `cmp` does. */
+    pub fn cmp_in_direction<T: KVMutation + ?Sized, U: KVMutation + ?Sized>(
+        schema: &KVPrimaryKeySchema,
+        this: &T,
+        that: &U,
+        direction: KVScanDirection,
+    ) -> Result<Ordering, KVRuntimeError> {
+        if direction == KVScanDirection::Reverse {
+            match KVPrimaryKeyComparator::cmp(schema, this.primary_key(), that.primary_key())? {
+                Ordering::Equal => {}
+                ord => return Ok(ord.reverse()),
+            }
+        }
+
+        Self::cmp(schema, this, that)
+    }
+
     pub fn eq<T: KVMutation + ?Sized, U: KVMutation + ?Sized>(
         schema: &KVPrimaryKeySchema,
         this: &T,
@@ -186,6 +225,16 @@ impl StructuredMutation {
         Self::try_from(0, 0, MutationType::End, primary_key, Box::new([]))
     }
 
+    pub fn delete_row(timestamp: u64, primary_key: Box<[u8]>) -> Result<Self, MutationError> {
+        Self::try_from(
+            timestamp,
+            0,
+            MutationType::DeleteRow,
+            primary_key,
+            Box::new([]),
+        )
+    }
+
     pub fn size(&self) -> usize {
         const CONSTANT_SIZE: usize = 8 + 2 + 1;
 
@@ -288,16 +337,36 @@ impl MutationUtils {
     }
 
     pub fn debug_fmt(schema: &KVTableSchema, mutation: &dyn KVMutation) -> Result<String, ()> {
-        let column = schema.lookup_by_column_id(mutation.column_id()).ok_or(())?;
         let primary_key = KVPrimaryKeyUtils::debug_fmt(schema, mutation.primary_key())?;
 
+        let (column_name, value) = if mutation.mutation_type() == MutationType::DeleteRow {
+            ("*".to_string(), "-".to_string())
+        } else {
+            // Column may be retired or added after the schema was captured
+            let column = schema.lookup_by_column_id(mutation.column_id());
+            let value = if mutation.mutation_type().is_tombstone() {
+                "-".to_string()
+            } else if let Some(column) = column {
+                KVColumnTypeUtils::debug_fmt(column.column_type, mutation.value())
+            } else {
+                format!("{:?}", mutation.value())
+            };
+
+            let column_name = column.map_or_else(
+                || format!("#{}", mutation.column_id()),
+                |column| column.column_name.clone(),
+            );
+
+            (column_name, value)
+        };
+
         let out = format!(
             "KVMutation(primary_key={}, ts={}, column={}, type={}, value={})",
             primary_key,
             mutation.timestamp(),
-            column.column_name,
+            column_name,
             mutation.mutation_type(),
-            KVColumnTypeUtils::debug_fmt(column.column_type, mutation.value())
+            value
         );
 
         Ok(out)
@@ -313,7 +382,7 @@ mod mutation_comparator_tests {
         column_type::{ColumnTypeCode, ColumnTypeSerialize, ColumnTypeText},
         mutation::{MutationComparator, MutationType, StructuredMutation},
         primary_key::{KVPrimaryKeySchema, PrimaryKeyBuilder},
-        schema::KVColumnSchema,
+        schema::{KVColumnRetention, KVColumnSchema},
     };
 
     #[test]
@@ -323,6 +392,9 @@ mod mutation_comparator_tests {
                 column_id: 1,
                 column_name: "test_col".into(),
                 column_type: ColumnTypeCode::Text,
+                retention: KVColumnRetention::default(),
+                nullable: true,
+                default_value: None,
             }],
             vec![1],
         )
@@ -331,11 +403,11 @@ mod mutation_comparator_tests {
 
         let mut pk_builder = PrimaryKeyBuilder::new(&pk_schema);
         pk_builder.add_value(&ColumnTypeText::serialize("abcd").unwrap());
-        let primary_key = pk_builder.build();
+        let primary_key = pk_builder.build().unwrap();
 
         let mut pk_builder = PrimaryKeyBuilder::new(&pk_schema);
         pk_builder.add_value(&ColumnTypeText::serialize("defg").unwrap());
-        let primary_key_2 = pk_builder.build();
+        let primary_key_2 = pk_builder.build().unwrap();
 
         let value: Box<[u8]> = Box::from("abcd".as_bytes());
         let mutation = StructuredMutation::try_from(
@@ -1,5 +1,10 @@
 mod object_id;
 mod object_id_generator;
+mod object_id_lease;
 
 pub use object_id::ObjectId;
 pub use object_id_generator::ObjectIdGenerator;
+pub use object_id_lease::KVObjectIdLeaseStore;
+
+#[cfg(test)]
+mod object_id_generator_tests;
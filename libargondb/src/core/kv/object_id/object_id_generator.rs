@@ -1,26 +1,54 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 
-use super::ObjectId;
+use super::{KVObjectIdLeaseStore, ObjectId};
+use crate::kv::KVRuntimeError;
 
 #[derive(Debug)]
 pub struct ObjectIdGenerator {
-    next_id: AtomicU64,
+    state: Mutex<ObjectIdGeneratorState>,
+    lease_size: u64,
+    lease_store: Arc<dyn KVObjectIdLeaseStore>,
+}
+
+#[derive(Debug)]
+struct ObjectIdGeneratorState {
+    next_id: u64,
+    leased_until: u64,
 }
 
 impl ObjectIdGenerator {
-    pub fn new(state: u64) -> Self {
+    pub fn new(state: u64, lease_size: u64, lease_store: Arc<dyn KVObjectIdLeaseStore>) -> Self {
         Self {
-            next_id: AtomicU64::new(state),
+            state: Mutex::new(ObjectIdGeneratorState {
+                next_id: state,
+                // Nothing is leased until the first id is requested
+                leased_until: state,
+            }),
+            lease_size: lease_size.max(1),
+            lease_store,
         }
     }
 
-    pub fn next(&self) -> ObjectId {
-        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+    /** Fails when a new lease can't be persisted, no id is handed out then. */
+    pub fn next(&self) -> Result<ObjectId, KVRuntimeError> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.next_id >= state.leased_until {
+            let leased_until = state.next_id + self.lease_size;
+
+            // Handing out an id which isn't durably leased could overwrite data after a crash
+            self.lease_store.persist_lease(leased_until)?;
+
+            state.leased_until = leased_until;
+        }
+
+        let id = state.next_id;
+        state.next_id += 1;
 
-        ObjectId(id)
+        Ok(ObjectId(id))
     }
 
     pub fn state(&self) -> u64 {
-        self.next_id.load(Ordering::SeqCst)
+        self.state.lock().unwrap().next_id
     }
 }
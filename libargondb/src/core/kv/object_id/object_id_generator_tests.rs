@@ -0,0 +1,60 @@
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicBool, Ordering},
+};
+
+use crate::kv::{
+    KVObjectIdLeaseStore, KVRuntimeError, KVRuntimeErrorKind, ObjectId, ObjectIdGenerator,
+};
+
+#[derive(Debug, Default)]
+struct TestLeaseStore {
+    leases: Mutex<Vec<u64>>,
+    failing: AtomicBool,
+}
+
+impl KVObjectIdLeaseStore for TestLeaseStore {
+    fn persist_lease(&self, leased_until: u64) -> Result<(), KVRuntimeError> {
+        if self.failing.load(Ordering::Acquire) {
+            return Err(KVRuntimeError::with_msg(
+                KVRuntimeErrorKind::OperationFailure,
+                "lease persist failed",
+            ));
+        }
+
+        self.leases.lock().unwrap().push(leased_until);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_ids_are_leased_before_use() {
+    let lease_store = Arc::new(TestLeaseStore::default());
+    let generator = ObjectIdGenerator::new(10, 4, lease_store.clone());
+
+    assert!(lease_store.leases.lock().unwrap().is_empty());
+
+    let ids: Vec<ObjectId> = (0..5).map(|_| generator.next().unwrap()).collect();
+
+    assert_eq!(ids, (10..15).map(ObjectId).collect::<Vec<_>>());
+    assert_eq!(*lease_store.leases.lock().unwrap(), vec![14, 18]);
+    assert_eq!(generator.state(), 15);
+}
+
+#[test]
+fn test_failed_lease_hands_out_no_id() {
+    let lease_store = Arc::new(TestLeaseStore::default());
+    let generator = ObjectIdGenerator::new(10, 2, lease_store.clone());
+
+    assert_eq!(generator.next().unwrap(), ObjectId(10));
+    assert_eq!(generator.next().unwrap(), ObjectId(11));
+
+    lease_store.failing.store(true, Ordering::Release);
+    assert!(generator.next().is_err());
+    assert_eq!(generator.state(), 12);
+
+    // Next lease starts where the failed one would have
+    lease_store.failing.store(false, Ordering::Release);
+    assert_eq!(generator.next().unwrap(), ObjectId(12));
+    assert_eq!(*lease_store.leases.lock().unwrap(), vec![12, 14]);
+}
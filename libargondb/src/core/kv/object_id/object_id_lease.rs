@@ -0,0 +1,12 @@
+use std::fmt::Debug;
+
+use crate::kv::KVRuntimeError;
+
+/**
+ * Durable storage of object id leases. Ids are handed out only from a persisted lease, so ids
+ * used before a crash are never handed out again after restart.
+ */
+pub trait KVObjectIdLeaseStore: Debug + Send + Sync {
+    /** Persists that ids below `leased_until` may be in use. Returns once the lease is durable. */
+    fn persist_lease(&self, leased_until: u64) -> Result<(), KVRuntimeError>;
+}
@@ -131,6 +131,51 @@ impl KVPrimaryKeyComparator {
         Ok(Ordering::Equal)
     }
 
+    /**
+     * Compares the leading columns of a key with a prefix built by `build_prefix`. Keys
+     * sharing all prefix columns compare as equal.
+     */
+    pub fn cmp_prefix(
+        schema: &KVPrimaryKeySchema,
+        key: &[u8],
+        prefix: &[u8],
+    ) -> Result<Ordering, KVRuntimeError> {
+        let prefix_count = prefix[0] as usize;
+        ensure!(
+            prefix_count <= schema.column_count() as usize,
+            KVRuntimeError::with_msg(
+                KVRuntimeErrorKind::DataMalformed,
+                format!("primary key prefix has {} columns", prefix_count)
+            )
+        );
+
+        let mut key_view = PrimaryKeyView::construct(schema, key)
+            .map_err(|e| KVRuntimeError::with_source(KVRuntimeErrorKind::DataMalformed, e))?;
+
+        let mut value_ptr = 1 + 2 * prefix_count;
+        for idx in 0..prefix_count {
+            let Some((column_type, key_value)) = key_view.next_column()? else {
+                return Err(KVRuntimeError::with_msg(
+                    KVRuntimeErrorKind::DataMalformed,
+                    "primary key shorter than prefix",
+                ));
+            };
+
+            let size_ptr = 1 + 2 * idx;
+            let value_size =
+                u16::from_le_bytes(prefix[size_ptr..(size_ptr + 2)].try_into().unwrap()) as usize;
+            let prefix_value = &prefix[value_ptr..(value_ptr + value_size)];
+            value_ptr += value_size;
+
+            match column_type.cmp(key_value, prefix_value) {
+                Ordering::Equal => {}
+                order => return Ok(order),
+            }
+        }
+
+        Ok(Ordering::Equal)
+    }
+
     pub fn eq(
         schema: &KVPrimaryKeySchema,
         this: &[u8],
@@ -190,66 +235,223 @@ impl<'a> PrimaryKeyBuilder<'a> {
         self.data.extend_from_slice(value);
     }
 
-    pub fn build(self) -> Box<[u8]> {
-        self.data.into_boxed_slice()
+    /** Fails unless values of all primary key columns were added. */
+    pub fn build(self) -> Result<Box<[u8]>, KVRuntimeError> {
+        ensure!(
+            self.column_idx == self.schema.column_count(),
+            KVRuntimeError::with_msg(
+                KVRuntimeErrorKind::OperationNotAllowed,
+                format!(
+                    "primary key has {} of {} column values",
+                    self.column_idx,
+                    self.schema.column_count()
+                )
+            )
+        );
+
+        Ok(self.data.into_boxed_slice())
+    }
+
+    /**
+     * Builds a key prefix from the leading columns added so far, for use in
+     * `KVPrimaryKeyMarker::Prefix`. Layout: column count, value sizes, values.
+     */
+    pub fn build_prefix(self) -> Box<[u8]> {
+        let column_count = self.schema.column_count() as usize;
+        let prefix_count = self.column_idx as usize;
+
+        let mut prefix =
+            Vec::with_capacity(1 + self.data.len() - 2 * (column_count - prefix_count));
+        prefix.push(self.column_idx);
+        prefix.extend_from_slice(&self.data[..(2 * prefix_count)]);
+        prefix.extend_from_slice(&self.data[(2 * column_count)..]);
+
+        prefix.into_boxed_slice()
     }
 }
 
+/**
+ * Bound of a range scan. `Key` is an inclusive bound on a full primary key, `Prefix` a bound
+ * on the leading key columns built by `PrimaryKeyBuilder::build_prefix`. An inclusive prefix
+ * bound includes every key sharing the prefix, an exclusive one none of them, which makes a
+ * prefix scan `Prefix(p, inclusive) ..= Prefix(p, inclusive)`.
+ */
 #[derive(Debug, Clone)]
 pub enum KVPrimaryKeyMarker {
     Start,
     End,
     Key(Box<[u8]>),
+    Prefix { prefix: Box<[u8]>, inclusive: bool },
 }
 
 pub struct PrimaryKeyMarkerComparator;
 
 impl PrimaryKeyMarkerComparator {
-    pub fn cmp(
+    /**
+     * Compares a marker with a key. A prefix marker is equal to every key sharing the prefix,
+     * regardless of whether it is inclusive.
+     */
+    pub fn cmp_with_key(
         schema: &KVPrimaryKeySchema,
         this: &KVPrimaryKeyMarker,
-        that: &KVPrimaryKeyMarker,
+        that: &[u8],
     ) -> Result<Ordering, KVRuntimeError> {
-        if let KVPrimaryKeyMarker::Start = this {
-            if let KVPrimaryKeyMarker::Start = that {
-                return Ok(Ordering::Equal);
-            } else {
-                return Ok(Ordering::Less);
+        match this {
+            KVPrimaryKeyMarker::Start => Ok(Ordering::Less),
+            KVPrimaryKeyMarker::End => Ok(Ordering::Greater),
+            KVPrimaryKeyMarker::Key(this) => KVPrimaryKeyComparator::cmp(schema, this, that),
+            KVPrimaryKeyMarker::Prefix { prefix, .. } => {
+                KVPrimaryKeyComparator::cmp_prefix(schema, that, prefix).map(Ordering::reverse)
             }
         }
+    }
+
+    /** Whether the key satisfies `from` used as the lower bound of a range. */
+    pub fn is_above_lower_bound(
+        schema: &KVPrimaryKeySchema,
+        from: &KVPrimaryKeyMarker,
+        key: &[u8],
+    ) -> Result<bool, KVRuntimeError> {
+        let order = Self::cmp_with_key(schema, from, key)?;
+
+        Ok(match from {
+            KVPrimaryKeyMarker::Prefix {
+                inclusive: false, ..
+            } => order == Ordering::Less,
+            _ => order != Ordering::Greater,
+        })
+    }
+
+    /** Whether the key satisfies `to` used as the upper bound of a range. */
+    pub fn is_below_upper_bound(
+        schema: &KVPrimaryKeySchema,
+        to: &KVPrimaryKeyMarker,
+        key: &[u8],
+    ) -> Result<bool, KVRuntimeError> {
+        let order = Self::cmp_with_key(schema, to, key)?;
+
+        Ok(match to {
+            KVPrimaryKeyMarker::Prefix {
+                inclusive: false, ..
+            } => order == Ordering::Greater,
+            _ => order != Ordering::Less,
+        })
+    }
+
+    /** Orders markers used as lower bounds of ranges by the first key each of them admits. */
+    pub fn cmp_lower_bounds(
+        schema: &KVPrimaryKeySchema,
+        this: &KVPrimaryKeyMarker,
+        that: &KVPrimaryKeyMarker,
+    ) -> Result<Ordering, KVRuntimeError> {
+        Self::cmp_bounds(schema, this, that, Ordering::Less)
+    }
 
-        if let KVPrimaryKeyMarker::End = this {
-            if let KVPrimaryKeyMarker::End = that {
-                return Ok(Ordering::Equal);
-            } else {
+    /** Orders markers used as upper bounds of ranges by the last key each of them admits. */
+    pub fn cmp_upper_bounds(
+        schema: &KVPrimaryKeySchema,
+        this: &KVPrimaryKeyMarker,
+        that: &KVPrimaryKeyMarker,
+    ) -> Result<Ordering, KVRuntimeError> {
+        Self::cmp_bounds(schema, this, that, Ordering::Greater)
+    }
+
+    /**
+     * Compares positions of two bounds among keys. A key bound sits at its key, an inclusive
+     * prefix bound on the `inclusive_side` of all keys sharing the prefix and an exclusive one on
+     * the opposite side.
+     */
+    fn cmp_bounds(
+        schema: &KVPrimaryKeySchema,
+        this: &KVPrimaryKeyMarker,
+        that: &KVPrimaryKeyMarker,
+        inclusive_side: Ordering,
+    ) -> Result<Ordering, KVRuntimeError> {
+        match (this, that) {
+            (KVPrimaryKeyMarker::Start, KVPrimaryKeyMarker::Start)
+            | (KVPrimaryKeyMarker::End, KVPrimaryKeyMarker::End) => return Ok(Ordering::Equal),
+            (KVPrimaryKeyMarker::Start, _) | (_, KVPrimaryKeyMarker::End) => {
+                return Ok(Ordering::Less);
+            }
+            (_, KVPrimaryKeyMarker::Start) | (KVPrimaryKeyMarker::End, _) => {
                 return Ok(Ordering::Greater);
             }
+            _ => {}
         }
 
-        if let KVPrimaryKeyMarker::Key(this_key) = this {
-            return match that {
-                KVPrimaryKeyMarker::Key(that_key) => {
-                    KVPrimaryKeyComparator::cmp(schema, &this_key, &that_key)
-                }
-                KVPrimaryKeyMarker::Start => Ok(Ordering::Greater),
-                KVPrimaryKeyMarker::End => Ok(Ordering::Less),
-            };
+        let (this_values, this_side) = Self::bound_position(schema, this, inclusive_side)?;
+        let (that_values, that_side) = Self::bound_position(schema, that, inclusive_side)?;
+
+        for (idx, (this_value, that_value)) in this_values.iter().zip(&that_values).enumerate() {
+            match schema.column_type(idx)?.cmp(this_value, that_value) {
+                Ordering::Equal => {}
+                order => return Ok(order),
+            }
         }
 
-        panic!("PrimaryKeyMarkerComparator fatal error");
+        // Shorter bound is a prefix shared by keys of the longer one
+        Ok(match this_values.len().cmp(&that_values.len()) {
+            Ordering::Less => this_side,
+            Ordering::Greater => that_side.reverse(),
+            Ordering::Equal => this_side.cmp(&that_side),
+        })
     }
 
-    pub fn cmp_with_key(
+    /** Column values of a key or prefix bound and its side of the keys sharing them. */
+    fn bound_position<'a>(
         schema: &KVPrimaryKeySchema,
-        this: &KVPrimaryKeyMarker,
-        that: &[u8],
-    ) -> Result<Ordering, KVRuntimeError> {
-        match this {
-            KVPrimaryKeyMarker::Start => Ok(Ordering::Less),
-            KVPrimaryKeyMarker::End => Ok(Ordering::Greater),
-            KVPrimaryKeyMarker::Key(this) => KVPrimaryKeyComparator::cmp(schema, this, that),
+        marker: &'a KVPrimaryKeyMarker,
+        inclusive_side: Ordering,
+    ) -> Result<(Vec<&'a [u8]>, Ordering), KVRuntimeError> {
+        match marker {
+            KVPrimaryKeyMarker::Key(key) => {
+                let column_count = schema.column_count() as usize;
+
+                Ok((
+                    Self::column_values(&key[..(2 * column_count)], &key[(2 * column_count)..]),
+                    Ordering::Equal,
+                ))
+            }
+            KVPrimaryKeyMarker::Prefix { prefix, inclusive } => {
+                let prefix_count = prefix[0] as usize;
+                ensure!(
+                    prefix_count <= schema.column_count() as usize,
+                    KVRuntimeError::with_msg(
+                        KVRuntimeErrorKind::DataMalformed,
+                        format!("primary key prefix has {} columns", prefix_count)
+                    )
+                );
+
+                let values_start = 1 + 2 * prefix_count;
+                let values = Self::column_values(&prefix[1..values_start], &prefix[values_start..]);
+
+                let side = if *inclusive {
+                    inclusive_side
+                } else {
+                    inclusive_side.reverse()
+                };
+
+                Ok((values, side))
+            }
+            KVPrimaryKeyMarker::Start | KVPrimaryKeyMarker::End => unreachable!(),
         }
     }
+
+    /** Splits concatenated column values by their u16 sizes. */
+    fn column_values<'a>(sizes: &[u8], values: &'a [u8]) -> Vec<&'a [u8]> {
+        let mut value_ptr = 0;
+
+        sizes
+            .chunks_exact(2)
+            .map(|size| {
+                let value_size = u16::from_le_bytes(size.try_into().unwrap()) as usize;
+                let value = &values[value_ptr..(value_ptr + value_size)];
+                value_ptr += value_size;
+
+                value
+            })
+            .collect()
+    }
 }
 
 pub struct KVPrimaryKeyUtils;
@@ -302,6 +504,34 @@ impl KVPrimaryKeyMarkerUtils {
                     );
                 }
 
+                Ok(out)
+            }
+            KVPrimaryKeyMarker::Prefix { prefix, inclusive } => {
+                let prefix_count = prefix[0] as usize;
+                let mut value_ptr = 1 + 2 * prefix_count;
+
+                let mut out = String::from(if *inclusive { "[|" } else { "(|" });
+
+                for idx in 0..prefix_count {
+                    let size_ptr = 1 + 2 * idx;
+                    let value_size =
+                        u16::from_le_bytes(prefix[size_ptr..(size_ptr + 2)].try_into().unwrap())
+                            as usize;
+                    let column_type = pk_schema.column_type(idx).map_err(|_| ())?;
+
+                    out += &format!(
+                        "{}|",
+                        KVColumnTypeUtils::debug_fmt(
+                            column_type.code(),
+                            &prefix[value_ptr..(value_ptr + value_size)]
+                        )
+                    );
+                    value_ptr += value_size;
+                }
+
+                out += "*";
+                out += if *inclusive { "]" } else { ")" };
+
                 Ok(out)
             }
         }
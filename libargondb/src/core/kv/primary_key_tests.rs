@@ -0,0 +1,168 @@
+use crate::kv::{
+    KVTableSchema,
+    column_type::{ColumnTypeCode, ColumnTypeSerialize, ColumnTypeText},
+    primary_key::{
+        KVPrimaryKeyMarker, KVPrimaryKeySchema, PrimaryKeyBuilder, PrimaryKeyMarkerComparator,
+    },
+    schema::{KVColumnRetention, KVColumnSchema},
+};
+
+fn pk_schema() -> KVPrimaryKeySchema {
+    let column = |column_id: u16, column_name: &str| KVColumnSchema {
+        column_id,
+        column_name: column_name.into(),
+        column_type: ColumnTypeCode::Text,
+        retention: KVColumnRetention::default(),
+        nullable: true,
+        default_value: None,
+    };
+
+    let table_schema =
+        KVTableSchema::build(vec![column(1, "tenant"), column(2, "id")], vec![1, 2]).unwrap();
+
+    KVPrimaryKeySchema::from_table_schema(&table_schema)
+}
+
+fn builder<'a>(schema: &'a KVPrimaryKeySchema, values: &[&str]) -> PrimaryKeyBuilder<'a> {
+    let mut pk_builder = PrimaryKeyBuilder::new(schema);
+    for value in values {
+        pk_builder.add_value(&ColumnTypeText::serialize(value).unwrap());
+    }
+
+    pk_builder
+}
+
+fn prefix(schema: &KVPrimaryKeySchema, values: &[&str], inclusive: bool) -> KVPrimaryKeyMarker {
+    KVPrimaryKeyMarker::Prefix {
+        prefix: builder(schema, values).build_prefix(),
+        inclusive,
+    }
+}
+
+/** Keys of the range, from keys sorted in primary key order. */
+fn keys_in_range<'a>(
+    schema: &KVPrimaryKeySchema,
+    from: &KVPrimaryKeyMarker,
+    to: &KVPrimaryKeyMarker,
+    keys: &[(&'a str, &'a str)],
+) -> Vec<(&'a str, &'a str)> {
+    keys.iter()
+        .filter(|(tenant, id)| {
+            let key = builder(schema, &[tenant, id]).build().unwrap();
+
+            PrimaryKeyMarkerComparator::is_above_lower_bound(schema, from, &key).unwrap()
+                && PrimaryKeyMarkerComparator::is_below_upper_bound(schema, to, &key).unwrap()
+        })
+        .copied()
+        .collect()
+}
+
+const KEYS: [(&str, &str); 6] = [
+    ("a", "1"),
+    ("b", "1"),
+    ("b", "2"),
+    ("b", "3"),
+    ("c", "1"),
+    ("c", "2"),
+];
+
+#[test]
+fn test_prefix_bounds() {
+    let schema = pk_schema();
+
+    let inclusive = prefix(&schema, &["b"], true);
+    assert_eq!(
+        keys_in_range(&schema, &inclusive, &inclusive, &KEYS),
+        vec![("b", "1"), ("b", "2"), ("b", "3")]
+    );
+
+    let exclusive = prefix(&schema, &["b"], false);
+    assert_eq!(
+        keys_in_range(&schema, &exclusive, &KVPrimaryKeyMarker::End, &KEYS),
+        vec![("c", "1"), ("c", "2")]
+    );
+    assert_eq!(
+        keys_in_range(&schema, &KVPrimaryKeyMarker::Start, &exclusive, &KEYS),
+        vec![("a", "1")]
+    );
+}
+
+#[test]
+fn test_key_bounds() {
+    let schema = pk_schema();
+
+    let from = prefix(&schema, &["b", "1"], false);
+    let to = KVPrimaryKeyMarker::Key(builder(&schema, &["c", "1"]).build().unwrap());
+    assert_eq!(
+        keys_in_range(&schema, &from, &to, &KEYS),
+        vec![("b", "2"), ("b", "3"), ("c", "1")]
+    );
+
+    let from = KVPrimaryKeyMarker::Key(builder(&schema, &["b", "2"]).build().unwrap());
+    let to = prefix(&schema, &["b", "3"], false);
+    assert_eq!(keys_in_range(&schema, &from, &to, &KEYS), vec![("b", "2")]);
+}
+
+#[test]
+fn test_builder_requires_all_columns() {
+    let schema = pk_schema();
+
+    assert!(builder(&schema, &["b"]).build().is_err());
+    assert!(builder(&schema, &["b", "1"]).build().is_ok());
+
+    // Prefix of leading columns is a valid bound on its own
+    let prefix = builder(&schema, &["b"]).build_prefix();
+    assert_eq!(prefix[0], 1);
+}
+
+#[test]
+fn test_bound_ordering() {
+    let schema = pk_schema();
+    let key = |tenant: &str, id: &str| {
+        KVPrimaryKeyMarker::Key(builder(&schema, &[tenant, id]).build().unwrap())
+    };
+
+    let assert_ascending = |bounds: &[KVPrimaryKeyMarker], lower: bool| {
+        for (i, this) in bounds.iter().enumerate() {
+            for (j, that) in bounds.iter().enumerate() {
+                let order = if lower {
+                    PrimaryKeyMarkerComparator::cmp_lower_bounds(&schema, this, that)
+                } else {
+                    PrimaryKeyMarkerComparator::cmp_upper_bounds(&schema, this, that)
+                };
+
+                assert_eq!(order.unwrap(), i.cmp(&j), "bounds {} and {}", i, j);
+            }
+        }
+    };
+
+    // Lower bounds are placed before the first key they admit
+    assert_ascending(
+        &[
+            KVPrimaryKeyMarker::Start,
+            prefix(&schema, &["b"], true),
+            key("b", "1"),
+            prefix(&schema, &["b", "1"], false),
+            key("b", "2"),
+            prefix(&schema, &["b"], false),
+            key("c", "1"),
+            KVPrimaryKeyMarker::End,
+        ],
+        true,
+    );
+
+    // Upper bounds are placed after the last key they admit
+    assert_ascending(
+        &[
+            KVPrimaryKeyMarker::Start,
+            prefix(&schema, &["b"], false),
+            key("b", "1"),
+            prefix(&schema, &["b", "1"], true),
+            key("b", "2"),
+            prefix(&schema, &["b"], true),
+            key("c", "1"),
+            KVPrimaryKeyMarker::End,
+        ],
+        false,
+    );
+}
@@ -0,0 +1,145 @@
+use bytes::{Buf, BufMut};
+
+use crate::kv::{
+    KVPrimaryKeyMarker, KVRuntimeError, KVRuntimeErrorKind,
+    primary_key::{KVPrimaryKeySchema, PrimaryKeyMarkerComparator},
+};
+
+/*
+ * Serialized layout:
+ * [timestamp: u64][from marker][to marker]
+ *
+ * Marker layout:
+ * [marker_type: u8]([key_size: u16][key])?
+ */
+const MARKER_TYPE_START: u8 = 0;
+const MARKER_TYPE_END: u8 = 1;
+const MARKER_TYPE_KEY: u8 = 2;
+const MARKER_TYPE_PREFIX_INCLUSIVE: u8 = 3;
+const MARKER_TYPE_PREFIX_EXCLUSIVE: u8 = 4;
+
+/**
+ * Deletes every row within the key range written at or before `timestamp`. Covered rows are
+ * shadowed the same way as by a row tombstone of each of them.
+ */
+#[derive(Debug, Clone)]
+pub struct KVRangeTombstone {
+    pub from: KVPrimaryKeyMarker,
+    pub to: KVPrimaryKeyMarker,
+    pub timestamp: u64,
+}
+
+impl KVRangeTombstone {
+    /** Serialized size of the smallest tombstone - a timestamp and two markers without keys. */
+    pub const MIN_SIZE: usize = 10;
+
+    pub fn covers_key(
+        &self,
+        schema: &KVPrimaryKeySchema,
+        key: &[u8],
+    ) -> Result<bool, KVRuntimeError> {
+        Ok(
+            PrimaryKeyMarkerComparator::is_above_lower_bound(schema, &self.from, key)?
+                && PrimaryKeyMarkerComparator::is_below_upper_bound(schema, &self.to, key)?,
+        )
+    }
+
+    pub fn size(&self) -> usize {
+        let marker_size = |marker: &KVPrimaryKeyMarker| match marker {
+            KVPrimaryKeyMarker::Start | KVPrimaryKeyMarker::End => 1,
+            KVPrimaryKeyMarker::Key(key) => 3 + key.len(),
+            KVPrimaryKeyMarker::Prefix { prefix, .. } => 3 + prefix.len(),
+        };
+
+        8 + marker_size(&self.from) + marker_size(&self.to)
+    }
+
+    pub fn serialize(&self, buf: &mut Vec<u8>) {
+        buf.put_u64_le(self.timestamp);
+        Self::serialize_marker(buf, &self.from);
+        Self::serialize_marker(buf, &self.to);
+    }
+
+    /** Parses a tombstone from the front of the buffer and advances it past the tombstone. */
+    pub fn parse(buf: &mut &[u8]) -> Result<Self, KVRuntimeError> {
+        if buf.remaining() < 8 {
+            return Err(Self::malformed());
+        }
+
+        let timestamp = buf.get_u64_le();
+        let from = Self::parse_marker(buf)?;
+        let to = Self::parse_marker(buf)?;
+
+        Ok(Self {
+            from,
+            to,
+            timestamp,
+        })
+    }
+
+    fn serialize_marker(buf: &mut Vec<u8>, marker: &KVPrimaryKeyMarker) {
+        let (marker_type, key) = match marker {
+            KVPrimaryKeyMarker::Start => (MARKER_TYPE_START, None),
+            KVPrimaryKeyMarker::End => (MARKER_TYPE_END, None),
+            KVPrimaryKeyMarker::Key(key) => (MARKER_TYPE_KEY, Some(key)),
+            KVPrimaryKeyMarker::Prefix {
+                prefix,
+                inclusive: true,
+            } => (MARKER_TYPE_PREFIX_INCLUSIVE, Some(prefix)),
+            KVPrimaryKeyMarker::Prefix {
+                prefix,
+                inclusive: false,
+            } => (MARKER_TYPE_PREFIX_EXCLUSIVE, Some(prefix)),
+        };
+
+        buf.put_u8(marker_type);
+
+        if let Some(key) = key {
+            let key_size = u16::try_from(key.len()).expect("range tombstone key too large");
+
+            buf.put_u16_le(key_size);
+            buf.put_slice(key);
+        }
+    }
+
+    fn parse_marker(buf: &mut &[u8]) -> Result<KVPrimaryKeyMarker, KVRuntimeError> {
+        if buf.remaining() < 1 {
+            return Err(Self::malformed());
+        }
+
+        let marker_type = buf.get_u8();
+        match marker_type {
+            MARKER_TYPE_START => return Ok(KVPrimaryKeyMarker::Start),
+            MARKER_TYPE_END => return Ok(KVPrimaryKeyMarker::End),
+            MARKER_TYPE_KEY | MARKER_TYPE_PREFIX_INCLUSIVE | MARKER_TYPE_PREFIX_EXCLUSIVE => {}
+            _ => return Err(Self::malformed()),
+        }
+
+        if buf.remaining() < 2 {
+            return Err(Self::malformed());
+        }
+
+        let key_size = buf.get_u16_le() as usize;
+        if buf.remaining() < key_size {
+            return Err(Self::malformed());
+        }
+
+        let key: Box<[u8]> = buf[..key_size].into();
+        buf.advance(key_size);
+
+        Ok(match marker_type {
+            MARKER_TYPE_KEY => KVPrimaryKeyMarker::Key(key),
+            _ => KVPrimaryKeyMarker::Prefix {
+                prefix: key,
+                inclusive: marker_type == MARKER_TYPE_PREFIX_INCLUSIVE,
+            },
+        })
+    }
+
+    fn malformed() -> KVRuntimeError {
+        KVRuntimeError::with_msg(
+            KVRuntimeErrorKind::DataMalformed,
+            "malformed range tombstone",
+        )
+    }
+}
@@ -1,19 +1,34 @@
 use std::{collections::BTreeMap, fmt::Debug};
 
 use crate::kv::{
-    KVRuntimeError, KVRuntimeErrorKind, KVScanIteratorItem, KVTableSchema,
+    KVColumnFilter, KVRuntimeError, KVRuntimeErrorKind, KVScanIteratorItem, KVTableSchema,
     column_type::{
-        ColumnTypeCode, ColumnTypeDeserialize, ColumnTypeText, ColumnTypeU16, ColumnTypeU16Array,
+        ColumnType, ColumnTypeCode, ColumnTypeDeserialize, ColumnTypeText, ColumnTypeU16,
+        ColumnTypeU16Array, KVColumnTypeUtils,
     },
     primary_key::{KVPrimaryKeyComparator, KVPrimaryKeySchema},
+    schema::KVColumnSchema,
 };
 
+type KVRowCellVersions = Vec<Box<dyn KVScanIteratorItem + Send + Sync>>;
+
+/**
+ * Cell versions are ordered newest first, single version is kept unless scan asked for more.
+ * Projected columns which were never written in the row read as their default value, reported
+ * as a single version with timestamp 0.
+ */
 pub struct KVRow {
     table_schema: KVTableSchema,
-    cells: BTreeMap<u16, Box<dyn KVScanIteratorItem + Send + Sync>>,
+    primary_key: Box<[u8]>,
+    cells: BTreeMap<u16, KVRowCellVersions>,
+    projection: KVColumnFilter,
 }
 
 impl KVRow {
+    pub fn primary_key(&self) -> &[u8] {
+        &self.primary_key
+    }
+
     pub fn column_deserialized<T>(
         &self,
         column_name: impl AsRef<str>,
@@ -21,28 +36,115 @@ impl KVRow {
     where
         T: ColumnTypeDeserialize,
     {
-        let column_schema = self
-            .table_schema
-            .lookup_by_name(column_name.as_ref())
-            .ok_or(KVRuntimeError::with_msg(
-                KVRuntimeErrorKind::OperationNotAllowed,
-                format!("no column with name {}", column_name.as_ref()),
-            ))?;
+        let column_schema = self.lookup_column_schema(column_name.as_ref())?;
 
-        let cell = self
-            .cells
-            .get(&column_schema.column_id)
-            .ok_or(KVRuntimeError::with_msg(
-                KVRuntimeErrorKind::OperationNotAllowed,
-                format!("no cell for column {} in the row", column_name.as_ref()),
-            ))?;
+        match self.cell_value(column_schema.column_id) {
+            Some(value) => T::deserialize(value),
+            None => Err(Self::missing_cell_error(column_name.as_ref())),
+        }
+    }
+
+    /**
+     * Returns all versions of the cell read by the scan as (timestamp, value) pairs, newest first.
+     */
+    pub fn column_versions_deserialized<T>(
+        &self,
+        column_name: impl AsRef<str>,
+    ) -> Result<Vec<(u64, T::Output)>, KVRuntimeError>
+    where
+        T: ColumnTypeDeserialize,
+    {
+        let column_schema = self.lookup_column_schema(column_name.as_ref())?;
+
+        let Some(versions) = self.cells.get(&column_schema.column_id) else {
+            return match self.default_value(column_schema.column_id) {
+                Some(default_value) => Ok(vec![(0, T::deserialize(default_value)?)]),
+                None => Err(Self::missing_cell_error(column_name.as_ref())),
+            };
+        };
 
-        T::deserialize(cell.mutation().value())
+        versions
+            .iter()
+            .map(|item| {
+                let mutation = item.mutation();
+                Ok((mutation.timestamp(), T::deserialize(mutation.value())?))
+            })
+            .collect()
     }
 
+    /** Checks whether the cell was written, column defaults are not considered. */
     pub fn has_cell(&self, column_id: u16) -> bool {
         self.cells.contains_key(&column_id)
     }
+
+    /** Checks whether the column reads as non-null, either written or defaulted. */
+    pub fn has_value(&self, column_id: u16) -> bool {
+        self.cell_value(column_id).is_some()
+    }
+
+    pub fn has_cell_by_name(&self, column_name: impl AsRef<str>) -> bool {
+        self.table_schema
+            .lookup_by_name(column_name)
+            .is_some_and(|column_schema| self.has_cell(column_schema.column_id))
+    }
+
+    /** Returns serialized value of the newest version of the cell, or the column default. */
+    pub fn cell_value(&self, column_id: u16) -> Option<&[u8]> {
+        self.cells
+            .get(&column_id)
+            .map(|versions| versions[0].mutation().value())
+            .or_else(|| self.default_value(column_id))
+    }
+
+    pub fn column_type(&self, column_id: u16) -> Result<&'static dyn ColumnType, KVRuntimeError> {
+        let column_schema =
+            self.table_schema
+                .lookup_by_column_id(column_id)
+                .ok_or(KVRuntimeError::with_msg(
+                    KVRuntimeErrorKind::OperationNotAllowed,
+                    format!("no column with id {}", column_id),
+                ))?;
+
+        ColumnTypeCode::type_for_code(column_schema.column_type as u8)
+    }
+
+    /** Drops cells of columns not selected by the filter, those don't read as defaults either. */
+    pub fn retain_columns(&mut self, columns: &KVColumnFilter) {
+        if let KVColumnFilter::Columns(column_ids) = columns {
+            self.cells
+                .retain(|column_id, _| column_ids.contains(column_id));
+        }
+
+        self.projection = columns.clone();
+    }
+
+    fn default_value(&self, column_id: u16) -> Option<&[u8]> {
+        if let KVColumnFilter::Columns(column_ids) = &self.projection
+            && !column_ids.contains(&column_id)
+        {
+            return None;
+        }
+
+        self.table_schema
+            .lookup_by_column_id(column_id)
+            .and_then(|column_schema| column_schema.default_value.as_deref())
+    }
+
+    fn lookup_column_schema(&self, column_name: &str) -> Result<&KVColumnSchema, KVRuntimeError> {
+        self.table_schema
+            .lookup_by_name(column_name)
+            .ok_or(KVRuntimeError::with_msg(
+                KVRuntimeErrorKind::OperationNotAllowed,
+                format!("no column with name {}", column_name),
+            ))
+    }
+
+    fn missing_cell_error(column_name: &str) -> KVRuntimeError {
+        KVRuntimeError::with_msg(
+            KVRuntimeErrorKind::OperationNotAllowed,
+            format!("no cell for column {} in the row", column_name),
+        )
+    }
 }
 
 impl Debug for KVRow {
@@ -53,28 +155,35 @@ impl Debug for KVRow {
         for column in &self.table_schema.columns {
             let name = &column.column_name;
 
-            match self.cells.get(&column.column_id) {
-                Some(ref item) => match column.column_type {
+            match self.cell_value(column.column_id) {
+                Some(value) => match column.column_type {
                     ColumnTypeCode::Bytes => {
-                        f_ref = f_ref.field(name, &item.mutation().value());
+                        f_ref = f_ref.field(name, &value);
                     }
                     ColumnTypeCode::Text => {
-                        f_ref = f_ref.field(
-                            name,
-                            &ColumnTypeText::deserialize(item.mutation().value()).unwrap(),
-                        );
+                        f_ref = f_ref.field(name, &ColumnTypeText::deserialize(value).unwrap());
                     }
                     ColumnTypeCode::U16 => {
-                        f_ref = f_ref.field(
-                            name,
-                            &ColumnTypeU16::deserialize(item.mutation().value()).unwrap(),
-                        );
+                        f_ref = f_ref.field(name, &ColumnTypeU16::deserialize(value).unwrap());
                     }
                     ColumnTypeCode::U16Array => {
+                        f_ref = f_ref.field(name, &ColumnTypeU16Array::deserialize(value).unwrap());
+                    }
+                    ColumnTypeCode::I32
+                    | ColumnTypeCode::I64
+                    | ColumnTypeCode::U32
+                    | ColumnTypeCode::U64
+                    | ColumnTypeCode::F64
+                    | ColumnTypeCode::Bool
+                    | ColumnTypeCode::Timestamp
+                    | ColumnTypeCode::Uuid => {
                         f_ref = f_ref.field(
                             name,
-                            &ColumnTypeU16Array::deserialize(item.mutation().value()).unwrap(),
-                        )
+                            &format_args!(
+                                "{}",
+                                KVColumnTypeUtils::debug_fmt(column.column_type, value)
+                            ),
+                        );
                     }
                 },
                 None => {
@@ -91,7 +200,7 @@ pub struct KVRowBuilder {
     table_schema: KVTableSchema,
     pk_schema: KVPrimaryKeySchema,
     primary_key: Box<[u8]>,
-    cells: BTreeMap<u16, Box<dyn KVScanIteratorItem + Send + Sync>>,
+    cells: BTreeMap<u16, KVRowCellVersions>,
 }
 
 impl KVRowBuilder {
@@ -104,7 +213,7 @@ impl KVRowBuilder {
         let primary_key = item.primary_key().to_vec().into_boxed_slice();
 
         let mut cells = BTreeMap::new();
-        cells.insert(item.mutation().column_id(), item);
+        cells.insert(item.mutation().column_id(), vec![item]);
 
         Self {
             table_schema,
@@ -132,7 +241,10 @@ impl KVRowBuilder {
             ));
         }
 
-        self.cells.insert(item.mutation().column_id(), item);
+        self.cells
+            .entry(item.mutation().column_id())
+            .or_default()
+            .push(item);
         Ok(())
     }
 }
@@ -141,7 +253,9 @@ impl Into<KVRow> for KVRowBuilder {
     fn into(self) -> KVRow {
         KVRow {
             table_schema: self.table_schema,
+            primary_key: self.primary_key,
             cells: self.cells,
+            projection: KVColumnFilter::All,
         }
     }
 }
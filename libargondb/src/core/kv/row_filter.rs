@@ -0,0 +1,163 @@
+use std::cmp::Ordering;
+
+use crate::kv::{
+    KVRow, KVRuntimeError, KVRuntimeErrorKind, KVTableSchema,
+    column_type::{ColumnTypeCode, ColumnTypeDeserialize, ColumnTypeU16Array},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KVCompareOp {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+}
+
+impl KVCompareOp {
+    fn matches(self, ordering: Ordering) -> bool {
+        match self {
+            KVCompareOp::Eq => ordering == Ordering::Equal,
+            KVCompareOp::NotEq => ordering != Ordering::Equal,
+            KVCompareOp::Lt => ordering == Ordering::Less,
+            KVCompareOp::LtEq => ordering != Ordering::Greater,
+            KVCompareOp::Gt => ordering == Ordering::Greater,
+            KVCompareOp::GtEq => ordering != Ordering::Less,
+        }
+    }
+}
+
+/**
+ * Predicate evaluated against rows of a scan, on the newest version of each cell. Values are
+ * serialized with the type of the column they are compared to. Comparisons, prefix matches and
+ * array lookups on a missing cell never match - use `IsNull` to select such rows.
+ */
+#[derive(Debug, Clone)]
+pub enum KVRowFilter {
+    Compare {
+        column_id: u16,
+        op: KVCompareOp,
+        value: Box<[u8]>,
+    },
+    TextPrefix {
+        column_id: u16,
+        prefix: Box<[u8]>,
+    },
+    ArrayContains {
+        column_id: u16,
+        item: u16,
+    },
+    IsNull(u16),
+    IsNotNull(u16),
+    And(Vec<KVRowFilter>),
+    Or(Vec<KVRowFilter>),
+    Not(Box<KVRowFilter>),
+}
+
+impl KVRowFilter {
+    /** Checks that referenced columns exist and support the predicates applied to them. */
+    pub fn validate(&self, schema: &KVTableSchema) -> Result<(), KVRuntimeError> {
+        let column_type = |column_id: u16| {
+            schema
+                .lookup_by_column_id(column_id)
+                .map(|column_schema| column_schema.column_type)
+                .ok_or(KVRuntimeError::with_msg(
+                    KVRuntimeErrorKind::OperationNotAllowed,
+                    format!("row filter references unknown column {}", column_id),
+                ))
+        };
+
+        match self {
+            KVRowFilter::Compare { column_id, .. }
+            | KVRowFilter::IsNull(column_id)
+            | KVRowFilter::IsNotNull(column_id) => column_type(*column_id).map(|_| ()),
+            KVRowFilter::TextPrefix { column_id, .. } => match column_type(*column_id)? {
+                ColumnTypeCode::Text => Ok(()),
+                _ => Err(KVRuntimeError::with_msg(
+                    KVRuntimeErrorKind::OperationNotAllowed,
+                    "row filter prefix match requires text column",
+                )),
+            },
+            KVRowFilter::ArrayContains { column_id, .. } => match column_type(*column_id)? {
+                ColumnTypeCode::U16Array => Ok(()),
+                _ => Err(KVRuntimeError::with_msg(
+                    KVRuntimeErrorKind::OperationNotAllowed,
+                    "row filter array contains requires u16 array column",
+                )),
+            },
+            KVRowFilter::And(filters) | KVRowFilter::Or(filters) => filters
+                .iter()
+                .try_for_each(|filter| filter.validate(schema)),
+            KVRowFilter::Not(filter) => filter.validate(schema),
+        }
+    }
+
+    pub fn column_ids(&self, column_ids: &mut Vec<u16>) {
+        match self {
+            KVRowFilter::Compare { column_id, .. }
+            | KVRowFilter::TextPrefix { column_id, .. }
+            | KVRowFilter::ArrayContains { column_id, .. }
+            | KVRowFilter::IsNull(column_id)
+            | KVRowFilter::IsNotNull(column_id) => {
+                if !column_ids.contains(column_id) {
+                    column_ids.push(*column_id);
+                }
+            }
+            KVRowFilter::And(filters) | KVRowFilter::Or(filters) => {
+                for filter in filters {
+                    filter.column_ids(column_ids);
+                }
+            }
+            KVRowFilter::Not(filter) => filter.column_ids(column_ids),
+        }
+    }
+
+    pub fn matches(&self, row: &KVRow) -> Result<bool, KVRuntimeError> {
+        match self {
+            KVRowFilter::Compare {
+                column_id,
+                op,
+                value,
+            } => {
+                let Some(cell) = row.cell_value(*column_id) else {
+                    return Ok(false);
+                };
+
+                let column_type = row.column_type(*column_id)?;
+                Ok(op.matches(column_type.cmp(cell, value)))
+            }
+            KVRowFilter::TextPrefix { column_id, prefix } => Ok(row
+                .cell_value(*column_id)
+                .is_some_and(|cell| cell.starts_with(prefix))),
+            KVRowFilter::ArrayContains { column_id, item } => {
+                let Some(cell) = row.cell_value(*column_id) else {
+                    return Ok(false);
+                };
+
+                Ok(ColumnTypeU16Array::deserialize(cell)?.contains(item))
+            }
+            KVRowFilter::IsNull(column_id) => Ok(row.cell_value(*column_id).is_none()),
+            KVRowFilter::IsNotNull(column_id) => Ok(row.cell_value(*column_id).is_some()),
+            KVRowFilter::And(filters) => {
+                for filter in filters {
+                    if !filter.matches(row)? {
+                        return Ok(false);
+                    }
+                }
+
+                Ok(true)
+            }
+            KVRowFilter::Or(filters) => {
+                for filter in filters {
+                    if filter.matches(row)? {
+                        return Ok(true);
+                    }
+                }
+
+                Ok(false)
+            }
+            KVRowFilter::Not(filter) => Ok(!filter.matches(row)?),
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::kv::{
+    KVCompareOp, KVRow, KVRowFilter, KVScanIteratorItem, KVTableSchema,
+    column_type::{ColumnTypeCode, ColumnTypeSerialize, ColumnTypeText, ColumnTypeU16Array},
+    kv_test_utils::TestIterItem,
+    mutation::{MutationType, StructuredMutation},
+    primary_key::{KVPrimaryKeySchema, PrimaryKeyBuilder},
+    row::KVRowBuilder,
+    schema::{KVColumnRetention, KVColumnSchema},
+};
+
+fn table_schema() -> KVTableSchema {
+    let column = |column_id: u16, column_name: &str, column_type: ColumnTypeCode| KVColumnSchema {
+        column_id,
+        column_name: column_name.into(),
+        column_type,
+        retention: KVColumnRetention::default(),
+        nullable: true,
+        default_value: None,
+    };
+
+    KVTableSchema::build(
+        vec![
+            column(1, "id", ColumnTypeCode::Text),
+            column(2, "tags", ColumnTypeCode::U16Array),
+            column(3, "note", ColumnTypeCode::Text),
+        ],
+        vec![1],
+    )
+    .unwrap()
+}
+
+fn row(schema: &KVTableSchema, id: &str, tags: &[u16]) -> KVRow {
+    let pk_schema = KVPrimaryKeySchema::from_table_schema(schema);
+    let id = ColumnTypeText::serialize(id).unwrap();
+
+    let mut pk_builder = PrimaryKeyBuilder::new(&pk_schema);
+    pk_builder.add_value(&id);
+    let primary_key = pk_builder.build().unwrap();
+
+    let cell = |column_id: u16, value: Box<[u8]>| -> Box<dyn KVScanIteratorItem + Send + Sync> {
+        Box::new(TestIterItem(
+            StructuredMutation::try_from(
+                1,
+                column_id,
+                MutationType::Put,
+                primary_key.clone(),
+                value,
+            )
+            .unwrap(),
+        ))
+    };
+
+    let mut builder = KVRowBuilder::new(schema.clone(), cell(1, id.clone()));
+    builder
+        .add(cell(2, ColumnTypeU16Array::serialize(tags).unwrap()))
+        .unwrap();
+
+    builder.into()
+}
+
+#[test]
+fn test_row_filter_matches() {
+    let schema = table_schema();
+    let row = row(&schema, "user#42", &[3, 7]);
+
+    let id_gt = |id: &str| KVRowFilter::Compare {
+        column_id: 1,
+        op: KVCompareOp::Gt,
+        value: ColumnTypeText::serialize(id).unwrap(),
+    };
+
+    assert!(id_gt("user#1").matches(&row).unwrap());
+    assert!(!id_gt("user#5").matches(&row).unwrap());
+
+    let filter = KVRowFilter::And(vec![
+        KVRowFilter::TextPrefix {
+            column_id: 1,
+            prefix: Box::from(b"user#".as_slice()),
+        },
+        KVRowFilter::ArrayContains {
+            column_id: 2,
+            item: 7,
+        },
+        KVRowFilter::IsNull(3),
+    ]);
+    assert!(filter.matches(&row).unwrap());
+    assert!(!KVRowFilter::Not(Box::new(filter)).matches(&row).unwrap());
+
+    // Comparison on a missing cell never matches, even when negated by the operator
+    let note_not_eq = KVRowFilter::Compare {
+        column_id: 3,
+        op: KVCompareOp::NotEq,
+        value: ColumnTypeText::serialize("x").unwrap(),
+    };
+    assert!(!note_not_eq.matches(&row).unwrap());
+    assert!(
+        KVRowFilter::Or(vec![note_not_eq, KVRowFilter::IsNotNull(2)])
+            .matches(&row)
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_row_filter_validate() {
+    let schema = table_schema();
+
+    assert!(
+        KVRowFilter::ArrayContains {
+            column_id: 1,
+            item: 7
+        }
+        .validate(&schema)
+        .is_err()
+    );
+    assert!(KVRowFilter::IsNull(9).validate(&schema).is_err());
+    assert!(
+        KVRowFilter::Not(Box::new(KVRowFilter::TextPrefix {
+            column_id: 3,
+            prefix: Box::from([]),
+        }))
+        .validate(&schema)
+        .is_ok()
+    );
+}
@@ -0,0 +1,85 @@
+use crate::kv::{
+    KVColumnFilter, KVRow, KVRowFilter, KVTableSchema,
+    column_type::{ColumnTypeCode, ColumnTypeSerialize, ColumnTypeText},
+    kv_test_utils::{TestIterItem, test_mutation, test_primary_key, test_schema},
+    mutation::{MutationType, StructuredMutation},
+    row::KVRowBuilder,
+    schema::{KVColumnRetention, KVColumnSchema},
+};
+
+/** Schema of [`test_schema`] with a text column `note` defaulting to `none`. */
+fn schema_with_default() -> KVTableSchema {
+    let mut columns = test_schema().columns;
+    columns.push(KVColumnSchema {
+        column_id: 3,
+        column_name: "note".into(),
+        column_type: ColumnTypeCode::Text,
+        retention: KVColumnRetention::default(),
+        nullable: true,
+        default_value: Some(ColumnTypeText::serialize("none").unwrap()),
+    });
+
+    KVTableSchema::build(columns, vec![1]).unwrap()
+}
+
+/** Row with the `value` cell set, and the `note` cell too when given. */
+fn row(note: Option<&str>) -> KVRow {
+    let mut builder = KVRowBuilder::new(
+        schema_with_default(),
+        Box::new(TestIterItem(test_mutation("user#42", 1, "value"))),
+    );
+
+    if let Some(note) = note {
+        let mutation = StructuredMutation::try_from(
+            2,
+            3,
+            MutationType::Put,
+            test_primary_key("user#42"),
+            ColumnTypeText::serialize(note).unwrap(),
+        )
+        .unwrap();
+
+        builder.add(Box::new(TestIterItem(mutation))).unwrap();
+    }
+
+    builder.into()
+}
+
+#[test]
+fn test_row_default_values() {
+    let mut row = row(None);
+
+    assert!(!row.has_cell(3));
+    assert!(row.has_value(3));
+    assert!(KVRowFilter::IsNotNull(3).matches(&row).unwrap());
+    assert_eq!(
+        row.column_deserialized::<ColumnTypeText>("note").unwrap(),
+        "none"
+    );
+    assert_eq!(
+        row.column_versions_deserialized::<ColumnTypeText>("note")
+            .unwrap(),
+        vec![(0, "none".to_string())]
+    );
+
+    // Columns outside of projection don't read as defaults
+    row.retain_columns(&KVColumnFilter::Columns(vec![1, 2]));
+    assert!(!row.has_value(3));
+    assert!(row.column_deserialized::<ColumnTypeText>("note").is_err());
+}
+
+#[test]
+fn test_row_written_cells_hide_default_values() {
+    let row = row(Some("set"));
+
+    assert!(row.has_cell(3));
+    assert_eq!(
+        row.column_deserialized::<ColumnTypeText>("note").unwrap(),
+        "set"
+    );
+    assert_eq!(
+        row.column_versions_deserialized::<ColumnTypeText>("note")
+            .unwrap(),
+        vec![(2, "set".to_string())]
+    );
+}
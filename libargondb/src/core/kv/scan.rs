@@ -1,9 +1,9 @@
 use async_trait::async_trait;
 
 use crate::kv::{
-    KVTableSchema,
+    KVRangeTombstone, KVRowFilter, KVTableSchema,
     error::KVRuntimeError,
-    mutation::KVMutation,
+    mutation::{KVMutation, MutationType},
     primary_key::{KVPrimaryKeyMarker, KVPrimaryKeyMarkerUtils, KVPrimaryKeyUtils},
 };
 
@@ -13,12 +13,26 @@ pub trait KVScanOp: std::fmt::Display {
         &self,
         scannable: &T,
     ) -> Result<KVRangeScanResult, KVRuntimeError>;
+
+    fn version_filter(&self) -> &KVVersionFilter;
+
+    fn row_filter(&self) -> Option<&KVRowFilter>;
+
+    /** Columns returned to the caller - scan may read more of them to evaluate the row filter. */
+    fn projection(&self) -> &KVColumnFilter;
+
+    fn direction(&self) -> KVScanDirection;
 }
 
 #[async_trait]
 pub trait KVScannable: Send + Sync + std::fmt::Display {
     async fn range_scan(&self, scan: &KVRangeScan) -> Result<KVRangeScanResult, KVRuntimeError>;
-    async fn row_scan(&self, primary_key: &[u8]) -> Result<KVRangeScanResult, KVRuntimeError>;
+    async fn row_scan(&self, scan: &KVRowScan) -> Result<KVRangeScanResult, KVRuntimeError>;
+    /**
+     * Range tombstones stored in the scannable. They may shadow data of other scannables, so
+     * they are returned regardless of the scanned range.
+     */
+    fn range_tombstones(&self) -> Vec<KVRangeTombstone>;
     // fn set_scan(&self, scan: SetScanParams) -> impl ScanResultIter;
 }
 
@@ -44,6 +58,10 @@ pub struct KVRangeScan {
     from: KVPrimaryKeyMarker,
     to: KVPrimaryKeyMarker,
     columns: KVColumnFilter,
+    projection: KVColumnFilter,
+    row_filter: Option<KVRowFilter>,
+    versions: KVVersionFilter,
+    direction: KVScanDirection,
 }
 
 impl KVRangeScan {
@@ -57,8 +75,32 @@ impl KVRangeScan {
             schema,
             from,
             to,
+            projection: columns.clone(),
             columns,
+            row_filter: None,
+            versions: KVVersionFilter::default(),
+            direction: KVScanDirection::Forward,
+        }
+    }
+
+    pub fn with_direction(mut self, direction: KVScanDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn with_version_filter(mut self, versions: KVVersionFilter) -> Self {
+        self.versions = versions;
+        self
+    }
+
+    /** Columns referenced by the filter are read even when not projected. */
+    pub fn with_row_filter(mut self, row_filter: KVRowFilter) -> Self {
+        if let KVColumnFilter::Columns(column_ids) = &mut self.columns {
+            row_filter.column_ids(column_ids);
         }
+
+        self.row_filter = Some(row_filter);
+        self
     }
 
     pub fn from(&self) -> &KVPrimaryKeyMarker {
@@ -68,6 +110,14 @@ impl KVRangeScan {
     pub fn to(&self) -> &KVPrimaryKeyMarker {
         &self.to
     }
+
+    pub fn columns(&self) -> &KVColumnFilter {
+        &self.columns
+    }
+
+    pub fn direction(&self) -> KVScanDirection {
+        self.direction
+    }
 }
 
 #[async_trait]
@@ -78,15 +128,35 @@ impl KVScanOp for KVRangeScan {
     ) -> Result<KVRangeScanResult, KVRuntimeError> {
         scannable.range_scan(&self).await
     }
+
+    fn version_filter(&self) -> &KVVersionFilter {
+        &self.versions
+    }
+
+    fn row_filter(&self) -> Option<&KVRowFilter> {
+        self.row_filter.as_ref()
+    }
+
+    fn projection(&self) -> &KVColumnFilter {
+        &self.projection
+    }
+
+    fn direction(&self) -> KVScanDirection {
+        self.direction
+    }
 }
 
 impl std::fmt::Display for KVRangeScan {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "KVRangeScan(from={}, to={})",
+            "KVRangeScan(from={}, to={}, direction={:?}, columns={}, versions={}, row_filter={:?})",
             KVPrimaryKeyMarkerUtils::debug_fmt(&self.schema, &self.from).unwrap(),
-            KVPrimaryKeyMarkerUtils::debug_fmt(&self.schema, &self.to).unwrap()
+            KVPrimaryKeyMarkerUtils::debug_fmt(&self.schema, &self.to).unwrap(),
+            self.direction,
+            self.columns,
+            self.versions,
+            self.row_filter
         )
     }
 }
@@ -95,6 +165,7 @@ pub struct KVRowScan {
     schema: KVTableSchema,
     primary_key: Box<[u8]>,
     columns: KVColumnFilter,
+    versions: KVVersionFilter,
 }
 
 impl KVRowScan {
@@ -103,8 +174,22 @@ impl KVRowScan {
             schema,
             primary_key,
             columns,
+            versions: KVVersionFilter::default(),
         }
     }
+
+    pub fn with_version_filter(mut self, versions: KVVersionFilter) -> Self {
+        self.versions = versions;
+        self
+    }
+
+    pub fn primary_key(&self) -> &[u8] {
+        &self.primary_key
+    }
+
+    pub fn columns(&self) -> &KVColumnFilter {
+        &self.columns
+    }
 }
 
 #[async_trait]
@@ -113,7 +198,23 @@ impl KVScanOp for KVRowScan {
         &self,
         scannable: &T,
     ) -> Result<KVRangeScanResult, KVRuntimeError> {
-        scannable.row_scan(&self.primary_key).await
+        scannable.row_scan(&self).await
+    }
+
+    fn version_filter(&self) -> &KVVersionFilter {
+        &self.versions
+    }
+
+    fn row_filter(&self) -> Option<&KVRowFilter> {
+        None
+    }
+
+    fn projection(&self) -> &KVColumnFilter {
+        &self.columns
+    }
+
+    fn direction(&self) -> KVScanDirection {
+        KVScanDirection::Forward
     }
 }
 
@@ -121,12 +222,110 @@ impl std::fmt::Display for KVRowScan {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "KVRowScan(primary_key={})",
-            KVPrimaryKeyUtils::debug_fmt(&self.schema, &self.primary_key).unwrap()
+            "KVRowScan(primary_key={}, columns={}, versions={})",
+            KVPrimaryKeyUtils::debug_fmt(&self.schema, &self.primary_key).unwrap(),
+            self.columns,
+            self.versions
         )
     }
 }
 
+/**
+ * Order of rows returned by a scan. Only rows are reversed - mutations of a single row keep their
+ * order (newest first, tombstones before puts), which shadowing and row assembly rely on. Range
+ * bounds keep their meaning in both directions, `from` is the lower one.
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KVScanDirection {
+    #[default]
+    Forward,
+    Reverse,
+}
+
+/** Selects columns returned by a scan, mutations of other columns are skipped by scannables. */
+#[derive(Debug, Clone)]
 pub enum KVColumnFilter {
     All,
+    Columns(Vec<u16>),
+}
+
+impl KVColumnFilter {
+    /** Row deletes and markers are never filtered out - row deletes shadow every column. */
+    pub fn matches(&self, mutation: &(dyn KVMutation + Send + Sync)) -> bool {
+        match self {
+            KVColumnFilter::All => true,
+            KVColumnFilter::Columns(column_ids) => {
+                let mutation_type = mutation.mutation_type();
+
+                mutation_type.is_marker()
+                    || mutation_type == MutationType::DeleteRow
+                    || column_ids.contains(&mutation.column_id())
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for KVColumnFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KVColumnFilter::All => write!(f, "All"),
+            KVColumnFilter::Columns(column_ids) => write!(f, "Columns({:?})", column_ids),
+        }
+    }
+}
+
+/**
+ * Selects versions of cells visible to a scan. Mutations written after `read_timestamp` are
+ * ignored, including tombstones - scan sees the table as it was at that point in time.
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct KVVersionFilter {
+    pub read_timestamp: u64,
+    /** Number of newest visible versions returned per cell, at least 1. */
+    pub max_versions: usize,
+}
+
+impl KVVersionFilter {
+    pub fn latest() -> Self {
+        Self {
+            read_timestamp: u64::MAX,
+            max_versions: 1,
+        }
+    }
+
+    pub fn at(read_timestamp: u64) -> Self {
+        Self {
+            read_timestamp,
+            max_versions: 1,
+        }
+    }
+
+    /** Selects every version kept by column retention, compaction must not drop any of them. */
+    pub fn retained() -> Self {
+        Self {
+            read_timestamp: u64::MAX,
+            max_versions: usize::MAX,
+        }
+    }
+
+    pub fn with_max_versions(mut self, max_versions: usize) -> Self {
+        self.max_versions = max_versions.max(1);
+        self
+    }
+}
+
+impl Default for KVVersionFilter {
+    fn default() -> Self {
+        Self::latest()
+    }
+}
+
+impl std::fmt::Display for KVVersionFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "KVVersionFilter(read_timestamp={}, max_versions={})",
+            self.read_timestamp, self.max_versions
+        )
+    }
 }
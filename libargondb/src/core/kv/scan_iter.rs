@@ -8,20 +8,26 @@ use std::{
 use async_trait::async_trait;
 
 use crate::kv::{
-    KVRow, KVRuntimeError, KVScanIterator, KVScanIteratorItem, KVTableSchema,
-    mutation::MutationComparator, primary_key::KVPrimaryKeySchema, row::KVRowBuilder,
+    KVColumnFilter, KVRow, KVRowFilter, KVRuntimeError, KVScanDirection, KVScanIterator,
+    KVScanIteratorItem, KVTableSchema, KVTableStorageGuard,
+    mutation::MutationComparator,
+    primary_key::{KVPrimaryKeyComparator, KVPrimaryKeySchema},
+    row::KVRowBuilder,
 };
 
 type BoxKVScanIterator = Box<dyn KVScanIterator + Send + Sync>;
 
+type BoxKVScanIteratorItem = Box<dyn KVScanIteratorItem + Send + Sync>;
+
+/** Merges iterators returning rows in the same direction. */
 pub struct KVMergeScanIter {
     heap: BinaryHeap<BoxKVScanIterator, KVMergeScanIterComparator>,
 }
 
 impl KVMergeScanIter {
-    pub fn new(schema: KVPrimaryKeySchema) -> Self {
+    pub fn new(schema: KVPrimaryKeySchema, direction: KVScanDirection) -> Self {
         Self {
-            heap: BinaryHeap::from_vec_cmp(vec![], KVMergeScanIterComparator { schema }),
+            heap: BinaryHeap::from_vec_cmp(vec![], KVMergeScanIterComparator { schema, direction }),
         }
     }
 
@@ -52,6 +58,7 @@ impl KVScanIterator for KVMergeScanIter {
 
 struct KVMergeScanIterComparator {
     schema: KVPrimaryKeySchema,
+    direction: KVScanDirection,
 }
 
 impl Compare<BoxKVScanIterator> for KVMergeScanIterComparator {
@@ -60,10 +67,45 @@ impl Compare<BoxKVScanIterator> for KVMergeScanIterComparator {
             (None, None) => Ordering::Equal,
             (Some(_), None) => Ordering::Greater,
             (None, Some(_)) => Ordering::Less,
-            (Some(a), Some(b)) => MutationComparator::cmp(&self.schema, a.mutation(), b.mutation())
-                .unwrap()
-                .reverse(),
+            (Some(a), Some(b)) => MutationComparator::cmp_in_direction(
+                &self.schema,
+                a.mutation(),
+                b.mutation(),
+                self.direction,
+            )
+            .unwrap()
+            .reverse(),
+        }
+    }
+}
+
+pub(crate) struct KVScanIterUtils;
+
+impl KVScanIterUtils {
+    /** Splits items ordered by primary key into rows, keeping order of items within each row. */
+    pub fn group_rows(
+        schema: &KVPrimaryKeySchema,
+        items: impl IntoIterator<Item = BoxKVScanIteratorItem>,
+    ) -> Vec<Vec<BoxKVScanIteratorItem>> {
+        let mut rows: Vec<Vec<BoxKVScanIteratorItem>> = vec![];
+
+        for item in items {
+            match rows.last_mut() {
+                Some(row)
+                    if KVPrimaryKeyComparator::eq(
+                        schema,
+                        row[0].primary_key(),
+                        item.primary_key(),
+                    )
+                    .unwrap() =>
+                {
+                    row.push(item);
+                }
+                _ => rows.push(vec![item]),
+            }
         }
+
+        rows
     }
 }
 
@@ -72,6 +114,9 @@ pub struct KVRowIter {
     iter: BoxKVScanIterator,
     current_row: Option<KVRowBuilder>,
     finished: bool,
+    row_filter: Option<KVRowFilter>,
+    projection: KVColumnFilter,
+    storage_guard: Option<KVTableStorageGuard>,
 }
 
 impl KVRowIter {
@@ -81,10 +126,46 @@ impl KVRowIter {
             iter,
             current_row: None,
             finished: false,
+            row_filter: None,
+            projection: KVColumnFilter::All,
+            storage_guard: None,
         }
     }
 
+    /** Skips rows not matching the filter, columns outside of projection are dropped afterwards. */
+    pub fn with_row_filter(
+        mut self,
+        row_filter: Option<KVRowFilter>,
+        projection: KVColumnFilter,
+    ) -> Self {
+        self.row_filter = row_filter;
+        self.projection = projection;
+        self
+    }
+
+    /** Keeps storage of the table from being removed until the iterator is dropped. */
+    pub fn with_storage_guard(mut self, storage_guard: KVTableStorageGuard) -> Self {
+        self.storage_guard = Some(storage_guard);
+        self
+    }
+
     pub async fn next_row(&mut self) -> Result<Option<KVRow>, KVRuntimeError> {
+        while let Some(mut row) = self.next_unfiltered_row().await? {
+            if let Some(row_filter) = &self.row_filter
+                && !row_filter.matches(&row)?
+            {
+                continue;
+            }
+
+            row.retain_columns(&self.projection);
+
+            return Ok(Some(row));
+        }
+
+        Ok(None)
+    }
+
+    async fn next_unfiltered_row(&mut self) -> Result<Option<KVRow>, KVRuntimeError> {
         if self.finished {
             return Ok(None);
         }
@@ -0,0 +1,46 @@
+use crate::kv::{
+    KVMergeScanIter, KVScanDirection, KVScanIterator,
+    kv_test_utils::{TestIter, test_mutation, test_primary_key, test_schema},
+    primary_key::KVPrimaryKeySchema,
+};
+
+#[test]
+fn test_reverse_merge() {
+    let direction = KVScanDirection::Reverse;
+
+    let mut merge_iter = KVMergeScanIter::new(
+        KVPrimaryKeySchema::from_table_schema(&test_schema()),
+        direction,
+    );
+    merge_iter.add_iter(Box::new(TestIter::sorted(
+        vec![
+            test_mutation("a", 10, "value"),
+            test_mutation("b", 30, "value"),
+            test_mutation("c", 10, "value"),
+        ],
+        direction,
+    )));
+    merge_iter.add_iter(Box::new(TestIter::sorted(
+        vec![
+            test_mutation("b", 20, "value"),
+            test_mutation("b", 40, "value"),
+        ],
+        direction,
+    )));
+
+    let result = smol::block_on(async {
+        let mut result = vec![];
+        while let Some(item) = merge_iter.next_mutation().await {
+            result.push((item.primary_key().to_vec(), item.mutation().timestamp()));
+        }
+        result
+    });
+
+    // Rows come in descending key order, versions of a row stay newest first
+    let expected: Vec<_> = [("c", 10), ("b", 40), ("b", 30), ("b", 20), ("a", 10)]
+        .into_iter()
+        .map(|(id, timestamp)| (test_primary_key(id).to_vec(), timestamp))
+        .collect();
+
+    assert_eq!(result, expected);
+}
@@ -1,6 +1,7 @@
 use std::{
     collections::{BTreeMap, HashSet},
     fmt::{Debug, Display},
+    time::Duration,
 };
 
 use crate::{
@@ -15,6 +16,11 @@ pub struct KVTableSchema {
     /** Contains IDs of columns being part of primary key in correct order */
     pub primary_key: Vec<u16>,
     pub column_name_map: BTreeMap<String, u16>,
+    /**
+     * IDs of dropped columns, sorted. Their cells stay in sstables until compaction purges them,
+     * reads skip them and the IDs are never given to new columns.
+     */
+    pub retired_column_ids: Vec<u16>,
 }
 
 impl KVTableSchema {
@@ -55,6 +61,11 @@ impl KVTableSchema {
                 KVTableSchemaBuildError::ColumnsSchemaInvalid
             );
 
+            ensure!(
+                column.retention.is_valid(),
+                KVTableSchemaBuildError::ColumnsSchemaInvalid
+            );
+
             column_name_map.insert(column_name, column.column_id);
         }
 
@@ -80,9 +91,40 @@ impl KVTableSchema {
             columns,
             primary_key,
             column_name_map,
+            retired_column_ids: vec![],
         })
     }
 
+    pub fn with_retired_column_ids(
+        mut self,
+        mut retired_column_ids: Vec<u16>,
+    ) -> Result<Self, KVTableSchemaBuildError> {
+        retired_column_ids.sort_unstable();
+        retired_column_ids.dedup();
+
+        for column_id in &retired_column_ids {
+            ensure!(
+                *column_id > 0 && !self.column_name_map.values().any(|id| id == column_id),
+                KVTableSchemaBuildError::ColumnsSchemaInvalid
+            );
+        }
+
+        self.retired_column_ids = retired_column_ids;
+        Ok(self)
+    }
+
+    pub fn is_retired(&self, column_id: u16) -> bool {
+        self.retired_column_ids.binary_search(&column_id).is_ok()
+    }
+
+    /** Returns ID for a new column, greater than IDs of all live and retired columns. */
+    pub fn next_column_id(&self) -> Option<u16> {
+        let last_column_id = self.columns.last().map_or(0, |column| column.column_id);
+        let last_retired_id = self.retired_column_ids.last().copied().unwrap_or(0);
+
+        last_column_id.max(last_retired_id).checked_add(1)
+    }
+
     pub fn columns_count(&self) -> u16 {
         let len = self.columns.len();
         assert!(len <= u16::MAX as usize);
@@ -119,6 +161,7 @@ impl Debug for KVTableSchema {
         f.debug_struct("KVTableSchema")
             .field("columns", &self.columns)
             .field("primary_key", &self.primary_key)
+            .field("retired_column_ids", &self.retired_column_ids)
             .finish()
     }
 }
@@ -128,6 +171,47 @@ pub struct KVColumnSchema {
     pub column_id: u16,
     pub column_name: String,
     pub column_type: ColumnTypeCode,
+    pub retention: KVColumnRetention,
+    /** Inserts must set columns which are neither nullable nor have a default. */
+    pub nullable: bool,
+    /** Serialized value returned by reads of rows in which the column was never written. */
+    pub default_value: Option<Box<[u8]>>,
+}
+
+/**
+ * Bounds versions kept for a column. Version is kept when it is one of `max_versions` newest
+ * versions and it has not expired, unless it is one of `min_versions` newest versions - those
+ * never expire. Reads hide versions which are not kept, compaction drops them.
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KVColumnRetention {
+    /** 0 means no limit. */
+    pub max_versions: u16,
+    pub min_versions: u16,
+    /** None means versions never expire. */
+    pub ttl: Option<Duration>,
+}
+
+impl KVColumnRetention {
+    pub fn is_valid(&self) -> bool {
+        self.max_versions == 0 || self.min_versions <= self.max_versions
+    }
+
+    /** Checks version with given index (0 being the newest version) against retention. */
+    pub fn is_retained(&self, version_idx: usize, timestamp: u64, now: u64) -> bool {
+        if self.max_versions > 0 && version_idx >= self.max_versions as usize {
+            return false;
+        }
+
+        if version_idx < self.min_versions as usize {
+            return true;
+        }
+
+        match self.ttl {
+            Some(ttl) => timestamp.saturating_add(ttl.as_millis() as u64) > now,
+            None => true,
+        }
+    }
 }
 
 #[derive(Debug)]
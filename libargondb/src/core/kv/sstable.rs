@@ -2,7 +2,10 @@ use std::fmt::Debug;
 
 use async_trait::async_trait;
 
-use crate::kv::{ObjectId, error::KVRuntimeError, mutation::KVMutation, scan::KVScanIteratorItem};
+use crate::kv::{
+    KVRangeTombstone, ObjectId, error::KVRuntimeError, mutation::KVMutation,
+    scan::KVScanIteratorItem,
+};
 
 use super::scan::KVScannable;
 
@@ -10,6 +13,12 @@ pub trait KVSSTable: KVScannable {
     fn level(&self) -> u64;
     fn sstable_id(&self) -> ObjectId;
     fn mutation_count(&self) -> u64;
+    fn min_row_key(&self) -> &[u8];
+    fn max_row_key(&self) -> &[u8];
+    fn min_timestamp(&self) -> u64;
+    fn max_timestamp(&self) -> u64;
+    /** Size of the sstable file in bytes. */
+    fn size(&self) -> u64;
 }
 
 #[async_trait]
@@ -33,6 +42,12 @@ pub trait KVSSTableBuilder {
         &mut self,
         mutation: &(dyn KVMutation + Send + Sync),
     ) -> Result<(), KVRuntimeError>;
+
+    /** Adds a range tombstone stored next to the data of the built file, in any order. */
+    async fn add_range_tombstone(
+        &mut self,
+        tombstone: &KVRangeTombstone,
+    ) -> Result<(), KVRuntimeError>;
 }
 
 #[derive(Clone, Copy, Hash, PartialEq, Eq)]
@@ -1,11 +1,23 @@
 mod table;
 mod table_id;
 mod table_name;
+mod table_options;
 mod table_state;
+mod write_stall;
 
+#[cfg(test)]
+mod table_tests;
+#[cfg(test)]
+mod write_stall_tests;
+
+pub use table::KVAlterSchemaError;
 pub use table::KVTable;
+pub use table::KVTableStorageGuard;
 pub use table_id::KVTableId;
 pub use table_id::KVTableIdConversionError;
 pub use table_name::KVTableName;
 pub use table_name::KVTableNameConversionError;
+pub use table_options::KVCompactionOptions;
+pub use table_options::KVTableOptions;
 pub use table_state::KVTableState;
+pub use write_stall::KVWriteStall;
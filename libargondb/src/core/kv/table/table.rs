@@ -1,27 +1,52 @@
-use super::{KVTableId, KVTableName, KVTableState};
+use super::{KVTableId, KVTableName, KVTableOptions, KVTableState};
 use crate::{
     kv::{
-        KVRangeScanResult, KVRuntimeError, KVRuntimeErrorKind, KVSSTable, KVScannable,
+        KVPrimaryKeyMarker, KVRangeScanResult, KVRangeTombstone, KVRuntimeError,
+        KVRuntimeErrorKind, KVSSTable, KVWriteStall,
+        commit_log::KVCommitLogRecord,
         instance::KVInstance,
         iter::{PrintIter, ShadowingIter},
         memtable::{Memtable, MemtableInsertError},
         mutation::StructuredMutation,
-        primary_key::KVPrimaryKeySchema,
+        primary_key::{KVPrimaryKeySchema, PrimaryKeyMarkerComparator},
         scan::KVScanOp,
         scan_iter::{KVMergeScanIter, KVRowIter},
         schema::KVTableSchema,
     },
     utils::rcu::RCU,
 };
-use std::sync::Arc;
+use async_io::Timer;
+use async_lock::{RwLock, RwLockReadGuardArc};
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/** Interval of re-checking whether a blocked table accepts writes again. */
+const WRITE_STALL_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/**
+ * Held by scans, flushes and compactions of the table - its storage is not removed until released.
+ */
+pub type KVTableStorageGuard = RwLockReadGuardArc<()>;
+
+#[derive(Debug)]
+pub enum KVAlterSchemaError<E> {
+    /** Memtables and sstables are keyed by the primary key, it can't change. */
+    PrimaryKeyChanged,
+    Alter(E),
+}
 
 #[derive(Debug)]
 pub struct KVTable {
     pub table_id: KVTableId<'static>,
     pub table_name: KVTableName<'static>,
-    pub table_schema: KVTableSchema,
+    pub table_options: KVTableOptions,
 
+    table_schema: RCU<KVTableSchema>,
     state: RCU<KVTableState>,
+    storage_lock: Arc<RwLock<()>>,
     pub instance: Arc<KVInstance>,
 }
 
@@ -31,6 +56,7 @@ impl KVTable {
         table_id: KVTableId<'static>,
         table_name: KVTableName<'static>,
         table_schema: KVTableSchema,
+        table_options: KVTableOptions,
         sstables: Vec<Box<dyn KVSSTable>>,
     ) -> Self {
         let sstables = sstables
@@ -44,25 +70,77 @@ impl KVTable {
 
             table_id,
             table_name,
-            table_schema,
+            table_options,
 
+            table_schema: RCU::new(Arc::new(table_schema)),
             state: RCU::new(Arc::new(table_state)),
+            storage_lock: Arc::new(RwLock::new(())),
         }
     }
 
-    pub fn open(self: &Arc<Self>) {
+    /** Returns the current schema, operations should hold on to it instead of loading it again. */
+    pub fn table_schema(&self) -> Arc<KVTableSchema> {
+        Arc::clone(&self.table_schema.load())
+    }
+
+    /**
+     * Replaces the schema with one returned by `alter_fn` once `persist_fn` stored it, getting the
+     * previous and the next schema. Concurrent alters are applied one after another. Existing
+     * memtables and sstables are kept as they are - dropped columns are hidden by their retired
+     * IDs, so primary key columns must stay unchanged.
+     */
+    pub async fn alter_schema<F, P, Fut, E>(
+        &self,
+        alter_fn: F,
+        persist_fn: P,
+    ) -> Result<Arc<KVTableSchema>, KVAlterSchemaError<E>>
+    where
+        F: FnOnce(&KVTableSchema) -> Result<KVTableSchema, E>,
+        P: FnOnce(Arc<KVTableSchema>, Arc<KVTableSchema>) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+    {
+        self.table_schema
+            .try_mutate(|table_schema| async move {
+                let next_schema = alter_fn(&table_schema).map_err(KVAlterSchemaError::Alter)?;
+
+                if next_schema.primary_key != table_schema.primary_key {
+                    return Err(KVAlterSchemaError::PrimaryKeyChanged);
+                }
+
+                let next_schema = Arc::new(next_schema);
+                persist_fn(table_schema, next_schema.clone())
+                    .await
+                    .map_err(KVAlterSchemaError::Alter)?;
+
+                Ok(next_schema)
+            })
+            .await
+    }
+
+    /** Fails when the first memtable can't be created, the table stays closed then. */
+    pub fn open(self: &Arc<Self>) -> Result<(), KVRuntimeError> {
+        let mut result = Ok(());
+
         self.state.mutate_blocking(|state| {
             let Ok(closed_state) = state.try_as_closed() else {
                 println!("table is not in closed state - operation aborted");
                 return None;
             };
 
-            let next_memtable = self.instance.new_memtable(self.clone());
+            let next_memtable = match self.instance.new_memtable(self.clone()) {
+                Ok(next_memtable) => next_memtable,
+                Err(e) => {
+                    result = Err(e);
+                    return None;
+                }
+            };
 
             let next_state = closed_state.move_to_active(next_memtable);
 
             Some(next_state)
         });
+
+        result
     }
 
     pub fn close(&self) {
@@ -78,59 +156,222 @@ impl KVTable {
         });
     }
 
+    /**
+     * Moves the table to dropped state - writes and new scans fail, while its memtables are
+     * handed over to the flusher, which discards them instead of writing sstables. Returns once
+     * scans, flushes and compactions holding the storage guard finish, so the storage of the
+     * table can be removed.
+     */
+    pub async fn discard(&self) {
+        let mut current_memtable = None;
+
+        self.state
+            .mutate(|state| {
+                if state.is_dropped() {
+                    println!("table is already dropped - operation aborted");
+                    return None;
+                }
+
+                // Read memtables were queued for flush once they stopped accepting writes
+                if let Ok(active_state) = state.try_as_active() {
+                    current_memtable = Some(active_state.current_memtable.clone());
+                }
+
+                Some(KVTableState::Dropped)
+            })
+            .await;
+
+        if let Some(memtable) = current_memtable
+            && let Err(e) = memtable.request_flush()
+        {
+            println!(
+                "failed to hand over memtable[object_id={}] of dropped table {} - {}",
+                memtable.object_id, self.table_name, e
+            );
+        }
+
+        drop(self.storage_lock.write().await);
+    }
+
+    /** Guards storage of the table against removal, see [`KVTable::discard`]. */
+    pub async fn storage_guard(&self) -> KVTableStorageGuard {
+        self.storage_lock.read_arc().await
+    }
+
+    /**
+     * Same as [`KVTable::storage_guard`], but fails instead of waiting while the table is being
+     * discarded. Used by flushes and compactions, which are skipped for discarded tables anyway.
+     */
+    pub fn try_storage_guard(&self) -> Option<KVTableStorageGuard> {
+        // Storage lock is locked for writing only by discard
+        let storage_guard = self.storage_lock.try_read_arc()?;
+
+        // Discard moves the table to dropped state before it waits for held guards
+        (!self.is_dropped()).then_some(storage_guard)
+    }
+
+    pub fn is_dropped(&self) -> bool {
+        self.state.load().is_dropped()
+    }
+
+    /**
+     * Inserts batch of mutations into a single memtable. Batch is appended to the commit log
+     * before it becomes visible, so the call returns only after the whole batch is durable.
+     * Writes are delayed or blocked while the table is stalled, see [`KVWriteStall`].
+     */
     pub async fn insert_mutations(
         self: &Arc<Self>,
         mutations: &Vec<StructuredMutation>,
     ) -> Result<(), KVRuntimeError> {
-        let mut state = self.state.load();
-        let mut active_state = state.try_as_active()?;
+        self.wait_for_write_stall().await?;
+        self.insert_batch(mutations, &vec![]).await?;
+        self.instance.write_buffer_manager().flush_if_needed().await;
 
-        for mutation in mutations {
-            let mut attempt_count = 0u8;
-            let mut success = false;
-            let max_attempts_count = 3u8;
+        Ok(())
+    }
 
-            while !success && attempt_count < max_attempts_count {
-                attempt_count += 1;
+    /** Deletes all rows of the tombstone range written at or before its timestamp. */
+    pub async fn delete_range(
+        self: &Arc<Self>,
+        tombstone: KVRangeTombstone,
+    ) -> Result<(), KVRuntimeError> {
+        self.wait_for_write_stall().await?;
+        self.insert_batch(&vec![], &vec![tombstone]).await?;
+        self.instance.write_buffer_manager().flush_if_needed().await;
 
-                let insert_result = active_state.current_memtable.insert_mutation(mutation);
+        Ok(())
+    }
 
-                if let Err(err) = insert_result {
-                    match err {
-                        MemtableInsertError::ReadOnlyMode | MemtableInsertError::SizeExceeded => {
-                            self.request_current_memtable_flush().await;
+    /**
+     * Inserts batch of a commit log record ignoring write stalls and the write buffer budget -
+     * memtables are not flushed before the replay finishes, so waiting for them would never end.
+     */
+    pub async fn replay_batch(
+        self: &Arc<Self>,
+        mutations: &Vec<StructuredMutation>,
+        range_tombstones: &Vec<KVRangeTombstone>,
+    ) -> Result<(), KVRuntimeError> {
+        self.insert_batch(mutations, range_tombstones).await
+    }
 
-                            state = self.state.load();
-                            active_state = state.try_as_active()?;
-                        }
+    /**
+     * Delays the write once while the table is slowed down, blocks it while the table is stopped.
+     * Fails once the table stays stopped for longer than the write stall timeout.
+     */
+    async fn wait_for_write_stall(&self) -> Result<(), KVRuntimeError> {
+        let config = self.instance.config();
+        let deadline = Instant::now() + config.write_stall_timeout;
+        let mut slowed_down = false;
+
+        loop {
+            let write_stall = self.state.load().try_as_active()?.write_stall(config);
+
+            match write_stall {
+                KVWriteStall::Stop => {
+                    if Instant::now() >= deadline {
+                        return Err(KVRuntimeError::with_msg(
+                            KVRuntimeErrorKind::WriteStalled,
+                            "insert failed - table writes stalled, flushes or compactions fall behind",
+                        ));
                     }
-                } else {
-                    success = true;
-                    break;
+
+                    Timer::after(WRITE_STALL_POLL_INTERVAL).await;
                 }
-            }
+                KVWriteStall::Slowdown if !slowed_down => {
+                    slowed_down = true;
 
-            if !success {
-                return Err(KVRuntimeError::with_msg(
-                    KVRuntimeErrorKind::OperationFailure,
-                    "insert failed - max attempt count exceeded",
-                ));
+                    Timer::after(config.write_slowdown_delay).await;
+                }
+                _ => return Ok(()),
             }
         }
+    }
 
-        Ok(())
+    async fn insert_batch(
+        self: &Arc<Self>,
+        mutations: &Vec<StructuredMutation>,
+        range_tombstones: &Vec<KVRangeTombstone>,
+    ) -> Result<(), KVRuntimeError> {
+        if mutations.is_empty() && range_tombstones.is_empty() {
+            return Ok(());
+        }
+
+        let config = self.instance.config();
+        let batch_size = mutations
+            .iter()
+            .map(StructuredMutation::size)
+            .sum::<usize>()
+            + range_tombstones
+                .iter()
+                .map(KVRangeTombstone::size)
+                .sum::<usize>();
+
+        // Batch that doesn't fit an empty memtable would keep rotating memtables forever
+        if batch_size > config.memtable_size {
+            return Err(KVRuntimeError::with_msg(
+                KVRuntimeErrorKind::OperationNotAllowed,
+                "insert failed - batch exceeds memtable size",
+            ));
+        }
+
+        let mut logged_memtable_id = None;
+
+        loop {
+            let memtable = self.state.load().try_as_active()?.current_memtable.clone();
+
+            // Appended before the write is reserved, the reservation holds off the memtable flush.
+            // Records logged for a memtable rotated in between are skipped on replay once it is
+            // flushed, so the batch is logged again for the current one.
+            if logged_memtable_id != Some(memtable.object_id) {
+                self.instance
+                    .commit_log()
+                    .append(KVCommitLogRecord {
+                        table_id: self.table_id.clone(),
+                        memtable_id: memtable.object_id,
+                        mutations: mutations.clone(),
+                        range_tombstones: range_tombstones.clone(),
+                    })
+                    .await?;
+
+                logged_memtable_id = Some(memtable.object_id);
+            }
+
+            match memtable.reserve_write(batch_size) {
+                Ok(write) => {
+                    for mutation in mutations {
+                        write.insert(mutation);
+                    }
+
+                    for tombstone in range_tombstones {
+                        write.insert_range_tombstone(tombstone);
+                    }
+                    drop(write);
+
+                    return Ok(());
+                }
+                Err(MemtableInsertError::ReadOnlyMode | MemtableInsertError::SizeExceeded) => {
+                    self.request_current_memtable_flush().await?;
+                }
+            }
+        }
     }
 
     pub async fn scan(&self, scan_op: impl KVScanOp) -> Result<KVRowIter, KVRuntimeError> {
         #[cfg(debug_assertions)]
         println!("table scan op: {}", scan_op);
 
+        // Acquired before the state is loaded, so scans of a discarded table fail
+        let storage_guard = self.storage_guard().await;
         let table_state = self.state.load();
+        let table_schema = self.table_schema();
 
-        let pk_schema = KVPrimaryKeySchema::from_table_schema(&self.table_schema);
-        let mut result_iter = KVMergeScanIter::new(pk_schema.clone());
+        let pk_schema = KVPrimaryKeySchema::from_table_schema(&table_schema);
+        let mut result_iter = KVMergeScanIter::new(pk_schema.clone(), scan_op.direction());
+        let mut range_tombstones = vec![];
 
         for scannable in table_state.list_scannable()? {
+            range_tombstones.extend(scannable.range_tombstones());
+
             let scan_result = scan_op.scan(scannable).await?;
             if let KVRangeScanResult::Iter(scannable_iter) = scan_result {
                 #[cfg(debug_assertions)]
@@ -144,14 +385,28 @@ impl KVTable {
 
         let scan_iter = Box::new(PrintIter::new(
             "Final",
-            ShadowingIter::new(result_iter, pk_schema).await,
-            self.table_schema.clone(),
+            ShadowingIter::new(
+                result_iter,
+                &table_schema,
+                *scan_op.version_filter(),
+                scan_op.direction(),
+                range_tombstones,
+            )
+            .await,
+            KVTableSchema::clone(&table_schema),
         ));
 
-        Ok(KVRowIter::new(self.table_schema.clone(), scan_iter))
+        Ok(
+            KVRowIter::new(KVTableSchema::clone(&table_schema), scan_iter)
+                .with_row_filter(scan_op.row_filter().cloned(), scan_op.projection().clone())
+                .with_storage_guard(storage_guard),
+        )
     }
 
-    pub async fn request_current_memtable_flush(self: &Arc<Self>) {
+    /** Fails when the next memtable can't be created, the current one is left in place then. */
+    pub async fn request_current_memtable_flush(self: &Arc<Self>) -> Result<(), KVRuntimeError> {
+        let mut result = Ok(());
+
         self.state
             .mutate(|state| {
                 let Ok(active_state) = state.try_as_active() else {
@@ -165,13 +420,21 @@ impl KVTable {
                     return None;
                 }
 
-                let next_memtable = self.instance.new_memtable(self.clone());
+                let next_memtable = match self.instance.new_memtable(self.clone()) {
+                    Ok(next_memtable) => next_memtable,
+                    Err(e) => {
+                        result = Err(e);
+                        return None;
+                    }
+                };
 
                 let next_state = active_state.replace_current_memtable(next_memtable);
 
                 Some(next_state)
             })
             .await;
+
+        result
     }
 
     // pub fn load_state(&self) -> impl Deref<Target = Arc<KVTableState>> {
@@ -200,13 +463,58 @@ impl KVTable {
         state.list_sstables()
     }
 
+    /**
+     * Returns the lowest timestamp a mutation of keys within given range may have outside of
+     * `excluded` sstables. Memtables are always taken into account, as their key range is unknown.
+     */
+    pub fn min_timestamp_outside(
+        &self,
+        excluded: &[Arc<Box<dyn KVSSTable>>],
+        from: &KVPrimaryKeyMarker,
+        to: &KVPrimaryKeyMarker,
+    ) -> Result<u64, KVRuntimeError> {
+        let state = self.state.load();
+        let pk_schema = KVPrimaryKeySchema::from_table_schema(&self.table_schema());
+
+        let mut min_timestamp = u64::MAX;
+
+        for memtable in state.list_memtables() {
+            min_timestamp = min_timestamp.min(memtable.min_timestamp());
+        }
+
+        for sstable in state.list_sstables() {
+            // SSTables holding only range tombstones have no data to shadow
+            if excluded.iter().any(|this| Arc::ptr_eq(this, &sstable))
+                || sstable.min_row_key().is_empty()
+            {
+                continue;
+            }
+
+            let is_overlapping = PrimaryKeyMarkerComparator::is_above_lower_bound(
+                &pk_schema,
+                from,
+                sstable.max_row_key(),
+            )? && PrimaryKeyMarkerComparator::is_below_upper_bound(
+                &pk_schema,
+                to,
+                sstable.min_row_key(),
+            )?;
+
+            if is_overlapping {
+                min_timestamp = min_timestamp.min(sstable.min_timestamp());
+            }
+        }
+
+        Ok(min_timestamp)
+    }
+
     pub fn replace_compacted_sstables(
         &self,
         compacted_sstables: &Vec<Arc<Box<dyn KVSSTable>>>,
-        new_sstable: Arc<Box<dyn KVSSTable>>,
+        new_sstables: Vec<Arc<Box<dyn KVSSTable>>>,
     ) -> Result<(), ()> {
         let state_replaced = self.state.mutate_blocking(|state| {
-            match state.replace_compacted_sstables(compacted_sstables, new_sstable) {
+            match state.replace_compacted_sstables(compacted_sstables, new_sstables) {
                 Err(e) => {
                     println!("Failed to replace flushed memtable with sstable: {:?}", e);
                     None
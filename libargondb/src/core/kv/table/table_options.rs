@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use crate::kv::schema::KVColumnRetention;
+
+/** Per table settings which do not affect the table schema. */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KVTableOptions {
+    pub compaction: KVCompactionOptions,
+    /** Retention of columns created or added without their own. */
+    pub default_retention: KVColumnRetention,
+}
+
+/** Selects how sstables of a table are compacted. */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KVCompactionOptions {
+    /**
+     * Merges levels once they hold too many sstables, suits write heavy ingest. Default, as levels
+     * of tables compacted before strategies were selectable may hold overlapping sstables.
+     */
+    #[default]
+    SizeTiered,
+    /** Keeps levels non-overlapping, suits update heavy tables. */
+    Leveled,
+    /** Merges sstables holding mutations of the same time window, suits append-only time series. */
+    TimeWindow { window: Duration },
+}
+
+impl KVTableOptions {
+    pub fn is_valid(&self) -> bool {
+        match self.compaction {
+            KVCompactionOptions::TimeWindow { window } => !window.is_zero(),
+            _ => true,
+        }
+    }
+}
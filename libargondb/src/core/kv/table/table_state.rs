@@ -1,13 +1,16 @@
 use std::{fmt::Debug, mem::replace, sync::Arc};
 
 use crate::kv::{
-    KVRuntimeError, KVRuntimeErrorKind, KVSSTable, memtable::Memtable, scan::KVScannable,
+    KVRuntimeError, KVRuntimeErrorKind, KVSSTable, KVWriteStall, config::KVConfig,
+    memtable::Memtable, scan::KVScannable,
 };
 
 #[derive(Clone)]
 pub enum KVTableState {
     Active(KVTableStateActive),
     Closed(KVTableStateClosed),
+    /** Table was dropped or truncated, it holds no memtables nor sstables anymore. */
+    Dropped,
 }
 
 impl Debug for KVTableState {
@@ -46,6 +49,10 @@ impl KVTableState {
         }
     }
 
+    pub fn is_dropped(&self) -> bool {
+        matches!(self, Self::Dropped)
+    }
+
     pub fn list_scannable(&self) -> Result<Vec<&dyn KVScannable>, KVRuntimeError> {
         let active_state = self.try_as_active()?;
 
@@ -73,6 +80,19 @@ impl KVTableState {
         match self {
             Self::Active(state) => state.replace_flushed_memtable_with_sstable(memtable, sstable),
             Self::Closed(state) => state.replace_flushed_memtable_with_sstable(memtable, sstable),
+            Self::Dropped => Err(Self::dropped_error()),
+        }
+    }
+
+    pub fn list_memtables(&self) -> Vec<Arc<Memtable>> {
+        match self {
+            Self::Active(state) => {
+                let mut memtables = state.read_memtables.clone();
+                memtables.push(state.current_memtable.clone());
+                memtables
+            }
+            Self::Closed(state) => state.read_memtables.clone(),
+            Self::Dropped => vec![],
         }
     }
 
@@ -80,23 +100,32 @@ impl KVTableState {
         match self {
             Self::Active(state) => state.sstables.clone(),
             Self::Closed(state) => state.sstables.clone(),
+            Self::Dropped => vec![],
         }
     }
 
     pub fn replace_compacted_sstables(
         &self,
         compacted_sstables: &Vec<Arc<Box<dyn KVSSTable>>>,
-        new_sstable: Arc<Box<dyn KVSSTable>>,
+        new_sstables: Vec<Arc<Box<dyn KVSSTable>>>,
     ) -> Result<KVTableState, KVRuntimeError> {
         match self {
             Self::Active(state) => {
-                state.replace_compacted_sstables(compacted_sstables, new_sstable)
+                state.replace_compacted_sstables(compacted_sstables, new_sstables)
             }
             Self::Closed(state) => {
-                state.replace_compacted_sstables(compacted_sstables, new_sstable)
+                state.replace_compacted_sstables(compacted_sstables, new_sstables)
             }
+            Self::Dropped => Err(Self::dropped_error()),
         }
     }
+
+    fn dropped_error() -> KVRuntimeError {
+        KVRuntimeError::with_msg(
+            KVRuntimeErrorKind::OperationNotAllowed,
+            "table state is dropped",
+        )
+    }
 }
 
 #[derive(Clone)]
@@ -121,6 +150,16 @@ impl KVTableStateActive {
         })
     }
 
+    pub fn write_stall(&self, config: &KVConfig) -> KVWriteStall {
+        let level0_sstable_count = self
+            .sstables
+            .iter()
+            .filter(|sstable| sstable.level() == 0)
+            .count();
+
+        KVWriteStall::for_counts(config, self.read_memtables.len(), level0_sstable_count)
+    }
+
     pub fn replace_current_memtable(&self, next_memtable: Arc<Memtable>) -> KVTableState {
         let mut next_state = self.clone();
 
@@ -157,7 +196,7 @@ impl KVTableStateActive {
     pub fn replace_compacted_sstables(
         &self,
         compacted_sstables: &Vec<Arc<Box<dyn KVSSTable>>>,
-        new_sstable: Arc<Box<dyn KVSSTable>>,
+        new_sstables: Vec<Arc<Box<dyn KVSSTable>>>,
     ) -> Result<KVTableState, KVRuntimeError> {
         let mut next_state = self.clone();
 
@@ -174,7 +213,7 @@ impl KVTableStateActive {
             next_state.sstables.remove(idx);
         }
 
-        next_state.sstables.push(new_sstable);
+        next_state.sstables.extend(new_sstables);
 
         Ok(KVTableState::Active(next_state))
     }
@@ -220,7 +259,7 @@ impl KVTableStateClosed {
     pub fn replace_compacted_sstables(
         &self,
         compacted_sstables: &Vec<Arc<Box<dyn KVSSTable>>>,
-        new_sstable: Arc<Box<dyn KVSSTable>>,
+        new_sstables: Vec<Arc<Box<dyn KVSSTable>>>,
     ) -> Result<KVTableState, KVRuntimeError> {
         let mut next_state = self.clone();
 
@@ -237,7 +276,7 @@ impl KVTableStateClosed {
             next_state.sstables.remove(idx);
         }
 
-        next_state.sstables.push(new_sstable);
+        next_state.sstables.extend(new_sstables);
 
         Ok(KVTableState::Closed(next_state))
     }
@@ -0,0 +1,64 @@
+use std::{thread, time::Duration};
+
+use smol::block_on;
+
+use crate::kv::{
+    KVColumnFilter, KVPrimaryKeyMarker, KVRangeScan,
+    config::KVConfig,
+    kv_test_utils::{test_instance, test_mutation, test_schema, test_table},
+};
+
+fn full_scan() -> KVRangeScan {
+    KVRangeScan::new(
+        test_schema(),
+        KVPrimaryKeyMarker::Start,
+        KVPrimaryKeyMarker::End,
+        KVColumnFilter::All,
+    )
+}
+
+#[test]
+fn test_discarded_table_rejects_writes_and_scans() {
+    let table = test_table(test_instance(KVConfig::default()), vec![]);
+    block_on(table.insert_mutations(&vec![test_mutation("a", 1, "value")])).unwrap();
+
+    block_on(table.discard());
+
+    assert!(table.is_dropped());
+    assert!(table.try_storage_guard().is_none());
+    assert!(block_on(table.insert_mutations(&vec![test_mutation("a", 2, "value")])).is_err());
+    assert!(block_on(table.scan(full_scan())).is_err());
+
+    // Current memtable was handed over to the flusher
+    let flush_request = table
+        .instance
+        .get_memtable_flush_queue_iter()
+        .next()
+        .unwrap();
+    assert!(flush_request.memtable.table().is_dropped());
+
+    // Discarding again is a no-op
+    block_on(table.discard());
+}
+
+#[test]
+fn test_discard_waits_for_storage_guards() {
+    let table = test_table(test_instance(KVConfig::default()), vec![]);
+    let storage_guard = block_on(table.storage_guard());
+
+    let discarding_table = table.clone();
+    let discard = thread::spawn(move || block_on(discarding_table.discard()));
+
+    while !table.is_dropped() {
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    // Flushes and compactions don't wait for the discard to finish
+    assert!(table.try_storage_guard().is_none());
+
+    thread::sleep(Duration::from_millis(50));
+    assert!(!discard.is_finished());
+
+    drop(storage_guard);
+    discard.join().unwrap();
+}
@@ -0,0 +1,29 @@
+use crate::kv::config::KVConfig;
+
+/** Backpressure applied to writes of a table whose memtable flushes or compactions fall behind. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KVWriteStall {
+    None,
+    Slowdown,
+    Stop,
+}
+
+impl KVWriteStall {
+    pub fn for_counts(
+        config: &KVConfig,
+        immutable_memtable_count: usize,
+        level0_sstable_count: usize,
+    ) -> Self {
+        if immutable_memtable_count >= config.memtable_stop_count
+            || level0_sstable_count >= config.level0_stop_count
+        {
+            Self::Stop
+        } else if immutable_memtable_count >= config.memtable_slowdown_count
+            || level0_sstable_count >= config.level0_slowdown_count
+        {
+            Self::Slowdown
+        } else {
+            Self::None
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use smol::block_on;
+
+use crate::kv::{
+    KVRuntimeErrorKind, KVTable, KVWriteStall,
+    config::KVConfig,
+    kv_test_utils::{TestSSTable, test_instance, test_mutation, test_table},
+};
+
+fn config(level0_slowdown_count: usize, level0_stop_count: usize) -> KVConfig {
+    KVConfig {
+        level0_slowdown_count,
+        level0_stop_count,
+        write_slowdown_delay: Duration::from_millis(100),
+        write_stall_timeout: Duration::from_millis(100),
+        ..Default::default()
+    }
+}
+
+/** Table holding a single level 0 sstable. */
+fn level0_table(config: KVConfig) -> Arc<KVTable> {
+    test_table(
+        test_instance(config),
+        vec![TestSSTable::new(1, 0, "a", "z").boxed()],
+    )
+}
+
+#[test]
+fn test_stall_for_counts() {
+    let config = KVConfig {
+        memtable_slowdown_count: 2,
+        memtable_stop_count: 4,
+        level0_slowdown_count: 10,
+        level0_stop_count: 20,
+        ..Default::default()
+    };
+
+    assert_eq!(KVWriteStall::for_counts(&config, 1, 9), KVWriteStall::None);
+    assert_eq!(
+        KVWriteStall::for_counts(&config, 2, 0),
+        KVWriteStall::Slowdown
+    );
+    assert_eq!(
+        KVWriteStall::for_counts(&config, 0, 10),
+        KVWriteStall::Slowdown
+    );
+    assert_eq!(KVWriteStall::for_counts(&config, 4, 0), KVWriteStall::Stop);
+    assert_eq!(KVWriteStall::for_counts(&config, 3, 20), KVWriteStall::Stop);
+}
+
+#[test]
+fn test_stopped_table_rejects_writes_after_timeout() {
+    let table = level0_table(config(1, 1));
+
+    let started_at = Instant::now();
+    let result = block_on(table.insert_mutations(&vec![test_mutation("a", 10, "value")]));
+
+    assert!(matches!(
+        result.unwrap_err().kind(),
+        KVRuntimeErrorKind::WriteStalled
+    ));
+    assert!(started_at.elapsed() >= Duration::from_millis(100));
+
+    // Replayed batches are never stalled
+    block_on(table.replay_batch(&vec![test_mutation("a", 10, "value")], &vec![])).unwrap();
+}
+
+#[test]
+fn test_stopped_table_resumes_writes_once_compacted() {
+    let table = level0_table(KVConfig {
+        write_stall_timeout: Duration::from_secs(10),
+        ..config(1, 1)
+    });
+
+    let compacting_table = table.clone();
+    let compaction = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        compacting_table
+            .replace_compacted_sstables(&compacting_table.list_sstables(), vec![])
+            .unwrap();
+    });
+
+    let started_at = Instant::now();
+    block_on(table.insert_mutations(&vec![test_mutation("a", 10, "value")])).unwrap();
+    assert!(started_at.elapsed() >= Duration::from_millis(50));
+    assert!(started_at.elapsed() < Duration::from_secs(10));
+
+    compaction.join().unwrap();
+}
+
+#[test]
+fn test_slowed_down_table_delays_writes() {
+    let table = level0_table(config(1, 10));
+
+    let started_at = Instant::now();
+    block_on(table.insert_mutations(&vec![test_mutation("a", 10, "value")])).unwrap();
+    assert!(started_at.elapsed() >= Duration::from_millis(100));
+}
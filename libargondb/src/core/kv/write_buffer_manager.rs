@@ -0,0 +1,137 @@
+use std::{
+    cmp::Reverse,
+    sync::{
+        Arc, Mutex, Weak,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+};
+
+use crate::kv::memtable::Memtable;
+
+/**
+ * Tracks memory of memtables of all tables against a process-wide budget. Once the budget is
+ * exceeded, the largest writable memtable is flushed, the oldest one among equally sized.
+ * Memory of memtables queued for flush is about to be released, so it doesn't trigger more
+ * flushes unless it's less than a half of the budget.
+ */
+#[derive(Debug)]
+pub struct KVWriteBufferManager {
+    budget: usize,
+    memory_usage: AtomicUsize,
+    flushing_memory_usage: AtomicUsize,
+    triggered_flush_count: AtomicU64,
+    memtables: Mutex<Vec<Weak<Memtable>>>,
+    flush_lock: async_lock::Mutex<()>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct KVWriteBufferStats {
+    pub budget: usize,
+    pub memory_usage: usize,
+    pub flushing_memory_usage: usize,
+    pub memtable_count: usize,
+    pub triggered_flush_count: u64,
+}
+
+impl KVWriteBufferManager {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            memory_usage: AtomicUsize::new(0),
+            flushing_memory_usage: AtomicUsize::new(0),
+            triggered_flush_count: AtomicU64::new(0),
+            memtables: Mutex::new(vec![]),
+            flush_lock: async_lock::Mutex::new(()),
+        }
+    }
+
+    pub(crate) fn register_memtable(&self, memtable: &Arc<Memtable>) {
+        let mut memtables = self.memtables.lock().unwrap();
+
+        memtables.retain(|memtable| memtable.strong_count() > 0);
+        memtables.push(Arc::downgrade(memtable));
+    }
+
+    pub(crate) fn reserve(&self, size: usize) {
+        self.memory_usage.fetch_add(size, Ordering::AcqRel);
+    }
+
+    pub(crate) fn mark_flushing(&self, size: usize) {
+        self.flushing_memory_usage.fetch_add(size, Ordering::AcqRel);
+    }
+
+    pub(crate) fn free(&self, size: usize, flushing_size: usize) {
+        self.memory_usage.fetch_sub(size, Ordering::AcqRel);
+        self.flushing_memory_usage
+            .fetch_sub(flushing_size, Ordering::AcqRel);
+    }
+
+    pub fn is_flush_needed(&self) -> bool {
+        let memory_usage = self.memory_usage.load(Ordering::Acquire);
+        let flushing_memory_usage = self.flushing_memory_usage.load(Ordering::Acquire);
+
+        memory_usage > self.budget
+            && memory_usage.saturating_sub(flushing_memory_usage) > self.budget / 2
+    }
+
+    /** Flushes a single memtable when over budget. Concurrent callers don't wait for each other. */
+    pub async fn flush_if_needed(&self) {
+        if !self.is_flush_needed() {
+            return;
+        }
+
+        let Some(_guard) = self.flush_lock.try_lock() else {
+            return;
+        };
+
+        // Flush may have been triggered in the meantime
+        if !self.is_flush_needed() {
+            return;
+        }
+
+        let Some(memtable) = self.pick_memtable_to_flush() else {
+            return;
+        };
+
+        println!(
+            "write buffer over budget ({} of {} bytes) - flushing memtable[object_id={}] of table {}",
+            self.memory_usage.load(Ordering::Acquire),
+            self.budget,
+            memtable.object_id,
+            memtable.table().table_name
+        );
+
+        self.triggered_flush_count.fetch_add(1, Ordering::AcqRel);
+        if let Err(e) = memtable.table().request_current_memtable_flush().await {
+            println!("write buffer flush failed - {}", e);
+        }
+    }
+
+    pub fn stats(&self) -> KVWriteBufferStats {
+        let memtable_count = self
+            .memtables
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|memtable| memtable.strong_count() > 0)
+            .count();
+
+        KVWriteBufferStats {
+            budget: self.budget,
+            memory_usage: self.memory_usage.load(Ordering::Acquire),
+            flushing_memory_usage: self.flushing_memory_usage.load(Ordering::Acquire),
+            memtable_count,
+            triggered_flush_count: self.triggered_flush_count.load(Ordering::Acquire),
+        }
+    }
+
+    pub(super) fn pick_memtable_to_flush(&self) -> Option<Arc<Memtable>> {
+        let memtables = self.memtables.lock().unwrap();
+
+        memtables
+            .iter()
+            .filter_map(Weak::upgrade)
+            .filter(|memtable| !memtable.is_read_only() && memtable.is_flush_needed())
+            .max_by_key(|memtable| (memtable.size(), Reverse(memtable.object_id.0)))
+    }
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use smol::block_on;
+
+use crate::kv::{
+    KVTable, KVWriteBufferManager,
+    config::KVConfig,
+    kv_test_utils::{test_instance, test_mutation, test_table},
+};
+
+fn insert(table: &Arc<KVTable>, count: usize) {
+    let mutations = (0..count)
+        .map(|i| test_mutation(&format!("row{}", i), 10, "value"))
+        .collect();
+
+    block_on(table.insert_mutations(&mutations)).unwrap();
+}
+
+fn picked_table(tables: &[&Arc<KVTable>], manager: &KVWriteBufferManager) -> usize {
+    let memtable = manager.pick_memtable_to_flush().unwrap();
+
+    tables
+        .iter()
+        .position(|table| Arc::ptr_eq(table, memtable.table()))
+        .unwrap()
+}
+
+#[test]
+fn test_accounting_of_flushing_memory() {
+    let manager = KVWriteBufferManager::new(100);
+
+    manager.reserve(60);
+    assert!(!manager.is_flush_needed());
+
+    manager.reserve(50);
+    assert!(manager.is_flush_needed());
+
+    // Memory being flushed is about to be released, unless most of the budget remains in use
+    manager.mark_flushing(60);
+    assert!(!manager.is_flush_needed());
+    manager.reserve(20);
+    assert!(manager.is_flush_needed());
+
+    manager.free(60, 60);
+    let stats = manager.stats();
+    assert_eq!(stats.memory_usage, 70);
+    assert_eq!(stats.flushing_memory_usage, 0);
+    assert!(!manager.is_flush_needed());
+}
+
+#[test]
+fn test_picks_largest_writable_memtable() {
+    let instance = test_instance(KVConfig::default());
+    let manager = instance.write_buffer_manager();
+
+    let small_table = test_table(instance.clone(), vec![]);
+    let large_table = test_table(instance.clone(), vec![]);
+    let empty_table = test_table(instance.clone(), vec![]);
+    let tables = [&small_table, &large_table, &empty_table];
+
+    insert(&small_table, 1);
+    insert(&large_table, 3);
+    assert_eq!(picked_table(&tables, manager), 1);
+
+    // Memtables queued for flush are not picked again
+    block_on(large_table.request_current_memtable_flush()).unwrap();
+    assert_eq!(picked_table(&tables, manager), 0);
+
+    // Oldest one among equally sized memtables is picked
+    insert(&large_table, 1);
+    assert_eq!(picked_table(&tables, manager), 0);
+
+    block_on(small_table.request_current_memtable_flush()).unwrap();
+    assert_eq!(picked_table(&tables, manager), 1);
+    assert_eq!(manager.stats().memtable_count, 5);
+}
+
+#[test]
+fn test_replay_does_not_trigger_flushes() {
+    let instance = test_instance(KVConfig {
+        write_buffer_size: 1,
+        ..Default::default()
+    });
+    let table = test_table(instance.clone(), vec![]);
+
+    block_on(table.replay_batch(&vec![test_mutation("a", 10, "value")], &vec![])).unwrap();
+    assert_eq!(
+        instance
+            .write_buffer_manager()
+            .stats()
+            .triggered_flush_count,
+        0
+    );
+
+    insert(&table, 1);
+    assert_eq!(
+        instance
+            .write_buffer_manager()
+            .stats()
+            .triggered_flush_count,
+        1
+    );
+}
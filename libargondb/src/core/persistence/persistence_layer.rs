@@ -2,7 +2,10 @@ use std::io::Write;
 
 use crate::{
     core::persistence::PersistenceError,
-    kv::{KVInstanceStateSnapshot, KVSSTable, KVTableId, KVTableSchema, ObjectId},
+    kv::{
+        KVInstanceStateSnapshot, KVSSTable, KVTableId, KVTableSchema, ObjectId,
+        commit_log::KVCommitLogRecord,
+    },
 };
 use async_trait::async_trait;
 
@@ -17,18 +20,37 @@ pub trait PersistenceLayer {
         snapshot: KVInstanceStateSnapshot,
     ) -> Result<(), PersistenceError>;
 
+    /** Returns the highest object id used by sstable files and commit log records found on disk. */
+    async fn scan_max_object_id(&self) -> Result<Option<ObjectId>, PersistenceError>;
+
+    /**
+     * Loads sstables committed to the table. Files left by interrupted flushes and compactions are
+     * removed.
+     */
     async fn scan_for_sstables(
         &self,
         table_id: &KVTableId,
         table_schema: &KVTableSchema,
     ) -> Result<Vec<Box<dyn KVSSTable + 'static>>, PersistenceError>;
 
+    /** Writer of a new sstable file. The sstable stays invisible until it is committed. */
     async fn new_file_writer_for_sstable(
         &self,
         table_id: &KVTableId<'_>,
         sstable_id: ObjectId,
     ) -> Result<Box<dyn Write + Send + Sync + 'static>, PersistenceError>;
 
+    /**
+     * Atomically adds sstables, whose writers were flushed, to the table and drops removed ones.
+     * Files of removed sstables are kept until `remove_compacted_sstables` is called.
+     */
+    async fn commit_sstables(
+        &self,
+        table_id: &KVTableId,
+        added: Vec<ObjectId>,
+        removed: Vec<ObjectId>,
+    ) -> Result<(), PersistenceError>;
+
     async fn open_sstable(
         &self,
         table_id: &KVTableId,
@@ -36,11 +58,32 @@ pub trait PersistenceLayer {
         table_schema: &KVTableSchema,
     ) -> Result<Box<dyn KVSSTable + 'static>, PersistenceError>;
 
+    /** Returns ids of tables having files on disk, including ones left by dropped tables. */
+    async fn scan_table_ids(&self) -> Result<Vec<KVTableId<'static>>, PersistenceError>;
+
+    /** Removes all files of the table. Must be called only once the table was discarded. */
+    async fn remove_table_storage(&self, table_id: &KVTableId) -> Result<(), PersistenceError>;
+
     async fn remove_compacted_sstables(
         &self,
         table_id: &KVTableId,
         sstable_ids: Vec<ObjectId>,
     ) -> Result<(), PersistenceError>;
+
+    /** Removes files of sstables which were written, but never committed. */
+    async fn remove_uncommitted_sstables(
+        &self,
+        table_id: &KVTableId,
+        sstable_ids: Vec<ObjectId>,
+    ) -> Result<(), PersistenceError>;
+
+    /** Reads records of commit log left by previous run, in order they were appended. */
+    async fn read_commit_log(&self) -> Result<Vec<KVCommitLogRecord>, PersistenceError>;
+
+    /**
+     * Removes commit log left by previous run. Must be called only after its records were replayed.
+     */
+    async fn remove_replayed_commit_log(&self) -> Result<(), PersistenceError>;
 }
 
 pub type BoxPersistenceLayer = Box<dyn PersistenceLayer + Send + Sync>;
@@ -15,10 +15,21 @@ pub use argonfs::ArgonFsError;
 pub use argonfs::ArgonFsMemtableFlusher;
 pub use argonfs::ArgonFsMemtableFlusherHandle;
 pub use argonfs::ArgonfileReader;
+pub use argonfs::CommitLogConfig;
+pub use argonfs::CommitLogSyncPolicy;
+pub use argonfs::CompactionStrategy;
+pub use argonfs::CompactionStrategyConfig;
+pub use argonfs::CompactionStrategyResolver;
+pub use argonfs::CompactionTask;
 pub use argonfs::FsFileSystem;
 pub use argonfs::FsFileSystemConfig;
+pub use argonfs::LeveledCompactionStrategy;
 pub use argonfs::SSTableCompactor;
+pub use argonfs::SSTableCompactorClient;
+pub use argonfs::SSTableCompactorConfig;
 pub use argonfs::SSTableCompactorHandle;
+pub use argonfs::SizeTieredCompactionStrategy;
+pub use argonfs::TimeWindowCompactionStrategy;
 pub use argonfs::argonfile;
 pub use catalog::Catalog;
 pub use connector::ConnectorError;
@@ -1,4 +1,4 @@
-use std::{fmt::Debug, ops::Deref, sync::Arc};
+use std::{fmt::Debug, future::Future, ops::Deref, sync::Arc};
 
 use arc_swap::ArcSwap;
 use async_lock::Mutex;
@@ -32,6 +32,23 @@ impl<T> RCU<T> {
         }
     }
 
+    /**
+     * Replaces the state with one returned by `mutate_fn`, other mutations wait until the future
+     * completes. State is kept when it fails.
+     */
+    pub async fn try_mutate<F, Fut, E>(&self, mutate_fn: F) -> Result<Arc<T>, E>
+    where
+        F: FnOnce(Arc<T>) -> Fut,
+        Fut: Future<Output = Result<Arc<T>, E>>,
+    {
+        let _guard = self.state_mut_lock.lock().await;
+
+        let next = mutate_fn(self.state.load_full()).await?;
+        self.state.store(next.clone());
+
+        Ok(next)
+    }
+
     pub fn mutate_blocking<F>(&self, mutate_fn: F) -> bool
     where
         F: FnOnce(&T) -> Option<T>,